@@ -812,6 +812,7 @@ class Config {
     public wasm_url: string
     public wasm_glue_url: string
     public node_labels: boolean
+    public new_searcher: boolean
     public crash_report_host: string
     public no_data_gathering: boolean
     public is_in_cloud: boolean
@@ -865,6 +866,9 @@ class Config {
         this.node_labels = ok(other.node_labels)
             ? tryAsBoolean(other.node_labels)
             : this.node_labels
+        this.new_searcher = ok(other.new_searcher)
+            ? tryAsBoolean(other.new_searcher)
+            : this.new_searcher
         this.crash_report_host = ok(other.crash_report_host)
             ? tryAsString(other.crash_report_host)
             : this.crash_report_host
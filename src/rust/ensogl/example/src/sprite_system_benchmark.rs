@@ -16,6 +16,24 @@ use nalgebra::Vector3;
 use wasm_bindgen::prelude::*;
 
 
+
+// =================
+// === Constants ===
+// =================
+
+/// The number of rows and columns of the sprite grid of the culling benchmark.
+const CULLING_GRID_SIZE : usize = 300;
+/// The distance between neighbouring sprites of the culling benchmark.
+const CULLING_GRID_SPACING : f32 = 20.0;
+/// The number of frames measured before switching the culling on or off.
+const CULLING_FRAMES_PER_MODE : usize = 300;
+
+
+
+// ========================
+// === Sprite Benchmark ===
+// ========================
+
 #[wasm_bindgen]
 #[allow(dead_code)]
 pub fn entry_point_sprite_system_benchmark() {
@@ -127,3 +145,67 @@ pub fn on_frame
         }
     }
 }
+
+
+
+// =========================
+// === Culling Benchmark ===
+// =========================
+
+/// A benchmark of the view frustum culling. It places a large grid of sprites, most of them far
+/// outside of the screen, and moves the camera over it. The culling is switched on and off every
+/// few seconds, and the average frame time of both modes is logged together with the numbers of
+/// drawn and culled sprites. The numbers are also shown by the stats monitor.
+#[wasm_bindgen]
+#[allow(dead_code)]
+pub fn entry_point_sprite_system_culling_benchmark() {
+    forward_panic_hook_to_console();
+
+    let world         = World::new(&web::get_html_element_by_id("root").unwrap());
+    let scene         = world.scene().clone_ref();
+    let camera        = scene.camera().clone_ref();
+    let sprite_system = SpriteSystem::new(&world);
+    let logger        = Logger::new("CullingBenchmark");
+    scene.add_child(&sprite_system);
+
+    let mut sprites : Vec<Sprite> = default();
+    for x in 0..CULLING_GRID_SIZE {
+        for y in 0..CULLING_GRID_SIZE {
+            let sprite   = sprite_system.new_instance();
+            let position = Vector2::new(x as f32,y as f32) * CULLING_GRID_SPACING;
+            sprite.size.set(Vector2::new(10.0,10.0));
+            sprite.set_position(Vector3::new(position.x,position.y,0.0));
+            sprites.push(sprite);
+        }
+    }
+
+    world.keep_alive_forever();
+
+    let grid_extent    = CULLING_GRID_SIZE as f32 * CULLING_GRID_SPACING;
+    let mut frames     = 0;
+    let mut total_time = 0.0;
+    world.on_frame(move |time| {
+        let _keep_alive = &sprites;
+        let _keep_alive = &sprite_system;
+
+        let t = time.local / 10000.0;
+        camera.mod_position(|p| {
+            p.x = (t.cos() + 1.0) / 2.0 * grid_extent;
+            p.y = (t.sin() + 1.0) / 2.0 * grid_extent;
+        });
+
+        frames     += 1;
+        total_time += time.frame;
+        if frames == CULLING_FRAMES_PER_MODE {
+            let enabled    = scene.culling.is_enabled();
+            let frame_time = format!("{:.2}",total_time / frames as f32);
+            let drawn      = scene.stats.drawn_sprite_count();
+            let culled     = scene.stats.culled_sprite_count();
+            info!(logger,"Culling enabled: {enabled}. Average frame time: {frame_time} ms. \
+                Drawn sprites: {drawn}, culled sprites: {culled}.");
+            scene.set_culling_enabled(!enabled);
+            frames     = 0;
+            total_time = 0.0;
+        }
+    }).forget();
+}
@@ -1,8 +1,11 @@
 //! This module provides different kind of animation utilities, such as physics based animation and
 //! animation with easing functions.
 
+pub mod clip;
+pub mod clock;
 pub mod easing;
 pub mod physics;
+pub mod trajectory;
 
 mod frp;
 mod loops;
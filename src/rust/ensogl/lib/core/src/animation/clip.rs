@@ -0,0 +1,529 @@
+//! Keyframe animations composed into clips. A [`Track`] animates a single typed value between
+//! keyframes, with an easing function per segment. Clips can be composed sequentially with
+//! [`Sequence`], in parallel with [`Parallel`], delayed with [`Delay`], and staggered over
+//! collections with [`stagger`]. A clip can be set to its state at any time, so the whole
+//! composition can be played, paused, reversed, and seeked with a [`Player`]. See
+//! [`crate::animation::timeline::Timeline`] for the FRP bindings.
+//!
+//! For example, the following clip fades an element in, then slides it in, while the entries of a
+//! list appear one after another:
+//!
+//! ```text
+//! let fade    = Track::new(f!((t) opacity.emit(t))).key(0.0,0.0).key(100.0,1.0);
+//! let slide   = Track::new(f!((t) offset.emit(t))).key(0.0,-20.0).key(200.0,0.0);
+//! let entries = stagger(30.0,entries.iter().map(|e| Track::new(..).key(0.0,0.0).key(100.0,1.0)));
+//! let clip    = Sequence::new().then(fade).then(slide).then(entries);
+//! ```
+
+use crate::prelude::*;
+
+use crate::animation;
+use crate::animation::easing::FnEasing;
+use crate::data::mix;
+
+
+
+// ============
+// === Clip ===
+// ============
+
+/// An animation which can be set to its state at any time.
+pub trait Clip : Debug + 'static {
+    /// The duration of the clip, in milliseconds.
+    fn duration(&self) -> f32;
+
+    /// Set the clip to its state at the given time, in milliseconds since its start. Times outside
+    /// of the clip duration are clamped to it.
+    fn seek(&self, time:f32);
+}
+
+/// A clip of any type.
+pub type AnyClip = Rc<dyn Clip>;
+
+impl<C:Clip+?Sized> Clip for Rc<C> {
+    fn duration(&self) -> f32 {
+        self.deref().duration()
+    }
+
+    fn seek(&self, time:f32) {
+        self.deref().seek(time)
+    }
+}
+
+
+
+// =============
+// === Track ===
+// =============
+
+/// A keyframe of a track.
+#[derive(Derivative)]
+#[derivative(Debug(bound="T:Debug"))]
+pub struct Keyframe<T> {
+    /// The time of the keyframe, in milliseconds since the track start.
+    pub time   : f32,
+    /// The value of the animated property at the keyframe.
+    pub value  : T,
+    /// The easing of the segment from the previous keyframe to this one.
+    #[derivative(Debug="ignore")]
+    pub easing : Rc<dyn Fn(f32)->f32>,
+}
+
+/// A clip animating a single value between keyframes. The value is passed to the callback every
+/// time it changes. Before the first keyframe the track has the value of the first keyframe, and
+/// after the last keyframe, the value of the last one. Any [`mix::Mixable`] value can be
+/// animated, including numbers, vectors, and colors.
+#[derive(CloneRef,Derivative)]
+#[derivative(Clone(bound=""))]
+pub struct Track<T> {
+    model : Rc<TrackModel<T>>,
+}
+
+struct TrackModel<T> {
+    keyframes : RefCell<Vec<Keyframe<T>>>,
+    last_time : Cell<Option<f32>>,
+    callback  : Box<dyn Fn(T)>,
+}
+
+impl<T:mix::Mixable+Clone+'static> Track<T> {
+    /// Constructor of a track without keyframes.
+    pub fn new(callback:impl Fn(T)+'static) -> Self {
+        let keyframes = default();
+        let last_time = default();
+        let callback  = Box::new(callback);
+        let model     = Rc::new(TrackModel {keyframes,last_time,callback});
+        Self {model}
+    }
+
+    /// Add a keyframe reached from the previous one with a linear easing.
+    pub fn key(self, time:f32, value:T) -> Self {
+        self.key_with_easing(time,value,animation::easing::linear())
+    }
+
+    /// Add a keyframe reached from the previous one with the given easing. See
+    /// [`crate::animation::easing`] for the available easing functions.
+    pub fn key_with_easing(self, time:f32, value:T, easing:impl FnEasing) -> Self {
+        let easing = Rc::new(easing);
+        {
+            let mut keyframes = self.model.keyframes.borrow_mut();
+            let index         = keyframes.iter().take_while(|key| key.time <= time).count();
+            keyframes.insert(index,Keyframe {time,value,easing});
+        }
+        self.model.last_time.set(None);
+        self
+    }
+
+    /// The value of the track at the given time. Returns `None` if there are no keyframes.
+    pub fn sample(&self, time:f32) -> Option<T> {
+        let keyframes = self.model.keyframes.borrow();
+        let next      = keyframes.iter().position(|key| key.time > time);
+        match next {
+            None    => keyframes.last().map(|key| key.value.clone()),
+            Some(0) => keyframes.first().map(|key| key.value.clone()),
+            Some(index) => {
+                let prev   = &keyframes[index-1];
+                let next   = &keyframes[index];
+                let weight = (next.easing)((time - prev.time) / (next.time - prev.time));
+                Some(mix::mix(prev.value.clone(),next.value.clone(),weight))
+            }
+        }
+    }
+}
+
+impl<T> Debug for Track<T> {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f,"Track")
+    }
+}
+
+impl<T:mix::Mixable+Clone+'static> Clip for Track<T> {
+    fn duration(&self) -> f32 {
+        self.model.keyframes.borrow().last().map(|key| key.time).unwrap_or_default()
+    }
+
+    /// Pass the value at the given time to the callback, unless the track was already seeked to
+    /// that time. Thanks to this, tracks which are not active do not report their values every
+    /// frame.
+    fn seek(&self, time:f32) {
+        let time = time.max(0.0).min(self.duration());
+        if self.model.last_time.get() != Some(time) {
+            self.model.last_time.set(Some(time));
+            if let Some(value) = self.sample(time) {
+                (self.model.callback)(value)
+            }
+        }
+    }
+}
+
+
+
+// ================
+// === Sequence ===
+// ================
+
+/// Clips played one after another.
+#[derive(Debug,Default)]
+pub struct Sequence {
+    clips : Vec<AnyClip>,
+}
+
+impl Sequence {
+    /// Constructor of an empty sequence.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Add a clip played after all already added ones.
+    pub fn then(mut self, clip:impl Clip) -> Self {
+        self.clips.push(Rc::new(clip));
+        self
+    }
+}
+
+impl Clip for Sequence {
+    fn duration(&self) -> f32 {
+        self.clips.iter().map(|clip| clip.duration()).sum()
+    }
+
+    /// The clip playing at the given time is seeked last, so it wins over other clips animating
+    /// the same value.
+    fn seek(&self, time:f32) {
+        let mut start  = 0.0;
+        let mut starts = Vec::with_capacity(self.clips.len());
+        for clip in &self.clips {
+            starts.push(start);
+            start += clip.duration();
+        }
+        let active = starts.iter().rposition(|start| *start <= time).unwrap_or_default();
+        for (index,(clip,start)) in self.clips.iter().zip(&starts).enumerate() {
+            if index != active {
+                clip.seek(time - start)
+            }
+        }
+        if let Some(clip) = self.clips.get(active) {
+            clip.seek(time - starts[active])
+        }
+    }
+}
+
+
+
+// ================
+// === Parallel ===
+// ================
+
+/// Clips played at the same time. The duration is the duration of the longest clip.
+#[derive(Debug,Default)]
+pub struct Parallel {
+    clips : Vec<AnyClip>,
+}
+
+impl Parallel {
+    /// Constructor of an empty composition.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Add a clip played together with the already added ones.
+    pub fn with(mut self, clip:impl Clip) -> Self {
+        self.clips.push(Rc::new(clip));
+        self
+    }
+}
+
+impl Clip for Parallel {
+    fn duration(&self) -> f32 {
+        self.clips.iter().map(|clip| clip.duration()).fold(0.0,f32::max)
+    }
+
+    fn seek(&self, time:f32) {
+        for clip in &self.clips {
+            clip.seek(time)
+        }
+    }
+}
+
+
+
+// =============
+// === Delay ===
+// =============
+
+/// A clip started after a delay.
+#[derive(Debug)]
+pub struct Delay {
+    delay : f32,
+    clip  : AnyClip,
+}
+
+impl Delay {
+    /// Constructor. The delay is given in milliseconds.
+    pub fn new(delay:f32, clip:impl Clip) -> Self {
+        let clip = Rc::new(clip);
+        Self {delay,clip}
+    }
+}
+
+impl Clip for Delay {
+    fn duration(&self) -> f32 {
+        self.delay + self.clip.duration()
+    }
+
+    fn seek(&self, time:f32) {
+        self.clip.seek(time - self.delay)
+    }
+}
+
+
+// === Stagger ===
+
+/// Play the clips in parallel, starting each one `interval` milliseconds after the previous one.
+pub fn stagger<C:Clip>(interval:f32, clips:impl IntoIterator<Item=C>) -> Parallel {
+    let delays = (0..).map(|index| index as f32 * interval);
+    let clips  = clips.into_iter().zip(delays).map(|(clip,delay)| Delay::new(delay,clip));
+    clips.fold(Parallel::new(),Parallel::with)
+}
+
+
+
+// ==============
+// === Player ===
+// ==============
+
+/// Plays a clip forward or backward using an animation loop. The animation loop is running only
+/// when the clip is being played.
+#[derive(Clone,CloneRef,Debug)]
+pub struct Player {
+    model : Rc<PlayerModel>,
+}
+
+type PlayerLoop = animation::Loop<Box<dyn FnMut(animation::TimeInfo)>>;
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+struct PlayerModel {
+    clip           : AnyClip,
+    time           : Cell<f32>,
+    reversed       : Cell<bool>,
+    #[derivative(Debug="ignore")]
+    animation_loop : RefCell<Option<PlayerLoop>>,
+    #[derivative(Debug="ignore")]
+    on_step        : Box<dyn Fn(f32)>,
+    #[derivative(Debug="ignore")]
+    on_end         : Box<dyn Fn()>,
+}
+
+impl Player {
+    /// Constructor. The clip is set to its initial state immediately. The `on_step` callback is
+    /// called with the current time whenever it changes, and `on_end` when the playback reaches
+    /// the end of the clip, or its start when playing backward.
+    pub fn new
+    (clip:impl Clip, on_step:impl Fn(f32)+'static, on_end:impl Fn()+'static) -> Self {
+        let clip           = Rc::new(clip);
+        let time           = default();
+        let reversed       = default();
+        let animation_loop = default();
+        let on_step        = Box::new(on_step);
+        let on_end         = Box::new(on_end);
+        let model = PlayerModel {clip,time,reversed,animation_loop,on_step,on_end};
+        let model = Rc::new(model);
+        model.set_time(0.0);
+        Self {model}
+    }
+
+    /// The duration of the clip, in milliseconds.
+    pub fn duration(&self) -> f32 {
+        self.model.clip.duration()
+    }
+
+    /// The current time, in milliseconds.
+    pub fn time(&self) -> f32 {
+        self.model.time.get()
+    }
+
+    /// Check whether the clip is being played.
+    pub fn is_playing(&self) -> bool {
+        self.model.animation_loop.borrow().is_some()
+    }
+
+    /// Check whether the clip is played backward.
+    pub fn is_reversed(&self) -> bool {
+        self.model.reversed.get()
+    }
+
+    /// Play the clip forward from the current time. Starts from the beginning if the end of the
+    /// clip was reached.
+    pub fn play(&self) {
+        self.start(false)
+    }
+
+    /// Play the clip backward from the current time. Starts from the end if the start of the clip
+    /// was reached.
+    pub fn reverse(&self) {
+        self.start(true)
+    }
+
+    /// Pause the playback.
+    pub fn pause(&self) {
+        self.model.animation_loop.borrow_mut().take();
+    }
+
+    /// Set the clip to its state at the given time, in milliseconds. Does not affect the playback.
+    pub fn seek(&self, time:f32) {
+        self.model.set_time(time)
+    }
+
+    fn start(&self, reversed:bool) {
+        let model = &self.model;
+        model.reversed.set(reversed);
+        if model.is_finished() {
+            model.set_time(if reversed {self.duration()} else {0.0});
+        }
+        if !self.is_playing() {
+            let weak     = Rc::downgrade(model);
+            let on_frame = move |time:animation::TimeInfo| {
+                weak.upgrade().for_each(|model| model.advance(time.frame))
+            };
+            let on_frame       = Box::new(on_frame) as Box<dyn FnMut(animation::TimeInfo)>;
+            let animation_loop = animation::Loop::new(on_frame);
+            *model.animation_loop.borrow_mut() = Some(animation_loop);
+        }
+    }
+}
+
+impl PlayerModel {
+    fn set_time(&self, time:f32) {
+        let time = time.max(0.0).min(self.clip.duration());
+        self.time.set(time);
+        self.clip.seek(time);
+        (self.on_step)(time);
+    }
+
+    fn is_finished(&self) -> bool {
+        let time = self.time.get();
+        if self.reversed.get() { time <= 0.0 } else { time >= self.clip.duration() }
+    }
+
+    fn advance(&self, delta:f32) {
+        let delta = if self.reversed.get() { -delta } else { delta };
+        if delta != 0.0 {
+            self.set_time(self.time.get() + delta);
+        }
+        if self.is_finished() {
+            self.animation_loop.borrow_mut().take();
+            (self.on_end)();
+        }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::animation::clock;
+    use crate::animation::clock::ManualClock;
+    use crate::animation::easing;
+
+    fn recorded<T:mix::Mixable+Clone+'static>() -> (Track<T>,Rc<RefCell<Vec<T>>>) {
+        let values = Rc::new(RefCell::new(Vec::new()));
+        let track  = Track::new(f!([values](t) values.borrow_mut().push(t)));
+        (track,values)
+    }
+
+    #[test]
+    fn track_samples_keyframes() {
+        let (track,values) = recorded::<f32>();
+        let track = track.key(100.0,1.0).key(0.0,0.0).key_with_easing(200.0,0.0,easing::quad_in());
+        assert_eq!(track.duration(),200.0);
+        assert_eq!(track.sample(-10.0),Some(0.0));
+        assert_eq!(track.sample(50.0),Some(0.5));
+        assert_eq!(track.sample(150.0),Some(0.75));
+        assert_eq!(track.sample(300.0),Some(0.0));
+        track.seek(50.0);
+        track.seek(50.0);
+        track.seek(500.0);
+        track.seek(200.0);
+        assert_eq!(*values.borrow(),vec![0.5,0.0]);
+    }
+
+    #[test]
+    fn vector_track_mixes_components() {
+        let (track,_) = recorded::<Vector2>();
+        let track     = track.key(0.0,Vector2(0.0,10.0)).key(100.0,Vector2(10.0,0.0));
+        assert_eq!(track.sample(25.0),Some(Vector2(2.5,7.5)));
+    }
+
+    #[test]
+    fn composition_durations() {
+        let track    = || Track::new(|_:f32| {}).key(0.0,0.0).key(100.0,1.0);
+        let sequence = Sequence::new().then(track()).then(Delay::new(50.0,track()));
+        let parallel = Parallel::new().with(track()).with(Delay::new(50.0,track()));
+        let staggered = stagger(30.0,vec![track(),track(),track()]);
+        assert_eq!(sequence.duration(),250.0);
+        assert_eq!(parallel.duration(),150.0);
+        assert_eq!(staggered.duration(),160.0);
+    }
+
+    #[test]
+    fn sequence_seeks_active_clip_last() {
+        let values = Rc::new(RefCell::new(Vec::new()));
+        let track  = || Track::new(f!([values](t) values.borrow_mut().push(t)));
+        let clip   = Sequence::new()
+            .then(track().key(0.0,0.0).key(100.0,1.0))
+            .then(track().key(0.0,1.0).key(100.0,0.0));
+        clip.seek(150.0);
+        assert_eq!(values.borrow().last(),Some(&0.5));
+        clip.seek(50.0);
+        assert_eq!(values.borrow().last(),Some(&0.5));
+        clip.seek(175.0);
+        assert_eq!(values.borrow().last(),Some(&0.25));
+    }
+
+    #[test]
+    fn staggered_clips_start_one_after_another() {
+        let tracks = (0..3).map(|_| recorded::<f32>()).collect_vec();
+        let clips  = tracks.iter().map(|(track,_)| track.clone_ref().key(0.0,0.0).key(100.0,1.0));
+        let clip   = stagger(50.0,clips.collect_vec());
+        clip.seek(75.0);
+        let values = tracks.iter().map(|(_,values)| values.borrow().last().copied()).collect_vec();
+        assert_eq!(values,vec![Some(0.75),Some(0.25),Some(0.0)]);
+    }
+
+    #[test]
+    fn player_plays_pauses_and_reverses() {
+        let clock = ManualClock::new();
+        clock::with_current(clock.clone_ref(), || {
+            clock.set_frame_time(10.0);
+            let (track,values) = recorded::<f32>();
+            let ends   = Rc::new(Cell::new(0));
+            let track  = track.key(0.0,0.0).key(100.0,1.0);
+            let player = Player::new(track,|_| {},f_!(ends.set(ends.get() + 1)));
+            assert_eq!(*values.borrow(),vec![0.0]);
+            player.play();
+            clock.run_frames(6);
+            assert_eq!(player.time(),50.0);
+            player.pause();
+            assert!(clock.run_until_idle(1));
+            assert_eq!(player.time(),50.0);
+            player.play();
+            assert!(clock.run_until_idle(100));
+            assert_eq!(player.time(),100.0);
+            assert_eq!(values.borrow().last(),Some(&1.0));
+            assert_eq!(ends.get(),1);
+            player.reverse();
+            assert!(player.is_reversed());
+            assert!(clock.run_until_idle(100));
+            assert_eq!(player.time(),0.0);
+            assert_eq!(values.borrow().last(),Some(&0.0));
+            assert_eq!(ends.get(),2);
+            player.seek(30.0);
+            assert_eq!(values.borrow().last(),Some(&0.3));
+        });
+    }
+}
@@ -0,0 +1,429 @@
+//! Time sources of the animation loops. By default, animation frames are requested from the
+//! browser with `requestAnimationFrame`. The clock used by the loops can be replaced per thread
+//! with [`set_current`], which allows running animations with a simulated time, for example, to
+//! render deterministic recordings or to step component animations in tests.
+
+use crate::prelude::*;
+
+use crate::system::web;
+
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::Closure;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The time between frames of the simulated clocks, if not provided explicitly, in milliseconds.
+pub const DEFAULT_FRAME_TIME : f64 = 1000.0 / 60.0;
+
+
+
+// =============
+// === Clock ===
+// =============
+
+/// A time source of animation loops. Every loop uses the clock which was current on its thread
+/// when the loop was created. See the module docs to learn more.
+#[derive(Clone,CloneRef,Debug)]
+pub enum Clock {
+    /// Frames are requested from the browser and get the browser frame time.
+    Browser,
+    /// Frames are requested from the browser, but the time advances by a fixed step every frame,
+    /// regardless of the real time which passed.
+    FixedStep(FixedStepClock),
+    /// Frames are run only on demand, by the methods of [`ManualClock`]. Does not need a browser.
+    Manual(ManualClock),
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::Browser
+    }
+}
+
+impl From<FixedStepClock> for Clock {
+    fn from(clock:FixedStepClock) -> Self {
+        Self::FixedStep(clock)
+    }
+}
+
+impl From<ManualClock> for Clock {
+    fn from(clock:ManualClock) -> Self {
+        Self::Manual(clock)
+    }
+}
+
+
+// === Current Clock ===
+
+thread_local! {
+    static CURRENT : RefCell<Clock> = default();
+}
+
+/// The clock used by the animation loops created on the current thread.
+pub fn current() -> Clock {
+    CURRENT.with(|current| current.borrow().clone_ref())
+}
+
+/// Set the clock used by the animation loops created on the current thread from now on. The
+/// already running loops keep their clocks.
+pub fn set_current(clock:impl Into<Clock>) {
+    CURRENT.with(|current| *current.borrow_mut() = clock.into());
+}
+
+/// Run `f` with the clock set as current, and restore the previous one afterwards.
+pub fn with_current<T>(clock:impl Into<Clock>, f:impl FnOnce()->T) -> T {
+    let previous = CURRENT.with(|current| current.replace(clock.into()));
+    let out      = f();
+    CURRENT.with(|current| *current.borrow_mut() = previous);
+    out
+}
+
+
+
+// ======================
+// === FixedStepClock ===
+// ======================
+
+/// A clock running frames together with the browser, but advancing the time by a fixed step every
+/// frame. All loops using the clock observe the same time in the same browser frame.
+#[derive(Clone,CloneRef,Debug)]
+pub struct FixedStepClock {
+    model : Rc<FixedStepClockModel>,
+}
+
+#[derive(Debug)]
+struct FixedStepClockModel {
+    frame_time        : f64,
+    time              : Cell<f64>,
+    last_browser_time : Cell<Option<f64>>,
+}
+
+impl FixedStepClock {
+    /// Constructor. The `frame_time` is the step of the time between frames, in milliseconds.
+    pub fn new(frame_time:f64) -> Self {
+        let time              = default();
+        let last_browser_time = default();
+        let model             = Rc::new(FixedStepClockModel {frame_time,time,last_browser_time});
+        Self {model}
+    }
+
+    /// Constructor of a clock advancing by `1000 / frame_rate` milliseconds every frame.
+    pub fn with_frame_rate(frame_rate:f64) -> Self {
+        Self::new(1000.0 / frame_rate)
+    }
+
+    /// The time of the last frame, in milliseconds.
+    pub fn time(&self) -> f64 {
+        self.model.time.get()
+    }
+
+    /// The step of the time between frames, in milliseconds.
+    pub fn frame_time(&self) -> f64 {
+        self.model.frame_time
+    }
+
+    /// The simulated time of the frame with the given browser time.
+    fn time_of_frame(&self, browser_time:f64) -> f64 {
+        let model = &self.model;
+        if model.last_browser_time.get() != Some(browser_time) {
+            model.last_browser_time.set(Some(browser_time));
+            model.time.set(model.time.get() + model.frame_time);
+        }
+        model.time.get()
+    }
+}
+
+impl Default for FixedStepClock {
+    fn default() -> Self {
+        Self::new(DEFAULT_FRAME_TIME)
+    }
+}
+
+
+
+// ===================
+// === ManualClock ===
+// ===================
+
+/// A callback run by a clock on the requested frame, with the frame time in milliseconds.
+pub type FrameCallback = Rc<dyn Fn(f64)>;
+
+/// A clock running frames only when asked to. Frames are requested the same way as with
+/// `requestAnimationFrame`: every request runs its callback only once, on the next frame, and
+/// requests made during a frame are run on the following one. The time starts at zero.
+///
+/// The clock can be stepped with a fixed frame time ([`ManualClock::step`],
+/// [`ManualClock::run_frames`], [`ManualClock::run_until_idle`]), or advanced by arbitrary time
+/// deltas ([`ManualClock::advance`]).
+#[derive(Clone,CloneRef,Debug,Default)]
+pub struct ManualClock {
+    model : Rc<ManualClockModel>,
+}
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+struct ManualClockModel {
+    time        : Cell<f64>,
+    frame_time  : Cell<f64>,
+    frame_count : Cell<usize>,
+    next_id     : Cell<usize>,
+    #[derivative(Debug="ignore")]
+    requests    : RefCell<BTreeMap<usize,FrameCallback>>,
+    #[derivative(Debug="ignore")]
+    running     : RefCell<BTreeMap<usize,FrameCallback>>,
+}
+
+impl Default for ManualClockModel {
+    fn default() -> Self {
+        let time        = default();
+        let frame_time  = Cell::new(DEFAULT_FRAME_TIME);
+        let frame_count = default();
+        let next_id     = default();
+        let requests    = default();
+        let running     = default();
+        Self {time,frame_time,frame_count,next_id,requests,running}
+    }
+}
+
+impl ManualClock {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// The time of the last frame, in milliseconds.
+    pub fn time(&self) -> f64 {
+        self.model.time.get()
+    }
+
+    /// The number of frames run so far.
+    pub fn frame_count(&self) -> usize {
+        self.model.frame_count.get()
+    }
+
+    /// The time between frames used when stepping the clock, in milliseconds.
+    pub fn frame_time(&self) -> f64 {
+        self.model.frame_time.get()
+    }
+
+    /// Set the time between frames used when stepping the clock, in milliseconds.
+    pub fn set_frame_time(&self, frame_time:f64) {
+        self.model.frame_time.set(frame_time);
+    }
+
+    /// Check whether no frames are requested, which means that no animation is running.
+    pub fn is_idle(&self) -> bool {
+        self.model.requests.borrow().is_empty()
+    }
+
+    /// Advance the time by the given delta and run a frame.
+    pub fn advance(&self, delta_ms:f64) {
+        self.model.time.set(self.time() + delta_ms);
+        self.run_frame();
+    }
+
+    /// Advance the time by the frame time and run a frame.
+    pub fn step(&self) {
+        self.advance(self.frame_time());
+    }
+
+    /// Run the given number of frames, stepping the clock by the frame time.
+    pub fn run_frames(&self, count:usize) {
+        for _ in 0..count {
+            self.step()
+        }
+    }
+
+    /// Run frames, stepping the clock by the frame time, until at least `duration_ms` passed.
+    pub fn run_for(&self, duration_ms:f64) {
+        let end = self.time() + duration_ms;
+        while self.time() < end {
+            self.step()
+        }
+    }
+
+    /// Run frames, stepping the clock by the frame time, until no more frames are requested. At
+    /// most `max_frames` are run. Returns `true` if the clock became idle.
+    pub fn run_until_idle(&self, max_frames:usize) -> bool {
+        for _ in 0..max_frames {
+            if self.is_idle() {
+                return true
+            }
+            self.step()
+        }
+        self.is_idle()
+    }
+
+    /// Request the callback to be run on the next frame. Returns the id of the request.
+    pub fn request_frame(&self, callback:FrameCallback) -> usize {
+        let id = self.model.next_id.get();
+        self.model.next_id.set(id + 1);
+        self.model.requests.borrow_mut().insert(id,callback);
+        id
+    }
+
+    /// Cancel the request. It will not be run even if its frame is already being run.
+    pub fn cancel_frame(&self, id:usize) {
+        self.model.requests.borrow_mut().remove(&id);
+        self.model.running.borrow_mut().remove(&id);
+    }
+
+    /// Run the callbacks of all requests made before this frame, in the order of requests. The
+    /// borrows are released while the callbacks run, as they request new frames and cancel them.
+    fn run_frame(&self) {
+        let time     = self.time();
+        let requests = mem::take(&mut *self.model.requests.borrow_mut());
+        *self.model.running.borrow_mut() = requests;
+        self.model.frame_count.set(self.frame_count() + 1);
+        while let Some(callback) = self.next_running() {
+            callback(time)
+        }
+    }
+
+    fn next_running(&self) -> Option<FrameCallback> {
+        let mut running = self.model.running.borrow_mut();
+        let id          = running.keys().next().copied()?;
+        running.remove(&id)
+    }
+}
+
+
+
+// ======================
+// === FrameScheduler ===
+// ======================
+
+/// A frame callback bound to a clock. Frames are requested one by one, and every request runs
+/// the callback once, on the next frame of the clock. Dropping the scheduler cancels the pending
+/// request.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub enum FrameScheduler {
+    /// Frames requested from the browser.
+    Browser {
+        #[derivative(Debug="ignore")]
+        on_frame : Closure<dyn FnMut(f64)>,
+        request  : Cell<Option<i32>>,
+    },
+    /// Frames requested from a [`ManualClock`].
+    Manual {
+        clock    : ManualClock,
+        #[derivative(Debug="ignore")]
+        on_frame : FrameCallback,
+        request  : Cell<Option<usize>>,
+    },
+}
+
+impl FrameScheduler {
+    /// Constructor. No frame is requested until [`FrameScheduler::request_frame`] is called.
+    pub fn new(clock:&Clock, on_frame:impl Fn(f64)+'static) -> Self {
+        let request = default();
+        match clock {
+            Clock::Browser => {
+                let on_frame = Closure::new(on_frame);
+                Self::Browser {on_frame,request}
+            }
+            Clock::FixedStep(clock) => {
+                let clock    = clock.clone_ref();
+                let on_frame = Closure::new(move |time| on_frame(clock.time_of_frame(time)));
+                Self::Browser {on_frame,request}
+            }
+            Clock::Manual(clock) => {
+                let clock    = clock.clone_ref();
+                let on_frame = Rc::new(on_frame) as FrameCallback;
+                Self::Manual {clock,on_frame,request}
+            }
+        }
+    }
+
+    /// Request the callback to be run on the next frame. Replaces the pending request, if any.
+    pub fn request_frame(&self) {
+        self.cancel_frame();
+        match self {
+            Self::Browser {on_frame,request} =>
+                request.set(Some(web::request_animation_frame(on_frame))),
+            Self::Manual {clock,on_frame,request} =>
+                request.set(Some(clock.request_frame(on_frame.clone()))),
+        }
+    }
+
+    /// Cancel the pending request, if any.
+    pub fn cancel_frame(&self) {
+        match self {
+            Self::Browser {request,..} =>
+                request.take().for_each(web::cancel_animation_frame),
+            Self::Manual {clock,request,..} =>
+                request.take().for_each(|id| clock.cancel_frame(id)),
+        }
+    }
+}
+
+impl Drop for FrameScheduler {
+    fn drop(&mut self) {
+        self.cancel_frame();
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manual_clock_runs_requests_once_per_frame() {
+        let clock     = ManualClock::new();
+        let times     = Rc::new(RefCell::new(Vec::new()));
+        let scheduler = Rc::new(RefCell::new(None::<FrameScheduler>));
+        let on_frame  = f!([times,scheduler](time) {
+            times.borrow_mut().push(time);
+            if times.borrow().len() < 3 {
+                scheduler.borrow().as_ref().for_each(|s| s.request_frame())
+            }
+        });
+        let new_scheduler = FrameScheduler::new(&clock.clone_ref().into(),on_frame);
+        new_scheduler.request_frame();
+        *scheduler.borrow_mut() = Some(new_scheduler);
+        assert!(!clock.is_idle());
+        assert!(clock.run_until_idle(10));
+        assert_eq!(clock.frame_count(),3);
+        let expected = vec![DEFAULT_FRAME_TIME,2.0*DEFAULT_FRAME_TIME,3.0*DEFAULT_FRAME_TIME];
+        assert_eq!(*times.borrow(),expected);
+        clock.advance(100.0);
+        assert_eq!(times.borrow().len(),3);
+        scheduler.borrow_mut().take();
+    }
+
+    #[test]
+    fn dropping_scheduler_cancels_request() {
+        let clock     = ManualClock::new();
+        let count     = Rc::new(Cell::new(0));
+        let scheduler = FrameScheduler::new(&clock.clone_ref().into(),f!([count](_) {
+            count.set(count.get() + 1)
+        }));
+        scheduler.request_frame();
+        scheduler.request_frame();
+        clock.step();
+        assert_eq!(count.get(),1);
+        scheduler.request_frame();
+        drop(scheduler);
+        assert!(clock.is_idle());
+        clock.step();
+        assert_eq!(count.get(),1);
+    }
+
+    #[test]
+    fn current_clock_is_restored() {
+        let clock = ManualClock::new();
+        with_current(clock.clone_ref(), || assert!(matches!(current(),Clock::Manual(_))));
+        assert!(matches!(current(),Clock::Browser));
+    }
+}
@@ -2,6 +2,7 @@
 
 pub mod hysteretic;
 pub mod delayed;
+pub mod timeline;
 
 use crate::prelude::*;
 
@@ -0,0 +1,152 @@
+//! Keyframe timeline animation, allowing to choreograph multi-step transitions of components. The
+//! transitions are defined as clips, see [`crate::animation::clip`] to learn more.
+use crate::prelude::*;
+
+use crate::animation::clip;
+use crate::animation::clip::Clip;
+
+use enso_frp as frp;
+
+
+
+// ===========
+// === Frp ===
+// ===========
+
+crate::define_endpoints! {
+    Input {
+        /// Play the timeline forward from the current time. Starts from the beginning if the end
+        /// was reached.
+        play(),
+        /// Play the timeline backward from the current time. Starts from the end if the beginning
+        /// was reached.
+        reverse(),
+        /// Pause the playback.
+        pause(),
+        /// Jump to the given time, in milliseconds. Does not affect the playback.
+        seek(f32),
+    }
+    Output {
+        /// The current time, in milliseconds.
+        time(f32),
+        /// The current time relative to the timeline duration, in the range 0..1.
+        progress(f32),
+        /// Whether the timeline is being played.
+        playing(bool),
+        /// Whether the timeline is played backward.
+        reversed(bool),
+        /// Triggered when the playback reaches the end, or the beginning when played backward.
+        on_end(),
+    }
+}
+
+
+
+// ================
+// === Timeline ===
+// ================
+
+/// Keyframe timeline animation. Plays the provided clip, which is set to its initial state on
+/// construction.
+#[derive(Clone,CloneRef,Debug,Shrinkwrap)]
+pub struct Timeline {
+    #[allow(missing_docs)]
+    #[shrinkwrap(main_field)]
+    pub frp : FrpEndpoints,
+    player  : clip::Player,
+}
+
+impl Timeline {
+    /// Constructor.
+    pub fn new(network:&frp::Network, clip:impl Clip) -> Self {
+        let frp      = Frp::extend(network);
+        let duration = clip.duration();
+        let on_step  = f!((t) frp.source.time.emit(t));
+        let on_end   = f_!(frp.source.on_end.emit(()));
+        let player   = clip::Player::new(clip,on_step,on_end);
+        network.store(&player);
+
+        frp::extend! { network
+            eval_ frp.play    (player.play());
+            eval_ frp.reverse (player.reverse());
+            eval_ frp.pause   (player.pause());
+            eval  frp.seek    ((t) player.seek(*t));
+
+            started <- any(&frp.play,&frp.reverse);
+            stopped <- any(&frp.pause,&frp.source.on_end);
+            frp.source.playing  <+ bool(&stopped,&started);
+            frp.source.reversed <+ bool(&frp.play,&frp.reverse);
+            frp.source.progress <+ frp.source.time.map(move |t|
+                if duration > 0.0 { t / duration } else { 1.0 });
+        }
+        frp.source.time.emit(player.time());
+
+        Self {frp,player}
+    }
+
+    /// The duration of the timeline, in milliseconds.
+    pub fn duration(&self) -> f32 {
+        self.player.duration()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::animation::clip::Sequence;
+    use crate::animation::clip::Track;
+    use crate::animation::clock;
+    use crate::animation::clock::ManualClock;
+    use crate::animation::trajectory::Trajectory;
+
+    #[test]
+    fn timeline_plays_sequence() {
+        let clock = ManualClock::new();
+        clock::with_current(clock.clone_ref(), || {
+            let network = frp::Network::new("test");
+            frp::extend! { network
+                opacity <- source::<f32>();
+                offset  <- source::<f32>();
+            }
+            let fade     = Track::new(f!((t) opacity.emit(t))).key(0.0,0.0).key(100.0,1.0);
+            let slide    = Track::new(f!((t) offset.emit(t))).key(0.0,-20.0).key(200.0,0.0);
+            let clip     = Sequence::new().then(fade).then(slide);
+            let timeline = Timeline::new(&network,clip);
+            let opacity  = Trajectory::record(&network,&opacity.into(),&clock);
+            let offset   = Trajectory::record(&network,&offset.into(),&clock);
+            assert_eq!(timeline.duration(),300.0);
+
+            timeline.play();
+            assert!(timeline.playing.value());
+            assert!(clock.run_until_idle(100));
+            assert!(!timeline.playing.value());
+            assert_eq!(timeline.progress.value(),1.0);
+            opacity.assert_approaches(1.0,0.0);
+            opacity.assert_settles_at(1.0,0.0);
+            offset.assert_approaches(0.0,0.0);
+            offset.assert_settles_at(0.0,0.0);
+            let fade_end    = opacity.last().map(|sample| sample.time).unwrap_or_default();
+            let slide_start = offset.first().map(|sample| sample.time).unwrap_or_default();
+            assert!(fade_end <= slide_start);
+
+            timeline.reverse();
+            assert!(timeline.reversed.value());
+            assert!(clock.run_until_idle(100));
+            assert_eq!(timeline.time.value(),0.0);
+            opacity.assert_settles_at(0.0,0.0);
+            offset.assert_settles_at(-20.0,0.0);
+
+            timeline.seek(150.0);
+            assert_eq!(timeline.progress.value(),0.5);
+            opacity.assert_settles_at(1.0,0.0);
+            offset.assert_settles_at(-15.0,0.0);
+        });
+    }
+}
@@ -3,10 +3,10 @@
 
 use crate::prelude::*;
 
+use crate::animation::clock;
+use crate::animation::clock::Clock;
+use crate::animation::clock::FrameScheduler;
 use crate::control::callback;
-use crate::system::web;
-
-use wasm_bindgen::prelude::Closure;
 
 
 
@@ -63,50 +63,48 @@ pub struct RawLoop<Callback> {
 
 impl<Callback> RawLoop<Callback>
 where Callback : RawLoopCallback {
-    /// Create and start a new animation loop.
+    /// Create and start a new animation loop running on the current clock. See the
+    /// [`clock`] module docs to learn more.
     pub fn new(callback:Callback) -> Self {
+        Self::new_with_clock(&clock::current(),callback)
+    }
+
+    /// Create and start a new animation loop running on the provided clock.
+    pub fn new_with_clock(clock:&Clock, callback:Callback) -> Self {
         let data      = Rc::new(RefCell::new(RawLoopData::new(callback)));
         let weak_data = Rc::downgrade(&data);
         let on_frame  = move |time| weak_data.upgrade().for_each(|t| t.borrow_mut().run(time));
-        data.borrow_mut().on_frame = Some(Closure::new(on_frame));
-        let handle_id = web::request_animation_frame(data.borrow_mut().on_frame.as_ref().unwrap());
-        data.borrow_mut().handle_id = handle_id;
+        let scheduler = FrameScheduler::new(clock,on_frame);
+        scheduler.request_frame();
+        data.borrow_mut().scheduler = Some(scheduler);
         Self {data}
     }
 }
 
-/// The internal state of the `RawLoop`.
+/// The internal state of the `RawLoop`. Dropping it cancels the requested frame.
 #[derive(Derivative)]
 #[derivative(Debug(bound=""))]
 pub struct RawLoopData<Callback> {
     #[derivative(Debug="ignore")]
     callback  : Callback,
-    on_frame  : Option<Closure<dyn FnMut(f64)>>,
-    handle_id : i32,
+    scheduler : Option<FrameScheduler>,
 }
 
 impl<Callback> RawLoopData<Callback> {
     /// Constructor.
     fn new(callback:Callback) -> Self {
-        let on_frame  = default();
-        let handle_id = default();
-        Self {callback,on_frame,handle_id}
+        let scheduler = default();
+        Self {callback,scheduler}
     }
 
     /// Run the animation frame.
     fn run(&mut self, current_time_ms:f64)
     where Callback:FnMut(f32) {
-        let callback   = &mut self.callback;
-        self.handle_id = self.on_frame.as_ref().map_or(default(), |on_frame| {
+        let callback = &mut self.callback;
+        if let Some(scheduler) = &self.scheduler {
             callback(current_time_ms as f32);
-            web::request_animation_frame(on_frame)
-        })
-    }
-}
-
-impl<Callback> Drop for RawLoopData<Callback> {
-    fn drop(&mut self) {
-        web::cancel_animation_frame(self.handle_id);
+            scheduler.request_frame();
+        }
     }
 }
 
@@ -136,10 +134,16 @@ pub struct Loop<Callback> {
 
 impl<Callback> Loop<Callback>
 where Callback : LoopCallback {
-    /// Constructor.
+    /// Constructor. The loop runs on the current clock.
     pub fn new(callback:Callback) -> Self {
+        Self::new_with_clock(&clock::current(),callback)
+    }
+
+    /// Constructor of a loop running on the provided clock.
+    pub fn new_with_clock(clock:&Clock, callback:Callback) -> Self {
         let time_info      = Rc::new(Cell::new(TimeInfo::new()));
-        let animation_loop = RawLoop::new(on_frame(callback,time_info.clone_ref()));
+        let on_frame       = on_frame(callback,time_info.clone_ref());
+        let animation_loop = RawLoop::new_with_clock(clock,on_frame);
         Self {animation_loop,time_info}
     }
 }
@@ -0,0 +1,282 @@
+//! Recording and asserting on animation trajectories, the values emitted by animations over time.
+//! Trajectories are meant to be recorded with a [`ManualClock`] set as the current clock, which
+//! makes them deterministic. Note that animations create their loops lazily, when they start, so
+//! the clock should be kept current as long as the animations are used.
+
+use crate::prelude::*;
+
+use crate::animation::clock::ManualClock;
+use crate::data::mix;
+
+use enso_frp as frp;
+
+
+
+// ==============
+// === Sample ===
+// ==============
+
+/// A value emitted by an animation, with the time of the frame it was emitted in.
+#[derive(Clone,Copy,Debug,PartialEq)]
+#[allow(missing_docs)]
+pub struct Sample<T> {
+    pub time  : f64,
+    pub value : T,
+}
+
+
+
+// ==================
+// === Trajectory ===
+// ==================
+
+/// A sequence of values emitted by an animation, ordered by time.
+#[derive(CloneRef,Derivative)]
+#[derivative(Clone(bound=""))]
+#[derivative(Debug(bound="T:Debug"))]
+#[derivative(Default(bound=""))]
+pub struct Trajectory<T> {
+    samples : Rc<RefCell<Vec<Sample<T>>>>,
+}
+
+impl<T:Clone> Trajectory<T> {
+    /// Constructor of an empty trajectory.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Record all values emitted by the stream, at the current time of the clock.
+    pub fn record(network:&frp::Network, stream:&frp::Stream<T>, clock:&ManualClock) -> Self
+    where T:frp::Data {
+        let trajectory = Self::new();
+        let clock      = clock.clone_ref();
+        frp::extend! { network
+            eval stream ([trajectory,clock](value) trajectory.push(clock.time(),value.clone()));
+        }
+        trajectory
+    }
+
+    /// Add a sample at the end of the trajectory.
+    pub fn push(&self, time:f64, value:T) {
+        self.samples.borrow_mut().push(Sample {time,value});
+    }
+
+    /// Remove all samples.
+    pub fn clear(&self) {
+        self.samples.borrow_mut().clear();
+    }
+
+    /// The number of samples.
+    pub fn len(&self) -> usize {
+        self.samples.borrow().len()
+    }
+
+    /// Check whether there are no samples.
+    pub fn is_empty(&self) -> bool {
+        self.samples.borrow().is_empty()
+    }
+
+    /// All samples.
+    pub fn samples(&self) -> Vec<Sample<T>> {
+        self.samples.borrow().clone()
+    }
+
+    /// The values of all samples.
+    pub fn values(&self) -> Vec<T> {
+        self.samples.borrow().iter().map(|sample| sample.value.clone()).collect()
+    }
+
+    /// The first sample.
+    pub fn first(&self) -> Option<Sample<T>> {
+        self.samples.borrow().first().cloned()
+    }
+
+    /// The last sample.
+    pub fn last(&self) -> Option<Sample<T>> {
+        self.samples.borrow().last().cloned()
+    }
+
+    /// The time between the first and the last sample, in milliseconds.
+    pub fn duration(&self) -> f64 {
+        let samples = self.samples.borrow();
+        let first   = samples.first().map(|sample| sample.time);
+        let last    = samples.last().map(|sample| sample.time);
+        first.zip(last).map(|(first,last)| last - first).unwrap_or_default()
+    }
+}
+
+
+// === Assertions ===
+
+impl<T> Trajectory<T>
+where T:Clone+Debug+mix::Mixable, mix::Repr<T>:Magnitude<Output=f32> {
+    /// The time of the first sample since which all samples are within `precision` from the
+    /// target. Returns `None` if the last sample is not within `precision` from the target.
+    pub fn settling_time(&self, target:T, precision:f32) -> Option<f64> {
+        let samples = self.samples.borrow();
+        let dist    = |sample:&Sample<T>| distance(sample.value.clone(),target.clone());
+        let inside  = |sample:&&Sample<T>| dist(sample) <= precision;
+        let settled = samples.iter().rev().take_while(inside).last();
+        settled.map(|sample| sample.time)
+    }
+
+    /// Assert that the last sample is within `precision` from the target.
+    pub fn assert_settles_at(&self, target:T, precision:f32) {
+        let last = self.last().map(|sample| sample.value);
+        let last = last.unwrap_or_else(|| panic!("The trajectory is empty."));
+        let dist = distance(last.clone(),target.clone());
+        assert!(dist <= precision,
+            "The trajectory ends at {:?}, which is {} away from the target {:?}.",last,dist,target);
+    }
+
+    /// Assert that every sample is not further from the target than the previous one, with the
+    /// given tolerance. Note that spring animations can overshoot the target, so they may fail
+    /// this assertion even if they settle at the target.
+    pub fn assert_approaches(&self, target:T, tolerance:f32) {
+        let samples   = self.samples.borrow();
+        let distances = samples.iter().map(|sample| distance(sample.value.clone(),target.clone()));
+        let distances = distances.collect_vec();
+        for (index,pair) in distances.windows(2).enumerate() {
+            let sample = &samples[index+1];
+            assert!(pair[1] <= pair[0] + tolerance,
+                "The sample {:?} at {}ms moves away from the target {:?}.",
+                sample.value,sample.time,target);
+        }
+    }
+}
+
+/// The distance between two values, measured in the space they are mixed in. See
+/// [`crate::data::mix`] to learn more.
+pub fn distance<T:mix::Mixable>(a:T, b:T) -> f32
+where mix::Repr<T>:Magnitude<Output=f32> {
+    (mix::into_space(a) + mix::into_space(b) * -1.0).magnitude()
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Animation;
+    use crate::Easing;
+    use crate::animation::clock;
+    use crate::animation::delayed::DelayedAnimation;
+    use crate::animation::hysteretic::HystereticAnimation;
+    use crate::data::color;
+
+    /// Run the test with a new manual clock set as the current one.
+    fn with_manual_clock(test:impl FnOnce(&ManualClock)) {
+        let clock = ManualClock::new();
+        clock::with_current(clock.clone_ref(), || test(&clock));
+    }
+
+    #[test]
+    fn spring_animation_settles() {
+        with_manual_clock(|clock| {
+            let network    = frp::Network::new("test");
+            let animation  = Animation::<f32>::new_with_init(&network,0.0);
+            let trajectory = Trajectory::record(&network,&animation.value,clock);
+            animation.target.emit(10.0);
+            assert!(clock.run_until_idle(1000));
+            assert!(trajectory.len() > 2);
+            trajectory.assert_settles_at(10.0,0.01);
+            let settling_time = trajectory.settling_time(10.0,0.01).unwrap_or_default();
+            let start_time    = trajectory.first().map(|sample| sample.time).unwrap_or_default();
+            assert!(settling_time > start_time);
+        });
+    }
+
+    #[test]
+    fn vector_animation_settles() {
+        with_manual_clock(|clock| {
+            let network    = frp::Network::new("test");
+            let animation  = Animation::<Vector2>::new_with_init(&network,Vector2(0.0,0.0));
+            let trajectory = Trajectory::record(&network,&animation.value,clock);
+            animation.target.emit(Vector2(5.0,-5.0));
+            assert!(clock.run_until_idle(1000));
+            trajectory.assert_settles_at(Vector2(5.0,-5.0),0.01);
+        });
+    }
+
+    #[test]
+    fn color_animation_settles() {
+        with_manual_clock(|clock| {
+            let network    = frp::Network::new("test");
+            let animation  = color::Animation::new(&network);
+            let trajectory = Trajectory::record(&network,&animation.value,clock);
+            let source     = color::Lcha::new(0.2,0.5,0.1,1.0);
+            let target     = color::Lcha::new(0.8,0.2,0.6,0.5);
+            animation.target.emit(source);
+            assert!(clock.run_until_idle(1000));
+            trajectory.clear();
+            animation.target.emit(target);
+            assert!(clock.run_until_idle(1000));
+            assert!(trajectory.len() > 2);
+            trajectory.assert_settles_at(target,0.01);
+        });
+    }
+
+    #[test]
+    fn easing_follows_duration() {
+        with_manual_clock(|clock| {
+            let network    = frp::Network::new("test");
+            let easing     = Easing::new(&network);
+            let trajectory = Trajectory::record(&network,&easing.value,clock);
+            easing.set_duration.emit(500.0);
+            easing.target.emit(1.0);
+            assert!(clock.run_until_idle(1000));
+            trajectory.assert_approaches(1.0,0.0);
+            trajectory.assert_settles_at(1.0,0.0);
+            let duration = trajectory.duration();
+            assert!(duration >= 500.0 && duration <= 500.0 + 3.0 * clock.frame_time());
+        });
+    }
+
+    #[test]
+    fn delayed_animation_waits_for_delay() {
+        with_manual_clock(|clock| {
+            let network    = frp::Network::new("test");
+            let animation  = DelayedAnimation::new(&network);
+            let trajectory = Trajectory::record(&network,&animation.value,clock);
+            animation.set_delay.emit(100.0);
+            animation.set_duration.emit(200.0);
+            animation.start.emit(());
+            assert!(clock.run_until_idle(1000));
+            let start = trajectory.first().map(|sample| sample.time).unwrap_or_default();
+            assert!(start >= 100.0);
+            trajectory.assert_approaches(1.0,0.0);
+            trajectory.assert_settles_at(1.0,0.0);
+        });
+    }
+
+    #[test]
+    fn hysteretic_animation_ignores_short_changes() {
+        with_manual_clock(|clock| {
+            let network    = frp::Network::new("test");
+            let animation  = HystereticAnimation::new(&network,100.0,100.0);
+            let trajectory = Trajectory::record(&network,&animation.value,clock);
+            animation.to_start.emit(());
+            assert!(clock.run_until_idle(1000));
+            let start = trajectory.first().map(|sample| sample.time).unwrap_or_default();
+            assert!(start >= 100.0);
+            trajectory.assert_settles_at(1.0,0.01);
+
+            // Going back before the delay passes is not visible.
+            trajectory.clear();
+            animation.to_end.emit(());
+            clock.run_for(50.0);
+            animation.to_start.emit(());
+            assert!(clock.run_until_idle(1000));
+            assert!(trajectory.values().iter().all(|value| (value - 1.0).abs() < 0.01));
+
+            animation.to_end.emit(());
+            assert!(clock.run_until_idle(1000));
+            trajectory.assert_settles_at(0.0,0.01);
+        });
+    }
+}
@@ -1,11 +1,16 @@
 //! Root module for generic data containers and modifiers.
 
+pub mod bounding_box;
 pub mod color;
 pub mod container;
 pub mod dirty;
 pub mod function;
 pub mod mix;
 pub mod seq;
+pub mod spatial_grid;
+
+pub use bounding_box::BoundingBox;
+pub use spatial_grid::SpatialGrid;
 
 pub use enso_data::dependency_graph;
 pub use enso_data::hash_map_tree;
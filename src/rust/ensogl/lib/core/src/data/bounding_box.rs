@@ -0,0 +1,158 @@
+//! Axis-aligned bounding boxes in 2D space.
+
+use crate::prelude::*;
+
+
+
+// ===================
+// === BoundingBox ===
+// ===================
+
+/// An axis-aligned rectangle described by its minimal and maximal corners. Boxes touching each
+/// other by an edge are considered intersecting, and points lying on the edge are considered
+/// contained.
+#[derive(Clone,Copy,Debug,Default,PartialEq)]
+pub struct BoundingBox {
+    min : Vector2<f32>,
+    max : Vector2<f32>,
+}
+
+impl BoundingBox {
+    /// Constructor from any two opposite corners.
+    pub fn from_corners(corner1:Vector2<f32>, corner2:Vector2<f32>) -> Self {
+        let min = Vector2(corner1.x.min(corner2.x),corner1.y.min(corner2.y));
+        let max = Vector2(corner1.x.max(corner2.x),corner1.y.max(corner2.y));
+        Self {min,max}
+    }
+
+    /// Constructor of a box of the given size, centered at the given point.
+    pub fn from_center_and_size(center:Vector2<f32>, size:Vector2<f32>) -> Self {
+        let half = size.abs() / 2.0;
+        Self {min:center - half, max:center + half}
+    }
+
+    /// Constructor of a box of the given size, centered at the origin.
+    pub fn from_size(size:Vector2<f32>) -> Self {
+        Self::from_center_and_size(zero(),size)
+    }
+
+    /// The smallest box containing all the points. Returns [`None`] if there are no points.
+    pub fn from_points(points:impl IntoIterator<Item=Vector2<f32>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first      = points.next()?;
+        let init       = Self {min:first, max:first};
+        Some(points.fold(init,|bbox,point| bbox.union(&Self {min:point, max:point})))
+    }
+
+    /// The corner with the minimal coordinates.
+    pub fn min(&self) -> Vector2<f32> {
+        self.min
+    }
+
+    /// The corner with the maximal coordinates.
+    pub fn max(&self) -> Vector2<f32> {
+        self.max
+    }
+
+    /// The size of the box.
+    pub fn size(&self) -> Vector2<f32> {
+        self.max - self.min
+    }
+
+    /// The center of the box.
+    pub fn center(&self) -> Vector2<f32> {
+        (self.min + self.max) / 2.0
+    }
+
+    /// Check whether the point lies inside the box or on its edge.
+    pub fn contains(&self, point:Vector2<f32>) -> bool {
+        let inside_x = point.x >= self.min.x && point.x <= self.max.x;
+        let inside_y = point.y >= self.min.y && point.y <= self.max.y;
+        inside_x && inside_y
+    }
+
+    /// Check whether the other box lies entirely inside this box.
+    pub fn contains_box(&self, other:&BoundingBox) -> bool {
+        self.contains(other.min) && self.contains(other.max)
+    }
+
+    /// Check whether the boxes have a common point.
+    pub fn intersects(&self, other:&BoundingBox) -> bool {
+        let overlap_x = self.min.x <= other.max.x && other.min.x <= self.max.x;
+        let overlap_y = self.min.y <= other.max.y && other.min.y <= self.max.y;
+        overlap_x && overlap_y
+    }
+
+    /// The smallest box containing both boxes.
+    pub fn union(&self, other:&BoundingBox) -> Self {
+        let min = Vector2(self.min.x.min(other.min.x),self.min.y.min(other.min.y));
+        let max = Vector2(self.max.x.max(other.max.x),self.max.y.max(other.max.y));
+        Self {min,max}
+    }
+
+    /// The box moved by the given vector.
+    pub fn translate(&self, offset:Vector2<f32>) -> Self {
+        Self {min:self.min + offset, max:self.max + offset}
+    }
+
+    /// The smallest box containing this box transformed by the matrix. The Z axis is ignored, so
+    /// the result is the bounding box of the transformed rectangle projected on the XY plane.
+    pub fn transform(&self, matrix:&Matrix4<f32>) -> Self {
+        let corners = [
+            Vector2(self.min.x,self.min.y),
+            Vector2(self.min.x,self.max.y),
+            Vector2(self.max.x,self.min.y),
+            Vector2(self.max.x,self.max.y),
+        ];
+        let transformed = corners.iter().map(|corner| {
+            let point = matrix * Vector4(corner.x,corner.y,0.0,1.0);
+            point.xy() / point.w
+        });
+        // The iterator is not empty, as it contains four corners.
+        Self::from_points(transformed).unwrap_or(*self)
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::f32::consts::PI;
+
+    #[test]
+    fn bounding_box_operations() {
+        let bbox = BoundingBox::from_corners(Vector2(4.0,-2.0),Vector2(-4.0,2.0));
+        assert_eq!(bbox, BoundingBox::from_size(Vector2(8.0,4.0)));
+        assert_eq!((bbox.min(),bbox.max()), (Vector2(-4.0,-2.0),Vector2(4.0,2.0)));
+        assert!(bbox.contains(Vector2(4.0,0.0)));
+        assert!(!bbox.contains(Vector2(4.1,0.0)));
+
+        let other = BoundingBox::from_center_and_size(Vector2(6.0,0.0),Vector2(4.0,4.0));
+        assert!(bbox.intersects(&other));
+        assert!(!bbox.intersects(&other.translate(Vector2(0.1,0.0))));
+        let union = bbox.union(&other);
+        assert_eq!(union, BoundingBox::from_corners(Vector2(-4.0,-2.0),Vector2(8.0,2.0)));
+        assert!(union.contains_box(&other));
+        assert!(!other.contains_box(&union));
+        assert_eq!(union.center(), Vector2(2.0,0.0));
+        assert_eq!(BoundingBox::from_points(vec![]), None);
+    }
+
+    #[test]
+    fn transforming_bounding_box() {
+        let bbox        = BoundingBox::from_size(Vector2(8.0,4.0));
+        let translation = Matrix4::new_translation(&Vector3(10.0,0.0,0.0));
+        let rotation    = Matrix4::from_euler_angles(0.0,0.0,PI/2.0);
+        let scale       = Matrix4::new_nonuniform_scaling(&Vector3(2.0,3.0,1.0));
+        let transformed = bbox.transform(&(translation * rotation * scale));
+        let expected    = BoundingBox::from_center_and_size(Vector2(10.0,0.0),Vector2(12.0,16.0));
+        assert!((transformed.min() - expected.min()).norm() < 1e-5);
+        assert!((transformed.max() - expected.max()).norm() < 1e-5);
+    }
+}
@@ -0,0 +1,201 @@
+//! A spatial index of bounding boxes, based on a uniform grid of cells.
+
+use crate::prelude::*;
+
+use crate::data::bounding_box::BoundingBox;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The maximal number of cells an entry is registered in. Entries covering more cells are stored
+/// in a separate list, which is checked linearly on every query.
+pub const MAX_CELLS_PER_ENTRY : i64 = 64;
+
+
+
+// =================
+// === CellRange ===
+// =================
+
+/// A rectangular range of grid cells, with both ends inclusive.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+struct CellRange {
+    min : (i32,i32),
+    max : (i32,i32),
+}
+
+impl CellRange {
+    fn new(bounds:&BoundingBox, cell_size:f32) -> Self {
+        let cell = |v:f32| (v / cell_size).floor() as i32;
+        let min  = (cell(bounds.min().x),cell(bounds.min().y));
+        let max  = (cell(bounds.max().x),cell(bounds.max().y));
+        Self {min,max}
+    }
+
+    fn cell_count(&self) -> i64 {
+        let width  = self.max.0 as i64 - self.min.0 as i64 + 1;
+        let height = self.max.1 as i64 - self.min.1 as i64 + 1;
+        width * height
+    }
+
+    fn cells(self) -> impl Iterator<Item=(i32,i32)> {
+        (self.min.0..=self.max.0).flat_map(move |x| (self.min.1..=self.max.1).map(move |y| (x,y)))
+    }
+}
+
+
+
+// ===================
+// === SpatialGrid ===
+// ===================
+
+/// A spatial index of bounding boxes identified by keys. The space is divided into square cells,
+/// and every entry is registered in the cells its bounding box overlaps. Queries check only the
+/// entries registered in the cells overlapping the queried area. Updating an entry which moved
+/// within the same cells does not modify the cells at all.
+///
+/// The cell size should be comparable with the size of typical entries and queried areas.
+#[derive(Clone,Debug)]
+pub struct SpatialGrid<K> {
+    cell_size : f32,
+    entries   : HashMap<K,(BoundingBox,Option<CellRange>)>,
+    cells     : HashMap<(i32,i32),HashSet<K>>,
+    large     : HashSet<K>,
+}
+
+impl<K:Copy+Eq+Hash+Ord> SpatialGrid<K> {
+    /// Constructor.
+    pub fn new(cell_size:f32) -> Self {
+        let entries = default();
+        let cells   = default();
+        let large   = default();
+        Self {cell_size,entries,cells,large}
+    }
+
+    /// The number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check whether the grid has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The bounding box of the entry.
+    pub fn get(&self, key:K) -> Option<BoundingBox> {
+        self.entries.get(&key).map(|(bounds,_)| *bounds)
+    }
+
+    /// Insert a new entry or update the bounding box of an existing one.
+    pub fn insert(&mut self, key:K, bounds:BoundingBox) {
+        let range = CellRange::new(&bounds,self.cell_size);
+        let range = (range.cell_count() <= MAX_CELLS_PER_ENTRY).as_some(range);
+        if let Some((old_bounds,old_range)) = self.entries.get_mut(&key) {
+            *old_bounds = bounds;
+            if *old_range == range {
+                return
+            }
+        }
+        self.unregister(key);
+        match range {
+            None        => { self.large.insert(key); }
+            Some(range) => for cell in range.cells() {
+                self.cells.entry(cell).or_default().insert(key);
+            }
+        }
+        self.entries.insert(key,(bounds,range));
+    }
+
+    /// Remove the entry. Returns its bounding box if it was present.
+    pub fn remove(&mut self, key:K) -> Option<BoundingBox> {
+        let bounds = self.get(key);
+        self.unregister(key);
+        self.entries.remove(&key);
+        bounds
+    }
+
+    /// Remove the entry from the cells, leaving it in the entries map.
+    fn unregister(&mut self, key:K) {
+        match self.entries.get(&key) {
+            None                  => {}
+            Some((_,None))        => { self.large.remove(&key); }
+            Some((_,Some(range))) => for cell in range.cells() {
+                if let Some(keys) = self.cells.get_mut(&cell) {
+                    keys.remove(&key);
+                    if keys.is_empty() {
+                        self.cells.remove(&cell);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Keys of all entries intersecting the area, sorted.
+    pub fn query(&self, area:&BoundingBox) -> Vec<K> {
+        let range      = CellRange::new(area,self.cell_size);
+        let candidates = if range.cell_count() <= self.cells.len() as i64 {
+            let cells = range.cells().filter_map(|cell| self.cells.get(&cell));
+            cells.flatten().chain(&self.large).copied().collect::<HashSet<_>>()
+        } else {
+            self.entries.keys().copied().collect()
+        };
+        let mut keys = candidates.into_iter().filter(|key| {
+            self.get(*key).map_or(false,|bounds| bounds.intersects(area))
+        }).collect_vec();
+        keys.sort();
+        keys
+    }
+
+    /// Keys of all entries containing the point, sorted.
+    pub fn query_point(&self, point:Vector2<f32>) -> Vec<K> {
+        self.query(&BoundingBox::from_corners(point,point))
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bbox(x:f32, y:f32, size:f32) -> BoundingBox {
+        BoundingBox::from_center_and_size(Vector2(x,y),Vector2(size,size))
+    }
+
+    #[test]
+    fn querying_grid() {
+        let mut grid = SpatialGrid::new(10.0);
+        grid.insert(1,bbox(5.0,5.0,4.0));
+        grid.insert(2,bbox(25.0,5.0,4.0));
+        grid.insert(3,bbox(0.0,0.0,30.0));
+        grid.insert(4,bbox(0.0,0.0,1000.0));
+        assert_eq!(grid.len(), 4);
+        assert_eq!(grid.query_point(Vector2(5.0,5.0)), vec![1,3,4]);
+        assert_eq!(grid.query_point(Vector2(25.0,5.0)), vec![2,4]);
+        assert_eq!(grid.query(&bbox(20.0,0.0,20.0)), vec![2,3,4]);
+        assert_eq!(grid.query(&bbox(0.0,0.0,2000.0)), vec![1,2,3,4]);
+
+        grid.insert(1,bbox(6.0,6.0,4.0));
+        grid.insert(2,bbox(-25.0,-5.0,4.0));
+        assert_eq!(grid.query_point(Vector2(25.0,5.0)), vec![4]);
+        assert_eq!(grid.query_point(Vector2(-25.0,-5.0)), vec![2,4]);
+        assert_eq!(grid.query_point(Vector2(7.0,7.0)), vec![1,3,4]);
+
+        assert_eq!(grid.remove(4), Some(bbox(0.0,0.0,1000.0)));
+        assert_eq!(grid.remove(4), None);
+        grid.remove(2);
+        assert_eq!(grid.query(&bbox(0.0,0.0,2000.0)), vec![1,3]);
+        grid.remove(1);
+        grid.remove(3);
+        assert!(grid.is_empty());
+        assert!(grid.cells.is_empty());
+    }
+}
@@ -0,0 +1,282 @@
+//! Bounded time series of the values measured by the performance monitor, and their export for
+//! offline analysis.
+
+use crate::prelude::*;
+
+use serde_json::json;
+use std::collections::VecDeque;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The default number of samples kept in a time series. It is a minute of history at 60 FPS.
+pub const DEFAULT_CAPACITY : usize = 3600;
+
+
+
+// ==============
+// === Sample ===
+// ==============
+
+/// A value measured at the given time, in milliseconds.
+#[derive(Clone,Copy,Debug,PartialEq)]
+#[allow(missing_docs)]
+pub struct Sample {
+    pub time  : f64,
+    pub value : f64,
+}
+
+
+
+// ===================
+// === Percentiles ===
+// ===================
+
+/// The percentiles of values of a time series.
+#[derive(Clone,Copy,Debug,PartialEq)]
+#[allow(missing_docs)]
+pub struct Percentiles {
+    pub p50 : f64,
+    pub p95 : f64,
+    pub p99 : f64,
+}
+
+
+
+// ==================
+// === TimeSeries ===
+// ==================
+
+/// A time series keeping only the given number of the newest samples.
+#[derive(Clone,Debug)]
+pub struct TimeSeries {
+    capacity : usize,
+    samples  : VecDeque<Sample>,
+}
+
+impl Default for TimeSeries {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl TimeSeries {
+    /// Constructor of a time series keeping at most `capacity` samples.
+    pub fn new(capacity:usize) -> Self {
+        let samples = default();
+        Self {capacity,samples}
+    }
+
+    /// The maximum number of kept samples.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Change the maximum number of kept samples, dropping the oldest ones if needed.
+    pub fn set_capacity(&mut self, capacity:usize) {
+        self.capacity = capacity;
+        self.truncate();
+    }
+
+    /// The number of samples.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Check whether there are no samples.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Add a sample, dropping the oldest one if the capacity is exceeded.
+    pub fn push(&mut self, time:f64, value:f64) {
+        self.samples.push_back(Sample {time,value});
+        self.truncate();
+    }
+
+    /// Remove all samples.
+    pub fn clear(&mut self) {
+        self.samples.clear()
+    }
+
+    /// The samples, from the oldest to the newest.
+    pub fn iter(&self) -> impl Iterator<Item=&Sample> {
+        self.samples.iter()
+    }
+
+    /// The newest sample.
+    pub fn last(&self) -> Option<Sample> {
+        self.samples.back().copied()
+    }
+
+    /// The nearest-rank percentile of the values, where `percent` is in the range 0..100. Returns
+    /// `None` if there are no samples.
+    pub fn percentile(&self, percent:f64) -> Option<f64> {
+        percentile_of_sorted(&self.sorted_values(),percent)
+    }
+
+    /// The 50th, 95th, and 99th percentile of the values. Returns `None` if there are no samples.
+    pub fn percentiles(&self) -> Option<Percentiles> {
+        let values = self.sorted_values();
+        let p50 = percentile_of_sorted(&values,50.0)?;
+        let p95 = percentile_of_sorted(&values,95.0)?;
+        let p99 = percentile_of_sorted(&values,99.0)?;
+        Some(Percentiles {p50,p95,p99})
+    }
+
+    fn sorted_values(&self) -> Vec<f64> {
+        let mut values = self.samples.iter().map(|sample| sample.value).collect_vec();
+        values.sort_by(|a,b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        values
+    }
+
+    fn truncate(&mut self) {
+        while self.samples.len() > self.capacity {
+            self.samples.pop_front();
+        }
+    }
+}
+
+fn percentile_of_sorted(values:&[f64], percent:f64) -> Option<f64> {
+    let count = values.len();
+    let rank  = (percent * count as f64 / 100.0).ceil() as usize;
+    let index = rank.max(1).min(count).checked_sub(1)?;
+    values.get(index).copied()
+}
+
+
+
+// ===============
+// === History ===
+// ===============
+
+/// A time series of a metric, labeled with the metric name.
+#[derive(Clone,Debug)]
+#[allow(missing_docs)]
+pub struct Metric {
+    pub label  : String,
+    pub series : TimeSeries,
+}
+
+/// The recorded time series of several metrics, which can be exported as JSON or in the Chrome
+/// trace format.
+#[derive(Clone,Debug,Default)]
+pub struct History {
+    metrics : Vec<Metric>,
+}
+
+impl History {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Add the time series of a metric.
+    pub fn add(&mut self, label:impl Into<String>, series:TimeSeries) {
+        let label = label.into();
+        self.metrics.push(Metric {label,series});
+    }
+
+    /// All metrics, in the order they were added.
+    pub fn metrics(&self) -> &[Metric] {
+        &self.metrics
+    }
+
+    /// Find the metric with the given label.
+    pub fn metric(&self, label:&str) -> Option<&Metric> {
+        self.metrics.iter().find(|metric| metric.label == label)
+    }
+
+    /// Export the history as JSON. Every metric is exported with its label, percentiles, and
+    /// samples given as `[time,value]` pairs.
+    pub fn to_json(&self) -> String {
+        let metrics = self.metrics.iter().map(|metric| {
+            let percentiles = metric.series.percentiles().map(|p| {
+                json!({"p50":p.p50,"p95":p.p95,"p99":p.p99})
+            });
+            let samples = metric.series.iter().map(|s| json!([s.time,s.value])).collect_vec();
+            json!({"label":metric.label,"percentiles":percentiles,"samples":samples})
+        }).collect_vec();
+        json!({"metrics":metrics}).to_string()
+    }
+
+    /// Export the history in the Chrome trace event format, which can be loaded in the Chrome
+    /// DevTools performance tab or at `chrome://tracing`. Every sample is exported as a counter
+    /// event.
+    pub fn to_chrome_trace(&self) -> String {
+        let events = self.metrics.iter().flat_map(|metric| {
+            metric.series.iter().map(move |sample| {
+                let ts = sample.time * 1000.0;
+                json!({"name":metric.label,"ph":"C","ts":ts,"pid":1,"tid":1,
+                    "args":{"value":sample.value}})
+            })
+        }).collect_vec();
+        json!({"traceEvents":events,"displayTimeUnit":"ms"}).to_string()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::Value;
+
+    fn series(values:impl IntoIterator<Item=usize>) -> TimeSeries {
+        let mut series = TimeSeries::new(100);
+        for value in values {
+            series.push(value as f64 * 10.0, value as f64);
+        }
+        series
+    }
+
+    #[test]
+    fn time_series_is_bounded() {
+        let mut series = series(1..=150);
+        assert_eq!(series.len(),100);
+        assert_eq!(series.iter().next().map(|s| s.value),Some(51.0));
+        assert_eq!(series.last(),Some(Sample {time:1500.0,value:150.0}));
+        series.set_capacity(10);
+        assert_eq!(series.len(),10);
+        assert_eq!(series.iter().next().map(|s| s.value),Some(141.0));
+    }
+
+    #[test]
+    fn percentiles() {
+        let expected = Percentiles {p50:50.0,p95:95.0,p99:99.0};
+        assert_eq!(series((1..=100).rev()).percentiles(),Some(expected));
+        assert_eq!(series(vec![7]).percentiles(),Some(Percentiles {p50:7.0,p95:7.0,p99:7.0}));
+        assert_eq!(series(vec![1,2,3,4]).percentile(0.0),Some(1.0));
+        assert_eq!(series(vec![]).percentiles(),None);
+    }
+
+    #[test]
+    fn export() {
+        let mut history = History::new();
+        history.add("Frame time (ms)",series(vec![1,2]));
+        history.add("Graph nodes",series(vec![]));
+
+        let json : Value = serde_json::from_str(&history.to_json()).unwrap();
+        let metrics      = &json["metrics"];
+        assert_eq!(metrics[0]["label"],"Frame time (ms)");
+        assert_eq!(metrics[0]["percentiles"]["p50"],1.0);
+        assert_eq!(metrics[0]["samples"][1],json!([20.0,2.0]));
+        assert_eq!(metrics[1]["percentiles"],Value::Null);
+
+        let trace : Value = serde_json::from_str(&history.to_chrome_trace()).unwrap();
+        let events        = trace["traceEvents"].as_array().unwrap();
+        assert_eq!(events.len(),2);
+        assert_eq!(events[1]["name"],"Frame time (ms)");
+        assert_eq!(events[1]["ph"],"C");
+        assert_eq!(events[1]["ts"],20_000.0);
+        assert_eq!(events[1]["args"]["value"],2.0);
+    }
+}
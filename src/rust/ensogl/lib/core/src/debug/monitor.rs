@@ -2,6 +2,10 @@
 
 use crate::prelude::*;
 
+use crate::debug::history;
+use crate::debug::history::History;
+use crate::debug::history::Percentiles;
+use crate::debug::history::TimeSeries;
 use crate::debug::stats::Stats;
 use crate::system::web;
 use crate::system::web::StyleSetter;
@@ -39,6 +43,7 @@ pub struct ConfigTemplate<Str,Num> {
     pub panel_height          : Num,
     pub labels_width          : Num,
     pub results_width         : Num,
+    pub percentiles_width     : Num,
     pub plots_width           : Num,
     pub font_size             : Num,
     pub font_vertical_offset  : Num,
@@ -67,6 +72,7 @@ fn dark_theme() -> Config {
         panel_height          : 15,
         labels_width          : 130,
         results_width         : 30,
+        percentiles_width     : 120,
         plots_width           : 100,
         font_size             : 9,
         font_vertical_offset  : 4,
@@ -90,6 +96,7 @@ fn light_theme() -> Config {
         panel_height          : 15,
         labels_width          : 130,
         results_width         : 30,
+        percentiles_width     : 120,
         plots_width           : 100,
         font_size             : 9,
         font_vertical_offset  : 4,
@@ -123,6 +130,7 @@ impl Config {
             panel_height          : self.panel_height         as f64 * ratio,
             labels_width          : self.labels_width         as f64 * ratio,
             results_width         : self.results_width        as f64 * ratio,
+            percentiles_width     : self.percentiles_width    as f64 * ratio,
             plots_width           : self.plots_width          as f64 * ratio,
             font_size             : self.font_size            as f64 * ratio,
             font_vertical_offset  : self.font_vertical_offset as f64 * ratio,
@@ -199,16 +207,18 @@ impl Drop for DomData {
 // === Monitor ===
 // ===============
 
-/// Implementation of the monitoring panel.
+/// Implementation of the monitoring panel. The values measured by the panels are recorded in
+/// bounded time series, which can be exported with [`Monitor::history`].
 #[derive(Debug)]
 pub struct Monitor {
-    user_config : Config,
-    config      : SamplerConfig,
-    width       : f64,
-    height      : f64,
-    dom         : Option<Dom>,
-    panels      : Vec<Panel>,
-    first_draw  : bool,
+    user_config      : Config,
+    config           : SamplerConfig,
+    width            : f64,
+    height           : f64,
+    dom              : Option<Dom>,
+    panels           : Vec<Panel>,
+    first_draw       : bool,
+    history_capacity : usize,
 }
 
 
@@ -216,14 +226,16 @@ pub struct Monitor {
 
 impl Default for Monitor {
     fn default() -> Self {
-        let user_config = Config::default();
-        let panels      = default();
-        let width       = default();
-        let height      = default();
-        let first_draw  = true;
-        let config      = user_config.to_js_config();
-        let dom         = None;
-        let mut out     = Self {user_config,config,width,height,dom,panels,first_draw};
+        let user_config      = Config::default();
+        let panels           = default();
+        let width            = default();
+        let height           = default();
+        let first_draw       = true;
+        let config           = user_config.to_js_config();
+        let dom              = None;
+        let history_capacity = history::DEFAULT_CAPACITY;
+        let mut out          = Self {user_config,config,width,height,dom,panels,first_draw
+                                    ,history_capacity};
         out.update_config();
         out
     }
@@ -242,11 +254,31 @@ impl Monitor {
     /// Add new display element.
     pub fn add<M:Sampler+'static>(&mut self, monitor:M) -> Panel {
         let panel = Panel::new(self.config.clone(),monitor);
+        panel.set_history_capacity(self.history_capacity);
         self.panels.push(panel.clone());
+        // Resizing clears the canvas, so the background has to be drawn again.
+        self.first_draw = true;
         self.resize();
         panel
     }
 
+    /// Set the maximum number of samples recorded by every panel.
+    pub fn set_history_capacity(&mut self, capacity:usize) {
+        self.history_capacity = capacity;
+        for panel in &self.panels {
+            panel.set_history_capacity(capacity);
+        }
+    }
+
+    /// The values recorded by all panels, labeled with the panel labels.
+    pub fn history(&self) -> History {
+        let mut history = History::new();
+        for panel in &self.panels {
+            history.add(panel.label(),panel.history());
+        }
+        history
+    }
+
     /// Check whether the mointor is visible.
     pub fn visible(&self) -> bool {
         self.dom.is_some()
@@ -302,8 +334,9 @@ impl Monitor {
             let ratio = web::window().device_pixel_ratio();
             let width = self.config.labels_width
                 + self.config.results_width
+                + self.config.percentiles_width
                 + self.config.plots_width
-                + 4.0 * self.config.margin
+                + 5.0 * self.config.margin
                 + self.config.outer_margin; // no outer_margin on the left side.
             let mut height = self.config.outer_margin;
             for _panel in &self.panels {
@@ -332,7 +365,8 @@ impl Monitor {
 
     fn clear_labels_area(&mut self, dom:&Dom) {
         let step  = self.config.plot_step_size;
-        let width = self.config.labels_width + self.config.results_width + 3.0 * self.config.margin;
+        let width = self.config.labels_width + self.config.results_width
+            + self.config.percentiles_width + 4.0 * self.config.margin;
         dom.context.set_fill_style(&self.config.background_color);
         dom.context.fill_rect(0.0,0.0,width,self.height);
         dom.context.fill_rect(self.width-step,0.0,step,self.height);
@@ -401,6 +435,26 @@ impl Panel {
         self.rc.borrow_mut().end()
     }
 
+    /// The label of the panel.
+    pub fn label(&self) -> String {
+        self.rc.borrow().label.clone()
+    }
+
+    /// The values measured so far, limited to the history capacity.
+    pub fn history(&self) -> TimeSeries {
+        self.rc.borrow().history.clone()
+    }
+
+    /// The percentiles of the values measured so far. Returns `None` if nothing was measured yet.
+    pub fn percentiles(&self) -> Option<Percentiles> {
+        self.rc.borrow().history.percentiles()
+    }
+
+    /// Set the maximum number of recorded values.
+    pub fn set_history_capacity(&self, capacity:usize) {
+        self.rc.borrow_mut().history.set_capacity(capacity)
+    }
+
     fn first_draw(&self, dom:&Dom) {
         self.rc.borrow_mut().first_draw(dom)
     }
@@ -499,6 +553,10 @@ pub trait Sampler: Debug {
 // === PanelData ===
 // =================
 
+/// The number of measurements after which the percentiles displayed in the panel are recomputed.
+/// Computing them requires sorting the whole history, so it is not done every frame.
+const PERCENTILES_REFRESH_PERIOD : usize = 30;
+
 /// A `Panel` is a single row in the monitor view.
 #[derive(Debug)]
 pub struct PanelData {
@@ -514,6 +572,9 @@ pub struct PanelData {
     draw_offset : f64,
     value_check : ValueCheck,
     precision   : usize,
+    history     : TimeSeries,
+    percentiles : Option<Percentiles>,
+    refresh_age : usize,
     sampler     : Box<dyn Sampler>
 }
 
@@ -536,8 +597,12 @@ impl PanelData {
         let value_check = default();
         let sampler     = Box::new(sampler);
         let precision   = sampler.precision();
+        let history     = default();
+        let percentiles = default();
+        let refresh_age = default();
         Self {label,performance,config,min_value,max_value,begin_value,value,last_values
-             ,norm_value,draw_offset,value_check,precision,sampler}
+             ,norm_value,draw_offset,value_check,precision,history,percentiles,refresh_age
+             ,sampler}
     }
 }
 
@@ -557,11 +622,22 @@ impl PanelData {
         self.sampler.end(time);
         self.value_check = self.sampler.check();
         self.value       = self.sampler.value();
+        self.history.push(time,self.value);
+        self.refresh_percentiles();
         self.clamp_value();
         self.smooth_value();
         self.normalize_value();
     }
 
+    /// Recompute the displayed percentiles if `PERCENTILES_REFRESH_PERIOD` measurements were made
+    /// since they were last computed.
+    fn refresh_percentiles(&mut self) {
+        if self.refresh_age == 0 {
+            self.percentiles = self.history.percentiles();
+        }
+        self.refresh_age = (self.refresh_age + 1) % PERCENTILES_REFRESH_PERIOD;
+    }
+
     /// Clamp the measured values to the `max_value` and `min_value`.
     fn clamp_value(&mut self) {
         if let Some(max_value) = self.sampler.max_value() {
@@ -627,6 +703,7 @@ impl PanelData {
         self.move_pen_to_next_element(dom,self.config.margin);
         self.draw_labels(dom);
         self.draw_results(dom);
+        self.draw_percentiles(dom);
     }
 
     fn draw_labels(&mut self, dom:&Dom) {
@@ -652,6 +729,20 @@ impl PanelData {
         self.move_pen_to_next_element(dom,self.config.results_width + self.config.margin);
     }
 
+    fn draw_percentiles(&mut self, dom:&Dom) {
+        if let Some(percentiles) = self.percentiles {
+            let precision = self.precision;
+            let Percentiles {p50,p95,p99} = percentiles;
+            let text  = format!("p50 {1:.0$}  p95 {2:.0$}  p99 {3:.0$}",precision,p50,p95,p99);
+            let fonts = "Helvetica,Arial,sans-serif";
+            let y_pos = self.config.panel_height - self.config.font_vertical_offset;
+            dom.context.set_font(&format!("{}px {}",self.config.font_size,fonts));
+            dom.context.set_fill_style(&self.config.label_color_ok);
+            dom.context.fill_text(&text,self.config.percentiles_width,y_pos).unwrap();
+        }
+        self.move_pen_to_next_element(dom,self.config.percentiles_width + self.config.margin);
+    }
+
     fn draw_plots(&mut self, dom:&Dom) {
         self.move_pen_to_next_element(dom,self.config.plots_width - self.config.plot_step_size);
         dom.context.set_fill_style(&self.config.plot_background_color);
@@ -782,6 +873,64 @@ impl Sampler for WasmMemory {
 
 
 
+// =============
+// === Gauge ===
+// =============
+
+/// Sampler displaying a value set by its user. It allows components to show their own metrics in
+/// the monitor, like the number of graph nodes or pending requests. The gauge is cheap to clone,
+/// and all clones share the value.
+#[derive(Clone,Debug)]
+pub struct Gauge {
+    data : Rc<GaugeData>,
+}
+
+#[derive(Debug)]
+struct GaugeData {
+    label      : String,
+    value      : Cell<f64>,
+    thresholds : Cell<Option<(f64,f64)>>,
+}
+
+impl Gauge {
+    /// Constructor.
+    pub fn new(label:impl Into<String>) -> Self {
+        let label      = label.into();
+        let value      = default();
+        let thresholds = default();
+        let data       = Rc::new(GaugeData {label,value,thresholds});
+        Self {data}
+    }
+
+    /// Set the displayed value.
+    pub fn set(&self, value:f64) {
+        self.data.value.set(value)
+    }
+
+    /// The displayed value.
+    pub fn get(&self) -> f64 {
+        self.data.value.get()
+    }
+
+    /// Display the value as a warning or an error when it exceeds the thresholds. See
+    /// [`ValueCheck::from_threshold`] to learn more.
+    pub fn set_thresholds(&self, warn_threshold:f64, err_threshold:f64) {
+        self.data.thresholds.set(Some((warn_threshold,err_threshold)))
+    }
+}
+
+impl Sampler for Gauge {
+    fn label     (&self) -> &str  { &self.data.label }
+    fn value     (&self) -> f64   { self.get() }
+    fn precision (&self) -> usize { 0 }
+    fn check     (&self) -> ValueCheck {
+        let thresholds = self.data.thresholds.get();
+        thresholds.map_or(ValueCheck::Correct, |(warn,err)| self.check_by_threshold(warn,err))
+    }
+}
+
+
+
 // ======================
 // === Stats Samplers ===
 // ======================
@@ -826,5 +975,7 @@ stats_sampler!("Data upload size (Mb)"  , DataUploadSize     , data_upload_size
 stats_sampler!("Sprite system count"    , SpriteSystemCount  , sprite_system_count  , 100.0     , 500.0     , 0 , 1.0);
 stats_sampler!("Symbol count"           , SymbolCount        , symbol_count         , 100.0     , 500.0     , 0 , 1.0);
 stats_sampler!("Sprite count"           , SpriteCount        , sprite_count         , 100_000.0 , 500_000.0 , 0 , 1.0);
+stats_sampler!("Drawn sprite count"     , DrawnSpriteCount   , drawn_sprite_count   , 100_000.0 , 500_000.0 , 0 , 1.0);
+stats_sampler!("Culled sprite count"    , CulledSpriteCount  , culled_sprite_count  , 100_000.0 , 500_000.0 , 0 , 1.0);
 stats_sampler!("Shader count"           , ShaderCount        , shader_count         , 100.0     , 500.0     , 0 , 1.0);
 stats_sampler!("Shader compile count"   , ShaderCompileCount , shader_compile_count , 10.0      , 100.0     , 0 , 1.0);
@@ -78,6 +78,8 @@ gen_stats!{
     data_upload_size     : u32,
     sprite_system_count  : usize,
     sprite_count         : usize,
+    drawn_sprite_count   : usize,
+    culled_sprite_count  : usize,
     symbol_count         : usize,
     mesh_count           : usize,
     shader_count         : usize,
@@ -3,6 +3,7 @@
 
 use crate::prelude::*;
 
+use crate::data::BoundingBox;
 use crate::data::dirty;
 use crate::display;
 use crate::display::scene::Scene;
@@ -456,6 +457,25 @@ impl Camera2d {
     pub fn view_projection_matrix(&self) -> Matrix4<f32> {
         self.data.borrow().matrix.view_projection
     }
+
+    /// The area of the `z = 0` plane visible by the camera, in world coordinates. Objects placed
+    /// at other depths are visible in a slightly different area, which can be ignored for typical
+    /// 2D scenes.
+    pub fn visible_area(&self) -> BoundingBox {
+        let matrix    = self.inversed_view_projection_matrix();
+        let unproject = |x:f32, y:f32, z:f32| {
+            let point = matrix * Vector4(x,y,z,1.0);
+            point.xyz() / point.w
+        };
+        let corners = [(-1.0,-1.0),(-1.0,1.0),(1.0,-1.0),(1.0,1.0)];
+        let points  = corners.iter().map(|&(x,y)| {
+            let near = unproject(x,y,-1.0);
+            let far  = unproject(x,y,1.0);
+            let t    = near.z / (near.z - far.z);
+            (near + (far - near) * t).xy()
+        });
+        BoundingBox::from_points(points).unwrap_or_default()
+    }
 }
 
 
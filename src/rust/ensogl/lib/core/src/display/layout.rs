@@ -1,5 +1,7 @@
 //! Module containing utilities for specifying layout.
 
 pub mod alignment;
+pub mod stack;
 
 pub use alignment::Alignment;
+pub use stack::Stack;
@@ -0,0 +1,365 @@
+//! Stack layout, placing items one after another along a horizontal or vertical axis, with
+//! padding, gaps between items, alignment, and grow / shrink factors.
+//!
+//! This module contains the layout math only. To learn how stacks are attached to display objects,
+//! see the docs of `ObjectOps::set_layout`. All placements are expressed in the coordinate space of
+//! the stack, which origin is in the center of the stack, with the Y axis pointing up.
+
+use crate::prelude::*;
+
+use super::alignment;
+use super::alignment::Alignment;
+
+
+
+// =================
+// === Direction ===
+// =================
+
+/// The axis along which the items of a stack are placed. Horizontal stacks place items from left
+/// to right, vertical ones from top to bottom.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+#[allow(missing_docs)]
+pub enum Direction {Horizontal,Vertical}
+
+impl Default for Direction {
+    fn default() -> Self {
+        Self::Horizontal
+    }
+}
+
+impl Direction {
+    /// The component of the vector along the main axis.
+    pub fn main(self, v:Vector2<f32>) -> f32 {
+        match self {
+            Self::Horizontal => v.x,
+            Self::Vertical   => v.y,
+        }
+    }
+
+    /// The component of the vector along the cross axis.
+    pub fn cross(self, v:Vector2<f32>) -> f32 {
+        match self {
+            Self::Horizontal => v.y,
+            Self::Vertical   => v.x,
+        }
+    }
+
+    /// Create a vector from its main and cross axis components.
+    pub fn vector(self, main:f32, cross:f32) -> Vector2<f32> {
+        match self {
+            Self::Horizontal => Vector2(main,cross),
+            Self::Vertical   => Vector2(cross,main),
+        }
+    }
+}
+
+
+
+// ===============
+// === Padding ===
+// ===============
+
+/// Space between the borders of a stack and its items.
+#[derive(Clone,Copy,Debug,Default,PartialEq)]
+#[allow(missing_docs)]
+pub struct Padding {
+    pub left   : f32,
+    pub right  : f32,
+    pub top    : f32,
+    pub bottom : f32,
+}
+
+impl Padding {
+    /// Constructor.
+    pub fn new(left:f32, right:f32, top:f32, bottom:f32) -> Self {
+        Self {left,right,top,bottom}
+    }
+
+    /// The same padding on every side.
+    pub fn all(value:f32) -> Self {
+        Self::new(value,value,value,value)
+    }
+
+    /// The `horizontal` padding on the left and right side and the `vertical` one on the top and
+    /// bottom side.
+    pub fn symmetric(horizontal:f32, vertical:f32) -> Self {
+        Self::new(horizontal,horizontal,vertical,vertical)
+    }
+
+    /// The total padding along both axes.
+    pub fn size(&self) -> Vector2<f32> {
+        Vector2(self.left + self.right, self.top + self.bottom)
+    }
+}
+
+
+
+// ============
+// === Item ===
+// ============
+
+/// An item placed by a stack.
+///
+/// When the main axis size of a stack is bigger than the sum of its items' sizes, the free space is
+/// distributed between items proportionally to their `grow` factors. When it is smaller, items are
+/// shrunk proportionally to their `shrink` factors multiplied by their sizes, but never below 0.
+#[derive(Clone,Copy,Debug,Default,PartialEq)]
+pub struct Item {
+    /// The natural size of the item.
+    pub size   : Vector2<f32>,
+    /// The grow factor. Items with 0 factor do not grow.
+    pub grow   : f32,
+    /// The shrink factor. Items with 0 factor do not shrink.
+    pub shrink : f32,
+}
+
+impl Item {
+    /// Constructor of an item which neither grows nor shrinks.
+    pub fn new(size:Vector2<f32>) -> Self {
+        Self {size,..default()}
+    }
+}
+
+
+
+// =================
+// === Placement ===
+// =================
+
+/// The computed box of an item.
+#[derive(Clone,Copy,Debug,Default,PartialEq)]
+pub struct Placement {
+    /// The center of the item.
+    pub position : Vector2<f32>,
+    /// The size of the item after growing or shrinking.
+    pub size     : Vector2<f32>,
+}
+
+
+
+// =============
+// === Stack ===
+// =============
+
+/// A stack layout definition. See the module docs to learn more.
+///
+/// The `alignment` describes both how the items are placed along the main axis, when there is free
+/// space left after growing them, and how they are placed along the cross axis. The stack sizes
+/// which are not fixed by `width` and `height` are computed from the sizes of the items.
+#[derive(Clone,Copy,Debug,Default,PartialEq)]
+#[allow(missing_docs)]
+pub struct Stack {
+    pub direction : Direction,
+    pub padding   : Padding,
+    pub gap       : f32,
+    pub alignment : Alignment,
+    pub width     : Option<f32>,
+    pub height    : Option<f32>,
+}
+
+
+// === Constructors ===
+
+impl Stack {
+    /// A horizontal stack, with items vertically centered.
+    pub fn horizontal() -> Self {
+        let direction = Direction::Horizontal;
+        let alignment = Alignment::center_left();
+        Self {direction,alignment,..default()}
+    }
+
+    /// A vertical stack, with items aligned to the left.
+    pub fn vertical() -> Self {
+        let direction = Direction::Vertical;
+        let alignment = Alignment::top_left();
+        Self {direction,alignment,..default()}
+    }
+
+    /// Set the padding.
+    pub fn with_padding(self, padding:Padding) -> Self {
+        Self {padding,..self}
+    }
+
+    /// Set the gap between items.
+    pub fn with_gap(self, gap:f32) -> Self {
+        Self {gap,..self}
+    }
+
+    /// Set the alignment.
+    pub fn with_alignment(self, alignment:Alignment) -> Self {
+        Self {alignment,..self}
+    }
+
+    /// Fix the width of the stack.
+    pub fn with_width(self, width:f32) -> Self {
+        Self {width:Some(width),..self}
+    }
+
+    /// Fix the height of the stack.
+    pub fn with_height(self, height:f32) -> Self {
+        Self {height:Some(height),..self}
+    }
+}
+
+
+// === Layout ===
+
+impl Stack {
+    /// The natural size of the stack with the given items.
+    pub fn measure(&self, items:&[Item]) -> Vector2<f32> {
+        let dir     = self.direction;
+        let main    = items.iter().map(|item| dir.main(item.size)).sum::<f32>() + self.gaps(items);
+        let cross   = items.iter().map(|item| dir.cross(item.size)).fold(0.0,f32::max);
+        let content = dir.vector(main,cross) + self.padding.size();
+        Vector2(self.width.unwrap_or(content.x),self.height.unwrap_or(content.y))
+    }
+
+    /// Place the items in a stack of the given size. The placements are returned in the order of
+    /// the items.
+    pub fn arrange(&self, size:Vector2<f32>, items:&[Item]) -> Vec<Placement> {
+        let dir       = self.direction;
+        let inner     = size - self.padding.size();
+        let available = dir.main(inner) - self.gaps(items);
+        let mut sizes = items.iter().map(|item| dir.main(item.size)).collect_vec();
+        let free      = available - sizes.iter().sum::<f32>();
+        let grow      = items.iter().map(|item| item.grow).sum::<f32>();
+        let shrink    = items.iter().map(|item| item.shrink * dir.main(item.size)).sum::<f32>();
+        if free > 0.0 && grow > 0.0 {
+            for (size,item) in sizes.iter_mut().zip(items) {
+                *size += free * item.grow / grow;
+            }
+        } else if free < 0.0 && shrink > 0.0 {
+            for (size,item) in sizes.iter_mut().zip(items) {
+                *size = (*size + free * item.shrink * *size / shrink).max(0.0);
+            }
+        }
+        let left_space = (available - sizes.iter().sum::<f32>()).max(0.0);
+        let mut offset = left_space * self.main_alignment_factor();
+
+        let left   = -size.x / 2.0 + self.padding.left;
+        let right  =  size.x / 2.0 - self.padding.right;
+        let top    =  size.y / 2.0 - self.padding.top;
+        let bottom = -size.y / 2.0 + self.padding.bottom;
+        items.iter().zip(sizes).map(|(item,main_size)| {
+            let cross_size = dir.cross(item.size);
+            let size       = dir.vector(main_size,cross_size);
+            let main       = offset + main_size / 2.0;
+            offset        += main_size + self.gap;
+            let position   = match dir {
+                Direction::Horizontal => {
+                    let x = left + main;
+                    let y = match self.alignment.vertical {
+                        alignment::Vertical::Top    => top - cross_size / 2.0,
+                        alignment::Vertical::Center => (top + bottom) / 2.0,
+                        alignment::Vertical::Bottom => bottom + cross_size / 2.0,
+                    };
+                    Vector2(x,y)
+                }
+                Direction::Vertical => {
+                    let y = top - main;
+                    let x = match self.alignment.horizontal {
+                        alignment::Horizontal::Left   => left + cross_size / 2.0,
+                        alignment::Horizontal::Center => (left + right) / 2.0,
+                        alignment::Horizontal::Right  => right - cross_size / 2.0,
+                    };
+                    Vector2(x,y)
+                }
+            };
+            Placement {position,size}
+        }).collect()
+    }
+
+    /// The total size of gaps between the items.
+    fn gaps(&self, items:&[Item]) -> f32 {
+        self.gap * items.len().saturating_sub(1) as f32
+    }
+
+    /// The part of the free space placed before the items along the main axis.
+    fn main_alignment_factor(&self) -> f32 {
+        match self.direction {
+            Direction::Horizontal => match self.alignment.horizontal {
+                alignment::Horizontal::Left   => 0.0,
+                alignment::Horizontal::Center => 0.5,
+                alignment::Horizontal::Right  => 1.0,
+            },
+            Direction::Vertical => match self.alignment.vertical {
+                alignment::Vertical::Top    => 0.0,
+                alignment::Vertical::Center => 0.5,
+                alignment::Vertical::Bottom => 1.0,
+            },
+        }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(width:f32, height:f32) -> Item {
+        Item::new(Vector2(width,height))
+    }
+
+    fn placement(x:f32, y:f32, width:f32, height:f32) -> Placement {
+        Placement {position:Vector2(x,y), size:Vector2(width,height)}
+    }
+
+    #[test]
+    fn measuring_stacks() {
+        let items = [item(10.0,4.0),item(20.0,8.0),item(5.0,2.0)];
+        let stack = Stack::horizontal().with_gap(2.0).with_padding(Padding::symmetric(1.0,3.0));
+        assert_eq!(stack.measure(&items), Vector2(41.0,14.0));
+        assert_eq!(stack.measure(&[]), Vector2(2.0,6.0));
+        let stack = Stack::vertical().with_gap(2.0).with_width(100.0);
+        assert_eq!(stack.measure(&items), Vector2(100.0,18.0));
+    }
+
+    #[test]
+    fn arranging_horizontal_stacks() {
+        let items = [item(10.0,4.0),item(20.0,8.0)];
+        let stack = Stack::horizontal().with_gap(2.0).with_padding(Padding::all(1.0));
+        let size  = stack.measure(&items);
+        assert_eq!(size, Vector2(34.0,10.0));
+        let expected = vec![placement(-11.0,0.0,10.0,4.0),placement(6.0,0.0,20.0,8.0)];
+        assert_eq!(stack.arrange(size,&items), expected);
+
+        let stack    = stack.with_alignment(Alignment::bottom_right());
+        let expected = vec![placement(-1.0,-2.0,10.0,4.0),placement(16.0,0.0,20.0,8.0)];
+        assert_eq!(stack.arrange(Vector2(54.0,10.0),&items), expected);
+    }
+
+    #[test]
+    fn arranging_vertical_stacks() {
+        let items    = [item(10.0,4.0),item(20.0,8.0)];
+        let stack    = Stack::vertical().with_gap(2.0);
+        let size     = Vector2(30.0,20.0);
+        let expected = vec![placement(-10.0,8.0,10.0,4.0),placement(-5.0,0.0,20.0,8.0)];
+        assert_eq!(stack.arrange(size,&items), expected);
+
+        let stack    = stack.with_alignment(Alignment::center());
+        let expected = vec![placement(0.0,5.0,10.0,4.0),placement(0.0,-3.0,20.0,8.0)];
+        assert_eq!(stack.arrange(size,&items), expected);
+    }
+
+    #[test]
+    fn growing_and_shrinking_items() {
+        let fixed   = item(10.0,4.0);
+        let growing = Item {grow:1.0,..item(10.0,4.0)};
+        let double  = Item {grow:2.0,shrink:1.0,..item(30.0,4.0)};
+        let stack   = Stack::horizontal();
+        let items   = [fixed,growing,double];
+        let sizes   = |width| stack.arrange(Vector2(width,4.0),&items).iter().map(|p| p.size.x)
+            .collect_vec();
+        assert_eq!(sizes(80.0), vec![10.0,20.0,50.0]);
+        assert_eq!(sizes(35.0), vec![10.0,10.0,15.0]);
+        assert_eq!(sizes(10.0), vec![10.0,10.0,0.0]);
+        let placements = stack.arrange(Vector2(80.0,4.0),&items);
+        assert_eq!(placements[2].position, Vector2(15.0,0.0));
+    }
+}
@@ -1,9 +1,11 @@
 //! Display object abstraction and related utilities.
 
 pub mod class;
+pub mod spatial_index;
 pub mod transform;
 
 pub use class::*;
+pub use spatial_index::SpatialIndex;
 
 pub use class::Any;
 
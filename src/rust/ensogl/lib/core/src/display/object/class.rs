@@ -4,13 +4,19 @@
 
 use crate::prelude::*;
 
+use super::spatial_index::SpatialIndex;
 use super::transform;
 
+use crate::data::BoundingBox;
 use crate::data::dirty::traits::*;
 use crate::data::dirty;
+use crate::display::layout::Alignment;
+use crate::display::layout::Stack;
+use crate::display::layout::stack;
 use crate::display::scene::Scene;
 use crate::display::scene::layer::Layer;
 use crate::display::scene::layer::WeakLayer;
+use crate::frp;
 
 use data::opt_vec::OptVec;
 use nalgebra::Matrix4;
@@ -105,6 +111,8 @@ type ChildrenDirty         = dirty::SharedSet<usize,OnDirtyCallback>;
 type RemovedChildren<Host> = dirty::SharedVector<WeakInstance<Host>,OnDirtyCallback>;
 type TransformDirty        = dirty::SharedBool<OnDirtyCallback>;
 type SceneLayerDirty       = dirty::SharedBool<OnDirtyCallback>;
+type LayoutDirty           = dirty::SharedBool<()>;
+type BoundsDirty           = dirty::SharedBool<OnDirtyCallback>;
 
 
 // === Definition ===
@@ -122,6 +130,8 @@ pub struct DirtyFlags<Host> {
     removed_children : RemovedChildren<Host>,
     transform        : TransformDirty,
     scene_layer      : SceneLayerDirty,
+    layout           : LayoutDirty,
+    bounds           : BoundsDirty,
     #[derivative(Debug="ignore")]
     on_dirty         : Rc<RefCell<Box<dyn Fn()>>>,
 }
@@ -141,7 +151,11 @@ impl<Host> DirtyFlags<Host> {
         let transform        = TransformDirty::new(sub_logger,on_dirty_callback(&on_dirty));
         let sub_logger       = logger::WarningLogger::new_sub(&logger,"scene_layer");
         let scene_layer      = SceneLayerDirty::new(sub_logger,on_dirty_callback(&on_dirty));
-        Self {parent,children,removed_children,transform,scene_layer,on_dirty}
+        let sub_logger       = logger::WarningLogger::new_sub(&logger,"layout");
+        let layout           = LayoutDirty::new(sub_logger,());
+        let sub_logger       = logger::WarningLogger::new_sub(&logger,"bounds");
+        let bounds           = BoundsDirty::new(sub_logger,on_dirty_callback(&on_dirty));
+        Self {parent,children,removed_children,transform,scene_layer,layout,bounds,on_dirty}
     }
 
     fn set_on_dirty<F:'static+Fn()>(&self,f:F) {
@@ -164,6 +178,130 @@ fn on_dirty_callback(f:&Rc<RefCell<Box<dyn Fn()>>>) -> OnDirtyCallback {
 
 
 
+// ==============
+// === Layout ===
+// ==============
+
+/// Layout properties of a display object. See the docs of [`ObjectOps::set_layout`] to learn more.
+#[derive(Debug,Default)]
+struct LayoutState {
+    /// The stack placing the children, if any.
+    stack            : Cell<Option<Stack>>,
+    /// The size set by the owner of the object. Used by objects without a stack.
+    measured_size    : Cell<Vector2<f32>>,
+    /// Cached size of the object before growing or shrinking. Valid if the layout is not dirty.
+    natural_size     : Cell<Vector2<f32>>,
+    /// The size assigned by the layout.
+    computed_size    : Cell<Vector2<f32>>,
+    grow             : Cell<f32>,
+    shrink           : Cell<f32>,
+    /// The position among the siblings. Children are laid out in the order they were added.
+    order            : Cell<usize>,
+    next_child_order : Cell<usize>,
+    frp              : RefCell<Option<LayoutFrp>>,
+}
+
+/// FRP endpoints of the layout of a display object. See [`ObjectOps::layout_frp`].
+///
+/// The inputs set the layout properties like the corresponding [`ObjectOps`] methods. The inputs
+/// changing a single property of the stack (`set_padding`, `set_gap` and `set_alignment`) are
+/// ignored if the object has no stack layout.
+#[derive(Clone,CloneRef,Debug)]
+pub struct LayoutFrp {
+    #[allow(missing_docs)]
+    pub network           : frp::Network,
+    /// Set the stack placing the children, or stop placing them with [`None`].
+    pub set_layout        : frp::Source<Option<Stack>>,
+    /// Set the padding of the stack.
+    pub set_padding       : frp::Source<stack::Padding>,
+    /// Set the gap between the children placed by the stack.
+    pub set_gap           : frp::Source<f32>,
+    /// Set the alignment of the children placed by the stack.
+    pub set_alignment     : frp::Source<Alignment>,
+    /// Set the size which the layout of the parent uses for the object.
+    pub set_measured_size : frp::Source<Vector2<f32>>,
+    /// Set the factor of growing the object in the stack of its parent.
+    pub set_grow          : frp::Source<f32>,
+    /// Set the factor of shrinking the object in the stack of its parent.
+    pub set_shrink        : frp::Source<f32>,
+    /// The size assigned to the object by the layout.
+    pub computed_size     : frp::Sampler<Vector2<f32>>,
+    computed_size_source  : frp::Source<Vector2<f32>>,
+}
+
+/// Call `f` with the model of the object, unless the object was already dropped. The layout FRP
+/// network refers to its object weakly, so it does not keep the object alive.
+fn with_model<Host>(object:&WeakInstance<Host>, f:impl FnOnce(&Model<Host>)) {
+    if let Some(object) = object.upgrade() {
+        f(&object.rc)
+    }
+}
+
+impl LayoutFrp {
+    fn new<Host:'static>(object:WeakInstance<Host>, computed_size:Vector2<f32>) -> Self {
+        frp::new_network! { network
+            set_layout           <- source();
+            set_padding          <- source();
+            set_gap              <- source();
+            set_alignment        <- source();
+            set_measured_size    <- source();
+            set_grow             <- source();
+            set_shrink           <- source();
+            computed_size_source <- source();
+            computed_size        <- computed_size_source.sampler();
+
+            eval set_layout ([object](t) with_model(&object,|o| o.set_layout(*t)));
+            eval set_padding ([object](t)
+                with_model(&object,|o| o.modify_layout(|stack| stack.with_padding(*t))));
+            eval set_gap ([object](t)
+                with_model(&object,|o| o.modify_layout(|stack| stack.with_gap(*t))));
+            eval set_alignment ([object](t)
+                with_model(&object,|o| o.modify_layout(|stack| stack.with_alignment(*t))));
+            eval set_measured_size ([object](t) with_model(&object,|o| o.set_measured_size(*t)));
+            eval set_grow          ([object](t) with_model(&object,|o| o.set_layout_grow(*t)));
+            eval set_shrink        ([object](t) with_model(&object,|o| o.set_layout_shrink(*t)));
+        }
+        computed_size_source.emit(computed_size);
+        Self {network,set_layout,set_padding,set_gap,set_alignment,set_measured_size,set_grow
+             ,set_shrink,computed_size,computed_size_source}
+    }
+}
+
+
+
+// ==============
+// === Bounds ===
+// ==============
+
+/// Bounds of a display object. See the docs of [`ObjectOps::set_bounds`] to learn more.
+#[derive(Derivative)]
+#[derivative(Debug(bound=""))]
+struct BoundsState<Host> {
+    /// The bounds in the object coordinate space, set by the owner of the object.
+    local      : Cell<Option<BoundingBox>>,
+    /// Cached bounds in the world coordinate space. Valid if the object was updated.
+    world      : Cell<Option<BoundingBox>>,
+    /// The index set explicitly for this object and its descendants.
+    own_index  : RefCell<Option<SpatialIndex<Host>>>,
+    /// The index the object is currently registered in.
+    registered : RefCell<Option<SpatialIndex<Host>>>,
+    /// Weak reference to the object itself, used to register it in the index.
+    this       : RefCell<WeakInstance<Host>>,
+}
+
+impl<Host> Default for BoundsState<Host> {
+    fn default() -> Self {
+        let local      = default();
+        let world      = default();
+        let own_index  = default();
+        let registered = default();
+        let this       = RefCell::new(WeakInstance {weak:Weak::new()});
+        Self {local,world,own_index,registered,this}
+    }
+}
+
+
+
 // =============
 // === Model ===
 // =============
@@ -201,6 +339,8 @@ pub struct Model<Host=Scene> {
     children     : RefCell     <OptVec<WeakInstance<Host>>>,
     transform    : RefCell     <CachedTransform>,
     visible      : Cell        <bool>,
+    layout       : LayoutState,
+    bounds       : BoundsState <Host>,
     logger       : Logger,
 }
 
@@ -217,8 +357,10 @@ impl<Host> Model<Host> {
         let host            = default();
         let assigned_layers = default();
         let layers          = default();
+        let layout          = default();
+        let bounds          = default();
         Self {host,assigned_layers,layers,dirty,callbacks,parent_bind,children,transform
-             ,visible,logger}
+             ,visible,layout,bounds,logger}
     }
 
     /// Checks whether the object is visible.
@@ -244,7 +386,7 @@ impl<Host> Model<Host> {
     /// Recompute the transformation matrix of this object and update all of its dirty children.
     pub fn update(&self, host:&Host) {
         let origin0 = Matrix4::identity();
-        self.update_with_origin(host,origin0,false,false,&[])
+        self.update_with_origin(host,origin0,false,false,&[],None)
     }
 
     /// The default visibility of a new [`Instance`] is false. You can use this function to override
@@ -261,6 +403,9 @@ impl<Host> Model<Host> {
             child.upgrade().for_each(|child| child.unsafe_unset_parent_without_update());
             self.dirty.children.unset(&index);
             self.dirty.removed_children.set(child);
+            if self.layout.stack.get().is_some() {
+                self.invalidate_layout();
+            }
         });
     }
 
@@ -285,7 +430,11 @@ impl<Host> Model<Host> {
     , parent_origin_changed : bool
     , parent_layers_changed : bool
     , parent_layers         : &[WeakLayer]
+    , parent_index          : Option<&SpatialIndex<Host>>
     ) {
+        // === Layout Update ===
+        self.update_layout();
+
         // === Scene Layers Update ===
         let has_new_parent          = self.dirty.parent.check();
         let assigned_layers_ref     = self.assigned_layers.borrow();
@@ -321,6 +470,13 @@ impl<Host> Model<Host> {
         let new_layers     = new_layers_opt.unwrap_or(&*current_layers);
 
 
+        // === Spatial Index ===
+
+        let own_index    = self.bounds.own_index.borrow().clone();
+        let index        = own_index.as_ref().or(parent_index);
+        let bounds_dirty = self.dirty.bounds.take().check();
+
+
         // === Origin & Visibility Update ===
 
         self.update_visibility(host,parent_layers);
@@ -330,6 +486,7 @@ impl<Host> Model<Host> {
         debug!(self.logger, "Update with {parent_origin_label} parent origin.", || {
             let origin_changed = self.transform.borrow_mut().update(new_parent_origin);
             let new_origin     = self.transform.borrow().matrix;
+            self.update_bounds(index,origin_changed || bounds_dirty);
             if origin_changed || layers_changed {
                 if origin_changed {
                     info!(self.logger,"Self origin changed.");
@@ -342,7 +499,7 @@ impl<Host> Model<Host> {
                         self.children.borrow().iter().for_each(|weak_child| {
                             weak_child.upgrade().for_each(|child|
                                 child.update_with_origin
-                                    (host,new_origin,true,layers_changed,new_layers)
+                                    (host,new_origin,true,layers_changed,new_layers,index)
                             );
                         });
                     })
@@ -355,7 +512,7 @@ impl<Host> Model<Host> {
                             self.children.borrow().safe_index(*ix).and_then(|t|t.upgrade())
                                 .for_each(|child|
                                     child.update_with_origin
-                                        (host,new_origin,false,layers_changed,new_layers))
+                                        (host,new_origin,false,layers_changed,new_layers,index))
                         });
                     })
                 }
@@ -366,6 +523,33 @@ impl<Host> Model<Host> {
         self.dirty.parent.unset();
     }
 
+    /// Recompute the world-space bounds if they could change, and register them in the spatial
+    /// index. Hidden objects and objects without bounds are removed from the index.
+    fn update_bounds(&self, index:Option<&SpatialIndex<Host>>, changed:bool) {
+        let has_bounds    = self.bounds.local.get().is_some();
+        let index         = index.filter(|_| has_bounds && self.visible.get());
+        let index_changed = self.bounds.registered.borrow().as_ref() != index;
+        if changed {
+            let matrix = self.transform.borrow().matrix;
+            self.bounds.world.set(self.bounds.local.get().map(|bounds| bounds.transform(&matrix)));
+        }
+        if index_changed {
+            self.unregister_bounds();
+        }
+        if changed || index_changed {
+            if let (Some(index),Some(world)) = (index,self.bounds.world.get()) {
+                index.insert(self.id(),&self.bounds.this.borrow(),world);
+                *self.bounds.registered.borrow_mut() = Some(index.clone_ref());
+            }
+        }
+    }
+
+    fn unregister_bounds(&self) {
+        if let Some(index) = self.bounds.registered.borrow_mut().take() {
+            index.remove(self.id());
+        }
+    }
+
     /// Hide all removed children and show this display object if it was attached to a new parent.
     fn update_visibility(&self, host:&Host, parent_layers:&[WeakLayer]) {
         self.take_removed_children_and_update_their_visibility(host);
@@ -398,6 +582,7 @@ impl<Host> Model<Host> {
         if self.visible.get() {
             info!(self.logger,"Hiding.");
             self.visible.set(false);
+            self.unregister_bounds();
             self.callbacks.on_hide(host);
             self.children.borrow().iter().for_each(|child| {
                 child.upgrade().for_each(|t| t.set_vis_false(host));
@@ -421,13 +606,25 @@ impl<Host> Model<Host> {
     }
 }
 
+impl<Host> Drop for Model<Host> {
+    fn drop(&mut self) {
+        self.unregister_bounds();
+    }
+}
+
 
 // === Register / Unregister ===
 
 impl<Host> Model<Host> {
     fn register_child<T:Object<Host>>(&self, child:&T) -> usize {
         let index = self.children.borrow_mut().insert(child.weak_display_object());
+        let order = self.layout.next_child_order.get();
+        self.layout.next_child_order.set(order + 1);
+        child.display_object().layout.order.set(order);
         self.dirty.children.set(index);
+        if self.layout.stack.get().is_some() {
+            self.invalidate_layout();
+        }
         index
     }
 
@@ -446,11 +643,107 @@ impl<Host> Model<Host> {
             self.dirty.set_on_dirty(move || dirty.set(index));
             self.dirty.parent.set();
             *self.parent_bind.borrow_mut() = Some(bind);
+            if self.dirty.layout.check() {
+                parent.invalidate_layout();
+            }
         }
     }
 }
 
 
+// === Layout ===
+
+impl<Host> Model<Host> {
+    /// Mark the layout of this object and all of its ancestors as dirty. It is an invariant that
+    /// ancestors of an object with a dirty layout have dirty layouts as well.
+    fn invalidate_layout(&self) {
+        if !self.dirty.layout.check() {
+            self.dirty.layout.set();
+            if let Some(parent) = self.parent() {
+                parent.invalidate_layout();
+            }
+        }
+    }
+
+    /// Recompute the layout of this object and its descendants if it is dirty. The object is
+    /// assigned its natural size. Objects placed by the stack of their parent are updated by the
+    /// parent instead.
+    fn update_layout(&self) {
+        if self.dirty.layout.check() {
+            let size = self.measure_layout();
+            self.arrange_layout(size);
+        }
+    }
+
+    /// The natural size of the object: its measured size, or the size of its stack computed from
+    /// the natural sizes of its children.
+    fn measure_layout(&self) -> Vector2<f32> {
+        if self.dirty.layout.check() {
+            let size = match self.layout.stack.get() {
+                None        => self.layout.measured_size.get(),
+                Some(stack) => {
+                    let children = self.layout_children();
+                    children.iter().for_each(|child| { child.measure_layout(); });
+                    let items = children.iter().map(|child| child.layout_item()).collect_vec();
+                    stack.measure(&items)
+                }
+            };
+            self.layout.natural_size.set(size);
+        }
+        self.layout.natural_size.get()
+    }
+
+    /// Assign the size to the object and place its children if it has a stack. The natural sizes
+    /// of the children have to be measured first.
+    fn arrange_layout(&self, size:Vector2<f32>) {
+        let is_dirty     = self.dirty.layout.check();
+        let size_changed = self.layout.computed_size.get() != size;
+        if is_dirty || size_changed {
+            self.dirty.layout.unset();
+            self.layout.computed_size.set(size);
+            match self.layout.stack.get() {
+                None => self.layout_children().iter().for_each(|child| child.update_layout()),
+                Some(stack) => {
+                    let children   = self.layout_children();
+                    let items      = children.iter().map(|child| child.layout_item()).collect_vec();
+                    let placements = stack.arrange(size,&items);
+                    for (child,placement) in children.iter().zip(placements) {
+                        let position = placement.position;
+                        child.mod_position(|p| {
+                            p.x = position.x;
+                            p.y = position.y;
+                        });
+                        child.arrange_layout(placement.size);
+                    }
+                }
+            }
+            if size_changed {
+                let frp = self.layout.frp.borrow().clone();
+                if let Some(frp) = frp {
+                    frp.computed_size_source.emit(size);
+                }
+            }
+        }
+    }
+
+    /// The children in the order they were added.
+    fn layout_children(&self) -> Vec<Instance<Host>> {
+        let children     = self.children.borrow();
+        let mut children = children.iter().filter_map(|child| child.upgrade()).collect_vec();
+        children.sort_by_key(|child| child.layout.order.get());
+        children
+    }
+
+    /// The description of this object for the stack of its parent.
+    fn layout_item(&self) -> stack::Item {
+        let size   = self.layout.natural_size.get();
+        let grow   = self.layout.grow.get();
+        let shrink = self.layout.shrink.get();
+        stack::Item {size,grow,shrink}
+    }
+}
+
+
 // === Getters ===
 
 impl<Host> Model<Host> {
@@ -478,6 +771,49 @@ impl<Host> Model<Host> {
     pub fn matrix(&self) -> Matrix4<f32> {
         self.transform.borrow().matrix()
     }
+
+    /// The stack placing the children of the object, if any.
+    pub fn layout(&self) -> Option<Stack> {
+        self.layout.stack.get()
+    }
+
+    /// The size of the object set by its owner.
+    pub fn measured_size(&self) -> Vector2<f32> {
+        self.layout.measured_size.get()
+    }
+
+    /// The size of the object assigned by the layout.
+    pub fn computed_size(&self) -> Vector2<f32> {
+        self.layout.computed_size.get()
+    }
+
+    /// FRP endpoints of the layout. They are created on the first call.
+    pub fn layout_frp(&self) -> LayoutFrp where Host:'static {
+        let object        = self.bounds.this.borrow().clone();
+        let computed_size = self.computed_size();
+        let mut frp       = self.layout.frp.borrow_mut();
+        frp.get_or_insert_with(|| LayoutFrp::new(object,computed_size)).clone_ref()
+    }
+
+    /// The bounds of the object in its own coordinate space.
+    pub fn bounds(&self) -> Option<BoundingBox> {
+        self.bounds.local.get()
+    }
+
+    /// The bounds of the object in the world coordinate space, computed during the last update.
+    pub fn world_bounds(&self) -> Option<BoundingBox> {
+        self.bounds.world.get()
+    }
+
+    /// The spatial index set explicitly for the object.
+    pub fn spatial_index(&self) -> Option<SpatialIndex<Host>> {
+        self.bounds.own_index.borrow().clone()
+    }
+
+    fn id(&self) -> Id {
+        let ptr : *const Self = self;
+        Id(ptr as usize)
+    }
 }
 
 
@@ -514,6 +850,49 @@ impl<Host> Model<Host> {
         self.with_mut_borrowed_transform(|t| t.mod_scale(f));
     }
 
+    fn set_layout(&self, stack:Option<Stack>) {
+        if self.layout.stack.replace(stack) != stack {
+            self.invalidate_layout();
+        }
+    }
+
+    /// Change the stack placing the children. Does nothing if the object has no stack.
+    fn modify_layout(&self, f:impl FnOnce(Stack) -> Stack) {
+        if let Some(stack) = self.layout.stack.get() {
+            self.set_layout(Some(f(stack)));
+        }
+    }
+
+    fn set_measured_size(&self, size:Vector2<f32>) {
+        if self.layout.measured_size.replace(size) != size {
+            self.invalidate_layout();
+        }
+    }
+
+    fn set_layout_grow(&self, grow:f32) {
+        if self.layout.grow.replace(grow) != grow {
+            self.invalidate_layout();
+        }
+    }
+
+    fn set_layout_shrink(&self, shrink:f32) {
+        if self.layout.shrink.replace(shrink) != shrink {
+            self.invalidate_layout();
+        }
+    }
+
+    fn set_bounds(&self, bounds:Option<BoundingBox>) {
+        if self.bounds.local.replace(bounds) != bounds {
+            self.dirty.bounds.set();
+        }
+    }
+
+    fn set_spatial_index(&self, index:Option<SpatialIndex<Host>>) {
+        *self.bounds.own_index.borrow_mut() = index;
+        // Forces the update of the whole subtree, so all descendants are registered in the index.
+        self.with_mut_borrowed_transform(|t| t.dirty = true);
+    }
+
     /// Sets a callback which will be called with a reference to the display object when the object
     /// will be updated.
     pub fn set_on_updated<F>(&self, f:F)
@@ -608,7 +987,9 @@ impl<Host> Deref for Instance<Host> {
 impl<Host> Instance<Host> {
     /// Constructor.
     pub fn new(logger:impl AnyLogger) -> Self {
-        Self {rc:Rc::new(Model::new(logger))}
+        let rc = Rc::new(Model::new(logger));
+        *rc.bounds.this.borrow_mut() = WeakInstance {weak:Rc::downgrade(&rc)};
+        Self {rc}
     }
 
     /// Create a new weak pointer to this display object instance.
@@ -1103,6 +1484,95 @@ pub trait ObjectOps<Host=Scene> : Object<Host> {
     fn set_rotation_z(&self, t:f32) {
         self.mod_rotation(|p| p.z = t)
     }
+
+
+    // === Layout ===
+
+    /// Place the children of this display object using the stack layout. Children are placed in
+    /// the order they were added, and their X and Y positions are overridden by the layout. The
+    /// layout is recomputed lazily, during the display object update, after any of its properties
+    /// or the sizes of the children change. See the [`stack`] module docs to learn more.
+    fn set_layout(&self, stack:Stack) {
+        self.display_object().rc.set_layout(Some(stack))
+    }
+
+    /// Stop placing the children of this display object. Their positions are left unchanged.
+    fn unset_layout(&self) {
+        self.display_object().rc.set_layout(None)
+    }
+
+    fn layout(&self) -> Option<Stack> {
+        self.display_object().rc.layout()
+    }
+
+    /// Set the size which the layout of the parent uses for this display object. The sizes of
+    /// display objects with a stack layout are computed from their children instead.
+    fn set_measured_size(&self, size:Vector2<f32>) {
+        self.display_object().rc.set_measured_size(size)
+    }
+
+    fn measured_size(&self) -> Vector2<f32> {
+        self.display_object().rc.measured_size()
+    }
+
+    /// The size assigned to this display object by the layout, after growing or shrinking it.
+    fn computed_size(&self) -> Vector2<f32> {
+        self.display_object().rc.computed_size()
+    }
+
+    /// Set the factor of growing this display object when the stack of its parent has free space.
+    fn set_layout_grow(&self, grow:f32) {
+        self.display_object().rc.set_layout_grow(grow)
+    }
+
+    /// Set the factor of shrinking this display object when its siblings do not fit in the stack
+    /// of its parent.
+    fn set_layout_shrink(&self, shrink:f32) {
+        self.display_object().rc.set_layout_shrink(shrink)
+    }
+
+    /// FRP endpoints of the layout of this display object.
+    fn layout_frp(&self) -> LayoutFrp where Host:'static {
+        self.display_object().rc.layout_frp()
+    }
+
+
+    // === Bounds ===
+
+    /// Set the bounds of this display object in its own coordinate space. They do not include the
+    /// children. During the display object update, the bounds are transformed to an axis-aligned
+    /// box in the world space (see [`world_bounds`]) and registered in the [`SpatialIndex`] of the
+    /// closest ancestor having one, like the scene root. The entry is updated only when the
+    /// object moves or its bounds change, and is removed when the object gets hidden.
+    fn set_bounds(&self, bounds:BoundingBox) {
+        self.display_object().rc.set_bounds(Some(bounds))
+    }
+
+    /// Remove the bounds of this display object. It will be removed from the spatial index.
+    fn unset_bounds(&self) {
+        self.display_object().rc.set_bounds(None)
+    }
+
+    fn bounds(&self) -> Option<BoundingBox> {
+        self.display_object().rc.bounds()
+    }
+
+    /// The bounds of this display object in the world space, computed during the last update.
+    fn world_bounds(&self) -> Option<BoundingBox> {
+        self.display_object().rc.world_bounds()
+    }
+
+    /// Register this display object and its descendants having bounds in the provided index,
+    /// unless they are placed under another object with its own index.
+    fn set_spatial_index(&self, index:&SpatialIndex<Host>) {
+        self.display_object().rc.set_spatial_index(Some(index.clone_ref()))
+    }
+
+    /// Stop using the index set by [`set_spatial_index`]. The descendants will use the index of
+    /// the ancestors instead, if any.
+    fn unset_spatial_index(&self) {
+        self.display_object().rc.set_spatial_index(None)
+    }
 }
 
 
@@ -1490,6 +1960,99 @@ mod tests {
         assert_eq!(node6.is_visible(),false);
     }
 
+    #[test]
+    fn layout_test() {
+        let root   = Instance::<()>::new(Logger::new("root"));
+        let bar    = Instance::<()>::new(Logger::new("bar"));
+        let node1  = Instance::<()>::new(Logger::new("node1"));
+        let node2  = Instance::<()>::new(Logger::new("node2"));
+        let layout = Stack::horizontal().with_gap(2.0).with_padding(stack::Padding::all(1.0));
+        root.add_child(&bar);
+        bar.set_layout(layout);
+        node1.set_measured_size(Vector2::new(10.0,4.0));
+        node2.set_measured_size(Vector2::new(20.0,8.0));
+        bar.add_child(&node1);
+        bar.add_child(&node2);
+        root.update(&());
+        assert_eq!(bar.computed_size()     , Vector2::new(34.0,10.0));
+        assert_eq!(node1.computed_size()   , Vector2::new(10.0,4.0));
+        assert_eq!(node1.global_position() , Vector3::new(-11.0,0.0,0.0));
+        assert_eq!(node2.global_position() , Vector3::new(6.0,0.0,0.0));
+
+        bar.set_layout(layout.with_width(50.0));
+        node2.set_layout_grow(1.0);
+        let frp = node2.layout_frp();
+        root.update(&());
+        assert_eq!(frp.computed_size.value() , Vector2::new(36.0,8.0));
+        assert_eq!(node1.global_position()   , Vector3::new(-19.0,0.0,0.0));
+        assert_eq!(node2.global_position()   , Vector3::new(6.0,0.0,0.0));
+
+        node1.set_measured_size(Vector2::new(20.0,4.0));
+        root.update(&());
+        assert_eq!(frp.computed_size.value(), Vector2::new(26.0,8.0));
+
+        bar.remove_child(&node1);
+        root.update(&());
+        assert_eq!(frp.computed_size.value() , Vector2::new(48.0,8.0));
+        assert_eq!(node2.global_position()   , Vector3::new(0.0,0.0,0.0));
+
+        let bar_frp = bar.layout_frp();
+        bar_frp.set_padding.emit(stack::Padding::all(5.0));
+        frp.set_grow.emit(0.0);
+        frp.set_measured_size.emit(Vector2::new(10.0,2.0));
+        root.update(&());
+        assert_eq!(bar.layout().map(|stack| stack.padding), Some(stack::Padding::all(5.0)));
+        assert_eq!(frp.computed_size.value(), Vector2::new(10.0,2.0));
+
+        bar_frp.set_layout.emit(None);
+        bar_frp.set_gap.emit(3.0);
+        assert_eq!(bar.layout(), None);
+    }
+
+    #[test]
+    fn bounds_test() {
+        let root  = Instance::<()>::new(Logger::new("root"));
+        let node1 = Instance::<()>::new(Logger::new("node1"));
+        let node2 = Instance::<()>::new(Logger::new("node2"));
+        let index = SpatialIndex::new();
+        root.force_set_visibility(true);
+        root.set_spatial_index(&index);
+        root.add_child(&node1);
+        node1.add_child(&node2);
+        node1.set_position(Vector3::new(100.0,0.0,0.0));
+        node2.set_position(Vector3::new(0.0,50.0,0.0));
+        node1.set_bounds(BoundingBox::from_size(Vector2::new(20.0,10.0)));
+        node2.set_bounds(BoundingBox::from_size(Vector2::new(4.0,4.0)));
+        root.update(&());
+        let center = Vector2::new(100.0,0.0);
+        let bounds = BoundingBox::from_center_and_size(center,Vector2::new(20.0,10.0));
+        assert_eq!(index.len()                                       , 2);
+        assert_eq!(node1.world_bounds()                              , Some(bounds));
+        assert_eq!(index.objects_containing(Vector2::new(105.0,0.0))  , vec![node1.clone_ref()]);
+        assert_eq!(index.objects_containing(Vector2::new(101.0,51.0)) , vec![node2.clone_ref()]);
+
+        node1.set_scale(Vector3::new(2.0,2.0,1.0));
+        root.update(&());
+        let area = BoundingBox::from_corners(Vector2::new(0.0,0.0),Vector2::new(200.0,200.0));
+        assert_eq!(index.objects_containing(Vector2::new(103.0,103.0)) , vec![node2.clone_ref()]);
+        assert_eq!(index.objects_intersecting(&area).len()             , 2);
+
+        node2.unset_bounds();
+        root.update(&());
+        assert_eq!(node2.world_bounds() , None);
+        assert_eq!(index.len()          , 1);
+
+        node1.unset_parent();
+        root.update(&());
+        assert!(index.is_empty());
+
+        root.add_child(&node1);
+        root.update(&());
+        assert_eq!(index.len(), 1);
+        drop(node1);
+        assert!(index.is_empty());
+    }
+
     #[test]
     fn layers_test() {
         let layer1 = Layer::new(Logger::new("0"));
@@ -0,0 +1,119 @@
+//! A spatial index of display objects, allowing to query objects by their world-space bounds.
+
+use crate::prelude::*;
+
+use crate::data::BoundingBox;
+use crate::data::SpatialGrid;
+use crate::display::object::Id;
+use crate::display::object::Instance;
+use crate::display::object::WeakInstance;
+use crate::display::scene::Scene;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The default size of the grid cells, in pixels. It is comparable with the size of typical
+/// components, like nodes or buttons.
+pub const DEFAULT_CELL_SIZE : f32 = 256.0;
+
+
+
+// ====================
+// === SpatialIndex ===
+// ====================
+
+/// A spatial index of display objects with bounds. Display objects register themselves in the
+/// index of their closest ancestor having one, and update their entries incrementally during the
+/// display object update, only when their world-space bounds change. Objects without bounds and
+/// hidden objects are not registered. See [`crate::display::object::ObjectOps::set_bounds`] to
+/// learn more.
+#[derive(Derivative,CloneRef)]
+#[derivative(Clone(bound=""))]
+#[derivative(Debug(bound=""))]
+pub struct SpatialIndex<Host=Scene> {
+    model : Rc<RefCell<Model<Host>>>,
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound=""))]
+struct Model<Host> {
+    grid    : SpatialGrid<Id>,
+    objects : HashMap<Id,WeakInstance<Host>>,
+}
+
+impl<Host> SpatialIndex<Host> {
+    /// Constructor.
+    pub fn new() -> Self {
+        Self::with_cell_size(DEFAULT_CELL_SIZE)
+    }
+
+    /// Constructor of an index with the given grid cell size. See [`SpatialGrid`] to learn more.
+    pub fn with_cell_size(cell_size:f32) -> Self {
+        let grid    = SpatialGrid::new(cell_size);
+        let objects = default();
+        let model   = Rc::new(RefCell::new(Model {grid,objects}));
+        Self {model}
+    }
+
+    /// The number of registered objects.
+    pub fn len(&self) -> usize {
+        self.model.borrow().objects.len()
+    }
+
+    /// Check whether there are no registered objects.
+    pub fn is_empty(&self) -> bool {
+        self.model.borrow().objects.is_empty()
+    }
+
+    /// Insert a new object or update the bounds of an already registered one.
+    pub(crate) fn insert(&self, id:Id, object:&WeakInstance<Host>, bounds:BoundingBox) {
+        let mut model = self.model.borrow_mut();
+        model.grid.insert(id,bounds);
+        model.objects.entry(id).or_insert_with(|| object.clone());
+    }
+
+    /// Remove the object. Does nothing if it was not registered.
+    pub(crate) fn remove(&self, id:Id) {
+        let mut model = self.model.borrow_mut();
+        model.grid.remove(id);
+        model.objects.remove(&id);
+    }
+
+    /// The world-space bounds under which the object is registered.
+    pub fn bounds(&self, id:Id) -> Option<BoundingBox> {
+        self.model.borrow().grid.get(id)
+    }
+
+    /// All registered objects whose bounds intersect the area, ordered by their ids.
+    pub fn objects_intersecting(&self, area:&BoundingBox) -> Vec<Instance<Host>> {
+        let ids = self.model.borrow().grid.query(area);
+        self.upgrade(ids)
+    }
+
+    /// All registered objects whose bounds contain the point, ordered by their ids.
+    pub fn objects_containing(&self, point:Vector2<f32>) -> Vec<Instance<Host>> {
+        let ids = self.model.borrow().grid.query_point(point);
+        self.upgrade(ids)
+    }
+
+    fn upgrade(&self, ids:Vec<Id>) -> Vec<Instance<Host>> {
+        let model   = self.model.borrow();
+        let objects = ids.iter().filter_map(|id| model.objects.get(id));
+        objects.filter_map(|object| object.upgrade()).collect()
+    }
+}
+
+impl<Host> Default for SpatialIndex<Host> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Host> PartialEq for SpatialIndex<Host> {
+    fn eq(&self, other:&Self) -> bool {
+        Rc::ptr_eq(&self.model,&other.model)
+    }
+}
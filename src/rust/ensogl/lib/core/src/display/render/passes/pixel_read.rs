@@ -56,7 +56,9 @@ pub struct PixelReadPass<T:JsTypedArrayItem> {
     threshold    : usize,
     to_next_read : usize,
     #[derivative(Debug="ignore")]
-    callback : Option<Rc<dyn Fn(Vec<T>)>>,
+    callback  : Option<Rc<dyn Fn(Vec<T>)>>,
+    #[derivative(Debug="ignore")]
+    condition : Option<Rc<dyn Fn()->bool>>,
 }
 
 impl<T:JsTypedArrayItem> PixelReadPass<T> {
@@ -66,9 +68,10 @@ impl<T:JsTypedArrayItem> PixelReadPass<T> {
         let sync         = default();
         let position     = position.clone_ref();
         let callback     = default();
+        let condition    = default();
         let threshold    = 0;
         let to_next_read = 0;
-        Self {data,sync,position,threshold,to_next_read,callback}
+        Self {data,sync,position,threshold,to_next_read,callback,condition}
     }
 
     /// Sets a callback which will be evaluated after a successful pixel read action. Please note
@@ -85,6 +88,12 @@ impl<T:JsTypedArrayItem> PixelReadPass<T> {
         self.threshold = threshold;
     }
 
+    /// Sets a condition checked on every run of this pass. The pass is skipped in frames in which
+    /// the condition does not hold.
+    pub fn set_condition<F:Fn()->bool+'static>(&mut self, f:F) {
+        self.condition = Some(Rc::new(f));
+    }
+
     fn init_if_fresh(&mut self, context:&Context, variables:&UniformScope) {
         if self.data.is_none() {
             let buffer   = context.create_buffer().unwrap();
@@ -153,6 +162,9 @@ impl<T:JsTypedArrayItem> PixelReadPass<T> {
 
 impl<T:JsTypedArrayItem> pass::Definition for PixelReadPass<T> {
     fn run(&mut self, instance:&pass::Instance) {
+        if self.condition.as_ref().map_or(false,|condition| !condition()) {
+            return
+        }
         if self.to_next_read > 0 {
             self.to_next_read -= 1;
         } else {
@@ -1,10 +1,15 @@
 #![allow(missing_docs)]
 
+#[warn(missing_docs)]
+pub mod culling;
 #[warn(missing_docs)]
 pub mod dom;
 #[warn(missing_docs)]
+pub mod hit_test;
+#[warn(missing_docs)]
 pub mod layer;
 
+pub use hit_test::PickingMode;
 pub use layer::Layer;
 
 pub use crate::system::web::dom::Shape;
@@ -15,6 +20,7 @@ use crate::animation;
 use crate::control::callback;
 use crate::control::io::mouse::MouseManager;
 use crate::control::io::mouse;
+use crate::data::BoundingBox;
 use crate::data::dirty::traits::*;
 use crate::data::dirty;
 use crate::debug::stats::Stats;
@@ -69,6 +75,7 @@ pub struct ShapeRegistryData {
     scene            : Option<Scene>,
     shape_system_map : HashMap<TypeId,Box<dyn Any>>,
     mouse_target_map : HashMap<(SymbolId,attribute::InstanceIndex),Rc<dyn MouseTarget>>,
+    hit_target_map   : HashMap<SymbolId,BTreeMap<attribute::InstanceIndex,hit_test::HitTarget>>,
 }
 
 impl {
@@ -105,9 +112,33 @@ impl {
         self.mouse_target_map.insert((symbol_id,instance_id),target);
     }
 
+    /// Register the sprite used to find the mouse target when the CPU picking is enabled. See
+    /// the [`hit_test`] module docs to learn more.
+    pub fn insert_hit_target
+    ( &mut self
+    , symbol_id   : SymbolId
+    , instance_id : attribute::InstanceIndex
+    , target      : hit_test::HitTarget
+    ) {
+        self.hit_target_map.entry(symbol_id).or_default().insert(instance_id,target);
+    }
+
+    /// Remove both the mouse target and the hit target registered for the given instance.
     pub fn remove_mouse_target
     (&mut self, symbol_id:SymbolId, instance_id:attribute::InstanceIndex) {
         self.mouse_target_map.remove(&(symbol_id,instance_id));
+        if let Some(targets) = self.hit_target_map.get_mut(&symbol_id) {
+            targets.remove(&instance_id);
+            if targets.is_empty() {
+                self.hit_target_map.remove(&symbol_id);
+            }
+        }
+    }
+
+    /// The hit-test candidates of all visible sprites of the symbol, in the drawing order.
+    pub fn hit_test_candidates(&self, symbol_id:SymbolId) -> Vec<hit_test::Candidate> {
+        let targets = self.hit_target_map.get(&symbol_id);
+        targets.map(|t| t.values().filter_map(|t| t.candidate()).collect()).unwrap_or_default()
     }
 
     pub fn get_mouse_target(&mut self, target:PointerTarget) -> Option<Rc<dyn MouseTarget>> {
@@ -310,6 +341,7 @@ pub struct Mouse {
     pub position      : Uniform<Vector2<i32>>,
     pub hover_ids     : Uniform<Vector4<u32>>,
     pub target        : Rc<Cell<PointerTarget>>,
+    pub picking_mode  : Rc<Cell<PickingMode>>,
     pub handles       : Rc<[callback::Handle;3]>,
     pub frp           : enso_frp::io::Mouse,
     pub scene_frp     : Frp,
@@ -330,6 +362,7 @@ impl Mouse {
         let position        = variables.add_or_panic("mouse_position",Vector2::new(0,0));
         let hover_ids       = variables.add_or_panic("mouse_hover_ids",target.to_internal(&logger));
         let target          = Rc::new(Cell::new(target));
+        let picking_mode    = default();
         let mouse_manager   = MouseManager::new_separated(&root.clone_ref().into(),&web::window());
         let frp             = frp::io::Mouse::new();
         let on_move         = mouse_manager.on_move.add(current_js_event.make_event_handler(
@@ -357,7 +390,8 @@ impl Mouse {
             f!((event:&mouse::OnUp) frp.up.emit(event.button())))
         );
         let handles = Rc::new([on_move,on_down,on_up]);
-        Self {mouse_manager,last_position,position,hover_ids,target,handles,frp,scene_frp,logger}
+        Self {mouse_manager,last_position,position,hover_ids,target,picking_mode,handles,frp
+             ,scene_frp,logger}
     }
 
     /// Re-emits FRP mouse changed position event with the last mouse position value.
@@ -743,6 +777,7 @@ impl Extensions {
 #[derive(Clone,CloneRef,Debug)]
 pub struct SceneData {
     pub display_object   : display::object::Instance,
+    pub spatial_index    : display::object::SpatialIndex,
     pub dom              : Dom,
     pub context          : Context,
     pub symbols          : SymbolRegistry,
@@ -752,6 +787,7 @@ pub struct SceneData {
     pub keyboard         : Keyboard,
     pub uniforms         : Uniforms,
     pub shapes           : ShapeRegistry,
+    pub culling          : culling::Registry,
     pub stats            : Stats,
     pub dirty            : Dirty,
     pub logger           : Logger,
@@ -777,6 +813,8 @@ impl SceneData {
 
         let display_object       = display::object::Instance::new(&logger);
         display_object.force_set_visibility(true);
+        let spatial_index        = display::object::SpatialIndex::new();
+        display_object.set_spatial_index(&spatial_index);
         let context              = web::get_webgl2_context(&dom.layers.canvas);
         let sub_logger           = Logger::new_sub(&logger,"shape_dirty");
         let shape_dirty          = ShapeDirty::new(sub_logger,Box::new(on_mut.clone()));
@@ -794,6 +832,7 @@ impl SceneData {
         let layers               = HardcodedLayers::new(&logger);
         let stats                = stats.clone();
         let shapes               = ShapeRegistry::default();
+        let culling              = culling::Registry::default();
         let uniforms             = Uniforms::new(&variables);
         let dirty                = Dirty {symbols:symbols_dirty,shape:shape_dirty};
         let renderer             = Renderer::new(&logger,&dom,&context,&variables);
@@ -820,9 +859,9 @@ impl SceneData {
         }
 
         uniforms.pixel_ratio.set(dom.shape().pixel_ratio);
-        Self {display_object,dom,context,symbols,variables,current_js_event,mouse,keyboard,uniforms
-             ,shapes,stats,dirty,logger,renderer,layers,style_sheet,bg_color_var,bg_color_change,frp
-             ,extensions,disable_context_menu}
+        Self {display_object,spatial_index,dom,context,symbols,variables,current_js_event,mouse
+             ,keyboard,uniforms,shapes,culling,stats,dirty,logger,renderer,layers,style_sheet
+             ,bg_color_var,bg_color_change,frp,extensions,disable_context_menu}
     }
 
     pub fn shape(&self) -> &frp::Sampler<Shape> {
@@ -841,8 +880,66 @@ impl SceneData {
         &self.symbols
     }
 
+    /// Display objects whose world-space bounds intersect the area. Only objects with bounds are
+    /// considered, see [`display::object::ObjectOps::set_bounds`]. The scene has to be updated for
+    /// the result to reflect recent changes.
+    pub fn objects_intersecting(&self, area:&BoundingBox) -> Vec<display::object::Instance> {
+        self.spatial_index.objects_intersecting(area)
+    }
+
+    /// Display objects whose world-space bounds contain the point.
+    pub fn objects_containing(&self, point:Vector2<f32>) -> Vec<display::object::Instance> {
+        self.spatial_index.objects_containing(point)
+    }
+
+    /// Display objects whose world-space bounds intersect the area visible by the camera. See
+    /// [`Camera2d::visible_area`] to learn more.
+    pub fn objects_visible_in(&self, camera:&Camera2d) -> Vec<display::object::Instance> {
+        self.objects_intersecting(&camera.visible_area())
+    }
+
+    /// Enable or disable the view frustum culling of sprites. See the [`culling`] module docs to
+    /// learn more.
+    pub fn set_culling_enabled(&self, enabled:bool) {
+        self.culling.set_enabled(enabled);
+    }
+
+    /// Set the method of finding the mouse target. See the [`hit_test`] module docs to learn more.
+    pub fn set_picking_mode(&self, mode:PickingMode) {
+        self.mouse.picking_mode.set(mode);
+    }
+
+    /// The method of finding the mouse target.
+    pub fn picking_mode(&self) -> PickingMode {
+        self.mouse.picking_mode.get()
+    }
+
+    /// Find the shape at the given position (in DOM pixels, computed from the top left corner of
+    /// the screen) on CPU. The scene has to be updated for the result to reflect recent changes.
+    pub fn hit_test(&self, position:Vector2<f32>, mode:PickingMode) -> PointerTarget {
+        let pointer = hit_test::Pointer::new(position,self.dom.shape());
+        self.hit_test_layer(&self.layers.root).hit_test(&pointer,mode)
+    }
+
+    fn hit_test_layer(&self, layer:&Layer) -> hit_test::Layer {
+        let mut snapshot     = hit_test::Layer::new(layer.camera().view_projection_matrix());
+        snapshot.scissor_box = layer.scissor_box();
+        snapshot.mask        = layer.mask().map(|mask| Box::new(self.hit_test_layer(&mask)));
+        snapshot.candidates  = layer.symbols().into_iter().flat_map(|symbol_id| {
+            self.shapes.hit_test_candidates(symbol_id)
+        }).collect();
+        snapshot.sublayers   = layer.sublayers().iter().map(|l| self.hit_test_layer(l)).collect();
+        snapshot
+    }
+
     fn handle_mouse_events(&self) {
-        let new_target     = PointerTarget::from_internal(self.mouse.hover_ids.get());
+        let new_target     = match self.picking_mode() {
+            PickingMode::PixelRead => PointerTarget::from_internal(self.mouse.hover_ids.get()),
+            mode                   => {
+                let position = self.mouse.last_position.get();
+                self.hit_test(Vector2(position.x as f32,position.y as f32),mode)
+            }
+        };
         let current_target = self.mouse.target.get();
         if new_target != current_target {
             self.mouse.target.set(new_target);
@@ -994,6 +1091,7 @@ impl Scene {
             self.update_camera(self);
             self.display_object.update(self);
             self.layers.update();
+            self.culling.update(&self.layers.root,&self.stats);
             self.update_shape();
             self.update_symbols();
             self.handle_mouse_events();
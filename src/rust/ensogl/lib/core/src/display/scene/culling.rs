@@ -0,0 +1,128 @@
+//! View frustum culling of sprites. Every frame, the area visible by the camera of each layer is
+//! computed and passed to the sprite systems whose symbols are rendered in the layer. Symbols
+//! rendered in several layers use the union of their areas. See [`Culling`] to learn how the
+//! sprites are culled.
+
+use crate::prelude::*;
+
+use crate::data::BoundingBox;
+use crate::debug::stats::Stats;
+use crate::display::scene::Layer;
+use crate::display::symbol::SymbolId;
+use crate::display::symbol::geometry::compound::sprite::Culling;
+use crate::display::symbol::geometry::compound::sprite::WeakCulling;
+
+
+
+// ================
+// === Registry ===
+// ================
+
+/// The culling of all sprite systems of a scene. Culling is enabled by default.
+#[derive(Clone,CloneRef,Debug)]
+pub struct Registry {
+    systems : Rc<RefCell<HashMap<SymbolId,WeakCulling>>>,
+    enabled : Rc<Cell<bool>>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        let systems = default();
+        let enabled = Rc::new(Cell::new(true));
+        Self {systems,enabled}
+    }
+}
+
+impl Registry {
+    /// Register the culling of the sprite system of the given symbol.
+    pub fn add(&self, symbol_id:SymbolId, culling:&Culling) {
+        self.systems.borrow_mut().insert(symbol_id,culling.downgrade());
+    }
+
+    /// Enable or disable the culling. Disabled culling draws all shown sprites.
+    pub fn set_enabled(&self, enabled:bool) {
+        self.enabled.set(enabled);
+    }
+
+    /// Check whether the culling is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.get()
+    }
+
+    /// Update the visible areas of all sprite systems and report the numbers of drawn and culled
+    /// sprites in the stats. Should be called after the display objects were updated.
+    pub fn update(&self, root:&Layer, stats:&Stats) {
+        let mut areas = HashMap::new();
+        if self.enabled.get() {
+            collect_visible_areas(root,&mut areas);
+        }
+        self.set_areas(&areas,stats);
+    }
+
+    /// Pass the visible areas to the registered sprite systems. Systems missing in the map are not
+    /// culled. Systems which no longer exist are removed from the registry.
+    fn set_areas(&self, areas:&HashMap<SymbolId,BoundingBox>, stats:&Stats) {
+        let mut drawn  = 0;
+        let mut culled = 0;
+        self.systems.borrow_mut().retain(|symbol_id,culling| {
+            culling.upgrade().map(|culling| {
+                culling.set_area(areas.get(symbol_id).copied());
+                drawn  += culling.drawn_count();
+                culled += culling.culled_count();
+            }).is_some()
+        });
+        stats.set_drawn_sprite_count(drawn);
+        stats.set_culled_sprite_count(culled);
+    }
+}
+
+fn collect_visible_areas(layer:&Layer, areas:&mut HashMap<SymbolId,BoundingBox>) {
+    let area = layer.camera().visible_area();
+    for symbol_id in layer.symbols() {
+        areas.entry(symbol_id).and_modify(|other| *other = other.union(&area)).or_insert(area);
+    }
+    if let Some(mask) = layer.mask() {
+        collect_visible_areas(&mask,areas);
+    }
+    for sublayer in layer.sublayers() {
+        collect_visible_areas(&sublayer,areas);
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passing_areas_to_registered_systems() {
+        let registry = Registry::default();
+        let stats    = Stats::default();
+        let first    = Culling::default();
+        let second   = Culling::default();
+        registry.add(SymbolId::new(1),&first);
+        registry.add(SymbolId::new(2),&second);
+        assert!(registry.is_enabled());
+
+        let area  = BoundingBox::from_corners(Vector2(0.0,0.0),Vector2(10.0,10.0));
+        let areas = vec![(SymbolId::new(1),area)].into_iter().collect();
+        registry.set_areas(&areas,&stats);
+        assert_eq!(first.area(), Some(area));
+        assert_eq!(second.area(), None);
+        assert_eq!(stats.drawn_sprite_count(), 0);
+        assert_eq!(stats.culled_sprite_count(), 0);
+
+        drop(second);
+        registry.set_areas(&default(),&stats);
+        assert_eq!(first.area(), None);
+        assert_eq!(registry.systems.borrow().len(), 1);
+
+        registry.set_enabled(false);
+        assert!(!registry.is_enabled());
+    }
+}
@@ -0,0 +1,375 @@
+//! CPU hit-testing of shapes. It is an alternative to the GPU picking done by the
+//! [`PixelReadPass`], which reads the symbol and instance ids under the cursor back from the
+//! rendered frame. The read is asynchronous, so the result is available a frame later at the
+//! earliest, and it requires a WebGL context. The hit-test implemented here computes the
+//! [`PointerTarget`] analytically from the sprite transformations and sizes, and optionally from
+//! the shape definitions evaluated with the [`cpu`] module. It follows the rules of the
+//! [`SymbolsRenderPass`]: layers are tested from the topmost one, and the scissor boxes and masks
+//! of layers limit the area in which their shapes can be hit.
+//!
+//! [`PixelReadPass`]: crate::display::render::passes::PixelReadPass
+//! [`SymbolsRenderPass`]: crate::display::render::passes::SymbolsRenderPass
+
+use crate::prelude::*;
+
+use crate::display::traits::*;
+
+use crate::display::scene::PointerTarget;
+use crate::display::scene::Shape;
+use crate::display::scene::layer::ScissorBox;
+use crate::display::shape::primitive::cpu::Sample;
+use crate::display::shape::primitive::cpu::Sampler;
+use crate::display::shape::primitive::def::AnyShape;
+use crate::display::shape::primitive::system::ShapeSystem;
+use crate::display::symbol::geometry::Sprite;
+
+
+
+// ===================
+// === PickingMode ===
+// ===================
+
+/// The method of finding the shape under the cursor.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub enum PickingMode {
+    /// Read the symbol and instance ids from the rendered frame on GPU.
+    PixelRead,
+    /// Test the cursor against the bounding boxes of sprites on CPU.
+    CpuBounds,
+    /// Test the cursor against the bounding boxes of sprites and then evaluate their shapes on
+    /// CPU. Shapes using GPU parameters other than the sprite size cannot be evaluated, so they are
+    /// tested against their bounding boxes only.
+    CpuShapes,
+}
+
+impl PickingMode {
+    /// Checks whether the mode requires reading pixels from the rendered frame.
+    pub fn uses_pixel_read(self) -> bool {
+        self == Self::PixelRead
+    }
+}
+
+impl Default for PickingMode {
+    fn default() -> Self {
+        Self::PixelRead
+    }
+}
+
+
+
+// ===============
+// === Pointer ===
+// ===============
+
+/// The position of the cursor in the screen space.
+#[derive(Clone,Copy,Debug)]
+pub struct Pointer {
+    /// The position in DOM pixels, computed from the top left corner of the screen.
+    pub position : Vector2<f32>,
+    /// The shape of the screen.
+    pub screen   : Shape,
+}
+
+impl Pointer {
+    /// Constructor.
+    pub fn new(position:Vector2<f32>, screen:Shape) -> Self {
+        Self {position,screen}
+    }
+
+    /// The position in the normalized device coordinates.
+    fn clip_space(&self) -> Vector2<f32> {
+        let x = 2.0 * self.position.x / self.screen.width - 1.0;
+        let y = 1.0 - 2.0 * self.position.y / self.screen.height;
+        Vector2(x,y)
+    }
+
+    /// The position in device pixels, computed from the bottom left corner of the screen, like the
+    /// coordinates of [`ScissorBox`].
+    fn device_position(&self) -> Vector2<f32> {
+        let x = self.position.x;
+        let y = self.screen.height - self.position.y;
+        Vector2(x,y) * self.screen.pixel_ratio
+    }
+
+    fn is_inside(&self, scissor_box:&ScissorBox) -> bool {
+        let position = self.device_position();
+        let min      = Vector2(scissor_box.min_x as f32,scissor_box.min_y as f32);
+        let max      = Vector2(scissor_box.max_x as f32,scissor_box.max_y as f32);
+        position.x >= min.x && position.x < max.x && position.y >= min.y && position.y < max.y
+    }
+}
+
+
+
+// =================
+// === Candidate ===
+// =================
+
+/// A sprite which can be hit by the cursor.
+#[derive(Clone,Debug)]
+pub struct Candidate {
+    /// The target reported when the sprite is hit.
+    pub target         : PointerTarget,
+    /// The world transformation matrix of the sprite.
+    pub transform      : Matrix4<f32>,
+    /// The size of the sprite.
+    pub size           : Vector2<f32>,
+    /// The position of the sprite origin, relative to its size. See [`SpriteSystem::origin`].
+    ///
+    /// [`SpriteSystem::origin`]: crate::display::symbol::geometry::SpriteSystem::origin
+    pub origin         : Vector2<f32>,
+    /// The shape drawn on the sprite, if it should be evaluated.
+    pub shape          : Option<AnyShape>,
+    /// Whether the sprite reacts to the mouse. Sprites not reacting to the mouse still take part
+    /// in masking.
+    pub pointer_events : bool,
+}
+
+impl Candidate {
+    /// Constructor of a centered sprite reacting to the mouse.
+    pub fn new(target:PointerTarget, transform:Matrix4<f32>, size:Vector2<f32>) -> Self {
+        let origin         = Vector2(0.5,0.5);
+        let shape          = None;
+        let pointer_events = true;
+        Self {target,transform,size,origin,shape,pointer_events}
+    }
+
+    /// The cursor position in the local space of the sprite. The cursor is projected on the plane
+    /// parallel to the screen, containing the sprite origin.
+    fn local_position(&self, view_projection:&Matrix4<f32>, pointer:&Pointer) -> Option<Vector2> {
+        let model_view_projection = view_projection * self.transform;
+        let origin                = model_view_projection * Vector4(0.0,0.0,0.0,1.0);
+        let clip_space            = pointer.clip_space() * origin.w;
+        let clip_space            = Vector4(clip_space.x,clip_space.y,origin.z,origin.w);
+        let local                 = model_view_projection.try_inverse()? * clip_space;
+        (local.w != 0.0).then(|| local.xy() / local.w)
+    }
+
+    fn contains(&self, position:Vector2<f32>, mode:PickingMode, pixel_ratio:f32) -> bool {
+        let min       = -self.origin.component_mul(&self.size);
+        let max       = min + self.size;
+        let in_bounds = position.x >= min.x && position.x <= max.x
+                     && position.y >= min.y && position.y <= max.y;
+        in_bounds && match (mode,&self.shape) {
+            (PickingMode::CpuShapes,Some(shape)) => {
+                let mut sampler = Sampler::new(self.size);
+                sampler.set_pixel_ratio(pixel_ratio);
+                shape.sample(&sampler,position).map_or(true,|shape| shape.alpha > 0.0)
+            }
+            _ => true
+        }
+    }
+}
+
+
+
+// =================
+// === HitTarget ===
+// =================
+
+/// A sprite of a shape system registered for CPU hit-testing. See [`ShapeRegistry`] to learn more.
+///
+/// [`ShapeRegistry`]: crate::display::scene::ShapeRegistry
+#[derive(Clone,CloneRef,Debug)]
+pub struct HitTarget {
+    sprite       : Sprite,
+    shape_system : ShapeSystem,
+}
+
+impl HitTarget {
+    /// Constructor.
+    pub fn new(sprite:&Sprite, shape_system:&ShapeSystem) -> Self {
+        let sprite       = sprite.clone_ref();
+        let shape_system = shape_system.clone_ref();
+        Self {sprite,shape_system}
+    }
+
+    /// The hit-test candidate of the current state of the sprite. Returns [`None`] if the sprite is
+    /// not visible.
+    pub fn candidate(&self) -> Option<Candidate> {
+        let size    = self.sprite.size.get();
+        let visible = self.sprite.is_visible();
+        (visible && size != zero()).then(|| {
+            let target         = PointerTarget::Symbol {
+                symbol_id   : self.sprite.symbol_id(),
+                instance_id : self.sprite.instance_id,
+            };
+            let transform      = self.sprite.transform_matrix();
+            let origin         = self.shape_system.sprite_system.origin();
+            let shape          = Some(self.shape_system.shape.borrow().clone());
+            let pointer_events = self.shape_system.pointer_events.get();
+            Candidate {target,transform,size,origin,shape,pointer_events}
+        })
+    }
+}
+
+
+
+// =============
+// === Layer ===
+// =============
+
+/// A snapshot of a scene [`Layer`] used for hit-testing.
+///
+/// [`Layer`]: crate::display::scene::Layer
+#[derive(Clone,Debug,Default)]
+pub struct Layer {
+    /// The view-projection matrix of the layer camera.
+    pub view_projection : Matrix4<f32>,
+    /// The scissor box of the layer. The scissor boxes of parent layers apply as well.
+    pub scissor_box     : Option<ScissorBox>,
+    /// The mask of the layer. Shapes of the layer can be hit only where the mask has a shape.
+    pub mask            : Option<Box<Layer>>,
+    /// The sprites of the layer in the drawing order, from the bottom to the top.
+    pub candidates      : Vec<Candidate>,
+    /// The sublayers in the drawing order. They are drawn above the sprites of this layer.
+    pub sublayers       : Vec<Layer>,
+}
+
+impl Layer {
+    /// Constructor.
+    pub fn new(view_projection:Matrix4<f32>) -> Self {
+        Self {view_projection,..default()}
+    }
+
+    /// Find the topmost sprite under the cursor.
+    pub fn hit_test(&self, pointer:&Pointer, mode:PickingMode) -> PointerTarget {
+        self.find(pointer,mode,None,false).unwrap_or(PointerTarget::Background)
+    }
+
+    /// Find the topmost sprite under the cursor. If `masking` is set, the sprites are tested
+    /// regardless of their pointer events setting.
+    fn find
+    ( &self
+    , pointer            : &Pointer
+    , mode               : PickingMode
+    , parent_scissor_box : Option<ScissorBox>
+    , masking            : bool
+    ) -> Option<PointerTarget> {
+        let scissor_box = parent_scissor_box.concat(self.scissor_box);
+        if scissor_box.map_or(false,|scissor_box| !pointer.is_inside(&scissor_box)) {
+            return None
+        }
+        if let Some(mask) = &self.mask {
+            mask.find(pointer,mode,scissor_box,true)?;
+        }
+        let mut sublayers = self.sublayers.iter().rev();
+        let in_sublayer   = sublayers.find_map(|l| l.find(pointer,mode,scissor_box,masking));
+        in_sublayer.or_else(|| {
+            let pixel_ratio    = pointer.screen.pixel_ratio;
+            let candidates     = self.candidates.iter().rev();
+            let mut candidates = candidates.filter(|candidate| masking || candidate.pointer_events);
+            let hit            = candidates.find(|candidate| {
+                let position = candidate.local_position(&self.view_projection,pointer);
+                position.map_or(false,|position| candidate.contains(position,mode,pixel_ratio))
+            });
+            hit.map(|candidate| candidate.target)
+        })
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::display::shape::primitive::def::primitive::Circle;
+    use crate::display::shape::primitive::def::unit::*;
+    use crate::display::shape::primitive::def::var::Var;
+    use crate::display::symbol::SymbolId;
+    use crate::system::gpu::data::attribute::InstanceIndex;
+
+    /// A camera showing the screen of 100x100 pixels with the origin in its center.
+    fn view_projection() -> Matrix4<f32> {
+        Matrix4::new_nonuniform_scaling(&Vector3(0.02,0.02,1.0))
+    }
+
+    fn pointer(x:f32, y:f32) -> Pointer {
+        let screen = Shape {width:100.0, height:100.0, pixel_ratio:2.0};
+        Pointer::new(Vector2(x,y),screen)
+    }
+
+    fn target(symbol:u32, instance:usize) -> PointerTarget {
+        let symbol_id   = SymbolId::new(symbol);
+        let instance_id = InstanceIndex::new(instance);
+        PointerTarget::Symbol {symbol_id,instance_id}
+    }
+
+    fn candidate(target:PointerTarget, x:f32, y:f32, size:f32) -> Candidate {
+        let transform = Matrix4::new_translation(&Vector3(x,y,0.0));
+        Candidate::new(target,transform,Vector2(size,size))
+    }
+
+    #[test]
+    fn hitting_sprites() {
+        let mut layer = Layer::new(view_projection());
+        let mut below = candidate(target(1,0),0.0,0.0,40.0);
+        below.origin  = Vector2(0.0,0.0);
+        layer.candidates.push(below);
+        layer.candidates.push(candidate(target(1,1),0.0,0.0,10.0));
+        let mut rotated   = candidate(target(1,2),-30.0,30.0,10.0);
+        rotated.transform *= Matrix4::from_euler_angles(0.0,0.0,std::f32::consts::PI/4.0);
+        layer.candidates.push(rotated);
+        let bounds = PickingMode::CpuBounds;
+
+        assert_eq!(layer.hit_test(&pointer(50.0,50.0),bounds), target(1,1));
+        assert_eq!(layer.hit_test(&pointer(70.0,40.0),bounds), target(1,0));
+        assert_eq!(layer.hit_test(&pointer(40.0,40.0),bounds), PointerTarget::Background);
+        assert_eq!(layer.hit_test(&pointer(20.0,20.0),bounds), target(1,2));
+        assert_eq!(layer.hit_test(&pointer(24.0,24.0),bounds), PointerTarget::Background);
+        assert_eq!(layer.hit_test(&pointer(26.0,20.0),bounds), target(1,2));
+
+        layer.candidates[1].pointer_events = false;
+        assert_eq!(layer.hit_test(&pointer(52.0,48.0),bounds), target(1,0));
+    }
+
+    #[test]
+    fn evaluating_shapes() {
+        let mut layer  = Layer::new(view_projection());
+        let mut circle = candidate(target(1,0),0.0,0.0,20.0);
+        circle.shape   = Some(Circle(10.px()).into());
+        layer.candidates.push(circle);
+        let corner = pointer(59.0,41.0);
+
+        assert_eq!(layer.hit_test(&corner,PickingMode::CpuBounds), target(1,0));
+        assert_eq!(layer.hit_test(&corner,PickingMode::CpuShapes), PointerTarget::Background);
+        assert_eq!(layer.hit_test(&pointer(58.0,50.0),PickingMode::CpuShapes), target(1,0));
+
+        let unknown_param = Var::<Pixels>::from("input_radius");
+        layer.candidates[0].shape = Some(Circle(unknown_param).into());
+        assert_eq!(layer.hit_test(&corner,PickingMode::CpuShapes), target(1,0));
+    }
+
+    #[test]
+    fn respecting_layers() {
+        let mut root   = Layer::new(view_projection());
+        let mut bottom = Layer::new(view_projection());
+        let mut top    = Layer::new(view_projection());
+        root.candidates.push(candidate(target(1,0),0.0,0.0,100.0));
+        bottom.candidates.push(candidate(target(2,0),0.0,0.0,20.0));
+        top.candidates.push(candidate(target(3,0),10.0,0.0,20.0));
+        let bounds = PickingMode::CpuBounds;
+
+        // The scissor box covers the right half of the screen, in device pixels.
+        let position    = Vector2(100,0);
+        let size        = Vector2(100,200);
+        top.scissor_box = Some(ScissorBox::new_with_position_and_size(position,size));
+        root.sublayers  = vec![bottom,top];
+        assert_eq!(root.hit_test(&pointer(55.0,50.0),bounds), target(3,0));
+        assert_eq!(root.hit_test(&pointer(45.0,50.0),bounds), target(2,0));
+        assert_eq!(root.hit_test(&pointer(90.0,50.0),bounds), target(1,0));
+
+        let mut mask       = Layer::new(view_projection());
+        let mut mask_shape = candidate(target(4,0),15.0,0.0,10.0);
+        mask_shape.pointer_events = false;
+        mask.candidates.push(mask_shape);
+        root.sublayers[1].mask = Some(Box::new(mask));
+        assert_eq!(root.hit_test(&pointer(65.0,50.0),bounds), target(3,0));
+        assert_eq!(root.hit_test(&pointer(55.0,50.0),bounds), target(2,0));
+    }
+}
@@ -1,5 +1,6 @@
 //! Root module for primitive shapes and their modifiers.
 
+pub mod cpu;
 pub mod def;
 pub mod shader;
 pub mod system;
@@ -0,0 +1,494 @@
+//! CPU evaluation of shapes.
+//!
+//! Shapes are normally compiled to GLSL by the [`shader::builder`] and rendered on GPU. This module
+//! interprets the same shape definitions on CPU, following the rules of `shape.glsl` and the
+//! fragment runner, including the anti-aliasing and premultiplied color blending. It allows
+//! testing how components look without a browser, by rasterizing their shapes to [`Image`]s and
+//! comparing them with golden images (see [`golden`]).
+//!
+//! [`shader::builder`]: crate::display::shape::primitive::shader::builder
+
+pub mod expr;
+#[cfg(not(target_arch="wasm32"))]
+pub mod golden;
+pub mod image;
+pub mod modifier;
+pub mod primitive;
+
+pub use image::Image;
+
+use crate::prelude::*;
+
+use crate::data::color;
+use crate::display::shape::primitive::def::var::Var;
+use crate::system::gpu::shader::glsl::Glsl;
+
+use expr::Value;
+
+
+
+// =============
+// === Error ===
+// =============
+
+/// An error of shape evaluation.
+#[derive(Clone,Debug,Fail,From,PartialEq)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[fail(display="{}",_0)]
+    Expression(expr::Error),
+    #[fail(display="The GLSL expression `{}` does not evaluate to {}.",code,expected)]
+    WrongType {code:String, expected:&'static str},
+}
+
+/// The result of shape evaluation.
+pub type Result<T> = std::result::Result<T,Error>;
+
+
+
+// =================
+// === FromValue ===
+// =================
+
+/// Types which values of dynamic [`Var`]s can be evaluated to.
+pub trait FromValue : Sized {
+    /// Description of the expected value used in error messages.
+    const EXPECTED : &'static str;
+    /// Convert the evaluated value. Returns [`None`] if the value has a wrong dimension.
+    fn from_value(value:Value) -> Option<Self>;
+}
+
+impl FromValue for f32 {
+    const EXPECTED : &'static str = "a float";
+    fn from_value(value:Value) -> Option<Self> {
+        value.as_float()
+    }
+}
+
+impl FromValue for Pixels {
+    const EXPECTED : &'static str = "a float";
+    fn from_value(value:Value) -> Option<Self> {
+        value.as_float().map(Pixels::from)
+    }
+}
+
+impl FromValue for Radians {
+    const EXPECTED : &'static str = "an angle";
+    fn from_value(value:Value) -> Option<Self> {
+        value.as_float().map(Radians::from)
+    }
+}
+
+impl FromValue for Vector2<Pixels> {
+    const EXPECTED : &'static str = "a vec2";
+    fn from_value(value:Value) -> Option<Self> {
+        match value {
+            Value::Vec2(t) => Some(Vector2(t.x.into(),t.y.into())),
+            _              => None,
+        }
+    }
+}
+
+impl FromValue for color::Rgba {
+    const EXPECTED : &'static str = "a color";
+    fn from_value(value:Value) -> Option<Self> {
+        match value {
+            Value::Vec4(t) => Some(color::Rgba::new(t.x,t.y,t.z,t.w)),
+            _              => None,
+        }
+    }
+}
+
+
+
+// ===================
+// === BoundingBox ===
+// ===================
+
+/// Describes the rectangular convex hull of an object. Infinite bounding boxes are represented as
+/// 0-sized ones, as in `shape.glsl`.
+#[derive(Clone,Copy,Debug,Default,PartialEq)]
+#[allow(missing_docs)]
+pub struct BoundingBox {
+    pub min_x : f32,
+    pub max_x : f32,
+    pub min_y : f32,
+    pub max_y : f32,
+}
+
+impl BoundingBox {
+    /// Constructor.
+    pub fn new(min_x:f32, max_x:f32, min_y:f32, max_y:f32) -> Self {
+        Self {min_x,max_x,min_y,max_y}
+    }
+
+    /// A bounding box spanning from `-w` to `w` horizontally and from `-h` to `h` vertically.
+    pub fn symmetric(w:f32, h:f32) -> Self {
+        Self::new(-w,w,-h,h)
+    }
+
+    /// A bounding box of the given size centered at the origin.
+    pub fn from_size(size:Vector2<f32>) -> Self {
+        Self::symmetric(size.x / 2.0, size.y / 2.0)
+    }
+
+    /// The representation of an infinite bounding box.
+    pub fn infinite() -> Self {
+        default()
+    }
+
+    /// The bounding box of the union of two shapes.
+    pub fn unify(&self, other:&Self) -> Self {
+        let min_x = self.min_x.min(other.min_x);
+        let max_x = self.max_x.max(other.max_x);
+        let min_y = self.min_y.min(other.min_y);
+        let max_y = self.max_y.max(other.max_y);
+        Self {min_x,max_x,min_y,max_y}
+    }
+
+    /// The bounding box of the intersection of two shapes.
+    pub fn intersection(&self, other:&Self) -> Self {
+        let min_x = self.min_x.max(other.min_x);
+        let max_x = self.max_x.min(other.max_x);
+        let min_y = self.min_y.max(other.min_y);
+        let max_y = self.max_y.min(other.max_y);
+        Self {min_x,max_x,min_y,max_y}
+    }
+
+    /// The bounding box grown by the given value in every direction.
+    pub fn grow(&self, value:f32) -> Self {
+        Self::new(self.min_x - value, self.max_x + value, self.min_y - value, self.max_y + value)
+    }
+}
+
+
+
+// ================
+// === BoundSdf ===
+// ================
+
+/// Signed distance field value with associated bounds.
+#[derive(Clone,Copy,Debug,PartialEq)]
+#[allow(missing_docs)]
+pub struct BoundSdf {
+    pub distance : f32,
+    pub bounds   : BoundingBox,
+}
+
+impl BoundSdf {
+    /// Constructor.
+    pub fn new(distance:f32, bounds:BoundingBox) -> Self {
+        Self {distance,bounds}
+    }
+
+    fn resample(mut self, multiplier:f32) -> Self {
+        self.distance *= multiplier;
+        self
+    }
+
+    fn pixel_snap(mut self) -> Self {
+        self.distance = self.distance.floor() + 0.5;
+        self
+    }
+
+    fn grow(self, size:f32) -> Self {
+        Self::new(self.distance - size, self.bounds.grow(size))
+    }
+
+    fn unify(&self, other:&Self) -> Self {
+        Self::new(self.distance.min(other.distance), self.bounds.unify(&other.bounds))
+    }
+
+    fn intersection(&self, other:&Self) -> Self {
+        Self::new(self.distance.max(other.distance), self.bounds.intersection(&other.bounds))
+    }
+
+    /// The bounds of a difference cannot be computed exactly, so the original bounds are kept.
+    fn difference(&self, other:&Self) -> Self {
+        Self::new(self.distance.max(-other.distance), self.bounds)
+    }
+}
+
+
+
+// =============
+// === Shape ===
+// =============
+
+/// The color of a primitive shape which was not filled.
+pub const DEFAULT_COLOR : Vector4<f32> = Vector4::new(1.0,0.0,0.0,1.0);
+
+/// A shape evaluated at a single point.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub struct Shape {
+    /// The signed distance field value at the point.
+    pub sdf   : BoundSdf,
+    /// Premultiplied sRGBA color at the point.
+    pub color : Vector4<f32>,
+    /// The coverage of the point after anti-aliasing.
+    pub alpha : f32,
+}
+
+fn premultiply(color:Vector4<f32>) -> Vector4<f32> {
+    Vector4(color.x * color.w, color.y * color.w, color.z * color.w, color.w)
+}
+
+/// Unpremultiply the color. The result for a fully transparent color is undefined on GPU; here it
+/// is a transparent black.
+fn unpremultiply(color:Vector4<f32>) -> Vector4<f32> {
+    if color.w == 0.0 { default() }
+    else              { Vector4(color.x / color.w, color.y / color.w, color.z / color.w, color.w) }
+}
+
+/// Blend premultiplied colors, like `glBlendFunc(GL_ONE,GL_ONE_MINUS_SRC_ALPHA)`.
+fn blend(bg:Vector4<f32>, fg:Vector4<f32>) -> Vector4<f32> {
+    fg + bg * (1.0 - fg.w)
+}
+
+impl Shape {
+    /// Create a shape with a not premultiplied sRGBA color, like `shape` in `shape.glsl`.
+    pub fn new(sampler:&Sampler, sdf:BoundSdf, mut color:Vector4<f32>) -> Self {
+        let alpha = sampler.render(&sdf);
+        color.w  *= alpha;
+        Self {sdf,color:premultiply(color),alpha}
+    }
+
+    /// Create a shape with a premultiplied color.
+    pub fn new_premultiplied(sampler:&Sampler, sdf:BoundSdf, color:Vector4<f32>) -> Self {
+        let alpha = sampler.render(&sdf);
+        Self {sdf,color,alpha}
+    }
+
+    /// Create a shape with the modified SDF, keeping the color of this shape.
+    fn with_sdf(&self, sampler:&Sampler, sdf:BoundSdf) -> Self {
+        let mut color = unpremultiply(self.color);
+        color.w       = if self.alpha == 0.0 { 0.0 } else { color.w / self.alpha };
+        Self::new(sampler,sdf,color)
+    }
+
+    /// Resample the shape, after scaling the space by the multiplier.
+    pub fn resample(&self, sampler:&Sampler, multiplier:f32) -> Self {
+        self.with_sdf(sampler,self.sdf.resample(multiplier))
+    }
+
+    /// Make the shape borders crisp.
+    pub fn pixel_snap(&self, sampler:&Sampler) -> Self {
+        self.with_sdf(sampler,self.sdf.pixel_snap())
+    }
+
+    /// Grow the shape by the given value.
+    pub fn grow(&self, sampler:&Sampler, value:f32) -> Self {
+        self.with_sdf(sampler,self.sdf.grow(value))
+    }
+
+    /// The union of shapes. The color of `other` is blended over the color of this shape.
+    pub fn unify(&self, sampler:&Sampler, other:&Self) -> Self {
+        let color = blend(self.color,other.color);
+        Self::new_premultiplied(sampler,self.sdf.unify(&other.sdf),color)
+    }
+
+    /// The difference of shapes, keeping the color of this shape.
+    pub fn difference(&self, sampler:&Sampler, other:&Self) -> Self {
+        Self::new_premultiplied(sampler,self.sdf.difference(&other.sdf),self.color)
+    }
+
+    /// The intersection of shapes. The color of `other` is blended over the color of this shape.
+    pub fn intersection(&self, sampler:&Sampler, other:&Self) -> Self {
+        let color = blend(self.color,other.color);
+        Self::new_premultiplied(sampler,self.sdf.intersection(&other.sdf),color)
+    }
+
+    /// Set the shape's color.
+    pub fn set_color(mut self, color:&color::Rgba) -> Self {
+        let alpha  = color.alpha * self.alpha;
+        self.color = premultiply(Vector4(color.red,color.green,color.blue,alpha));
+        self
+    }
+
+    /// Make the shape's bounds infinite.
+    pub fn with_infinite_bounds(&self, sampler:&Sampler) -> Self {
+        let sdf = BoundSdf::new(self.sdf.distance,BoundingBox::infinite());
+        Self::new_premultiplied(sampler,sdf,self.color)
+    }
+
+    /// The final color written to the framebuffer: premultiplied sRGBA.
+    pub fn output_color(&self) -> Vector4<f32> {
+        let alpha = self.color.w;
+        let color = unpremultiply(self.color);
+        Vector4(color.x * alpha, color.y * alpha, color.z * alpha, alpha)
+    }
+}
+
+
+
+// ==============
+// === Sample ===
+// ==============
+
+/// Shapes which can be evaluated on CPU.
+pub trait Sample {
+    /// Evaluate the shape at the given position of the shape's local space, where the origin is
+    /// in the center of the sprite.
+    fn sample(&self, sampler:&Sampler, position:Vector2<f32>) -> Result<Shape>;
+}
+
+
+
+// ===============
+// === Sampler ===
+// ===============
+
+/// The environment of CPU shape evaluation: the values of the GLSL inputs and the rendering
+/// parameters.
+///
+/// The values of dynamic [`Var`]s are cached, as they do not depend on the sampled position.
+#[derive(Debug)]
+pub struct Sampler {
+    variables   : expr::Variables,
+    pixel_ratio : f32,
+    zoom        : f32,
+    cache       : RefCell<HashMap<String,Value>>,
+}
+
+impl Sampler {
+    /// Create a sampler of a sprite of the given size. The size is bound to the `input_size`
+    /// variable.
+    pub fn new(size:Vector2<f32>) -> Self {
+        let mut variables = expr::Variables::new();
+        variables.insert("input_size".into(),size.into());
+        let pixel_ratio = 1.0;
+        let zoom        = 1.0;
+        let cache       = default();
+        Self {variables,pixel_ratio,zoom,cache}
+    }
+
+    /// Set the value of a GLSL input, like `input_time`.
+    pub fn set_variable(&mut self, name:impl Into<String>, value:impl Into<Value>) {
+        self.variables.insert(name.into(),value.into());
+        self.cache.borrow_mut().clear();
+    }
+
+    /// Set the ratio of device pixels to the shape's units.
+    pub fn set_pixel_ratio(&mut self, pixel_ratio:f32) {
+        self.pixel_ratio = pixel_ratio;
+    }
+
+    /// Set the camera zoom.
+    pub fn set_zoom(&mut self, zoom:f32) {
+        self.zoom = zoom;
+    }
+
+    /// The size of the sampled sprite.
+    pub fn size(&self) -> Vector2<f32> {
+        match self.variables.get("input_size") {
+            Some(Value::Vec2(size)) => *size,
+            _                       => default(),
+        }
+    }
+
+    /// Evaluate the variable.
+    pub fn eval<T>(&self, var:&Var<T>) -> Result<T>
+    where T:Clone+FromValue+RefInto<Glsl> {
+        match var {
+            Var::Static(value) => Ok(value.clone()),
+            Var::Dynamic(code) => {
+                let code   = &code.str;
+                let cached = self.cache.borrow().get(code).copied();
+                let value  = match cached {
+                    Some(value) => value,
+                    None        => {
+                        let value = expr::Expr::parse(code)?.eval(&self.variables)?;
+                        self.cache.borrow_mut().insert(code.clone(),value);
+                        value
+                    }
+                };
+                let error = || Error::WrongType {code:code.clone(), expected:T::EXPECTED};
+                T::from_value(value).ok_or_else(error)
+            }
+        }
+    }
+
+    /// The coverage of a point with the given SDF value, like `render` in `shape.glsl`.
+    pub fn render(&self, sdf:&BoundSdf) -> f32 {
+        ((-sdf.distance * self.pixel_ratio + 0.5) * self.zoom).clamp(0.0,1.0)
+    }
+
+    /// Evaluate the shape in the center of every device pixel of the sprite.
+    pub fn rasterize<S:Sample+?Sized>(&self, shape:&S) -> Result<Image> {
+        let size   = self.size() * self.pixel_ratio;
+        let width  = size.x.ceil() as usize;
+        let height = size.y.ceil() as usize;
+        let mut image = Image::new(width,height);
+        for y in 0..height {
+            for x in 0..width {
+                let pixel    = Vector2(x as f32 + 0.5, height as f32 - y as f32 - 0.5);
+                let position = (pixel - size / 2.0) / self.pixel_ratio;
+                let shape    = shape.sample(self,position)?;
+                image.set(x,y,shape.output_color());
+            }
+        }
+        Ok(image)
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::display::shape::*;
+
+    fn pixel(image:&Image, x:usize, y:usize) -> [u8;4] {
+        image.get(x,y).unwrap()
+    }
+
+    #[test]
+    fn rasterizing_circle() {
+        let sampler = Sampler::new(Vector2(20.0,20.0));
+        let circle  = Circle(5.px()).fill(color::Rgba::new(0.0,0.0,1.0,1.0));
+        let image   = sampler.rasterize(&circle).unwrap();
+        assert_eq!((image.width(),image.height()), (20,20));
+        assert_eq!(pixel(&image,10,10), [0,0,255,255]);
+        assert_eq!(pixel(&image,0,0), [0,0,0,0]);
+
+        // The center of the pixel (14,10) lies on the rectangle's edge, so it is half-covered.
+        let rect  = Rect((9.px(),9.px())).fill(color::Rgba::new(0.0,0.0,1.0,1.0));
+        let image = sampler.rasterize(&rect).unwrap();
+        assert_eq!(pixel(&image,13,10), [0,0,255,255]);
+        assert_eq!(pixel(&image,14,10), [0,0,128,128]);
+        assert_eq!(pixel(&image,15,10), [0,0,0,0]);
+    }
+
+    #[test]
+    fn rasterizing_modified_shapes() {
+        let sampler = Sampler::new(Vector2(40.0,20.0));
+        let width   = Var::<Pixels>::from("input_size.x") - 20.px();
+        let height  = Var::<Pixels>::from("input_size.y") - 10.px();
+        let rect    = Rect((width,height));
+        let hole    = Circle(2.px()).translate_x(5.px());
+        let white   = color::Rgba::new(1.0,1.0,1.0,1.0);
+        let shape   = (rect - hole).fill(white).translate_x(5.px());
+        let image   = sampler.rasterize(&shape).unwrap();
+        assert_eq!(pixel(&image,25,10), [255,255,255,255]);
+        assert_eq!(pixel(&image,30,10), [0,0,0,0]);
+        assert_eq!(pixel(&image,5,10), [0,0,0,0]);
+
+        let union = Circle(3.px()).fill(color::Rgba::new(1.0,0.0,0.0,1.0))
+            + Circle(3.px()).fill(color::Rgba::new(0.0,1.0,0.0,0.5)).translate_x(2.px());
+        let image = sampler.rasterize(&union).unwrap();
+        assert_eq!(pixel(&image,20,10), [128,128,0,255]);
+    }
+
+    #[test]
+    fn reporting_unknown_inputs() {
+        let sampler = Sampler::new(Vector2(10.0,10.0));
+        let circle  = Circle(Var::<Pixels>::from("input_time"));
+        let error   = expr::Error::UnknownVariable("input_time".into());
+        assert_eq!(sampler.rasterize(&circle), Err(Error::Expression(error)));
+    }
+}
@@ -0,0 +1,472 @@
+//! Evaluator of the GLSL expressions stored in dynamic [`Var`]s.
+//!
+//! Only the subset of GLSL generated by the `Var` operators and used in the shape definitions is
+//! supported: number literals, variables, swizzles, the `+ - * /` operators, and a set of builtin
+//! and `math.glsl` functions.
+//!
+//! [`Var`]: crate::display::shape::primitive::def::var::Var
+
+use crate::prelude::*;
+
+use std::f32::consts::PI;
+
+
+
+// =============
+// === Error ===
+// =============
+
+/// An error of evaluating a GLSL expression.
+#[derive(Clone,Debug,Fail,PartialEq)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[fail(display="Cannot parse the GLSL expression `{}`: {}.",code,reason)]
+    Parse {code:String, reason:String},
+    #[fail(display="The GLSL variable `{}` is not defined.",_0)]
+    UnknownVariable(String),
+    #[fail(display="The GLSL function `{}` is not supported by the CPU evaluator.",_0)]
+    UnknownFunction(String),
+    #[fail(display="Wrong arguments of the GLSL function `{}`.",_0)]
+    WrongArguments(String),
+    #[fail(display="The `{}` component is not available in a {}-dimensional value.",_0,_1)]
+    WrongSwizzle(String,usize),
+}
+
+/// The result of GLSL expression evaluation.
+pub type Result<T> = std::result::Result<T,Error>;
+
+
+
+// =============
+// === Value ===
+// =============
+
+/// A value of a GLSL expression. The colors and angles are represented by their raw components.
+#[derive(Clone,Copy,Debug,PartialEq)]
+#[allow(missing_docs)]
+pub enum Value {
+    Float (f32),
+    Vec2  (Vector2<f32>),
+    Vec3  (Vector3<f32>),
+    Vec4  (Vector4<f32>),
+}
+
+impl Value {
+    /// Construct a value from the components. The number of components must be between 1 and 4.
+    fn from_components(components:&[f32]) -> Option<Self> {
+        match *components {
+            [x]         => Some(Self::Float(x)),
+            [x,y]       => Some(Self::Vec2(Vector2(x,y))),
+            [x,y,z]     => Some(Self::Vec3(Vector3(x,y,z))),
+            [x,y,z,w]   => Some(Self::Vec4(Vector4(x,y,z,w))),
+            _           => None,
+        }
+    }
+
+    /// The components of the value.
+    pub fn components(&self) -> Vec<f32> {
+        match self {
+            Self::Float (t) => vec![*t],
+            Self::Vec2  (t) => t.as_slice().to_vec(),
+            Self::Vec3  (t) => t.as_slice().to_vec(),
+            Self::Vec4  (t) => t.as_slice().to_vec(),
+        }
+    }
+
+    /// The number of components.
+    pub fn dim(&self) -> usize {
+        match self {
+            Self::Float (_) => 1,
+            Self::Vec2  (_) => 2,
+            Self::Vec3  (_) => 3,
+            Self::Vec4  (_) => 4,
+        }
+    }
+
+    /// Apply the function to every component.
+    pub fn map(&self, f:impl Fn(f32) -> f32) -> Self {
+        let components = self.components().into_iter().map(f).collect_vec();
+        Self::from_components(&components).unwrap_or(*self)
+    }
+
+    /// Apply the function to the corresponding components of both values. A float is broadcast to
+    /// all components of the other value, as in GLSL.
+    pub fn zip(&self, other:&Self, f:impl Fn(f32,f32) -> f32) -> Option<Self> {
+        let lhs = self.components();
+        let rhs = other.components();
+        let components = match (lhs.len(),rhs.len()) {
+            (1,_)               => rhs.iter().map(|r| f(lhs[0],*r)).collect_vec(),
+            (_,1)               => lhs.iter().map(|l| f(*l,rhs[0])).collect_vec(),
+            (l,r) if l == r     => lhs.iter().zip(&rhs).map(|(l,r)| f(*l,*r)).collect_vec(),
+            _                   => return None,
+        };
+        Self::from_components(&components)
+    }
+
+    /// Get the value as a float.
+    pub fn as_float(&self) -> Option<f32> {
+        match self {
+            Self::Float(t) => Some(*t),
+            _              => None,
+        }
+    }
+}
+
+impl From<f32>          for Value { fn from(t:f32)          -> Self { Self::Float(t) } }
+impl From<Vector2<f32>> for Value { fn from(t:Vector2<f32>) -> Self { Self::Vec2(t)  } }
+impl From<Vector3<f32>> for Value { fn from(t:Vector3<f32>) -> Self { Self::Vec3(t)  } }
+impl From<Vector4<f32>> for Value { fn from(t:Vector4<f32>) -> Self { Self::Vec4(t)  } }
+
+
+
+// =============
+// === Lexer ===
+// =============
+
+#[derive(Clone,Debug,PartialEq)]
+enum Token {
+    Number (f32),
+    Ident  (String),
+    Symbol (char),
+}
+
+fn tokenize(code:&str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars  = code.chars().peekable();
+    while let Some(&char) = chars.peek() {
+        if char.is_whitespace() {
+            chars.next();
+        } else if char.is_ascii_digit() || char == '.' && tokens_allow_number(&tokens) {
+            let mut literal = String::new();
+            while let Some(&char) = chars.peek() {
+                let is_exponent_sign = (char == '-' || char == '+') && literal.ends_with('e');
+                if char.is_ascii_digit() || char == '.' || char == 'e' || is_exponent_sign {
+                    literal.push(char);
+                    chars.next();
+                } else {
+                    break
+                }
+            }
+            tokens.push(Token::Number(literal.parse().ok()?));
+        } else if char.is_alphabetic() || char == '_' {
+            let mut ident = String::new();
+            while let Some(&char) = chars.peek() {
+                if char.is_alphanumeric() || char == '_' {
+                    ident.push(char);
+                    chars.next();
+                } else {
+                    break
+                }
+            }
+            tokens.push(Token::Ident(ident));
+        } else if "(),.+-*/".contains(char) {
+            tokens.push(Token::Symbol(char));
+            chars.next();
+        } else {
+            return None
+        }
+    }
+    Some(tokens)
+}
+
+/// Checks if a dot at the current position starts a number rather than a swizzle.
+fn tokens_allow_number(tokens:&[Token]) -> bool {
+    !matches!(tokens.last(), Some(Token::Ident(_)) | Some(Token::Symbol(')')))
+}
+
+
+
+// ==============
+// === Parser ===
+// ==============
+
+/// A parsed GLSL expression.
+#[derive(Clone,Debug,PartialEq)]
+pub enum Expr {
+    /// A number literal.
+    Number(f32),
+    /// A variable.
+    Variable(String),
+    /// A component selection, like `size.x` or `color.raw`.
+    Swizzle(Box<Expr>,String),
+    /// A function call. The operators are represented as calls of `add`, `sub`, `mul`, `div` and
+    /// `neg` functions.
+    Call(String,Vec<Expr>),
+}
+
+impl Expr {
+    /// Parse the GLSL expression.
+    pub fn parse(code:&str) -> Result<Self> {
+        let error  = |reason:&str| Error::Parse {code:code.into(), reason:reason.into()};
+        let tokens = tokenize(code).ok_or_else(|| error("unexpected character"))?;
+        let mut parser = Parser {tokens:&tokens, position:0};
+        let expr       = parser.expr().ok_or_else(|| error("unexpected token"))?;
+        if parser.position == tokens.len() { Ok(expr) } else { Err(error("unexpected token")) }
+    }
+}
+
+struct Parser<'a> {
+    tokens   : &'a [Token],
+    position : usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek_symbol(&self) -> Option<char> {
+        match self.tokens.get(self.position) {
+            Some(Token::Symbol(symbol)) => Some(*symbol),
+            _                           => None,
+        }
+    }
+
+    fn eat(&mut self, symbol:char) -> bool {
+        let matches = self.peek_symbol() == Some(symbol);
+        if matches { self.position += 1 }
+        matches
+    }
+
+    fn binary(&mut self, symbols:&[(char,&str)], operand:fn(&mut Self) -> Option<Expr>)
+    -> Option<Expr> {
+        let mut lhs = operand(self)?;
+        loop {
+            let symbol   = self.peek_symbol();
+            let function = symbols.iter().find(|(s,_)| Some(*s) == symbol).map(|(_,f)| *f);
+            match function {
+                Some(function) => {
+                    self.position += 1;
+                    let rhs = operand(self)?;
+                    lhs = Expr::Call(function.into(),vec![lhs,rhs]);
+                }
+                None => break Some(lhs),
+            }
+        }
+    }
+
+    fn expr(&mut self) -> Option<Expr> {
+        self.binary(&[('+',"add"),('-',"sub")],Self::term)
+    }
+
+    fn term(&mut self) -> Option<Expr> {
+        self.binary(&[('*',"mul"),('/',"div")],Self::unary)
+    }
+
+    fn unary(&mut self) -> Option<Expr> {
+        if self.eat('-') { Some(Expr::Call("neg".into(),vec![self.unary()?])) }
+        else             { self.postfix() }
+    }
+
+    fn postfix(&mut self) -> Option<Expr> {
+        let mut expr = self.primary()?;
+        while self.eat('.') {
+            match self.tokens.get(self.position) {
+                Some(Token::Ident(field)) => {
+                    self.position += 1;
+                    expr = Expr::Swizzle(Box::new(expr),field.clone());
+                }
+                _ => return None,
+            }
+        }
+        Some(expr)
+    }
+
+    fn primary(&mut self) -> Option<Expr> {
+        let token = self.tokens.get(self.position)?.clone();
+        self.position += 1;
+        match token {
+            Token::Number(number)  => Some(Expr::Number(number)),
+            Token::Symbol('(')     => {
+                let expr = self.expr()?;
+                self.eat(')').then(|| expr)
+            }
+            Token::Ident(name) if self.eat('(') => {
+                let mut args = Vec::new();
+                if !self.eat(')') {
+                    loop {
+                        args.push(self.expr()?);
+                        if self.eat(')') { break }
+                        if !self.eat(',') { return None }
+                    }
+                }
+                Some(Expr::Call(name,args))
+            }
+            Token::Ident(name) => Some(Expr::Variable(name)),
+            Token::Symbol(_)   => None,
+        }
+    }
+}
+
+
+
+// ==================
+// === Evaluation ===
+// ==================
+
+/// The values of the GLSL variables, like `input_size`.
+pub type Variables = HashMap<String,Value>;
+
+impl Expr {
+    /// Evaluate the expression.
+    pub fn eval(&self, variables:&Variables) -> Result<Value> {
+        match self {
+            Self::Number(number) => Ok(Value::Float(*number)),
+            Self::Variable(name) => match name.as_str() {
+                "PI"  => Ok(Value::Float(PI)),
+                "TAU" => Ok(Value::Float(2.0 * PI)),
+                _     => {
+                    let error = || Error::UnknownVariable(name.clone());
+                    variables.get(name).copied().ok_or_else(error)
+                }
+            }
+            Self::Swizzle(expr,field) => swizzle(expr.eval(variables)?,field),
+            Self::Call(name,args) => {
+                let args = args.iter().map(|arg| arg.eval(variables)).collect::<Result<Vec<_>>>()?;
+                call(name,&args)
+            }
+        }
+    }
+}
+
+fn swizzle(value:Value, field:&str) -> Result<Value> {
+    // Colors are stored as raw `vec4`s, so the `raw` field is an identity.
+    if field == "raw" {
+        return Ok(value)
+    }
+    let components = value.components();
+    let selected   = field.chars().map(|char| {
+        let index = match char {
+            'x' | 'r' => 0,
+            'y' | 'g' => 1,
+            'z' | 'b' => 2,
+            'w' | 'a' => 3,
+            _         => usize::MAX,
+        };
+        components.get(index).copied()
+    }).collect::<Option<Vec<_>>>();
+    let error = || Error::WrongSwizzle(field.into(),value.dim());
+    selected.and_then(|selected| Value::from_components(&selected)).ok_or_else(error)
+}
+
+/// The GLSL `mod` function, which differs from the Rust's `%` operator for negative numbers.
+pub fn glsl_mod(x:f32, y:f32) -> f32 {
+    x - y * (x / y).floor()
+}
+
+/// The GLSL `smoothstep` function.
+pub fn smoothstep(edge0:f32, edge1:f32, x:f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0,1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn call(name:&str, args:&[Value]) -> Result<Value> {
+    let error  = || Error::WrongArguments(name.into());
+    let zip    = |f:fn(f32,f32) -> f32| match args {
+        [a,b] => a.zip(b,f).ok_or_else(error),
+        _     => Err(error()),
+    };
+    let map    = |f:fn(f32) -> f32| match args {
+        [a] => Ok(a.map(f)),
+        _   => Err(error()),
+    };
+    let concat = || {
+        let components = args.iter().flat_map(Value::components).collect_vec();
+        match args {
+            [Value::Float(t)] => Value::from_components(&vec![*t;name_dim(name)]),
+            _                 => Value::from_components(&components),
+        }.ok_or_else(error)
+    };
+    match name {
+        "add"                  => zip(|a,b| a + b),
+        "sub"                  => zip(|a,b| a - b),
+        "mul"                  => zip(|a,b| a * b),
+        "div"                  => zip(|a,b| a / b),
+        "rem" | "mod"          => zip(glsl_mod),
+        "min"                  => zip(f32::min),
+        "max"                  => zip(f32::max),
+        "pow"                  => zip(f32::powf),
+        "neg"                  => map(|a| -a),
+        "abs"                  => map(f32::abs),
+        "sin"                  => map(f32::sin),
+        "cos"                  => map(f32::cos),
+        "asin"                 => map(f32::asin),
+        "acos"                 => map(f32::acos),
+        "sqrt"                 => map(f32::sqrt),
+        "floor"                => map(f32::floor),
+        "fract"                => map(f32::fract),
+        "sign"                 => map(|a| if a == 0.0 { 0.0 } else { a.signum() }),
+        "radians"              => map(f32::to_radians),
+        "value" | "Radians" | "Degrees" => map(|a| a),
+        "length"               => match args {
+            [a] => Ok(Value::Float(a.components().iter().map(|c| c * c).sum::<f32>().sqrt())),
+            _   => Err(error()),
+        },
+        "clamp"                => match args {
+            [a]     => Ok(a.map(|a| a.clamp(0.0,1.0))),
+            [a,l,u] => a.zip(l,f32::max).and_then(|a| a.zip(u,f32::min)).ok_or_else(error),
+            _       => Err(error()),
+        },
+        "smoothstep"           => match args {
+            [a]     => Ok(a.map(|a| smoothstep(0.0,1.0,a))),
+            [e0,e1,a] => {
+                let (e0,e1) = (e0.as_float().ok_or_else(error)?,e1.as_float().ok_or_else(error)?);
+                Ok(a.map(|a| smoothstep(e0,e1,a)))
+            }
+            _       => Err(error()),
+        },
+        "mix"                  => match args {
+            [a,b,t] => {
+                let t = t.as_float().ok_or_else(error)?;
+                a.zip(b,|a,b| a * (1.0 - t) + b * t).ok_or_else(error)
+            }
+            _ => Err(error()),
+        },
+        "vec2" | "vec3" | "vec4" | "srgba" => concat(),
+        "srgb"                 => {
+            let components = args.iter().flat_map(Value::components).chain(iter::once(1.0));
+            Value::from_components(&components.collect_vec()).ok_or_else(error)
+        }
+        _ => Err(Error::UnknownFunction(name.into())),
+    }
+}
+
+/// The number of components of a value built by the constructor of the given name.
+fn name_dim(name:&str) -> usize {
+    match name {
+        "vec2" => 2,
+        "vec3" => 3,
+        _      => 4,
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(code:&str) -> Result<Value> {
+        let mut variables = Variables::new();
+        variables.insert("input_size".into(),Vector2(100.0,40.0).into());
+        Expr::parse(code)?.eval(&variables)
+    }
+
+    #[test]
+    fn evaluating_var_expressions() {
+        assert_eq!(eval("input_size.x"), Ok(Value::Float(100.0)));
+        assert_eq!(eval("input_size.yx"), Ok(Value::Vec2(Vector2(40.0,100.0))));
+        assert_eq!(eval("sub(input_size.x,10.0)"), Ok(Value::Float(90.0)));
+        assert_eq!(eval("div(input_size,2.0)"), Ok(Value::Vec2(Vector2(50.0,20.0))));
+        assert_eq!(eval("-input_size.y / 4.0 + 1.5"), Ok(Value::Float(-8.5)));
+        assert_eq!(eval("rem(-1.0,4.0)"), Ok(Value::Float(3.0)));
+        assert_eq!(eval("value(Radians(0.5))"), Ok(Value::Float(0.5)));
+        assert_eq!(eval("vec2(1.0,2.0).y"), Ok(Value::Float(2.0)));
+        assert_eq!(eval("srgba(0.5,0.25,0.0,1.0).raw.w"), Ok(Value::Float(1.0)));
+        assert_eq!(eval("clamp(2.0)"), Ok(Value::Float(1.0)));
+        assert_eq!(eval("input_time"), Err(Error::UnknownVariable("input_time".into())));
+        assert_eq!(eval("input_size.z"), Err(Error::WrongSwizzle("z".into(),2)));
+        assert!(matches!(eval("sample(1.0)"), Err(Error::UnknownFunction(_))));
+        assert!(matches!(eval("add(1.0"), Err(Error::Parse{..})));
+    }
+}
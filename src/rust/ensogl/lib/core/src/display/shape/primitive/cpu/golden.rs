@@ -0,0 +1,185 @@
+//! Golden-image snapshot tests of shapes rasterized on CPU.
+//!
+//! A snapshot is compared with the `<name>.pam` file in the golden images directory. If the
+//! `ENSOGL_UPDATE_GOLDEN` environment variable is set, the snapshot is written as the new golden
+//! image instead. A missing golden image is an error, so a test cannot pass without being checked
+//! against a committed image. On a mismatch, the snapshot is saved as `<name>.actual.pam` in the
+//! [`ACTUAL_DIR`] subdirectory of the system's temporary directory, so it can be inspected and, if
+//! correct, used to replace the golden image. The tracked golden images directory is never
+//! polluted with snapshots.
+
+use crate::prelude::*;
+
+use super::Image;
+use super::image::DecodeError;
+
+use std::path::Path;
+use std::path::PathBuf;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The environment variable which makes the snapshot tests overwrite the golden images.
+pub const UPDATE_VARIABLE : &str = "ENSOGL_UPDATE_GOLDEN";
+
+/// The subdirectory of the system's temporary directory where the mismatched snapshots are saved.
+pub const ACTUAL_DIR : &str = "ensogl-golden";
+
+/// The maximum difference of a channel value which is not reported as a mismatch. It accounts for
+/// the differences of floating-point math between platforms.
+pub const TOLERANCE : u8 = 2;
+
+
+
+// =============
+// === Error ===
+// =============
+
+/// An error of comparing a snapshot with the golden image.
+#[allow(missing_docs)]
+#[derive(Debug,Fail)]
+pub enum Error {
+    #[fail(display="The golden image {:?} does not exist. Run the test with the {} environment \
+        variable set to create it.",path,UPDATE_VARIABLE)]
+    Missing {path:PathBuf},
+    #[fail(display="Cannot access the golden image {:?}: {}.",path,error)]
+    Io {path:PathBuf, error:std::io::Error},
+    #[fail(display="Cannot read the golden image {:?}: {}",path,error)]
+    Decode {path:PathBuf, error:DecodeError},
+    #[fail(display="The golden image {:?} has the size {}x{}, while the snapshot has {}x{}.",
+        path,expected_width,expected_height,width,height)]
+    SizeMismatch {
+        path            : PathBuf,
+        expected_width  : usize,
+        expected_height : usize,
+        width           : usize,
+        height          : usize,
+    },
+    #[fail(display="{} pixels differ from the golden image {:?} (max channel difference: {}). \
+        The snapshot was saved to {:?}.",differing_pixels,path,max_difference,actual_path)]
+    Mismatch {
+        path             : PathBuf,
+        actual_path      : PathBuf,
+        differing_pixels : usize,
+        max_difference   : u8,
+    },
+}
+
+
+
+// =============
+// === Check ===
+// =============
+
+fn io_error(path:&Path) -> impl FnOnce(std::io::Error) -> Error {
+    let path = path.to_owned();
+    move |error| Error::Io {path,error}
+}
+
+/// Compare the image with the golden image `<name>.pam` stored in the `dir` directory. See the
+/// module docs to learn more.
+pub fn check(dir:impl AsRef<Path>, name:&str, image:&Image) -> Result<(),Error> {
+    let update     = std::env::var_os(UPDATE_VARIABLE).is_some();
+    let actual_dir = std::env::temp_dir().join(ACTUAL_DIR);
+    check_or_update(dir.as_ref(),&actual_dir,name,image,update)
+}
+
+fn check_or_update
+(dir:&Path, actual_dir:&Path, name:&str, image:&Image, update:bool) -> Result<(),Error> {
+    let path        = dir.join(format!("{}.pam",name));
+    let actual_path = actual_dir.join(format!("{}.actual.pam",name));
+    if update {
+        std::fs::create_dir_all(dir).map_err(io_error(dir))?;
+        std::fs::write(&path,image.encode()).map_err(io_error(&path))?;
+        return Ok(())
+    }
+    if !path.exists() {
+        return Err(Error::Missing {path})
+    }
+    let data     = std::fs::read(&path).map_err(io_error(&path))?;
+    let expected = Image::decode(&data).map_err(|error| Error::Decode {path:path.clone(),error})?;
+    let result   = expected.compare(image,TOLERANCE);
+    if result.size_mismatch {
+        let expected_width  = expected.width();
+        let expected_height = expected.height();
+        let width           = image.width();
+        let height          = image.height();
+        Err(Error::SizeMismatch {path,expected_width,expected_height,width,height})
+    } else if !result.is_match() {
+        std::fs::create_dir_all(actual_dir).map_err(io_error(actual_dir))?;
+        std::fs::write(&actual_path,image.encode()).map_err(io_error(&actual_path))?;
+        let differing_pixels = result.differing_pixels;
+        let max_difference   = result.max_difference;
+        Err(Error::Mismatch {path,actual_path,differing_pixels,max_difference})
+    } else {
+        if actual_path.exists() {
+            std::fs::remove_file(&actual_path).map_err(io_error(&actual_path))?;
+        }
+        Ok(())
+    }
+}
+
+/// Assert that the image matches the golden image of the given name, stored in the `tests/golden`
+/// directory of the crate calling the macro.
+///
+/// ```ignore
+/// let image = Sampler::new(Vector2(20.0,20.0)).rasterize(&shape).unwrap();
+/// assert_golden_image!("circle",image);
+/// ```
+#[macro_export]
+macro_rules! assert_golden_image {
+    ($name:expr, $image:expr) => {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden");
+        let result = $crate::display::shape::primitive::cpu::golden::check(dir,$name,&$image);
+        if let Err(error) = result {
+            panic!("{}",error)
+        }
+    };
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::display::shape::*;
+    use crate::display::shape::primitive::cpu::Sampler;
+
+    #[test]
+    fn comparing_with_golden_images() {
+        let dir        = std::env::temp_dir().join(format!("ensogl-golden-{}",std::process::id()));
+        let actual_dir = dir.join("actual");
+        let golden     = dir.join("shape.pam");
+        let actual     = actual_dir.join("shape.actual.pam");
+        let check      = |image:&Image, update| {
+            check_or_update(&dir,&actual_dir,"shape",image,update)
+        };
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let sampler = Sampler::new(Vector2(16.0,16.0));
+        let circle  = sampler.rasterize(&Circle(5.px())).unwrap();
+        let rect    = sampler.rasterize(&Rect((10.px(),10.px()))).unwrap();
+        assert!(matches!(check(&circle,false), Err(Error::Missing{..})));
+        assert!(!golden.exists());
+        check(&circle,true).unwrap();
+        assert!(golden.exists());
+        check(&circle,false).unwrap();
+        assert!(matches!(check(&rect,false), Err(Error::Mismatch{..})));
+        assert!(actual.exists());
+        assert!(!dir.join("shape.actual.pam").exists());
+        let small = Sampler::new(Vector2(8.0,8.0)).rasterize(&Circle(5.px())).unwrap();
+        assert!(matches!(check(&small,false), Err(Error::SizeMismatch{..})));
+        check(&circle,false).unwrap();
+        assert!(!actual.exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
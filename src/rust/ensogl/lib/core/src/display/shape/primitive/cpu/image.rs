@@ -0,0 +1,195 @@
+//! RGBA images produced by the CPU shape evaluation.
+
+use crate::prelude::*;
+
+
+
+// =============
+// === Error ===
+// =============
+
+/// An error of decoding an image.
+#[derive(Clone,Debug,Fail,PartialEq)]
+#[fail(display="Malformed PAM image: {}.",_0)]
+pub struct DecodeError(pub String);
+
+
+
+// =============
+// === Image ===
+// =============
+
+/// An 8-bit premultiplied sRGBA image. Pixels are stored row by row, starting from the top-left
+/// corner.
+#[derive(Clone,Debug,PartialEq)]
+pub struct Image {
+    width  : usize,
+    height : usize,
+    pixels : Vec<[u8;4]>,
+}
+
+impl Image {
+    /// Constructor of a transparent image.
+    pub fn new(width:usize, height:usize) -> Self {
+        let pixels = vec![[0;4];width * height];
+        Self {width,height,pixels}
+    }
+
+    /// The width of the image.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The height of the image.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The pixel at the given coordinates, if they are inside the image.
+    pub fn get(&self, x:usize, y:usize) -> Option<[u8;4]> {
+        (x < self.width && y < self.height).then(|| self.pixels[y * self.width + x])
+    }
+
+    /// Set the pixel from the color with components in range 0..1. Coordinates outside of the
+    /// image are ignored.
+    pub fn set(&mut self, x:usize, y:usize, color:Vector4<f32>) {
+        if x < self.width && y < self.height {
+            let to_byte = |v:f32| (v.clamp(0.0,1.0) * 255.0).round() as u8;
+            let pixel   = [to_byte(color.x),to_byte(color.y),to_byte(color.z),to_byte(color.w)];
+            self.pixels[y * self.width + x] = pixel;
+        }
+    }
+
+    /// Compare the image with another one. Pixels are considered equal if none of their channels
+    /// differ by more than `tolerance`.
+    pub fn compare(&self, other:&Image, tolerance:u8) -> Comparison {
+        if (self.width,self.height) != (other.width,other.height) {
+            let size_mismatch = true;
+            Comparison {size_mismatch,..default()}
+        } else {
+            let mut comparison = Comparison::default();
+            for (a,b) in self.pixels.iter().zip(&other.pixels) {
+                let diff = a.iter().zip(b).map(|(a,b)| (*a as i16 - *b as i16).abs() as u8).max();
+                let diff = diff.unwrap_or_default();
+                comparison.max_difference = comparison.max_difference.max(diff);
+                if diff > tolerance {
+                    comparison.differing_pixels += 1;
+                }
+            }
+            comparison
+        }
+    }
+}
+
+
+// === Encoding ===
+
+const PAM_HEADER_END : &str = "ENDHDR\n";
+
+impl Image {
+    /// Encode the image in the Netpbm PAM format with the `RGB_ALPHA` tuple type.
+    pub fn encode(&self) -> Vec<u8> {
+        let size     = format!("WIDTH {}\nHEIGHT {}",self.width,self.height);
+        let format   = "DEPTH 4\nMAXVAL 255\nTUPLTYPE RGB_ALPHA";
+        let header   = format!("P7\n{}\n{}\n{}",size,format,PAM_HEADER_END);
+        let mut data = header.into_bytes();
+        data.extend(self.pixels.iter().flatten());
+        data
+    }
+
+    /// Decode the image from the format produced by [`Image::encode`].
+    pub fn decode(data:&[u8]) -> Result<Self,DecodeError> {
+        let error      = |msg:&str| DecodeError(msg.into());
+        let header_end = PAM_HEADER_END.as_bytes();
+        let header_len = data.windows(header_end.len()).position(|w| w == header_end);
+        let header_len = header_len.ok_or_else(|| error("missing header end"))?;
+        let header     = std::str::from_utf8(&data[..header_len]);
+        let header     = header.map_err(|_| error("header is not valid UTF-8"))?;
+        let mut lines  = header.lines();
+        if lines.next() != Some("P7") {
+            return Err(error("missing the P7 magic number"))
+        }
+        let mut width  = None;
+        let mut height = None;
+        for line in lines {
+            let mut words = line.split_whitespace();
+            let key       = words.next();
+            let value     = words.next();
+            match (key,value) {
+                (Some("WIDTH"),Some(value))          => width  = value.parse().ok(),
+                (Some("HEIGHT"),Some(value))         => height = value.parse().ok(),
+                (Some("DEPTH"),Some("4"))            => {},
+                (Some("MAXVAL"),Some("255"))         => {},
+                (Some("TUPLTYPE"),Some("RGB_ALPHA")) => {},
+                _ => return Err(error(&iformat!("unsupported header line `{line}`"))),
+            }
+        }
+        let width  = width.ok_or_else(|| error("missing width"))?;
+        let height = height.ok_or_else(|| error("missing height"))?;
+        let body   = &data[header_len + header_end.len()..];
+        if body.len() != width * height * 4 {
+            return Err(error("wrong number of pixels"))
+        }
+        let pixels = body.chunks_exact(4).map(|p| [p[0],p[1],p[2],p[3]]).collect();
+        Ok(Self {width,height,pixels})
+    }
+}
+
+
+
+// ==================
+// === Comparison ===
+// ==================
+
+/// The result of comparing two images.
+#[derive(Clone,Copy,Debug,Default,PartialEq)]
+pub struct Comparison {
+    /// Whether the images have different sizes. Other fields are not computed in such case.
+    pub size_mismatch    : bool,
+    /// The number of pixels which differ by more than the tolerance.
+    pub differing_pixels : usize,
+    /// The maximum difference of a single channel value.
+    pub max_difference   : u8,
+}
+
+impl Comparison {
+    /// Whether the images are considered equal.
+    pub fn is_match(&self) -> bool {
+        !self.size_mismatch && self.differing_pixels == 0
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encoding_and_comparing_images() {
+        let mut image = Image::new(3,2);
+        image.set(0,0,Vector4(1.0,0.5,0.0,1.0));
+        image.set(2,1,Vector4(0.0,0.0,2.0,0.5));
+        image.set(5,5,Vector4(1.0,1.0,1.0,1.0));
+        assert_eq!(image.get(0,0), Some([255,128,0,255]));
+        assert_eq!(image.get(2,1), Some([0,0,255,128]));
+        assert_eq!(image.get(3,0), None);
+
+        let decoded = Image::decode(&image.encode()).unwrap();
+        assert_eq!(decoded, image);
+        assert!(Image::decode(b"P6\n").is_err());
+
+        let mut other = image.clone();
+        other.set(1,1,Vector4(0.0,0.0,0.0,0.008));
+        let comparison = image.compare(&other,2);
+        assert!(comparison.is_match());
+        assert_eq!(comparison.max_difference, 2);
+        other.set(1,0,Vector4(0.1,0.0,0.0,0.0));
+        assert_eq!(image.compare(&other,2).differing_pixels, 1);
+        assert!(image.compare(&Image::new(2,3),2).size_mismatch);
+    }
+}
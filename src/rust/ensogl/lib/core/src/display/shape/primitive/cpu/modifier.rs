@@ -0,0 +1,152 @@
+//! CPU implementations of the shape modifiers. Each implementation follows the canvas code
+//! generated for the modifier in [`Canvas`] and the GLSL functions of `shape.glsl`.
+//!
+//! [`Canvas`]: crate::display::shape::primitive::shader::canvas::Canvas
+
+use crate::prelude::*;
+
+use super::Result;
+use super::Sample;
+use super::Sampler;
+use super::Shape;
+use super::expr::glsl_mod;
+
+use crate::display::shape::primitive::def::modifier::*;
+
+
+
+// ===============
+// === Helpers ===
+// ===============
+
+fn pixels(v:Vector2<Pixels>) -> Vector2<f32> {
+    Vector2(v.x.value,v.y.value)
+}
+
+
+
+// =================
+// === Modifiers ===
+// =================
+
+impl<Child:Sample> Sample for Translate<Child> {
+    fn sample(&self, sampler:&Sampler, position:Vector2<f32>) -> Result<Shape> {
+        let v = pixels(sampler.eval(&self.v)?);
+        self.child.sample(sampler,position - v)
+    }
+}
+
+impl<Child:Sample> Sample for Rotation<Child> {
+    fn sample(&self, sampler:&Sampler, position:Vector2<f32>) -> Result<Shape> {
+        let angle    = -sampler.eval(&self.angle)?.value;
+        let rotated  = Vector2(position.y,-position.x);
+        let position = position * angle.cos() + rotated * angle.sin();
+        self.child.sample(sampler,position)
+    }
+}
+
+impl<Child:Sample> Sample for Scale<Child> {
+    fn sample(&self, sampler:&Sampler, position:Vector2<f32>) -> Result<Shape> {
+        let value = sampler.eval(&self.value)?;
+        let shape = self.child.sample(sampler,position / value)?;
+        Ok(shape.resample(sampler,value))
+    }
+}
+
+impl<Child1:Sample,Child2:Sample> Sample for Union<Child1,Child2> {
+    fn sample(&self, sampler:&Sampler, position:Vector2<f32>) -> Result<Shape> {
+        let shape1 = self.child1.sample(sampler,position)?;
+        let shape2 = self.child2.sample(sampler,position)?;
+        Ok(shape1.unify(sampler,&shape2))
+    }
+}
+
+impl<Child1:Sample,Child2:Sample> Sample for Difference<Child1,Child2> {
+    fn sample(&self, sampler:&Sampler, position:Vector2<f32>) -> Result<Shape> {
+        let shape1 = self.child1.sample(sampler,position)?;
+        let shape2 = self.child2.sample(sampler,position)?;
+        Ok(shape1.difference(sampler,&shape2))
+    }
+}
+
+impl<Child1:Sample,Child2:Sample> Sample for Intersection<Child1,Child2> {
+    fn sample(&self, sampler:&Sampler, position:Vector2<f32>) -> Result<Shape> {
+        let shape1 = self.child1.sample(sampler,position)?;
+        let shape2 = self.child2.sample(sampler,position)?;
+        Ok(shape1.intersection(sampler,&shape2))
+    }
+}
+
+impl<Child:Sample> Sample for Fill<Child> {
+    fn sample(&self, sampler:&Sampler, position:Vector2<f32>) -> Result<Shape> {
+        let color = sampler.eval(&self.color)?;
+        Ok(self.child.sample(sampler,position)?.set_color(&color))
+    }
+}
+
+impl<Child:Sample> Sample for PixelSnap<Child> {
+    fn sample(&self, sampler:&Sampler, position:Vector2<f32>) -> Result<Shape> {
+        Ok(self.child.sample(sampler,position)?.pixel_snap(sampler))
+    }
+}
+
+impl<Child:Sample> Sample for Grow<Child> {
+    fn sample(&self, sampler:&Sampler, position:Vector2<f32>) -> Result<Shape> {
+        let value = sampler.eval(&self.value)?;
+        Ok(self.child.sample(sampler,position)?.grow(sampler,value))
+    }
+}
+
+impl<Child:Sample> Sample for Shrink<Child> {
+    fn sample(&self, sampler:&Sampler, position:Vector2<f32>) -> Result<Shape> {
+        let value = sampler.eval(&self.value)?;
+        Ok(self.child.sample(sampler,position)?.grow(sampler,-value))
+    }
+}
+
+impl<Child:Sample> Sample for Repeat<Child> {
+    fn sample(&self, sampler:&Sampler, position:Vector2<f32>) -> Result<Shape> {
+        let tile_size = pixels(sampler.eval(&self.tile_size)?);
+        let half      = tile_size / 2.0;
+        let offset    = position + half;
+        let x         = glsl_mod(offset.x,tile_size.x) - half.x;
+        let y         = glsl_mod(offset.y,tile_size.y) - half.y;
+        let shape     = self.child.sample(sampler,Vector2(x,y))?;
+        Ok(shape.with_infinite_bounds(sampler))
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::display::shape::*;
+
+    use std::f32::consts::PI;
+
+    fn alpha(shape:&impl Sample, x:f32, y:f32) -> f32 {
+        let sampler = Sampler::new(Vector2(100.0,100.0));
+        shape.sample(&sampler,Vector2(x,y)).unwrap().alpha
+    }
+
+    #[test]
+    fn transforming_the_space() {
+        let circle = Circle(2.px());
+        assert_eq!(alpha(&circle.translate((10.px(),0.px())),10.0,0.0), 1.0);
+        assert_eq!(alpha(&circle.translate((10.px(),0.px())),0.0,0.0), 0.0);
+        let rect = Rect((10.px(),2.px())).translate_x(5.px());
+        let rect = rect.rotate((PI/2.0).radians());
+        assert_eq!(alpha(&rect,0.0,-8.0), 1.0);
+        assert_eq!(alpha(&rect,8.0,0.0), 0.0);
+        assert_eq!(alpha(&circle.scale(3.0),5.0,0.0), 1.0);
+        assert_eq!(alpha(&circle.repeat((10.px(),10.px())),30.0,-20.0), 1.0);
+        assert_eq!(alpha(&circle.grow(2.px()),3.0,0.0), 1.0);
+        assert_eq!(alpha(&circle.shrink(1.px()),1.6,0.0), 0.0);
+    }
+}
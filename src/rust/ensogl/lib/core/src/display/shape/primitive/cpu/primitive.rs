@@ -0,0 +1,299 @@
+//! CPU implementations of the primitive SDF shapes. Each implementation follows the GLSL code of
+//! the shape defined in [`def::primitive`].
+//!
+//! [`def::primitive`]: crate::display::shape::primitive::def::primitive
+
+use crate::prelude::*;
+
+use super::BoundSdf;
+use super::BoundingBox;
+use super::DEFAULT_COLOR;
+use super::Result;
+use super::Sample;
+use super::Sampler;
+use super::Shape;
+use super::expr::glsl_mod;
+
+use crate::display::shape::primitive::def::primitive::*;
+
+use std::f32::consts::PI;
+
+
+
+// ===============
+// === Helpers ===
+// ===============
+
+/// The `FLOAT_MAX` constant of `math.glsl`.
+const FLOAT_MAX : f32 = 3.402_823_5e38;
+
+/// The `FLOAT_MIN` constant of `math.glsl`.
+const FLOAT_MIN : f32 = 1.175_494_4e-38;
+
+/// The GLSL `sign` function, which returns 0 for 0.
+fn sign(value:f32) -> f32 {
+    if value == 0.0 { 0.0 } else { value.signum() }
+}
+
+fn abs(v:Vector2<f32>) -> Vector2<f32> {
+    Vector2(v.x.abs(),v.y.abs())
+}
+
+/// Define the CPU sampling of primitive shapes. The body computes the [`BoundSdf`] from the
+/// `position` and the evaluated shape fields; the shape is given the default color.
+macro_rules! define_samplers {
+    ($($name:ident ($($field:ident),*) |$sampler:ident,$position:ident| $body:block)*) => {$(
+        impl Sample for $name {
+            #[allow(unused_mut)]
+            fn sample(&self, $sampler:&Sampler, mut $position:Vector2<f32>) -> Result<Shape> {
+                $(let $field = $sampler.eval(self.$field())?;)*
+                let sdf:BoundSdf = $body;
+                Ok(Shape::new($sampler,sdf,DEFAULT_COLOR))
+            }
+        }
+    )*};
+}
+
+
+
+// ==================
+// === Primitives ===
+// ==================
+
+define_samplers! {
+    EmptyShape () |_sampler,_position| {
+        BoundSdf::new(FLOAT_MAX,BoundingBox::symmetric(0.0,0.0))
+    }
+
+    Plane () |_sampler,_position| {
+        BoundSdf::new(FLOAT_MIN,BoundingBox::symmetric(0.0,0.0))
+    }
+
+    HalfPlane () |_sampler,position| {
+        BoundSdf::new(position.y,BoundingBox::symmetric(0.0,0.0))
+    }
+
+    BottomHalfPlane () |_sampler,position| {
+        BoundSdf::new(-position.y,BoundingBox::symmetric(0.0,0.0))
+    }
+
+    PlaneAngle (angle) |_sampler,position| {
+        let pi_2       = 2.0 * PI;
+        let angle_norm = angle.value / pi_2;
+        let angle_norm = 1.0 - (glsl_mod(angle_norm,2.0) - 1.0).abs();
+        let angle_rad  = angle_norm * pi_2;
+        let off        = angle_norm - 0.5;
+        let half       = angle_rad / 2.0;
+        let distance   = position.x.abs() * half.cos() - position.y * half.sin() - off;
+        BoundSdf::new(distance,BoundingBox::symmetric(0.0,0.0))
+    }
+
+    PlaneAngleFast (angle) |_sampler,position| {
+        let half     = angle.value / 2.0;
+        let off      = 0.5;
+        let distance = position.x.abs() * half.cos() - position.y * half.sin() + off;
+        BoundSdf::new(distance,BoundingBox::symmetric(0.0,0.0))
+    }
+
+    Line (width) |_sampler,position| {
+        BoundSdf::new(position.y.abs() - width,BoundingBox::symmetric(0.0,width))
+    }
+
+    Segment (start,end,width) |_sampler,position| {
+        let start         = Vector2(start.x.value,start.y.value);
+        let end           = Vector2(end.x.value,end.y.value);
+        let half_width    = width.value / 2.0;
+        let delta         = end - start;
+        let projection    = ((position - start).dot(&delta) / delta.dot(&delta)).clamp(0.0,1.0);
+        let closest_point = start + delta * projection;
+        let left          = start.x.min(end.x) - half_width;
+        let right         = start.x.max(end.x) + half_width;
+        let bottom        = start.y.min(end.y) - half_width;
+        let top           = start.y.max(end.y) + half_width;
+        let bounds        = BoundingBox::new(left,right,bottom,top);
+        let distance      = (position - closest_point).norm() - half_width;
+        BoundSdf::new(distance,bounds)
+    }
+
+    Circle (radius) |_sampler,position| {
+        let radius = radius.value;
+        BoundSdf::new(position.norm() - radius,BoundingBox::symmetric(radius,radius))
+    }
+
+    Ellipse (x_radius,y_radius) |_sampler,position| {
+        let mut ab = Vector2(x_radius,y_radius);
+        position   = abs(position);
+        if position.x > position.y {
+            position = Vector2(position.y,position.x);
+            ab       = Vector2(ab.y,ab.x);
+        }
+        let l  = ab.y * ab.y - ab.x * ab.x;
+        let m  = ab.x * position.x / l;
+        let m2 = m * m;
+        let n  = ab.y * position.y / l;
+        let n2 = n * n;
+        let c  = (m2 + n2 - 1.0) / 3.0;
+        let c3 = c * c * c;
+        let q  = c3 + m2 * n2 * 2.0;
+        let d  = c3 + m2 * n2;
+        let g  = m + m * n2;
+        let co = if d < 0.0 {
+            let h  = (q / c3).acos() / 3.0;
+            let s  = h.cos();
+            let t  = h.sin() * 3.0_f32.sqrt();
+            let rx = (-c * (s + t + 2.0) + m2).sqrt();
+            let ry = (-c * (s - t + 2.0) + m2).sqrt();
+            (ry + sign(l) * rx + g.abs() / (rx * ry) - m) / 2.0
+        } else {
+            let h  = 2.0 * m * n * d.sqrt();
+            let s  = sign(q + h) * (q + h).abs().powf(1.0 / 3.0);
+            let u  = sign(q - h) * (q - h).abs().powf(1.0 / 3.0);
+            let rx = -s - u - c * 4.0 + 2.0 * m2;
+            let ry = (s - u) * 3.0_f32.sqrt();
+            let rm = (rx * rx + ry * ry).sqrt();
+            (ry / (rm - rx).sqrt() + 2.0 * g / rm - m) / 2.0
+        };
+        let r        = Vector2(ab.x * co, ab.y * (1.0 - co * co).sqrt());
+        let distance = (r - position).norm() * sign(position.y - r.y);
+        BoundSdf::new(distance,BoundingBox::symmetric(2.0 * x_radius,2.0 * y_radius))
+    }
+
+    Rect (size) |_sampler,position| {
+        let size     = Vector2(size.x.value,size.y.value);
+        let dir      = abs(position) - size / 2.0;
+        let inside   = dir.x.min(0.0).max(dir.y.min(0.0));
+        let outside  = Vector2(dir.x.max(0.0),dir.y.max(0.0)).norm();
+        BoundSdf::new(inside + outside,BoundingBox::from_size(size))
+    }
+
+    RoundedRectByCorner (size,top_left,top_right,bottom_left,bottom_right) |_sampler,position| {
+        let size          = Vector2(size.x.value,size.y.value);
+        let top_left      = top_left.value;
+        let top_right     = top_right.value;
+        let bottom_left   = bottom_left.value;
+        let bottom_right  = bottom_right.value;
+        let top_weight    = (size.x / (top_left    + top_right)   ).clamp(0.0,1.0);
+        let bottom_weight = (size.x / (bottom_left + bottom_right)).clamp(0.0,1.0);
+        let left_weight   = (size.y / (top_left    + bottom_left) ).clamp(0.0,1.0);
+        let right_weight  = (size.y / (top_right   + bottom_right)).clamp(0.0,1.0);
+
+        let tl = top_weight.min(left_weight)     * top_left;
+        let tr = top_weight.min(right_weight)    * top_right;
+        let bl = bottom_weight.min(left_weight)  * bottom_left;
+        let br = bottom_weight.min(right_weight) * bottom_right;
+
+        let size = size / 2.0;
+        let p    = position;
+
+        let is_top_left     = p.x < -size.x + tl && p.y >  size.y - tl;
+        let is_top_right    = p.x >  size.x - tr && p.y >  size.y - tr;
+        let is_bottom_left  = p.x < -size.x + bl && p.y < -size.y + bl;
+        let is_bottom_right = p.x >  size.x - br && p.y < -size.y + br;
+
+        let distance = if is_top_left {
+            (p - Vector2(-size.x + tl,  size.y - tl)).norm() - tl
+        } else if is_top_right {
+            (p - Vector2( size.x - tr,  size.y - tr)).norm() - tr
+        } else if is_bottom_left {
+            (p - Vector2(-size.x + bl, -size.y + bl)).norm() - bl
+        } else if is_bottom_right {
+            (p - Vector2( size.x - br, -size.y + br)).norm() - br
+        } else {
+            let dir = abs(p) - size;
+            dir.x.max(dir.y).min(0.0) + Vector2(dir.x.max(0.0),dir.y.max(0.0)).norm()
+        };
+        // The GLSL code computes the bounds from the halved size.
+        BoundSdf::new(distance,BoundingBox::from_size(size))
+    }
+
+    Triangle (width,height) |_sampler,position| {
+        let norm     = Vector2(height,width / 2.0).normalize();
+        let pos_y    = -position.y - height / 2.0;
+        let edge     = position.x.abs() * norm.x + position.y * norm.y - height / 2.0 * norm.y;
+        let distance = edge.max(pos_y);
+        BoundSdf::new(distance,BoundingBox::symmetric(width,height / 2.0))
+    }
+
+    UnevenCapsule (radius_top,radius_bottom,inner_height) |_sampler,position| {
+        let radius_top    = radius_top.value;
+        let radius_bottom = radius_bottom.value;
+        let inner_height  = inner_height.value;
+        position.x        = position.x.abs();
+        let b             = (radius_bottom - radius_top) / inner_height;
+        let a             = (1.0 - b * b).sqrt();
+        let k             = position.dot(&Vector2(-b,a));
+        let distance = if k < 0.0 {
+            position.norm() - radius_bottom
+        } else if k > a * inner_height {
+            (position - Vector2(0.0,inner_height)).norm() - radius_top
+        } else {
+            position.dot(&Vector2(a,b)) - radius_bottom
+        };
+        let max_radius = radius_top.max(radius_bottom);
+        let bounds     = BoundingBox::new
+            (-max_radius,max_radius,-radius_bottom,inner_height + radius_top);
+        BoundSdf::new(distance,bounds)
+    }
+
+    FiveStar (radius,ratio) |_sampler,position| {
+        let radius   = radius.value;
+        let k1       = Vector2(0.809_017,-0.587_785_24);
+        let k2       = Vector2(-k1.x,k1.y);
+        position.x   = position.x.abs();
+        position    -= k1 * 2.0 * k1.dot(&position).max(0.0);
+        position    -= k2 * 2.0 * k2.dot(&position).max(0.0);
+        position.x   = position.x.abs();
+        position.y  -= radius;
+        let ba       = Vector2(-k1.y,k1.x) * ratio - Vector2(0.0,1.0);
+        let h        = (position.dot(&ba) / ba.dot(&ba)).clamp(0.0,radius);
+        let distance = (position - ba * h).norm() * sign(position.y * ba.x - position.x * ba.y);
+        BoundSdf::new(distance,BoundingBox::symmetric(2.0 * radius,2.0 * radius))
+    }
+
+    RoundedArc (radius,angle,width) |_sampler,position| {
+        let ra       = radius.value;
+        let rb       = width.value / 2.0;
+        position.x   = position.x.abs();
+        let scb      = Vector2((angle.value / 2.0).sin(),(angle.value / 2.0).cos());
+        let is_side  = scb.y * position.x > scb.x * position.y;
+        let k        = if is_side { position.dot(&scb) } else { position.norm() };
+        let distance = (position.dot(&position) + ra * ra - 2.0 * ra * k).max(0.0).sqrt() - rb;
+        let size     = 2.0 * ra + width.value;
+        BoundSdf::new(distance,BoundingBox::symmetric(size,size))
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::display::shape::primitive::def::unit::PixelDistance;
+
+    fn distance(shape:&impl Sample, x:f32, y:f32) -> f32 {
+        let sampler = Sampler::new(Vector2(100.0,100.0));
+        shape.sample(&sampler,Vector2(x,y)).unwrap().sdf.distance
+    }
+
+    #[test]
+    fn primitive_distances() {
+        let close = |a:f32, b:f32| (a - b).abs() < 1e-4;
+        assert!(close(distance(&Circle(10.px()),3.0,4.0), -5.0));
+        assert!(close(distance(&Rect((20.px(),10.px())),15.0,0.0), 5.0));
+        assert!(close(distance(&Rect((20.px(),10.px())),0.0,0.0), -5.0));
+        assert!(close(distance(&Rect((20.px(),10.px())),13.0,9.0), 5.0));
+        let rounded = Rect((20.px(),20.px())).corners_radius(5.px());
+        assert!(close(distance(&rounded,10.0,10.0), 50.0_f32.sqrt() - 5.0));
+        let segment = Segment((0.px(),0.px()),(10.px(),0.px()),2.px());
+        assert!(close(distance(&segment,5.0,3.0), 2.0));
+        assert!(close(distance(&segment,13.0,4.0), 4.0));
+        let capsule = UnevenCapsule(2.px(),2.px(),10.px());
+        assert!(close(distance(&capsule,5.0,5.0), 3.0));
+        assert!(close(distance(&HalfPlane(),0.0,-3.0), -3.0));
+    }
+}
@@ -5,6 +5,7 @@ use crate::prelude::*;
 use super::unit::*;
 use super::modifier::*;
 
+use crate::display::shape::primitive::cpu;
 use crate::display::shape::primitive::shader::canvas;
 use crate::display::shape::primitive::shader::canvas::Canvas;
 use crate::display::shape::primitive::def::var::Var;
@@ -43,6 +44,12 @@ impl canvas::Draw for AnyShape {
     }
 }
 
+impl cpu::Sample for AnyShape {
+    fn sample(&self, sampler:&cpu::Sampler, position:Vector2<f32>) -> cpu::Result<cpu::Shape> {
+        self.rc.sample(sampler,position)
+    }
+}
+
 
 
 // ================
@@ -1,6 +1,7 @@
 //! Canvas for drawing vector graphics. See the documentation of `Canvas` to learn more.
 
 use crate::prelude::*;
+use crate::display::shape::primitive::cpu;
 use crate::display::shape::primitive::def::var::Var;
 use crate::system::gpu::shader::glsl::Glsl;
 use crate::system::gpu::types::*;
@@ -319,8 +320,9 @@ impl Canvas {
 // === Draw ===
 // ============
 
-/// Trait for every object which can be drawn on the canvas.
-pub trait Draw: Debug {
+/// Trait for every object which can be drawn on the canvas. Every such object can also be
+/// evaluated on CPU, see the [`cpu`] module.
+pub trait Draw: Debug + cpu::Sample {
     /// Draw the element on the canvas.
     fn draw(&self, canvas:&mut Canvas) -> Shape;
 }
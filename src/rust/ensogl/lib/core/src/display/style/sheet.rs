@@ -257,7 +257,7 @@ impl PartialSemigroup<Value> for Value {
 
 /// Defines a change to a style sheet. Style sheets allow bulk-application of changes in order to
 /// optimize the amount of necessary computations.
-#[derive(Debug)]
+#[derive(Clone,Debug)]
 #[allow(missing_docs)]
 pub struct Change {
     pub path  : Path,
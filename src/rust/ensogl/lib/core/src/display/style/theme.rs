@@ -237,11 +237,19 @@ impl Manager {
         self.data.borrow().keys()
     }
 
-    /// Registers a new theme.
+    /// Registers a new theme. A theme registered under the name of an existing one replaces it,
+    /// and the enabled themes are refreshed on the next update.
     pub fn register<T:Into<Theme>>(&self, name:impl Str, theme:T) {
         self.register_internal(name.into(),theme.into())
     }
 
+    /// Removes the theme from the registry.
+    pub fn remove(&self, name:impl Str) {
+        let name = name.as_ref();
+        self.data.borrow_mut().remove(name);
+        self.handles.borrow_mut().remove(name);
+    }
+
     /// Compute changes between the source and the target theme.
     pub fn diff(&self, src:&str, tgt:&str) -> Vec<Change> {
         self.data.borrow().diff(src,tgt)
@@ -257,10 +265,12 @@ impl Manager {
     }
 
     fn register_internal(&self, name:String, theme:Theme) {
-        let dirty  = self.current_dirty.clone_ref();
-        let handle = theme.on_mut(move || dirty.set());
+        let dirty    = self.current_dirty.clone_ref();
+        let handle   = theme.on_mut(move || dirty.set());
+        let replaced = self.data.borrow().get(&name).is_some();
         self.data.borrow_mut().register(&name,theme);
         self.handles.borrow_mut().insert(name,handle);
+        if replaced { self.current_dirty.set() }
     }
 
     /// Sets a new set of enabled themes.
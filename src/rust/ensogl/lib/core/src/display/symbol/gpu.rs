@@ -271,6 +271,8 @@ pub struct Symbol {
     bindings          : Rc<RefCell<Bindings>>,
     stats             : SymbolStats,
     is_hidden         : Rc<Cell<bool>>,
+    /// Whether all instances are outside of the visible area, so the draw call can be skipped.
+    is_culled         : Rc<Cell<bool>>,
 }
 
 impl Symbol {
@@ -303,8 +305,10 @@ impl Symbol {
             let symbol_id_uniform = variables.add_or_panic("symbol_id",(*id) as i32);
             let display_object    = display::object::Instance::new(logger.clone());
             let is_hidden         = Rc::new(Cell::new(false));
+            let is_culled         = Rc::new(Cell::new(false));
             Self {id,display_object,surface,shader,surface_dirty,shader_dirty,variables
-                 ,global_variables,symbol_id_uniform,context,logger,bindings,stats,is_hidden}.init()
+                 ,global_variables,symbol_id_uniform,context,logger,bindings,stats,is_hidden
+                 ,is_culled}.init()
         })
     }
 
@@ -353,7 +357,7 @@ impl Symbol {
 
     pub fn render(&self) {
         debug!(self.logger, "Rendering.", || {
-            if self.is_hidden() {
+            if self.is_hidden() || self.is_culled() {
                 return;
             }
             if let Some(context) = &*self.context.borrow() {
@@ -427,6 +431,16 @@ impl Symbol {
     pub fn is_hidden(&self) -> bool {
         self.is_hidden.get()
     }
+
+    /// Mark that none of the instances is in the visible area, so the symbol is not rendered.
+    /// Unlike hiding, it does not affect the display object hierarchy.
+    pub fn set_culled(&self, culled:bool) {
+        self.is_culled.set(culled)
+    }
+
+    pub fn is_culled(&self) -> bool {
+        self.is_culled.get()
+    }
 }
 
 
@@ -6,6 +6,7 @@ use crate::prelude::*;
 
 use crate::display::traits::*;
 
+use crate::data::BoundingBox;
 use crate::debug::Stats;
 use crate::display::layout::alignment;
 use crate::display::layout::Alignment;
@@ -64,7 +65,7 @@ impl Drop for SpriteStats {
 pub struct SpriteGuard {
     instance_id    : attribute::InstanceIndex,
     symbol         : Symbol,
-    size           : Attribute<Vector2<f32>>,
+    size           : Size,
     display_object : display::object::Instance,
 }
 
@@ -72,7 +73,7 @@ impl SpriteGuard {
     fn new
     ( instance_id    : attribute::InstanceIndex
     , symbol         : &Symbol
-    , size           : &Attribute<Vector2<f32>>
+    , size           : &Size
     , display_object : &display::object::Instance
     ) -> Self {
         let symbol         = symbol.clone_ref();
@@ -84,7 +85,7 @@ impl SpriteGuard {
 
 impl Drop for SpriteGuard {
     fn drop(&mut self) {
-        self.size.set(zero());
+        self.size.model.dispose();
         self.symbol.surface().instance_scope().dispose(self.instance_id);
         self.display_object.unset_parent();
     }
@@ -92,49 +93,266 @@ impl Drop for SpriteGuard {
 
 
 
+// =====================
+// === InstanceModel ===
+// =====================
+
+/// The state of a single sprite, shared by its [`Size`] and the [`Culling`] of its system. It
+/// uploads the size and transformation attributes only if they are needed by the GPU.
+#[derive(Debug)]
+struct InstanceModel {
+    instance_id : attribute::InstanceIndex,
+    hidden      : Cell<bool>,
+    culled      : Cell<bool>,
+    size        : Cell<Vector2<f32>>,
+    matrix      : Cell<Matrix4<f32>>,
+    size_attr   : Attribute<Vector2<f32>>,
+    transform   : Attribute<Matrix4<f32>>,
+    culling     : Culling,
+}
+
+impl InstanceModel {
+    fn new
+    ( instance_id : attribute::InstanceIndex
+    , transform   : Attribute<Matrix4<f32>>
+    , size_attr   : Attribute<Vector2<f32>>
+    , culling     : &Culling
+    ) -> Rc<Self> {
+        let hidden  = Cell::new(true);
+        let culled  = default();
+        let size    = default();
+        let matrix  = Cell::new(Matrix4::identity());
+        let culling = culling.clone_ref();
+        let model   = Self {instance_id,hidden,culled,size,matrix,size_attr,transform,culling};
+        let model   = Rc::new(model);
+        model.culling.register(&model);
+        model
+    }
+
+    fn is_drawn(&self) -> bool {
+        !self.hidden.get() && !self.culled.get()
+    }
+
+    fn set_size(&self, size:Vector2<f32>) {
+        self.size.set(size);
+        if !self.update_culled() && self.is_drawn() {
+            self.size_attr.set(size)
+        }
+    }
+
+    fn set_matrix(&self, matrix:Matrix4<f32>) {
+        self.matrix.set(matrix);
+        if !self.update_culled() && !self.culled.get() {
+            self.transform.set(matrix)
+        }
+    }
+
+    fn set_hidden(&self, hidden:bool) {
+        if self.hidden.get() != hidden {
+            self.culling.with_counted(self,|| self.hidden.set(hidden));
+            self.upload_size();
+        }
+    }
+
+    /// The bounds of the sprite in the world space.
+    fn bounds(&self, origin:Vector2<f32>) -> BoundingBox {
+        let size   = self.size.get();
+        let corner = -origin.component_mul(&size);
+        let local  = BoundingBox::from_corners(corner,corner + size);
+        local.transform(&self.matrix.get())
+    }
+
+    /// Check whether the sprite lies outside of the visible area, and update the attributes if
+    /// the result changed. Returns true in such a case.
+    fn update_culled(&self) -> bool {
+        let origin  = self.culling.model.origin.get();
+        let area    = self.culling.model.area.get();
+        let culled  = area.map_or(false,|area| !self.bounds(origin).intersects(&area));
+        let changed = self.culled.get() != culled;
+        if changed {
+            self.culling.with_counted(self,|| self.culled.set(culled));
+            if !culled {
+                self.transform.set(self.matrix.get());
+            }
+            self.upload_size();
+        }
+        changed
+    }
+
+    fn upload_size(&self) {
+        self.size_attr.set(if self.is_drawn() {self.size.get()} else {zero()});
+    }
+
+    fn dispose(&self) {
+        self.set_hidden(true);
+        self.culling.unregister(self);
+    }
+}
+
+
+
+// ===============
+// === Culling ===
+// ===============
+
+/// View frustum culling of the sprites of a [`SpriteSystem`]. Sprites whose bounds, computed from
+/// their transformation and size, do not intersect the visible area are culled: their size
+/// attribute is set to zero, so the GPU discards them before rasterization, and their
+/// transformation is not uploaded until they become visible again. If all shown sprites are
+/// culled, the draw call of the system's symbol is skipped altogether. A sprite is checked when it
+/// moves or changes its size, and all sprites are checked when the visible area changes. The area
+/// is set by the scene every frame, see [`crate::display::scene::culling`] to learn more. Sprites
+/// are never culled if the area is not set.
+#[derive(Clone,CloneRef,Debug,Default)]
+pub struct Culling {
+    model : Rc<CullingModel>,
+}
+
+#[derive(Debug,Default)]
+struct CullingModel {
+    symbol    : Option<Symbol>,
+    area      : Cell<Option<BoundingBox>>,
+    origin    : Cell<Vector2<f32>>,
+    instances : RefCell<HashMap<attribute::InstanceIndex,Weak<InstanceModel>>>,
+    drawn     : Cell<usize>,
+    culled    : Cell<usize>,
+}
+
+impl Culling {
+    /// Constructor. The draw call of the symbol is skipped when all of its shown sprites are
+    /// culled.
+    pub fn new(symbol:&Symbol) -> Self {
+        let symbol = Some(symbol.clone_ref());
+        let model  = Rc::new(CullingModel {symbol,..default()});
+        Self {model}
+    }
+
+    /// Set the visible area, in world coordinates. Passing [`None`] disables culling.
+    pub fn set_area(&self, area:Option<BoundingBox>) {
+        if self.model.area.replace(area) != area {
+            self.update_all();
+        }
+    }
+
+    /// The visible area, if set.
+    pub fn area(&self) -> Option<BoundingBox> {
+        self.model.area.get()
+    }
+
+    /// The number of shown sprites which are not culled.
+    pub fn drawn_count(&self) -> usize {
+        self.model.drawn.get()
+    }
+
+    /// The number of shown sprites which are culled.
+    pub fn culled_count(&self) -> usize {
+        self.model.culled.get()
+    }
+
+    /// Check whether there are shown sprites and all of them are culled.
+    pub fn is_fully_culled(&self) -> bool {
+        self.drawn_count() == 0 && self.culled_count() > 0
+    }
+
+    /// Create a new weak reference.
+    pub fn downgrade(&self) -> WeakCulling {
+        let model = Rc::downgrade(&self.model);
+        WeakCulling {model}
+    }
+
+    fn set_origin(&self, origin:Vector2<f32>) {
+        if self.model.origin.replace(origin) != origin {
+            self.update_all();
+        }
+    }
+
+    fn update_all(&self) {
+        let instances = self.model.instances.borrow().values().cloned().collect_vec();
+        for instance in instances.iter().filter_map(|instance| instance.upgrade()) {
+            instance.update_culled();
+        }
+    }
+
+    fn register(&self, instance:&Rc<InstanceModel>) {
+        let weak = Rc::downgrade(instance);
+        self.model.instances.borrow_mut().insert(instance.instance_id,weak);
+    }
+
+    fn unregister(&self, instance:&InstanceModel) {
+        self.model.instances.borrow_mut().remove(&instance.instance_id);
+    }
+
+    /// Run the function modifying the state of the instance, keeping the counters up to date.
+    /// Hidden instances are not counted.
+    fn with_counted(&self, instance:&InstanceModel, f:impl FnOnce()) {
+        self.count(instance,false);
+        f();
+        self.count(instance,true);
+        if let Some(symbol) = &self.model.symbol {
+            symbol.set_culled(self.is_fully_culled());
+        }
+    }
+
+    fn count(&self, instance:&InstanceModel, add:bool) {
+        if !instance.hidden.get() {
+            let counter = if instance.culled.get() {&self.model.culled} else {&self.model.drawn};
+            let count   = counter.get();
+            debug_assert!(add || count > 0, "Uncounting a sprite which was not counted.");
+            counter.set(if add {count + 1} else {count.saturating_sub(1)});
+        }
+    }
+}
+
+
+// === WeakCulling ===
+
+/// Weak version of [`Culling`].
+#[derive(Clone,CloneRef,Debug)]
+pub struct WeakCulling {
+    model : Weak<CullingModel>,
+}
+
+impl WeakCulling {
+    /// Upgrade to a strong reference if the culling still exists.
+    pub fn upgrade(&self) -> Option<Culling> {
+        self.model.upgrade().map(|model| Culling {model})
+    }
+}
+
+
+
 // ============
 // === Size ===
 // ============
 
 /// Smart wrapper for size attribute of sprite. The size attribute is set to zero in order to hide
-/// the sprite. This wrapper remembers the real size when the sprite is hidden and allows changing
-/// it without making the sprite appear on the screen.
+/// or cull the sprite. This wrapper remembers the real size when the sprite is hidden and allows
+/// changing it without making the sprite appear on the screen.
 #[derive(Debug,Clone,CloneRef)]
 pub struct Size {
-    hidden : Rc<Cell<bool>>,
-    value  : Rc<Cell<Vector2<f32>>>,
-    attr   : Attribute<Vector2<f32>>,
+    model : Rc<InstanceModel>,
 }
 
 // === Setters ===
 
 impl HasItem    for Size { type Item = Vector2; }
-impl CellGetter for Size { fn get(&self) -> Vector2 { self.value.get() } }
-impl CellSetter for Size { fn set(&self, v:Vector2) {
-    self.value.set(v);
-    if !self.hidden.get() {
-        self.attr.set(v)
-    }
-}}
+impl CellGetter for Size { fn get(&self) -> Vector2 { self.model.size.get() } }
+impl CellSetter for Size { fn set(&self, v:Vector2) { self.model.set_size(v) } }
 
 
 // === Private API ===
 
 impl Size {
-    fn new(attr:Attribute<Vector2<f32>>) -> Self {
-        let hidden = Rc::new(Cell::new(true));
-        let value  = Rc::new(Cell::new(zero()));
-        Self {hidden,value,attr}
-    }
-
     fn hide(&self) {
-        self.hidden.set(true);
-        self.attr.set(zero());
+        self.model.set_hidden(true);
     }
 
     fn show(&self) {
-        self.hidden.set(false);
-        self.attr.set(self.value.get());
+        self.model.set_hidden(false);
+    }
+
+    fn set_matrix(&self, matrix:Matrix4<f32>) {
+        self.model.set_matrix(matrix);
     }
 }
 
@@ -155,7 +373,6 @@ pub struct Sprite {
     pub instance_id : attribute::InstanceIndex,
     pub size        : Size,
     display_object  : display::object::Instance,
-    transform       : Attribute<Matrix4<f32>>,
     stats           : Rc<SpriteStats>,
     guard           : Rc<SpriteGuard>,
 }
@@ -167,25 +384,26 @@ impl Sprite {
     , instance_id : attribute::InstanceIndex
     , transform   : Attribute<Matrix4<f32>>
     , size        : Attribute<Vector2<f32>>
+    , culling     : &Culling
     , stats       : &Stats
     ) -> Self {
         let symbol         = symbol.clone_ref();
         let logger         = Logger::new(iformat!("Sprite{instance_id}"));
         let display_object = display::object::Instance::new(logger);
         let stats          = Rc::new(SpriteStats::new(stats));
+        let model          = InstanceModel::new(instance_id,transform,size,culling);
+        let size           = Size {model};
         let guard          = Rc::new(SpriteGuard::new(instance_id,&symbol,&size,&display_object));
-        let size           = Size::new(size);
         let default_size   = Vector2(DEFAULT_SPRITE_SIZE.0,DEFAULT_SPRITE_SIZE.1);
         size.set(default_size);
-        Self {symbol,instance_id,size,display_object,transform,stats,guard}.init()
+        Self {symbol,instance_id,size,display_object,stats,guard}.init()
     }
 
     /// Init display object bindings. In particular defines the behavior of the show and hide
     /// callbacks.
     fn init(self) -> Self {
-        let size      = &self.size;
-        let transform = &self.transform;
-        self.display_object.set_on_updated(f!((t) transform.set(t.matrix())));
+        let size = &self.size;
+        self.display_object.set_on_updated(f!((t) size.set_matrix(t.matrix())));
         self.display_object.set_on_hide(f_!(size.hide()));
         self.display_object.set_on_show(f__!(size.show()));
         self
@@ -229,6 +447,7 @@ pub struct SpriteSystem {
     uv         : Buffer  <Vector2<f32>>,
     size       : Buffer  <Vector2<f32>>,
     alignment  : Uniform <Vector2<f32>>,
+    culling    : Culling,
     stats      : Stats,
 }
 
@@ -246,10 +465,13 @@ impl SpriteSystem {
         let size              = instance_scope.add_buffer("size");
         let initial_alignment = Self::uv_offset(Alignment::center());
         let alignment         = symbol.variables().add_or_panic("alignment",initial_alignment);
+        let culling           = Culling::new(&symbol);
+        culling.set_origin(initial_alignment);
+        scene.culling.add(symbol.id,&culling);
 
         stats.inc_sprite_system_count();
 
-        let this = Self {symbol,transform,uv,size,alignment,stats};
+        let this = Self {symbol,transform,uv,size,alignment,culling,stats};
         this.init_attributes();
         this.init_shader();
         this
@@ -260,7 +482,8 @@ impl SpriteSystem {
         let instance_id  = self.symbol.surface().instance_scope().add_instance();
         let transform    = self.transform.at(instance_id);
         let size         = self.size.at(instance_id);
-        let sprite       = Sprite::new(&self.symbol,instance_id,transform,size,&self.stats);
+        let culling      = &self.culling;
+        let sprite       = Sprite::new(&self.symbol,instance_id,transform,size,culling,&self.stats);
         self.add_child(&sprite);
         sprite
     }
@@ -282,7 +505,20 @@ impl SpriteSystem {
 
     /// Set alignment of sprites.
     pub fn set_alignment(&self, alignment:Alignment) {
-        self.alignment.set(Self::uv_offset(alignment));
+        let origin = Self::uv_offset(alignment);
+        self.alignment.set(origin);
+        self.culling.set_origin(origin);
+    }
+
+    /// The position of the origin of sprites, relative to their size. For example, it is
+    /// `(0.5,0.5)` for the center alignment and `(0.0,1.0)` for the top left one.
+    pub fn origin(&self) -> Vector2<f32> {
+        self.alignment.get()
+    }
+
+    /// The view frustum culling of sprites of this system.
+    pub fn culling(&self) -> &Culling {
+        &self.culling
     }
 
     /// Run the renderer.
@@ -376,3 +612,118 @@ impl display::Object for SpriteSystem {
         self.symbol.display_object()
     }
 }
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::system::gpu::data::attribute::InstanceIndex;
+
+    fn buffer<T:Storable>(stats:&Stats, size:usize) -> Buffer<T> {
+        let buffer = Buffer::new(Logger::new("test"),stats,||{},||{});
+        buffer.add_elements(size);
+        buffer
+    }
+
+    struct Fixture {
+        culling   : Culling,
+        size      : Buffer<Vector2<f32>>,
+        transform : Buffer<Matrix4<f32>>,
+    }
+
+    impl Fixture {
+        fn new(instance_count:usize) -> Self {
+            let stats     = Stats::default();
+            let culling   = Culling::default();
+            let size      = buffer(&stats,instance_count);
+            let transform = buffer(&stats,instance_count);
+            culling.set_origin(Vector2(0.5,0.5));
+            Self {culling,size,transform}
+        }
+
+        fn shown_instance(&self, index:usize, x:f32, y:f32) -> Rc<InstanceModel> {
+            let index     = InstanceIndex::new(index);
+            let size      = self.size.at(index);
+            let transform = self.transform.at(index);
+            let instance  = InstanceModel::new(index,transform,size,&self.culling);
+            instance.set_size(Vector2(10.0,10.0));
+            instance.set_matrix(Matrix4::new_translation(&Vector3(x,y,0.0)));
+            instance.set_hidden(false);
+            instance
+        }
+
+        fn uploaded_size(&self, index:usize) -> Vector2<f32> {
+            self.size.get(index)
+        }
+    }
+
+    #[test]
+    fn culling_sprites_outside_of_visible_area() {
+        let fixture = Fixture::new(2);
+        let inside  = fixture.shown_instance(0,0.0,0.0);
+        let outside = fixture.shown_instance(1,100.0,0.0);
+        assert_eq!(fixture.culling.drawn_count(), 2);
+        assert_eq!(fixture.culling.culled_count(), 0);
+
+        let area = BoundingBox::from_corners(Vector2(-50.0,-50.0),Vector2(50.0,50.0));
+        fixture.culling.set_area(Some(area));
+        assert_eq!(fixture.culling.drawn_count(), 1);
+        assert_eq!(fixture.culling.culled_count(), 1);
+        assert_eq!(fixture.uploaded_size(0), Vector2(10.0,10.0));
+        assert_eq!(fixture.uploaded_size(1), Vector2(0.0,0.0));
+        assert!(!fixture.culling.is_fully_culled());
+
+        // The partially visible sprite is not culled.
+        outside.set_matrix(Matrix4::new_translation(&Vector3(54.0,0.0,0.0)));
+        assert_eq!(fixture.culling.drawn_count(), 2);
+        assert_eq!(fixture.uploaded_size(1), Vector2(10.0,10.0));
+        assert_eq!(fixture.transform.get(1), Matrix4::new_translation(&Vector3(54.0,0.0,0.0)));
+
+        inside.set_matrix(Matrix4::new_translation(&Vector3(-100.0,0.0,0.0)));
+        outside.set_matrix(Matrix4::new_translation(&Vector3(100.0,0.0,0.0)));
+        assert!(fixture.culling.is_fully_culled());
+
+        fixture.culling.set_area(None);
+        assert_eq!(fixture.culling.drawn_count(), 2);
+        assert_eq!(fixture.culling.culled_count(), 0);
+        assert_eq!(fixture.uploaded_size(0), Vector2(10.0,10.0));
+    }
+
+    #[test]
+    fn counting_hidden_and_disposed_sprites() {
+        let fixture  = Fixture::new(1);
+        let instance = fixture.shown_instance(0,100.0,0.0);
+        let area     = BoundingBox::from_corners(Vector2(-50.0,-50.0),Vector2(50.0,50.0));
+        fixture.culling.set_area(Some(area));
+        assert_eq!(fixture.culling.culled_count(), 1);
+
+        instance.set_hidden(true);
+        assert_eq!(fixture.culling.culled_count(), 0);
+        assert!(!fixture.culling.is_fully_culled());
+        instance.set_matrix(Matrix4::identity());
+        assert_eq!(fixture.culling.drawn_count(), 0);
+        assert_eq!(fixture.uploaded_size(0), Vector2(0.0,0.0));
+
+        instance.set_hidden(false);
+        assert_eq!(fixture.culling.drawn_count(), 1);
+        instance.dispose();
+        assert_eq!(fixture.culling.drawn_count(), 0);
+        assert_eq!(fixture.culling.culled_count(), 0);
+        assert!(fixture.culling.model.instances.borrow().is_empty());
+    }
+
+    #[test]
+    fn upgrading_weak_culling() {
+        let culling = Culling::default();
+        let weak    = culling.downgrade();
+        assert!(weak.upgrade().is_some());
+        drop(culling);
+        assert!(weak.upgrade().is_none());
+    }
+}
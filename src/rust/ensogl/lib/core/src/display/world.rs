@@ -111,6 +111,7 @@ impl World {
     fn init_hotkeys(&self) {
         // -----------------------------------------------------------------------------------------
         // FIXME[WD]: Hacky way of switching display_mode. To be fixed and refactored out.
+        let logger        = self.logger.clone_ref();
         let stats_monitor = self.stats_monitor.clone_ref();
         let display_mode  = self.uniforms.display_mode.clone_ref();
         let c: Closure<dyn Fn(JsValue)> = Closure::wrap(Box::new(move |val| {
@@ -118,6 +119,11 @@ impl World {
             if event.alt_key() && event.ctrl_key() {
                 let key   = event.code();
                 if      key == "Backquote" { stats_monitor.toggle() }
+                else if key == "KeyS"      {
+                    if let Err(err) = stats_monitor.download_history() {
+                        error!(logger,"Cannot export the stats history: {err}");
+                    }
+                }
                 else if key == "Digit0"    { display_mode.set(0) }
                 else if key == "Digit1"    { display_mode.set(1) }
                 else if key == "Digit2"    { display_mode.set(2) }
@@ -131,10 +137,12 @@ impl World {
 
     fn init_composer(&self) {
         let mouse_hover_ids     = self.scene.mouse.hover_ids.clone_ref();
+        let picking_mode        = self.scene.mouse.picking_mode.clone_ref();
         let mut pixel_read_pass = PixelReadPass::<u8>::new(&self.scene.mouse.position);
         pixel_read_pass.set_callback(move |v| {
             mouse_hover_ids.set(Vector4::from_iterator(v.iter().map(|value| *value as u32)))
         });
+        pixel_read_pass.set_condition(move || picking_mode.get().uses_pixel_read());
         // TODO: We may want to enable it on weak hardware.
         // pixel_read_pass.set_threshold(1);
         let logger   = &self.scene.renderer.logger;
@@ -150,6 +158,12 @@ impl World {
         &self.scene
     }
 
+    /// Performance monitor accessor. Allows registering custom panels and exporting the recorded
+    /// history of measurements.
+    pub fn stats_monitor(&self) -> &stats::Monitor {
+        &self.stats_monitor
+    }
+
     /// Register a callback which should be run before each animation frame.
     pub fn on_before_frame<F:FnMut(animation::TimeInfo)+'static>
     (&self, mut callback:F) -> callback::Handle {
@@ -3,7 +3,23 @@
 use crate::prelude::*;
 
 use crate::debug;
+use crate::debug::history::History;
+use crate::debug::monitor::Panel;
+use crate::debug::monitor::Sampler;
 use crate::debug::stats::Stats;
+use crate::system::web;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The name of the file with the history exported in the Chrome trace event format.
+pub const TRACE_FILE_NAME : &str = "ensogl-stats.trace.json";
+
+/// The name of the file with the history exported as JSON.
+pub const JSON_FILE_NAME : &str = "ensogl-stats.json";
 
 
 
@@ -13,12 +29,14 @@ use crate::debug::stats::Stats;
 
 shared! { Monitor
 
-/// Visual panel showing performance-related methods.
+/// Visual panel showing performance-related methods. The panels measure values only when the
+/// monitor is visible or the recording is enabled.
 #[derive(Debug)]
 pub struct MonitorData {
-    stats   : Stats,
-    monitor : debug::Monitor,
-    panels  : Vec<debug::monitor::Panel>
+    stats     : Stats,
+    monitor   : debug::Monitor,
+    panels    : Vec<debug::monitor::Panel>,
+    recording : bool,
 }
 
 impl {
@@ -40,13 +58,48 @@ impl {
             monitor.add( debug::monitor::ShaderCompileCount :: new(&stats) ),
             monitor.add( debug::monitor::SpriteSystemCount  :: new(&stats) ),
             monitor.add( debug::monitor::SpriteCount        :: new(&stats) ),
+            monitor.add( debug::monitor::DrawnSpriteCount   :: new(&stats) ),
+            monitor.add( debug::monitor::CulledSpriteCount  :: new(&stats) ),
         ];
-        Self {stats,monitor,panels}
+        let recording = false;
+        Self {stats,monitor,panels,recording}
+    }
+
+    /// Add a custom panel, for example a [`debug::monitor::Gauge`] showing a metric of a component.
+    pub fn add_panel<S>(&mut self, sampler:S) -> Panel
+    where S : Sampler+'static {
+        let panel = self.monitor.add(sampler);
+        self.panels.push(panel.clone());
+        panel
+    }
+
+    /// Enable or disable measuring the data while the monitor is hidden, in order to record its
+    /// history.
+    pub fn set_recording(&mut self, recording:bool) {
+        self.recording = recording;
+    }
+
+    /// Set the maximum number of samples recorded by every panel.
+    pub fn set_history_capacity(&mut self, capacity:usize) {
+        self.monitor.set_history_capacity(capacity)
+    }
+
+    /// The values recorded by all panels. See [`History`] to learn how to export them.
+    pub fn history(&self) -> History {
+        self.monitor.history()
+    }
+
+    /// Let the user download the recorded history, both in the Chrome trace event format and as
+    /// JSON. See [`History`] to learn more about the formats.
+    pub fn download_history(&self) -> web::Result<()> {
+        let history = self.monitor.history();
+        web::try_download_text_file(TRACE_FILE_NAME,&history.to_chrome_trace())?;
+        web::try_download_text_file(JSON_FILE_NAME,&history.to_json())
     }
 
     /// Start measuring data.
     pub fn begin(&mut self) {
-        if self.visible() {
+        if self.is_measuring() {
             for panel in &self.panels {
                 panel.begin();
             }
@@ -55,7 +108,7 @@ impl {
 
     /// Finish measuring data.
     pub fn end(&mut self) {
-        if self.visible() {
+        if self.is_measuring() {
             for panel in &self.panels {
                 panel.end();
             }
@@ -70,6 +123,12 @@ impl {
         self.monitor.visible()
     }
 
+    /// Checks if the panels measure the data, which happens when the monitor is visible or the
+    /// recording is enabled.
+    pub fn is_measuring(&self) -> bool {
+        self.visible() || self.recording
+    }
+
     /// Show the monitor.
     pub fn show(&mut self) {
         self.monitor.show()
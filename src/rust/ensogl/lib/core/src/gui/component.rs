@@ -15,6 +15,7 @@ use crate::display::scene::layer::WeakLayer;
 use crate::display::scene;
 use crate::display::shape::primitive::system::DynamicShape;
 use crate::display::shape::primitive::system::DynamicShapeInternals;
+use crate::display::shape::primitive::system::ShapeSystemInstance;
 use crate::display;
 use crate::display::symbol::SymbolId;
 use crate::system::gpu::data::attribute;
@@ -187,9 +188,22 @@ impl<S:DynamicShape> ShapeViewModel<S> {
     fn add_to_scene_layer(&self, scene:&Scene, layer:&scene::Layer) {
         let instance = layer.instantiate(scene,&self.shape);
         scene.shapes.insert_mouse_target(instance.symbol_id,instance.instance_id,self.events.clone_ref());
+        self.register_hit_target(scene,layer,&instance);
         self.pointer_targets.borrow_mut().push((instance.symbol_id,instance.instance_id));
         *self.registry.borrow_mut() = Some(scene.shapes.clone_ref());
     }
+
+    fn register_hit_target
+    (&self, scene:&Scene, layer:&scene::Layer, instance:&scene::layer::LayerDynamicShapeInstance) {
+        let system = layer.shape_system_registry.shape_system(scene,PhantomData::<S>);
+        let sprite = self.shape.sprites().into_iter().find(|sprite|
+            sprite.symbol_id() == instance.symbol_id && sprite.instance_id == instance.instance_id
+        );
+        if let Some(sprite) = sprite {
+            let target = scene::hit_test::HitTarget::new(&sprite,system.shape_system());
+            scene.shapes.insert_hit_target(instance.symbol_id,instance.instance_id,target);
+        }
+    }
 }
 
 impl<S> ShapeViewModel<S> {
@@ -476,3 +476,33 @@ impl Cursor {
 impl display::Object for Cursor {
     fn display_object(&self) -> &display::object::Instance { &self.model.display_object }
 }
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(all(test,not(target_arch="wasm32")))]
+mod tests {
+    use super::*;
+
+    use crate::display::shape::primitive::cpu::Sampler;
+    use crate::display::style;
+
+    fn rasterize(press:f32) -> crate::display::shape::primitive::cpu::Image {
+        let style_watch = StyleWatch::new(&style::Sheet::new());
+        let shape       = shape::ShapeSystem::shape_def(&style_watch);
+        let mut sampler = Sampler::new(Vector2(32.0,20.0));
+        sampler.set_variable("input_press",press);
+        sampler.set_variable("input_radius",DEFAULT_RADIUS);
+        sampler.set_variable("input_color",Vector4(0.2,0.4,0.6,0.8));
+        sampler.rasterize(&shape).unwrap()
+    }
+
+    #[test]
+    fn cursor_shape_snapshots() {
+        crate::assert_golden_image!("cursor",rasterize(0.0));
+        crate::assert_golden_image!("cursor_pressed",rasterize(1.0));
+    }
+}
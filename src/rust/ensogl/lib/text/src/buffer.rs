@@ -62,11 +62,13 @@ impl Buffer {
 // === BufferData ===
 // ==================
 
-/// Internal data of `Buffer`.
+/// Internal data of `Buffer`. The `version` is incremented on every change of the text or its
+/// style, which allows caching values computed from them.
 #[derive(Debug,Default)]
 pub struct BufferData {
-    pub(crate) text  : TextCell,
-    pub(crate) style : StyleCell,
+    pub(crate) text    : TextCell,
+    pub(crate) style   : StyleCell,
+    pub(crate) version : Cell<usize>,
 }
 
 impl Deref for BufferData {
@@ -90,6 +92,7 @@ impl BufferData {
     /// Text setter.
     pub(crate) fn set_text(&self, text:impl Into<Text>) {
         self.text.set(text);
+        self.bump_version();
     }
 
     /// Style getter.
@@ -99,7 +102,18 @@ impl BufferData {
 
     /// Style setter.
     pub(crate) fn set_style(&self, style:Style) {
-        self.style.set(style)
+        self.style.set(style);
+        self.bump_version();
+    }
+
+    /// The number of changes of the text and its style so far.
+    pub fn version(&self) -> usize {
+        self.version.get()
+    }
+
+    /// Mark the text or its style as changed.
+    pub(crate) fn bump_version(&self) {
+        self.version.set(self.version.get() + 1)
     }
 
     /// Query style information for the provided range.
@@ -135,6 +149,7 @@ impl Setter<Text> for Buffer {
         let size  = text.byte_size();
         self.text.replace(range,text);
         self.style.set_resize_with_default(range,size);
+        self.bump_version();
     }
 }
 
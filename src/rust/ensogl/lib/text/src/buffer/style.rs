@@ -115,7 +115,9 @@ macro_rules! define_styles {
             impl Setter<Option<$field_type>> for Buffer {
                 fn replace(&self, range:impl data::RangeBounds, data:Option<$field_type>) {
                     let range = self.crop_byte_range(range);
-                    self.data.style.cell.borrow_mut().$field.replace_resize(range,range.size(),data)
+                    let size  = range.size();
+                    self.data.style.cell.borrow_mut().$field.replace_resize(range,size,data);
+                    self.bump_version();
                 }
             }
 
@@ -128,6 +130,7 @@ macro_rules! define_styles {
             impl DefaultSetter<$field_type> for Buffer {
                 fn set_default(&self, data:$field_type) {
                     self.style.cell.borrow_mut().$field.default = data;
+                    self.bump_version();
                 }
             }
         )*
@@ -219,19 +222,95 @@ impl<T:Clone> DerefMut for Property<T> {
 def_style_property!(Size(f32));
 def_style_property!(Bold(bool));
 def_style_property!(Italic(bool));
-def_style_property!(Underline(bool));
+def_style_property!(Strikethrough(bool));
+def_style_property!(LetterSpacing(f32));
 
-impl Default for Size      { fn default() -> Self { Self::new(12.0) } }
-impl Default for Bold      { fn default() -> Self { Self::new(false) } }
-impl Default for Italic    { fn default() -> Self { Self::new(false) } }
-impl Default for Underline { fn default() -> Self { Self::new(false) } }
+impl Default for Size          { fn default() -> Self { Self::new(12.0) } }
+impl Default for Bold          { fn default() -> Self { Self::new(false) } }
+impl Default for Italic        { fn default() -> Self { Self::new(false) } }
+impl Default for Strikethrough { fn default() -> Self { Self::new(false) } }
+impl Default for LetterSpacing { fn default() -> Self { Self::new(0.0) } }
+
+
+// === Underline ===
+
+/// The shape of the underline.
+#[derive(Clone,Copy,Debug,PartialEq)]
+#[allow(missing_docs)]
+pub enum UnderlineKind {None,Straight,Wavy}
+
+impl Default for UnderlineKind {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+/// Underline style property. The underline uses the text color if its own color is not provided.
+#[derive(Clone,Copy,Debug,Default,PartialEq)]
+#[allow(missing_docs)]
+pub struct Underline {
+    pub kind  : UnderlineKind,
+    pub color : Option<color::Rgba>,
+}
+
+impl Underline {
+    /// Constructor of a straight underline in the text color.
+    pub fn straight() -> Self {
+        let kind = UnderlineKind::Straight;
+        Self {kind,..default()}
+    }
+
+    /// Constructor of a wavy underline in the text color, like the ones used to mark errors.
+    pub fn wavy() -> Self {
+        let kind = UnderlineKind::Wavy;
+        Self {kind,..default()}
+    }
+
+    /// Set the color of the underline.
+    pub fn with_color(mut self, color:impl Into<color::Rgba>) -> Self {
+        self.color = Some(color.into());
+        self
+    }
+
+    /// Checks whether the underline is drawn.
+    pub fn is_visible(&self) -> bool {
+        self.kind != UnderlineKind::None
+    }
+}
+
+
+// === Background ===
+
+/// Background color style property. The background is transparent by default.
+#[derive(Clone,Copy,Debug,From,PartialEq)]
+pub struct Background {
+    /// The raw, weakly typed value.
+    pub raw : color::Rgba
+}
+
+impl Background {
+    /// Constructor.
+    pub fn new(raw:impl Into<color::Rgba>) -> Self {
+        let raw = raw.into();
+        Self {raw}
+    }
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Self::new(color::Rgba::transparent())
+    }
+}
 
 define_styles! {
-    size      : Size,
-    color     : color::Rgba,
-    bold      : Bold,
-    italics   : Italic,
-    underline : Underline,
+    size           : Size,
+    color          : color::Rgba,
+    bold           : Bold,
+    italics        : Italic,
+    underline      : Underline,
+    strikethrough  : Strikethrough,
+    background     : Background,
+    letter_spacing : LetterSpacing,
 }
 
 
@@ -274,3 +353,39 @@ impl StyleCell {
         self.cell.borrow_mut().set_resize_with_default(range,len)
     }
 }
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rich_text_spans() {
+        let buffer    = Buffer::new();
+        let underline = Underline::wavy().with_color(color::Rgba::red());
+        let highlight = Background::new(color::Rgba::blue());
+        buffer.replace(..,Text::from("hello world"));
+        buffer.replace(Bytes(0)..Bytes(5),underline);
+        buffer.replace(Bytes(6)..Bytes(11),Strikethrough(true));
+        buffer.replace(Bytes(3)..Bytes(8),highlight);
+        buffer.set_default(LetterSpacing(1.5));
+
+        let values = buffer.style().iter().collect_vec();
+        assert_eq!(values.len(),11);
+        assert_eq!(values[0].underline,underline);
+        assert_eq!(values[5].underline,Underline::default());
+        assert!(!values[5].underline.is_visible());
+        assert_eq!(values[5].strikethrough,Strikethrough(false));
+        assert_eq!(values[6].strikethrough,Strikethrough(true));
+        assert_eq!(values[2].background,Background::default());
+        assert_eq!(values[3].background,highlight);
+        assert_eq!(values[7].background,highlight);
+        assert_eq!(values[8].background,Background::default());
+        assert!(values.iter().all(|value| value.letter_spacing == LetterSpacing(1.5)));
+    }
+}
@@ -5,9 +5,11 @@ use crate::prelude::*;
 pub mod movement;
 pub mod selection;
 pub mod word;
+pub mod wrap;
 
 pub use movement::*;
 pub use selection::Selection;
+pub use wrap::WrapMode;
 
 use crate::buffer::Buffer;
 use crate::buffer::DefaultSetter;
@@ -101,6 +103,9 @@ pub struct ViewBuffer {
     pub selection         : Rc<RefCell<selection::Group>>,
     pub next_selection_id : Rc<Cell<usize>>,
     pub history           : History,
+    pub wrap_mode         : Rc<Cell<WrapMode>>,
+    measure               : Rc<RefCell<Rc<dyn wrap::Measure>>>,
+    layout_cache          : Rc<RefCell<Option<CachedLayout>>>,
 }
 
 impl Deref for ViewBuffer {
@@ -115,7 +120,11 @@ impl From<Buffer> for ViewBuffer {
         let selection         = default();
         let next_selection_id = default();
         let history           = default();
-        Self {buffer,selection,next_selection_id,history}
+        let wrap_mode         = default();
+        let measure           = Rc::new(wrap::Unmeasured) as Rc<dyn wrap::Measure>;
+        let measure           = Rc::new(RefCell::new(measure));
+        let layout_cache      = default();
+        Self {buffer,selection,next_selection_id,history,wrap_mode,measure,layout_cache}
     }
 }
 
@@ -330,6 +339,59 @@ impl ViewBuffer {
 }
 
 
+// === Soft Wrapping ===
+
+/// The layout computed for the given buffer version and wrap mode.
+#[derive(Clone,Debug)]
+struct CachedLayout {
+    version   : usize,
+    wrap_mode : WrapMode,
+    layout    : Rc<wrap::Layout>,
+}
+
+impl ViewBuffer {
+    /// Set the measure of the displayed chars, used to find the wrap points. Until it is set, the
+    /// lines are not wrapped.
+    pub fn set_measure(&self, measure:Rc<dyn wrap::Measure>) {
+        *self.measure.borrow_mut()      = measure;
+        *self.layout_cache.borrow_mut() = None;
+    }
+
+    /// The visual lines of the whole buffer, laid out according to the current `WrapMode`. The
+    /// layout is cached until the text, its style, the wrap mode, or the measure changes.
+    pub fn layout(&self) -> Rc<wrap::Layout> {
+        let version   = self.buffer.version();
+        let wrap_mode = self.wrap_mode.get();
+        let is_valid  = |c:&&CachedLayout| c.version == version && c.wrap_mode == wrap_mode;
+        let cache     = self.layout_cache.borrow().as_ref().filter(is_valid).cloned();
+        cache.map(|c| c.layout).unwrap_or_else(|| {
+            let measure = self.measure.borrow().clone();
+            let layout  = wrap::Layout::new(&self.text(),&self.style(),wrap_mode,&*measure);
+            let layout  = Rc::new(layout);
+            let cache   = CachedLayout {version,wrap_mode,layout:layout.clone()};
+            *self.layout_cache.borrow_mut() = Some(cache);
+            layout
+        })
+    }
+
+    /// Byte range of the part of the buffer line displayed in the given visual line.
+    pub fn byte_range_of_visual_line(&self, line:&wrap::VisualLine) -> Range<Bytes> {
+        let start = self.byte_offset_of_location_snapped(Location(line.line,line.start));
+        let end   = self.byte_offset_of_location_snapped(Location(line.line,line.end));
+        start .. end
+    }
+
+    /// The text displayed in the given visual line, including the ellipsis if the line was
+    /// truncated.
+    pub fn visual_line_content(&self, line:&wrap::VisualLine) -> String {
+        let range       = self.byte_range_of_visual_line(line);
+        let mut content = String::from(self.text().sub(range));
+        if line.ellipsis { content.push(wrap::ELLIPSIS) }
+        content
+    }
+}
+
+
 
 // ===========
 // === FRP ===
@@ -364,6 +426,11 @@ ensogl_core::define_endpoints! {
         set_default_color          (color::Rgba),
         set_default_text_size      (style::Size),
         set_color_bytes            (buffer::Range<Bytes>,color::Rgba),
+        set_underline_bytes        (buffer::Range<Bytes>,style::Underline),
+        set_strikethrough_bytes    (buffer::Range<Bytes>,style::Strikethrough),
+        set_background_bytes       (buffer::Range<Bytes>,style::Background),
+        set_letter_spacing_bytes   (buffer::Range<Bytes>,style::LetterSpacing),
+        set_wrap_mode              (WrapMode),
     }
 
     Output {
@@ -441,10 +508,15 @@ impl View {
             sel_on_remove_all <- input.remove_all_cursors.map(|_| default());
             sel_on_undo       <= input.undo.map(f_!(m.undo()));
 
-            eval input.set_default_color     ((t) m.set_default(*t));
-            eval input.set_default_text_size ((t) m.set_default(*t));
-            eval input.set_color_bytes       (((range,color)) m.replace(range,*color));
-            eval input.set_default_color     ((color) m.set_default(*color));
+            eval input.set_default_color        ((t) m.set_default(*t));
+            eval input.set_default_text_size    ((t) m.set_default(*t));
+            eval input.set_color_bytes          (((range,color)) m.replace(range,*color));
+            eval input.set_underline_bytes      (((range,underline)) m.replace(range,*underline));
+            eval input.set_strikethrough_bytes  (((range,strike)) m.replace(range,*strike));
+            eval input.set_background_bytes     (((range,background)) m.replace(range,*background));
+            eval input.set_letter_spacing_bytes (((range,spacing)) m.replace(range,*spacing));
+            eval input.set_default_color        ((color) m.set_default(*color));
+            eval input.set_wrap_mode            ((mode) m.wrap_mode.set(*mode));
 
             output.source.selection_edit_mode     <+ sel_on_modification;
             output.source.selection_edit_mode     <+ sel_on_undo;
@@ -605,4 +677,12 @@ impl ViewModel {
     pub fn view_lines(&self) -> Vec<String> {
         self.lines_vec(self.view_byte_range())
     }
+
+    /// The visual lines of this buffer view, laid out according to the current `WrapMode`. The
+    /// visual line indexes are relative to the first line of the view.
+    pub fn view_layout(&self) -> wrap::Layout {
+        let first = self.first_view_line_index();
+        let last  = self.last_view_line_index();
+        self.layout().sub(first,last)
+    }
 }
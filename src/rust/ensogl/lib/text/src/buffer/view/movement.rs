@@ -60,9 +60,13 @@ impl ViewBuffer {
         if modify {end} else if move_up {selection.min()} else {selection.max()}
     }
 
-    /// Compute movement based on vertical motion by the given number of lines.
+    /// Compute movement based on vertical motion by the given number of lines. If the soft
+    /// wrapping is enabled, the motion is done by visual lines instead.
     fn vertical_motion
     (&self, selection:Selection, line_delta:Line, modify:bool) -> selection::Shape {
+        if self.wrap_mode.get() != WrapMode::None {
+            return self.visual_vertical_motion(selection,line_delta,modify)
+        }
         let move_up      = line_delta < 0.line();
         let location     = self.vertical_motion_selection_to_location(selection,move_up,modify);
         let min_line     = 0.line();
@@ -77,6 +81,22 @@ impl ViewBuffer {
         selection::Shape(selection.start,tgt_location)
     }
 
+    /// Compute movement based on vertical motion by the given number of visual lines. The column
+    /// in the visual line is preserved if possible. Moving above the first line or below the last
+    /// one snaps to the text start or end respectively.
+    fn visual_vertical_motion
+    (&self, selection:Selection, line_delta:Line, modify:bool) -> selection::Shape {
+        let move_up        = line_delta < 0.line();
+        let location       = self.vertical_motion_selection_to_location(selection,move_up,modify);
+        let layout         = self.layout();
+        let (index,column) = layout.to_visual(location);
+        let target         = index as i32 + line_delta.value;
+        let tgt_location   = if target < 0 { default() }
+            else if target as usize >= layout.len() { self.text().location_of_text_end() }
+            else { layout.from_visual(target as usize,column) };
+        selection::Shape(selection.start,tgt_location)
+    }
+
     /// Apply the movement to each region in the selection, and returns the union of the results.
     ///
     /// If `modify` is `true`, the selections are modified, otherwise the results of individual
@@ -0,0 +1,502 @@
+//! Soft wrapping of long lines. A buffer line is displayed as one or more visual lines, depending
+//! on the `WrapMode`. All widths are expressed in pixels, computed from the glyph advances of the
+//! displayed characters in the same way the `Pen` places glyphs, so the lines end where the text
+//! actually reaches the given width, also for proportional fonts.
+
+use crate::prelude::*;
+
+use crate::buffer::data::Text;
+use crate::buffer::data::unit::*;
+use crate::buffer::style::Style;
+use crate::typeface::font::Font;
+use crate::typeface::pen;
+use crate::typeface::pen::CharInfo;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The character displayed at the end of lines truncated in the `WrapMode::Ellipsis` mode.
+pub const ELLIPSIS : char = '…';
+
+
+
+// ================
+// === WrapMode ===
+// ================
+
+/// Describes how lines longer than the available width are displayed. The widths are given in
+/// pixels.
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum WrapMode {
+    /// Every buffer line is displayed as a single visual line, regardless of its length.
+    None,
+    /// Lines are broken at the last grapheme cluster fitting in the given width.
+    Anywhere(f32),
+    /// Lines are broken after the last word fitting in the given width. The whitespace after the
+    /// word is kept at the end of the visual line. Words longer than the width are broken
+    /// anywhere.
+    AtWords(f32),
+    /// Lines longer than the given width are truncated and end with the `ELLIPSIS` character,
+    /// which fits in the width as well. Used mainly by single line labels.
+    Ellipsis(f32),
+}
+
+impl Default for WrapMode {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+
+
+// ===============
+// === Measure ===
+// ===============
+
+/// Measures the displayed characters to find the wrap points.
+pub trait Measure : Debug {
+    /// The advance of the pen after every char of the sequence, including the kerning with the
+    /// next char and the letter spacing, in pixels.
+    fn advances(&self, chars:&[CharInfo]) -> Vec<f32>;
+}
+
+/// Measures all chars as having no width, so no line is ever wrapped. Used until the font of the
+/// displayed text is known.
+#[derive(Clone,Copy,Debug,Default)]
+pub struct Unmeasured;
+
+impl Measure for Unmeasured {
+    fn advances(&self, chars:&[CharInfo]) -> Vec<f32> {
+        vec![0.0;chars.len()]
+    }
+}
+
+impl Measure for Font {
+    fn advances(&self, chars:&[CharInfo]) -> Vec<f32> {
+        let mut pen     = pen::Pen::new(self);
+        let mut offsets = chars.iter().map(|info| pen.advance(Some(*info)).offset).collect_vec();
+        offsets.push(pen.advance(None).offset);
+        offsets.windows(2).map(|pair| pair[1] - pair[0]).collect()
+    }
+}
+
+
+
+// ==================
+// === VisualLine ===
+// ==================
+
+/// A part of a buffer line displayed as a single row of text.
+#[derive(Clone,Copy,Debug,Default,PartialEq,Eq)]
+pub struct VisualLine {
+    /// The index of the buffer line.
+    pub line     : Line,
+    /// The first column of the buffer line displayed in this visual line.
+    pub start    : Column,
+    /// The column after the last one displayed in this visual line.
+    pub end      : Column,
+    /// Whether the line was truncated and should be displayed with the `ELLIPSIS` at the end.
+    pub ellipsis : bool,
+}
+
+impl VisualLine {
+    /// The number of displayed columns, not including the ellipsis.
+    pub fn len(&self) -> Column {
+        self.end - self.start
+    }
+
+    /// Checks whether the visual line displays no columns.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// The buffer location of the given column of this visual line. The column is clamped to the
+    /// displayed part of the line.
+    pub fn location(&self, column:Column) -> Location {
+        let column = column.max(0.column()).min(self.len());
+        Location(self.line,self.start + column)
+    }
+}
+
+
+
+// ==============
+// === Layout ===
+// ==============
+
+/// The visual lines of a text, ordered by their buffer locations.
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct Layout {
+    lines : Vec<VisualLine>,
+}
+
+impl Layout {
+    /// Compute the layout of the given text with its style, measuring the chars with `measure`.
+    pub fn new(text:&Text, style:&Style, mode:WrapMode, measure:&dyn Measure) -> Self {
+        let last_line = text.last_line_index();
+        let mut lines = vec![];
+        let mut line  = 0.line();
+        while line <= last_line {
+            let chars = LineChars::new(text,style,line);
+            lines.extend(wrap_line(line,&chars,mode,measure));
+            line += 1.line();
+        }
+        Self {lines}
+    }
+
+    /// The layout of the given range of buffer lines, including the `last_line`. The visual line
+    /// indexes of the returned layout are relative to the `first_line`.
+    pub fn sub(&self, first_line:Line, last_line:Line) -> Self {
+        let in_range = |line:&&VisualLine| line.line >= first_line && line.line <= last_line;
+        let lines    = self.lines.iter().filter(in_range).copied().collect();
+        Self {lines}
+    }
+
+    /// All visual lines.
+    pub fn lines(&self) -> &[VisualLine] {
+        &self.lines
+    }
+
+    /// The number of visual lines.
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Checks whether there are no visual lines.
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// The visual line at the given index.
+    pub fn line(&self, index:usize) -> Option<&VisualLine> {
+        self.lines.get(index)
+    }
+
+    /// The index of the visual line displaying the given location. A location at a wrap point is
+    /// displayed at the beginning of the next visual line. Locations past the line end are
+    /// displayed in the last visual line of their buffer line.
+    pub fn visual_line_index(&self, location:Location) -> usize {
+        let key    = (location.line,location.column);
+        let search = self.lines.binary_search_by(|line| (line.line,line.start).cmp(&key));
+        search.unwrap_or_else(|index| index.saturating_sub(1))
+    }
+
+    /// The visual line index and the column in the visual line of the given location. The column
+    /// is clamped to the displayed part of the line.
+    pub fn to_visual(&self, location:Location) -> (usize,Column) {
+        let index  = self.visual_line_index(location);
+        let column = self.lines.get(index).map(|line| {
+            if line.line != location.line { 0.column() } else {
+                (location.column - line.start).min(line.len())
+            }
+        });
+        (index,column.unwrap_or_default())
+    }
+
+    /// The buffer location of the given visual line index and column. Both are clamped to the
+    /// valid values. The column of a line followed by its wrapped part is clamped to the last
+    /// displayed column, as the location after it is displayed in the next visual line.
+    pub fn from_visual(&self, index:usize, column:Column) -> Location {
+        let index = index.min(self.lines.len().saturating_sub(1));
+        self.lines.get(index).map(|line| {
+            let next_line = self.lines.get(index + 1).map(|next| next.line);
+            let wrapped   = next_line == Some(line.line);
+            let column    = if wrapped { column.min(line.len() - 1.column()) } else { column };
+            line.location(column)
+        }).unwrap_or_default()
+    }
+}
+
+/// A grapheme cluster of a buffer line.
+#[derive(Clone,Copy,Debug)]
+struct Grapheme {
+    /// The first char of the cluster with its style, used to measure the `ELLIPSIS`.
+    first      : CharInfo,
+    /// The number of chars in the cluster.
+    char_count : usize,
+    whitespace : bool,
+}
+
+/// The chars of a buffer line with their styles, grouped into grapheme clusters.
+#[derive(Clone,Debug,Default)]
+struct LineChars {
+    graphemes : Vec<Grapheme>,
+    chars     : Vec<CharInfo>,
+}
+
+impl LineChars {
+    fn new(text:&Text, style:&Style, line:Line) -> Self {
+        let range      = text.byte_range_of_line_index_snapped(line);
+        let mut styles = style.sub(range.clone().into()).iter();
+        let mut offset = range.start;
+        let mut this   = Self::default();
+        while offset < range.end {
+            let next = text.next_grapheme_offset(offset).unwrap_or(range.end).min(range.end);
+            let grapheme : String = text.sub(offset..next).into();
+            let chars = grapheme.chars().map(|char| {
+                let style = styles.next().unwrap_or_default();
+                styles.drop((char.len_utf8() - 1).into());
+                CharInfo::new(char,style.size.raw).with_letter_spacing(style.letter_spacing.raw)
+            }).collect_vec();
+            if let Some(first) = chars.first().copied() {
+                let char_count = chars.len();
+                let whitespace = grapheme.chars().all(char::is_whitespace);
+                this.graphemes.push(Grapheme {first,char_count,whitespace});
+                this.chars.extend(chars);
+            }
+            offset = next;
+        }
+        this
+    }
+
+    /// The offsets of the grapheme clusters from the line start, followed by the line width.
+    fn offsets(&self, measure:&dyn Measure) -> Vec<f32> {
+        let mut advances = measure.advances(&self.chars).into_iter();
+        let mut offset   = 0.0;
+        let mut offsets  = vec![offset];
+        for grapheme in &self.graphemes {
+            offset += advances.by_ref().take(grapheme.char_count).sum::<f32>();
+            offsets.push(offset);
+        }
+        offsets
+    }
+
+    /// The width of the `ELLIPSIS` displayed after the given number of grapheme clusters. It uses
+    /// the style of the last displayed cluster.
+    fn ellipsis_width(&self, end:usize, measure:&dyn Measure) -> f32 {
+        let style = self.graphemes.get(end.saturating_sub(1)).map(|grapheme| grapheme.first);
+        let style = style.unwrap_or_else(|| CharInfo::new(ELLIPSIS,default()));
+        let chars = [CharInfo {char:ELLIPSIS,..style}];
+        measure.advances(&chars).first().copied().unwrap_or_default()
+    }
+}
+
+/// Break a line into visual lines. The chars are measured only if the mode wraps lines.
+fn wrap_line(line:Line, chars:&LineChars, mode:WrapMode, measure:&dyn Measure) -> Vec<VisualLine> {
+    let graphemes = &chars.graphemes;
+    let len       = graphemes.len();
+    let visual    = |start:usize, end:usize, ellipsis| {
+        VisualLine {line,start:start.into(),end:end.into(),ellipsis}
+    };
+    match mode {
+        WrapMode::None => vec![visual(0,len,false)],
+        WrapMode::Ellipsis(max_width) => {
+            let offsets = chars.offsets(measure);
+            if offsets[len] <= max_width { vec![visual(0,len,false)] } else {
+                let fits = |&end:&usize| {
+                    offsets[end] + chars.ellipsis_width(end,measure) <= max_width
+                };
+                let end = (0..len).rev().find(fits).unwrap_or_default();
+                vec![visual(0,end,true)]
+            }
+        }
+        WrapMode::Anywhere(max_width) | WrapMode::AtWords(max_width) => {
+            let offsets   = chars.offsets(measure);
+            let at_words  = matches!(mode,WrapMode::AtWords(_));
+            let width     = |start:usize, end:usize| offsets[end] - offsets[start];
+            let mut lines = vec![];
+            let mut start = 0;
+            while width(start,len) > max_width {
+                let fitting  = (start + 1 ..= len).take_while(|&end| width(start,end) <= max_width);
+                let limit    = fitting.last().unwrap_or(start + 1);
+                let word_end = (start + 1 ..= limit).rev().find(|&ix| graphemes[ix - 1].whitespace);
+                let mut end  = if at_words { word_end.unwrap_or(limit) } else { limit };
+                if at_words && word_end.is_some() {
+                    while end < len && graphemes[end].whitespace { end += 1 }
+                }
+                lines.push(visual(start,end,false));
+                start = end;
+            }
+            if start < len || lines.is_empty() {
+                lines.push(visual(start,len,false));
+            }
+            lines
+        }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::buffer::Buffer;
+    use crate::buffer::Setter;
+    use crate::buffer::view::Selection;
+    use crate::buffer::view::Transform;
+    use crate::buffer::view::ViewBuffer;
+
+    /// Measures every char as 10 pixels wide, except of `i` and `l`, which are 4 pixels wide.
+    #[derive(Clone,Copy,Debug)]
+    struct TestMeasure;
+
+    impl Measure for TestMeasure {
+        fn advances(&self, chars:&[CharInfo]) -> Vec<f32> {
+            chars.iter().map(|info| if "il".contains(info.char) {4.0} else {10.0}).collect()
+        }
+    }
+
+    fn layout(text:&str, mode:WrapMode) -> Layout {
+        let text      = Text::from(text);
+        let mut style = Style::default();
+        style.set_resize_with_default(default(),text.byte_size());
+        Layout::new(&text,&style,mode,&TestMeasure)
+    }
+
+    fn columns(text:&str, mode:WrapMode) -> Vec<(i32,i32,i32)> {
+        let layout = layout(text,mode);
+        layout.lines().iter().map(|l| (l.line.value,l.start.value,l.end.value)).collect()
+    }
+
+    #[test]
+    fn no_wrapping() {
+        assert_eq!(columns("hello world\nfoo",WrapMode::None),vec![(0,0,11),(1,0,3)]);
+        assert_eq!(columns("",WrapMode::None),vec![(0,0,0)]);
+    }
+
+    #[test]
+    fn wrapping_anywhere() {
+        let mode = WrapMode::Anywhere(40.0);
+        assert_eq!(columns("abcdefghij\nab",mode),vec![(0,0,4),(0,4,8),(0,8,10),(1,0,2)]);
+        assert_eq!(columns("abcd",mode),vec![(0,0,4)]);
+        assert_eq!(columns("\n",mode),vec![(0,0,0),(1,0,0)]);
+        assert_eq!(columns("abcdef",WrapMode::Anywhere(5.0)),vec![(0,0,1),(0,1,2),(0,2,3)
+            ,(0,3,4),(0,4,5),(0,5,6)]);
+    }
+
+    #[test]
+    fn wrapping_by_glyph_advances() {
+        let mode = WrapMode::Anywhere(40.0);
+        assert_eq!(columns("illlliabc",mode),vec![(0,0,7),(0,7,9)]);
+        assert_eq!(columns("wiiiiiiiiiw",mode),vec![(0,0,8),(0,8,11)]);
+    }
+
+    #[test]
+    fn wrapping_at_words() {
+        let mode = WrapMode::AtWords(80.0);
+        assert_eq!(columns("foo bar baz qux",mode),vec![(0,0,8),(0,8,15)]);
+        assert_eq!(columns("foo   bar",WrapMode::AtWords(40.0)),vec![(0,0,6),(0,6,9)]);
+        assert_eq!(columns("abcdefghijkl ab cd",mode),vec![(0,0,8),(0,8,16),(0,16,18)]);
+    }
+
+    #[test]
+    fn wrapping_multibyte_graphemes() {
+        let mode = WrapMode::Anywhere(20.0);
+        assert_eq!(columns("ąęść",mode),vec![(0,0,2),(0,2,4)]);
+    }
+
+    #[test]
+    fn ellipsis() {
+        let layout = layout("hello world\nhi",WrapMode::Ellipsis(60.0));
+        let lines  = layout.lines();
+        assert_eq!(lines.len(),2);
+        assert_eq!((lines[0].end,lines[0].ellipsis),(5.column(),true));
+        assert_eq!((lines[1].end,lines[1].ellipsis),(2.column(),false));
+        assert_eq!(layout.to_visual(Location(0.line(),9.column())),(0,5.column()));
+        let narrow = layout("hillo world",WrapMode::Ellipsis(60.0));
+        assert_eq!((narrow.lines()[0].end,narrow.lines()[0].ellipsis),(6.column(),true));
+    }
+
+    #[test]
+    fn visual_locations() {
+        let layout    = layout("abcdefghij\nab",WrapMode::Anywhere(40.0));
+        let to_visual = |line:i32, column:i32| {
+            layout.to_visual(Location(line.line(),column.column()))
+        };
+        assert_eq!(to_visual(0,0),(0,0.column()));
+        assert_eq!(to_visual(0,4),(1,0.column()));
+        assert_eq!(to_visual(0,6),(1,2.column()));
+        assert_eq!(to_visual(0,10),(2,2.column()));
+        assert_eq!(to_visual(1,1),(3,1.column()));
+        assert_eq!(layout.from_visual(1,3.column()),Location(0.line(),7.column()));
+        assert_eq!(layout.from_visual(1,7.column()),Location(0.line(),7.column()));
+        assert_eq!(layout.from_visual(2,9.column()),Location(0.line(),10.column()));
+        assert_eq!(layout.from_visual(9,0.column()),Location(1.line(),0.column()));
+        let sub = layout.sub(1.line(),1.line());
+        assert_eq!(sub.lines(),&[VisualLine {line:1.line(),start:0.column(),end:2.column()
+            ,ellipsis:false}]);
+    }
+
+    fn view_buffer(text:&str, mode:WrapMode) -> ViewBuffer {
+        let buffer = Buffer::new();
+        buffer.replace(..,Text::from(text));
+        let view_buffer = ViewBuffer::from(buffer);
+        view_buffer.set_measure(Rc::new(TestMeasure));
+        view_buffer.wrap_mode.set(mode);
+        view_buffer
+    }
+
+    fn moved(view:&ViewBuffer, transform:Transform, selection:Selection, modify:bool) -> Selection {
+        view.moved_selection_region(transform,selection,modify)
+    }
+
+    #[test]
+    fn cursor_moves_by_visual_lines() {
+        let view   = view_buffer("abcdefghij\nab",WrapMode::Anywhere(40.0));
+        let cursor = Selection::new_cursor(Location(0.line(),1.column()),0);
+        let down_1 = moved(&view,Transform::Down,cursor,false);
+        let down_2 = moved(&view,Transform::Down,down_1,false);
+        let down_3 = moved(&view,Transform::Down,down_2,false);
+        let down_4 = moved(&view,Transform::Down,down_3,false);
+        assert_eq!(down_1.end,Location(0.line(),5.column()));
+        assert_eq!(down_2.end,Location(0.line(),9.column()));
+        assert_eq!(down_3.end,Location(1.line(),1.column()));
+        assert_eq!(down_4.end,Location(1.line(),2.column()));
+        let up_1 = moved(&view,Transform::Up,down_2,false);
+        let up_2 = moved(&view,Transform::Up,cursor,false);
+        assert_eq!(up_1.end,Location(0.line(),5.column()));
+        assert_eq!(up_2.end,Location(0.line(),0.column()));
+    }
+
+    #[test]
+    fn selection_extends_by_visual_lines() {
+        let view      = view_buffer("foo bar baz qux",WrapMode::AtWords(80.0));
+        let cursor    = Selection::new_cursor(Location(0.line(),2.column()),0);
+        let selection = moved(&view,Transform::Down,cursor,true);
+        assert_eq!(selection.start,Location(0.line(),2.column()));
+        assert_eq!(selection.end,Location(0.line(),10.column()));
+    }
+
+    #[test]
+    fn cursor_moves_by_buffer_lines_without_wrapping() {
+        let view   = view_buffer("abcdefghij\nab",WrapMode::None);
+        let cursor = Selection::new_cursor(Location(0.line(),5.column()),0);
+        let down   = moved(&view,Transform::Down,cursor,false);
+        assert_eq!(down.end,Location(1.line(),5.column()));
+    }
+
+    #[test]
+    fn unmeasured_lines_are_not_wrapped() {
+        let buffer = Buffer::new();
+        buffer.replace(..,Text::from("abcdefghij"));
+        let view = ViewBuffer::from(buffer);
+        view.wrap_mode.set(WrapMode::Anywhere(40.0));
+        assert_eq!(view.layout().lines(),&[VisualLine {line:0.line(),start:0.column()
+            ,end:10.column(),ellipsis:false}]);
+        view.set_measure(Rc::new(TestMeasure));
+        assert_eq!(view.layout().len(),3);
+    }
+
+    #[test]
+    fn caching_layout() {
+        let view   = view_buffer("abcdefghij",WrapMode::Anywhere(40.0));
+        let layout = view.layout();
+        assert_eq!(layout.len(),3);
+        assert!(Rc::ptr_eq(&layout,&view.layout()));
+        view.wrap_mode.set(WrapMode::Anywhere(50.0));
+        assert_eq!(view.layout().len(),2);
+        view.buffer.replace(..,Text::from("abc"));
+        assert_eq!(view.layout().len(),1);
+        let layout = view.layout();
+        view.buffer.replace(0.bytes()..1.bytes(),crate::buffer::style::Size::new(40.0));
+        assert!(!Rc::ptr_eq(&layout,&view.layout()));
+    }
+}
@@ -246,16 +246,21 @@ ensogl_core::define_endpoints! {
         single_line(bool),
         set_hover(bool),
 
-        set_cursor            (Location),
-        add_cursor            (Location),
-        paste_string          (String),
-        insert                (String),
-        set_color_bytes       (buffer::Range<Bytes>,color::Rgba),
-        set_color_all         (color::Rgba),
-        set_default_color     (color::Rgba),
-        set_selection_color   (color::Rgb),
-        set_default_text_size (style::Size),
-        set_content           (String),
+        set_cursor               (Location),
+        add_cursor               (Location),
+        paste_string             (String),
+        insert                   (String),
+        set_color_bytes          (buffer::Range<Bytes>,color::Rgba),
+        set_underline_bytes      (buffer::Range<Bytes>,style::Underline),
+        set_strikethrough_bytes  (buffer::Range<Bytes>,style::Strikethrough),
+        set_background_bytes     (buffer::Range<Bytes>,style::Background),
+        set_letter_spacing_bytes (buffer::Range<Bytes>,style::LetterSpacing),
+        set_wrap_mode            (buffer::WrapMode),
+        set_color_all            (color::Rgba),
+        set_default_color        (color::Rgba),
+        set_selection_color      (color::Rgb),
+        set_default_text_size    (style::Size),
+        set_content              (String),
     }
     Output {
         pointer_style   (cursor::Style),
@@ -484,6 +489,36 @@ impl Area {
             });
             self.frp.source.selection_color <+ self.frp.set_selection_color;
 
+            // === Decorations ===
+
+            eval input.set_underline_bytes ((t) {
+                m.buffer.frp.set_underline_bytes.emit(*t);
+                m.redraw(false);
+            });
+            eval input.set_strikethrough_bytes ((t) {
+                m.buffer.frp.set_strikethrough_bytes.emit(*t);
+                m.redraw(false);
+            });
+            eval input.set_background_bytes ((t) {
+                m.buffer.frp.set_background_bytes.emit(*t);
+                m.redraw(false);
+            });
+            eval input.set_letter_spacing_bytes ((t) {
+                m.buffer.frp.set_letter_spacing_bytes.emit(*t);
+                m.redraw(true);
+            });
+
+            // === Soft Wrapping ===
+
+            eval input.set_wrap_mode ((mode) {
+                m.buffer.frp.set_wrap_mode(mode);
+                m.redraw(true);
+            });
+            time_on_wrap_mode_change <- scene.frp.frame_time.sample(&input.set_wrap_mode);
+            eval time_on_wrap_mode_change ((time)
+                m.on_modified_selection(&m.buffer.selections(),*time,false)
+            );
+
             // === Changes ===
 
             self.frp.source.changed <+ m.buffer.frp.text_change;
@@ -522,13 +557,21 @@ impl Area {
         for symbol in self.symbols() { layer.remove_symbol(&symbol); }
     }
 
-    fn symbols(&self) -> SmallVec<[display::Symbol;1]> {
-        let text_symbol       = self.data.glyph_system.sprite_system().symbol.clone_ref();
+    /// The location of the newest cursor, or of the moving end of the newest selection. Returns
+    /// `None` if there are no cursors.
+    pub fn newest_cursor_location(&self) -> Option<Location> {
+        self.data.buffer.selections().newest().map(|selection| selection.end)
+    }
+
+    fn symbols(&self) -> SmallVec<[display::Symbol;2]> {
+        let glyph_system      = &self.data.glyph_system;
+        let decoration_symbol = glyph_system.decoration_sprite_system().symbol.clone_ref();
+        let text_symbol       = glyph_system.sprite_system().symbol.clone_ref();
         let shapes            = &self.data.app.display.scene().shapes;
         let selection_system  = shapes.shape_system(PhantomData::<selection::shape::Shape>);
         let _selection_symbol = selection_system.shape_system.symbol.clone_ref();
         //TODO[ao] we cannot move selection symbol, as it is global for all the text areas.
-        SmallVec::from_buf([text_symbol,/*selection_symbol*/])
+        SmallVec::from_buf([decoration_symbol,text_symbol,/*selection_symbol*/])
     }
 }
 
@@ -596,18 +639,19 @@ impl AreaModel {
         {
             let mut selection_map     = self.selection_map.borrow_mut();
             let mut new_selection_map = SelectionMap::default();
+            let layout                = self.buffer.view_layout();
             for sel in selections {
                 let sel        = self.buffer.snap_selection(*sel);
                 let id         = sel.id;
-                let start_line = sel.start.line.as_usize();
-                let end_line   = sel.end.line.as_usize();
+                let (start_line,start_column) = layout.to_visual(sel.start);
+                let (end_line,end_column)     = layout.to_visual(sel.end);
                 let pos_x      = |line:usize, column:Column| if line >= self.lines.len() {
                     self.lines.rc.borrow().last().and_then(|l| l.divs.last().cloned()).unwrap_or(0.0)
                 } else {
                     self.lines.rc.borrow()[line].div_by_column(column)
                 };
-                let min_pos_x  = pos_x(start_line,sel.start.column);
-                let max_pos_x  = pos_x(end_line  ,sel.end  .column);
+                let min_pos_x  = pos_x(start_line,start_column);
+                let max_pos_x  = pos_x(end_line  ,end_column);
                 let logger     = Logger::new_sub(&self.logger,"cursor");
                 let min_pos_y  = -LINE_HEIGHT/2.0 - LINE_HEIGHT * start_line as f32;
                 let pos        = Vector2(min_pos_x,min_pos_y);
@@ -652,7 +696,7 @@ impl AreaModel {
                 selection.edit_mode.set(do_edit);
                 selection.start_time.set(time);
                 new_selection_map.id_map.insert(id,selection);
-                new_selection_map.location_map.entry(start_line).or_default().insert(sel.start.column,id);
+                new_selection_map.location_map.entry(start_line).or_default().insert(start_column,id);
             }
             *selection_map = new_selection_map;
         }
@@ -680,23 +724,24 @@ impl AreaModel {
         let line_index   = (-object_space.y / LINE_HEIGHT) as usize;
         let line_index   = std::cmp::min(line_index,self.lines.len() - 1);
         let div_index    = self.lines.rc.borrow()[line_index].div_index_close_to(object_space.x);
-        let line         = line_index.into();
         let column       = div_index.into();
-        Location(line,column)
+        self.buffer.view_layout().from_visual(line_index,column)
     }
 
     fn init(self) -> Self {
+        self.buffer.set_measure(Rc::new(self.glyph_system.font.clone_ref()));
         self.redraw(true);
         self
     }
 
-    /// Redraw the text.
+    /// Redraw the text. Every visual line is drawn as a separate line, so the lines wrapped
+    /// according to the current `buffer::WrapMode` span several lines on the screen.
     fn redraw(&self, size_may_change:bool) {
-        let lines      = self.buffer.view_lines();
-        let line_count = lines.len();
+        let layout     = self.buffer.view_layout();
+        let line_count = layout.len();
         self.lines.resize_with(line_count,|ix| self.new_line(ix));
-        let widths = lines.into_iter().enumerate().map(|(view_line_index,content)|{
-            self.redraw_line(view_line_index,content)
+        let widths = layout.lines().iter().enumerate().map(|(view_line_index,visual_line)|{
+            self.redraw_line(view_line_index,visual_line)
         }).collect_vec();
         let width = widths.into_iter().max_by(|x, y|x.partial_cmp(y).unwrap()).unwrap_or_default();
         if size_may_change {
@@ -710,25 +755,34 @@ impl AreaModel {
         self.lines.len() as f32 * LINE_HEIGHT
     }
 
-    fn redraw_line(&self, view_line_index:usize, content:String) -> f32 {
+    fn redraw_line(&self, view_line_index:usize, visual_line:&buffer::wrap::VisualLine) -> f32 {
         let cursor_map = self.selection_map.borrow()
             .location_map.get(&view_line_index).cloned().unwrap_or_default();
         let line            = &mut self.lines.rc.borrow_mut()[view_line_index];
         let line_object     = line.display_object().clone_ref();
-        let line_range      = self.buffer.byte_range_of_view_line_index_snapped(view_line_index.into());
+        let content         = self.buffer.visual_line_content(visual_line);
+        let line_range      = self.buffer.byte_range_of_visual_line(visual_line);
         let mut line_style  = self.buffer.sub_style(line_range.start .. line_range.end).iter();
+        let mut last_style  = default();
         let mut pen         = pen::Pen::new(&self.glyph_system.font);
         let mut divs        = vec![];
         let mut column      = 0.column();
         let mut last_cursor = None;
         let mut last_cursor_target = default();
+        let mut decorations = vec![];
         line.resize_with(content.chars().count(),||self.glyph_system.new_glyph());
         let mut iter = line.glyphs.iter_mut().zip(content.chars());
         loop {
             let next      = iter.next();
-            let style     = line_style.next().unwrap_or_default();
+            // The ellipsis of truncated lines has no style, so it uses the style of the last
+            // displayed character.
+            let style     = line_style.next().unwrap_or(last_style);
+            last_style    = style;
             let chr_size  = style.size.raw;
-            let char_info = next.as_ref().map(|t|pen::CharInfo::new(t.1,chr_size));
+            let spacing   = style.letter_spacing.raw;
+            let char_info = next.as_ref().map(|t|{
+                pen::CharInfo::new(t.1,chr_size).with_letter_spacing(spacing)
+            });
             let info      = pen.advance(char_info);
 
             cursor_map.get(&column).for_each(|id| {
@@ -756,6 +810,7 @@ impl AreaModel {
                     glyph.set_char(chr);
                     glyph.set_color(style.color);
                     glyph.size.set(size);
+                    decorations.push((glyph_offset,glyph::Decoration::from(&style)));
                     match &last_cursor {
                         None         => line_object.add_child(glyph),
                         Some(cursor) => {
@@ -769,6 +824,19 @@ impl AreaModel {
             column += 1.column();
         }
 
+        // The decoration cells span between subsequent pen positions, which are known only after
+        // all glyphs were placed.
+        for (index,(glyph_offset,decoration)) in decorations.into_iter().enumerate() {
+            let cell_start  = divs[index];
+            let cell_width  = divs[index + 1] - cell_start;
+            let offset      = Vector2(-glyph_offset.x,-LINE_VERTICAL_OFFSET - glyph_offset.y);
+            let size        = Vector2(cell_width,LINE_HEIGHT);
+            let baseline    = LINE_VERTICAL_OFFSET;
+            let line_offset = cell_start;
+            let layout      = glyph::CellLayout {offset,size,baseline,line_offset};
+            line.glyphs[index].set_decoration(&decoration,&layout);
+        }
+
         let last_offset   = divs.last().cloned().unwrap_or_default();
         let cursor_offset = last_cursor.map(|cursor| last_cursor_target.x - cursor.position().x);
         let cursor_offset = cursor_offset.unwrap_or_default();
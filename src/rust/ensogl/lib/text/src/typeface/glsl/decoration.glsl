@@ -0,0 +1,39 @@
+const highp float PI               = 3.14159265;
+const highp float WAVE_LENGTH      = 4.0;
+const highp float UNDERLINE_OFFSET = 1.5;
+
+// Alpha of a horizontal line of the given width, anti-aliased on the edges.
+highp float line_alpha(highp float y, highp float center, highp float width) {
+    return clamp(width / 2.0 + 0.5 - abs(y - center), 0.0, 1.0);
+}
+
+// Draws the premultiplied `top` color over the premultiplied `bottom` one.
+highp vec4 blend(highp vec4 bottom, highp vec4 top) {
+    return top + bottom * (1.0 - top.a);
+}
+
+highp vec4 premultiply(highp vec4 color, highp float alpha) {
+    color.a   *= alpha;
+    color.rgb *= color.a;
+    return color;
+}
+
+// The `input_underline` is 0 for no underline, 1 for a straight underline, and 2 for a wavy one.
+// The wave phase is computed from the position in the line, so waves of neighbour glyphs connect.
+highp vec4 color_from_decoration() {
+    highp vec2  position    = input_uv * input_size;
+    highp float thickness   = max(1.0, floor(input_size.y / 14.0));
+    highp float underline_y = input_baseline - UNDERLINE_OFFSET * thickness;
+    highp float wave_x      = (input_line_offset + position.x) * 2.0 * PI / WAVE_LENGTH;
+    highp float wave        = input_underline > 1.5 ? sin(wave_x) * thickness : 0.0;
+    highp float strike_y    = input_baseline + (input_size.y - input_baseline) * 0.35;
+
+    highp float underline_alpha = input_underline > 0.5 ? 1.0 : 0.0;
+    underline_alpha *= line_alpha(position.y, underline_y + wave, thickness);
+    highp float strike_alpha = line_alpha(position.y, strike_y, thickness);
+
+    highp vec4 color = premultiply(input_background, 1.0);
+    color = blend(color, premultiply(input_underline_color, underline_alpha));
+    color = blend(color, premultiply(input_strikethrough, strike_alpha));
+    return color;
+}
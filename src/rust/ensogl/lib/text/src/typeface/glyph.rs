@@ -7,6 +7,9 @@ use super::font;
 use font::GlyphRenderInfo;
 use font::Font;
 
+use crate::buffer::style::StyleValue;
+use crate::buffer::style::UnderlineKind;
+
 use ensogl_core::system::gpu;
 use ensogl_core::system::gpu::texture;
 use ensogl_core::system::gpu::types::*;
@@ -20,6 +23,56 @@ use ensogl_core::display::scene::Scene;
 
 
 
+// ==================
+// === Decoration ===
+// ==================
+
+/// Decorations drawn in the cell of a glyph, the area between its pen position and the next one.
+/// The decorations of subsequent glyphs connect, forming continuous lines and backgrounds.
+#[derive(Clone,Copy,Debug,Default,PartialEq)]
+#[allow(missing_docs)]
+pub struct Decoration {
+    pub background      : Rgba,
+    pub underline       : UnderlineKind,
+    pub underline_color : Rgba,
+    /// The color of the strikethrough line, or `None` if it should not be drawn.
+    pub strikethrough   : Option<Rgba>,
+}
+
+impl Decoration {
+    /// Checks whether any decoration is drawn.
+    pub fn is_visible(&self) -> bool {
+        let has_background = self.background.alpha > 0.0;
+        let has_underline  = self.underline != UnderlineKind::None;
+        has_background || has_underline || self.strikethrough.is_some()
+    }
+}
+
+impl From<&StyleValue> for Decoration {
+    fn from(style:&StyleValue) -> Self {
+        let background      = style.background.raw;
+        let underline       = style.underline.kind;
+        let underline_color = style.underline.color.unwrap_or(style.color);
+        let strikethrough   = style.strikethrough.raw.as_some(style.color);
+        Self {background,underline,underline_color,strikethrough}
+    }
+}
+
+/// The layout of the glyph cell, used to draw decorations.
+#[derive(Clone,Copy,Debug,Default,PartialEq)]
+pub struct CellLayout {
+    /// The bottom left corner of the cell, relative to the glyph position.
+    pub offset      : Vector2<f32>,
+    /// The size of the cell. Its height is usually the line height.
+    pub size        : Vector2<f32>,
+    /// The distance between the cell bottom and the text baseline.
+    pub baseline    : f32,
+    /// The horizontal position of the cell in its line, used to align decoration patterns.
+    pub line_offset : f32,
+}
+
+
+
 // =============
 // === Glyph ===
 // =============
@@ -32,12 +85,30 @@ pub type Texture = gpu::Texture<texture::GpuOnly,texture::Rgb,u8>;
 #[derive(Clone,CloneRef,Debug,Shrinkwrap)]
 pub struct Glyph {
     #[shrinkwrap(main_field)]
-    sprite      : Sprite,
-    context     : Context,
-    font        : Font,
-    color       : Attribute<Vector4<f32>>,
-    atlas_index : Attribute<f32>,
-    atlas       : Uniform<Texture>,
+    sprite            : Sprite,
+    context           : Context,
+    font              : Font,
+    color             : Attribute<Vector4<f32>>,
+    atlas_index       : Attribute<f32>,
+    atlas             : Uniform<Texture>,
+    decorations       : DecorationSystem,
+    decoration        : Rc<Cell<Decoration>>,
+    /// The sprite drawing the decoration. It exists only while the decoration is visible, so the
+    /// glyphs without decorations do not occupy instances of the decoration sprite system.
+    decoration_sprite : Rc<RefCell<Option<DecorationSprite>>>,
+}
+
+/// A sprite drawing the decorations of a glyph. It is a child of the glyph sprite, so it follows
+/// the glyph when it moves.
+#[derive(Clone,CloneRef,Debug)]
+struct DecorationSprite {
+    sprite          : Sprite,
+    background      : Attribute<Vector4<f32>>,
+    underline       : Attribute<f32>,
+    underline_color : Attribute<Vector4<f32>>,
+    strikethrough   : Attribute<Vector4<f32>>,
+    baseline        : Attribute<f32>,
+    line_offset     : Attribute<f32>,
 }
 
 impl Glyph {
@@ -50,6 +121,28 @@ impl Glyph {
         self.color.set(color.into().into())
     }
 
+    /// The decorations drawn in the glyph cell.
+    pub fn decoration(&self) -> Decoration {
+        self.decoration.get()
+    }
+
+    /// Set the decorations drawn in the glyph cell. The decoration sprite is created when the
+    /// decoration becomes visible and dropped when there is nothing to draw.
+    pub fn set_decoration(&self, decoration:&Decoration, layout:&CellLayout) {
+        self.decoration.set(*decoration);
+        let mut sprite = self.decoration_sprite.borrow_mut();
+        if !decoration.is_visible() {
+            *sprite = None;
+        } else {
+            let sprite = sprite.get_or_insert_with(|| {
+                let sprite = self.decorations.new_decoration();
+                self.sprite.add_child(&sprite.sprite);
+                sprite
+            });
+            sprite.set(decoration,layout);
+        }
+    }
+
     /// Change the displayed character.
     pub fn set_char(&self, ch:char) {
         let glyph_info = self.font.glyph_info(ch);
@@ -72,6 +165,25 @@ impl Glyph {
     }
 }
 
+impl DecorationSprite {
+    fn set(&self, decoration:&Decoration, layout:&CellLayout) {
+        let underline = match decoration.underline {
+            UnderlineKind::None     => 0.0,
+            UnderlineKind::Straight => 1.0,
+            UnderlineKind::Wavy     => 2.0,
+        };
+        let strikethrough = decoration.strikethrough.unwrap_or_else(Rgba::transparent);
+        self.background.set(decoration.background.into());
+        self.underline.set(underline);
+        self.underline_color.set(decoration.underline_color.into());
+        self.strikethrough.set(strikethrough.into());
+        self.baseline.set(layout.baseline);
+        self.line_offset.set(layout.line_offset);
+        self.sprite.set_position_xy(layout.offset);
+        self.sprite.size.set(layout.size);
+    }
+}
+
 impl display::Object for Glyph {
     fn display_object(&self) -> &display::object::Instance {
         self.sprite.display_object()
@@ -95,6 +207,51 @@ pub struct System {
     color         : Buffer<Vector4<f32>>,
     atlas_index   : Buffer<f32>,
     atlas         : Uniform<Texture>,
+    decorations   : DecorationSystem,
+}
+
+/// A system for displaying glyph decorations, like backgrounds, underlines, and strikethroughs.
+#[derive(Clone,CloneRef,Debug)]
+struct DecorationSystem {
+    sprite_system   : SpriteSystem,
+    background      : Buffer<Vector4<f32>>,
+    underline       : Buffer<f32>,
+    underline_color : Buffer<Vector4<f32>>,
+    strikethrough   : Buffer<Vector4<f32>>,
+    baseline        : Buffer<f32>,
+    line_offset     : Buffer<f32>,
+}
+
+impl DecorationSystem {
+    fn new(scene:&Scene) -> Self {
+        let sprite_system = SpriteSystem::new(scene);
+        let symbol        = sprite_system.symbol();
+        let mesh          = symbol.surface();
+        let scope         = mesh.instance_scope();
+        sprite_system.set_material(Self::material());
+        sprite_system.set_alignment(Alignment::bottom_left());
+        Self {sprite_system,
+            background      : scope.add_buffer("background"),
+            underline       : scope.add_buffer("underline"),
+            underline_color : scope.add_buffer("underline_color"),
+            strikethrough   : scope.add_buffer("strikethrough"),
+            baseline        : scope.add_buffer("baseline"),
+            line_offset     : scope.add_buffer("line_offset"),
+        }
+    }
+
+    fn new_decoration(&self) -> DecorationSprite {
+        let sprite          = self.sprite_system.new_instance();
+        let instance_id     = sprite.instance_id;
+        let background      = self.background.at(instance_id);
+        let underline       = self.underline.at(instance_id);
+        let underline_color = self.underline_color.at(instance_id);
+        let strikethrough   = self.strikethrough.at(instance_id);
+        let baseline        = self.baseline.at(instance_id);
+        let line_offset     = self.line_offset.at(instance_id);
+        DecorationSprite {sprite,background,underline,underline_color,strikethrough,baseline
+            ,line_offset}
+    }
 }
 
 impl System {
@@ -104,6 +261,8 @@ impl System {
         let size          = font::msdf::Texture::size();
         let scene         = scene.as_ref();
         let context       = scene.context.clone_ref();
+        // The decorations are created first, so they are rendered below the glyphs.
+        let decorations   = DecorationSystem::new(scene);
         let sprite_system = SpriteSystem::new(scene);
         let symbol        = sprite_system.symbol();
         let texture       = Texture::new(&context,(0,0));
@@ -113,7 +272,7 @@ impl System {
         sprite_system.set_alignment(Alignment::bottom_left());
         scene.variables.add("msdf_range",GlyphRenderInfo::MSDF_PARAMS.range as f32);
         scene.variables.add("msdf_size",size);
-        Self {logger,context,sprite_system,font,
+        Self {logger,context,sprite_system,font,decorations,
             atlas       : symbol.variables().add_or_panic("atlas",texture),
             color       : mesh.instance_scope().add_buffer("color"),
             atlas_index : mesh.instance_scope().add_buffer("atlas_index"),
@@ -130,15 +289,24 @@ impl System {
         let atlas_index = self.atlas_index.at(instance_id);
         let font        = self.font.clone_ref();
         let atlas       = self.atlas.clone();
+        let decorations = self.decorations.clone_ref();
+        let decoration  = default();
         color.set(Vector4::new(0.0,0.0,0.0,0.0));
         atlas_index.set(0.0);
-        Glyph {sprite,context,font,color,atlas_index,atlas}
+        Glyph {sprite,context,font,color,atlas_index,atlas,decorations,decoration
+            ,decoration_sprite:default()}
     }
 
     /// Get underlying sprite system.
     pub fn sprite_system(&self) -> &SpriteSystem {
         &self.sprite_system
     }
+
+    /// Get the sprite system drawing glyph decorations. Its symbol should be placed in the same
+    /// scene layer as the glyphs, below them.
+    pub fn decoration_sprite_system(&self) -> &SpriteSystem {
+        &self.decorations.sprite_system
+    }
 }
 
 impl display::Object for System {
@@ -156,6 +324,10 @@ const FUNCTIONS : &str = include_str!("glsl/glyph.glsl");
 
 const MAIN      : &str = "output_color = color_from_msdf(); output_id=vec4(0.0,0.0,0.0,0.0);";
 
+const DECORATION_FUNCTIONS : &str = include_str!("glsl/decoration.glsl");
+const DECORATION_MAIN      : &str =
+    "output_color = color_from_decoration(); output_id=vec4(0.0,0.0,0.0,0.0);";
+
 impl System {
     /// Defines a default material of this system.
     fn material() -> Material {
@@ -178,3 +350,63 @@ impl System {
         material
     }
 }
+
+impl DecorationSystem {
+    /// Defines a material drawing the decorations.
+    fn material() -> Material {
+        let mut material = Material::new();
+        let transparent  = Vector4::new(0.0,0.0,0.0,0.0);
+        material.add_input("background"      , transparent);
+        material.add_input("underline"       , 0.0);
+        material.add_input("underline_color" , transparent);
+        material.add_input("strikethrough"   , transparent);
+        material.add_input("baseline"        , 0.0);
+        material.add_input("line_offset"     , 0.0);
+        // FIXME See the comment in the glyph material.
+        material.add_output("id", Vector4::<f32>::new(0.0,0.0,0.0,0.0));
+
+        let code = CodeTemplate::new(DECORATION_FUNCTIONS,DECORATION_MAIN,"");
+        material.set_code(code);
+        material
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ensogl_core::system::web;
+    use wasm_bindgen_test::wasm_bindgen_test;
+    use wasm_bindgen_test::wasm_bindgen_test_configure;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test(async)]
+    async fn setting_and_clearing_decoration() {
+        ensogl_text_msdf_sys::initialized().await;
+        let root = web::create_div();
+        web::body().append_child(&root).unwrap();
+        let world       = World::new(&root);
+        let system      = System::new(world.scene(),Font::mock("Test font"));
+        let glyph       = system.new_glyph();
+        let layout      = CellLayout {size:Vector2::new(10.0,20.0),..default()};
+        let sprite_size = || glyph.decoration_sprite.borrow().as_ref().map(|t| t.sprite.size.get());
+        assert_eq!(glyph.decoration(),Decoration::default());
+        assert_eq!(sprite_size(),None);
+
+        let decoration = Decoration {underline:UnderlineKind::Straight,..default()};
+        glyph.set_decoration(&decoration,&layout);
+        assert_eq!(glyph.decoration(),decoration);
+        assert_eq!(sprite_size(),Some(layout.size));
+
+        glyph.set_decoration(&default(),&layout);
+        assert_eq!(glyph.decoration(),Decoration::default());
+        assert_eq!(sprite_size(),None);
+    }
+}
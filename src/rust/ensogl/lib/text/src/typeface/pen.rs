@@ -30,13 +30,21 @@ pub struct AdvanceResult {
 #[derive(Clone,Copy,Debug)]
 #[allow(missing_docs)]
 pub struct CharInfo {
-    pub char : char,
-    pub size : f32
+    pub char           : char,
+    pub size           : f32,
+    pub letter_spacing : f32,
 }
 
 impl CharInfo {
     pub fn new(char:char, size:f32) -> Self {
-        Self {char,size}
+        let letter_spacing = 0.0;
+        Self {char,size,letter_spacing}
+    }
+
+    /// Set the additional space after the char, in pixels.
+    pub fn with_letter_spacing(mut self, letter_spacing:f32) -> Self {
+        self.letter_spacing = letter_spacing;
+        self
     }
 }
 
@@ -73,7 +81,7 @@ impl Pen {
         if let Some(current) = self.current_char {
             let kerning = next_char.map(|ch|self.font.kerning(current.char,ch)).unwrap_or_default();
             let advance = self.font.glyph_info(current.char).advance + kerning;
-            let offset  = advance * current.size;
+            let offset  = advance * current.size + current.letter_spacing;
             self.offset += offset;
         }
         self.current_char = next;
@@ -115,6 +123,27 @@ mod tests {
         assert_eq!(expected,result);
     }
 
+    #[wasm_bindgen_test(async)]
+    async fn moving_pen_with_letter_spacing(){
+        ensogl_text_msdf_sys::initialized().await;
+        let font = Font::mock("Test font");
+        mock_a_glyph_info(font.clone_ref());
+        mock_w_glyph_info(font.clone_ref());
+        font.mock_kerning_info('A', 'W', -0.16);
+        font.mock_kerning_info('W', 'A', 0.0);
+
+        let mut pen    = Pen::new(&font);
+        let mut result = Vec::new();
+        for chr in "AWA".chars() {
+            let char_info = CharInfo::new(chr,1.0).with_letter_spacing(0.5);
+            result.push(pen.advance(Some(char_info)).offset);
+        }
+        let expected = vec![0.0,0.9,2.1];
+        for (expected,result) in expected.into_iter().zip(result) {
+            assert!((expected - result).abs() < 1e-6);
+        }
+    }
+
     fn mock_a_glyph_info(font:Font) -> GlyphRenderInfo {
         let advance = 0.56;
         let scale   = Vector2::new(0.5, 0.8);
@@ -0,0 +1,304 @@
+//! Accessibility audit of themes. Checks whether the text colors of a theme are readable on the
+//! backgrounds they are displayed on, using the contrast ratio defined by WCAG 2.1
+//! (https://www.w3.org/TR/WCAG21/#dfn-contrast-ratio).
+
+use ensogl_core::prelude::*;
+
+use ensogl_core::data::color;
+use ensogl_core::display::style::Data;
+use ensogl_core::display::style::Path;
+use ensogl_core::display::style::StaticPath;
+use ensogl_core::display::style::Value;
+use ensogl_core::display::style::theme::Theme;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The minimum contrast ratio of a normal text required by the WCAG level AA.
+pub const AA_RATIO : f32 = 4.5;
+
+/// The minimum contrast ratio of a normal text required by the WCAG level AAA.
+pub const AAA_RATIO : f32 = 7.0;
+
+/// The maximum depth of style references followed when resolving a style. Deeper references are
+/// treated as cycles.
+const MAX_REFERENCE_DEPTH : usize = 32;
+
+/// The number of steps of the search for a foreground lightness meeting the required contrast.
+const LIGHTNESS_SEARCH_STEPS : usize = 16;
+
+
+
+// ====================
+// === ContrastPair ===
+// ====================
+
+/// A foreground color style which is displayed on top of the background color style.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+#[allow(missing_docs)]
+pub struct ContrastPair {
+    pub foreground : StaticPath,
+    pub background : StaticPath,
+}
+
+impl ContrastPair {
+    /// Constructor.
+    pub const fn new(foreground:StaticPath, background:StaticPath) -> Self {
+        Self {foreground,background}
+    }
+}
+
+/// The text colors of the builtin themes with the backgrounds they are displayed on. The styles of
+/// disabled and placeholder texts are not listed, as they are not required to be readable.
+pub fn text_contrast_pairs() -> Vec<ContrastPair> {
+    use crate::*;
+    use graph_editor as editor;
+    vec!
+    [ ContrastPair::new(text                          , application::background)
+    , ContrastPair::new(editor::node::text            , editor::node::background)
+    , ContrastPair::new(code::syntax::base            , editor::node::background)
+    , ContrastPair::new(editor::visualization::text   , editor::visualization::background)
+    , ContrastPair::new(editor::breadcrumbs::full     , editor::breadcrumbs::background)
+    , ContrastPair::new(editor::prompt::text          , editor::prompt::background)
+    , ContrastPair::new(application::status_bar::text , application::status_bar::background)
+    , ContrastPair::new(component::label::text        , component::label::background)
+    , ContrastPair::new(widget::list_view::text       , widget::list_view::background)
+    ]
+}
+
+
+
+// =====================
+// === ResolvedTheme ===
+// =====================
+
+/// The theme styles with all the expressions evaluated.
+#[derive(Clone,Debug,Default)]
+pub struct ResolvedTheme {
+    values : HashMap<String,Data>,
+}
+
+impl ResolvedTheme {
+    /// Evaluate all the styles of the theme. The styles referring to styles not defined in the
+    /// theme, or being part of a reference cycle, are skipped.
+    pub fn new(theme:&Theme) -> Self {
+        let definitions = theme.values().into_iter().collect::<HashMap<_,_>>();
+        let values      = definitions.keys().filter_map(|path| {
+            Self::resolve(&definitions,path,0).map(|data| (path.clone(),data))
+        }).collect();
+        Self {values}
+    }
+
+    fn resolve(definitions:&HashMap<String,Value>, path:&str, depth:usize) -> Option<Data> {
+        if depth > MAX_REFERENCE_DEPTH { return None }
+        match definitions.get(path)? {
+            Value::Data(data)       => Some(data.clone()),
+            Value::Expression(expr) => {
+                let args = expr.args.iter().map(|arg| {
+                    Self::resolve(definitions,&path_to_string(arg),depth+1)
+                }).collect::<Option<Vec<_>>>()?;
+                let arg_refs = args.iter().collect_vec();
+                Some((expr.function)(&arg_refs))
+            }
+        }
+    }
+
+    /// Get the color of the given style.
+    pub fn color(&self, path:impl Into<Path>) -> Option<color::Rgba> {
+        match self.values.get(&path_to_string(&path.into()))? {
+            Data::Color(color) => Some(*color),
+            _                  => None,
+        }
+    }
+}
+
+fn path_to_string(path:&Path) -> String {
+    path.rev_segments.iter().rev().join(".")
+}
+
+
+
+// ================
+// === Contrast ===
+// ================
+
+/// The relative luminance of the color, ignoring its alpha.
+pub fn relative_luminance(color:color::Rgba) -> f32 {
+    let linear = color.opaque.into_linear();
+    0.2126 * linear.red + 0.7152 * linear.green + 0.0722 * linear.blue
+}
+
+/// The contrast ratio between the foreground color displayed over the background color. It ranges
+/// from 1.0 (no contrast) to 21.0 (black on white). The foreground alpha is taken into account by
+/// blending it with the background, while the background is assumed to be opaque.
+pub fn contrast_ratio(foreground:color::Rgba, background:color::Rgba) -> f32 {
+    let foreground = blend(foreground,background);
+    let luminance1 = relative_luminance(foreground);
+    let luminance2 = relative_luminance(background);
+    let lighter    = luminance1.max(luminance2);
+    let darker     = luminance1.min(luminance2);
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+fn blend(foreground:color::Rgba, background:color::Rgba) -> color::Rgba {
+    let alpha = foreground.alpha;
+    let mix   = |fg:f32, bg:f32| fg * alpha + bg * (1.0 - alpha);
+    let red   = mix(foreground.red,background.red);
+    let green = mix(foreground.green,background.green);
+    let blue  = mix(foreground.blue,background.blue);
+    color::Rgba::new(red,green,blue,1.0)
+}
+
+
+
+// =============
+// === Audit ===
+// =============
+
+/// A problem found by the theme audit.
+#[allow(missing_docs)]
+#[derive(Clone,Debug,Fail,PartialEq)]
+pub enum Issue {
+    #[fail(display="The contrast of \"{}\" on \"{}\" is {:.2}, while at least {:.2} is required.",
+        foreground, background, ratio, required)]
+    LowContrast {foreground:String, background:String, ratio:f32, required:f32},
+    #[fail(display="The style \"{}\" is not a color.", path)]
+    MissingColor {path:String},
+}
+
+/// Check whether all the pairs of styles have at least the `required` contrast ratio in the theme.
+pub fn audit(theme:&Theme, pairs:&[ContrastPair], required:f32) -> Vec<Issue> {
+    let resolved = ResolvedTheme::new(theme);
+    pairs.iter().filter_map(|pair| {
+        let color = |path:StaticPath| {
+            resolved.color(path).ok_or_else(|| Issue::MissingColor {path:path.str.to_owned()})
+        };
+        let colors = color(pair.foreground).and_then(|fg| Ok((fg,color(pair.background)?)));
+        match colors {
+            Err(issue)                  => Some(issue),
+            Ok((foreground,background)) => {
+                let ratio = contrast_ratio(foreground,background);
+                (ratio < required).as_some_from(|| Issue::LowContrast {
+                    foreground : pair.foreground.str.to_owned(),
+                    background : pair.background.str.to_owned(),
+                    ratio,
+                    required,
+                })
+            }
+        }
+    }).collect()
+}
+
+/// Create a copy of the theme in which every foreground of the pairs has at least the `required`
+/// contrast ratio with its background. The foregrounds which do not meet the requirement are made
+/// opaque and their lightness is moved away from the background, keeping their hue and chroma.
+pub fn high_contrast(theme:&Theme, pairs:&[ContrastPair], required:f32) -> Theme {
+    let resolved = ResolvedTheme::new(theme);
+    let derived  = theme.deep_clone();
+    for pair in pairs {
+        let foreground = resolved.color(pair.foreground);
+        let background = resolved.color(pair.background);
+        if let (Some(foreground),Some(background)) = (foreground,background) {
+            if contrast_ratio(foreground,background) < required {
+                let adjusted = adjust_lightness(foreground,background,required);
+                derived.set(pair.foreground,adjusted);
+            }
+        }
+    }
+    derived
+}
+
+/// Find the foreground lightness closest to the current one which meets the required contrast. If
+/// the requirement cannot be met, the most contrasting lightness is used.
+fn adjust_lightness
+(foreground:color::Rgba, background:color::Rgba, required:f32) -> color::Rgba {
+    let foreground     = color::Lcha::from(blend(foreground,background));
+    let with_lightness = |lightness:f32| {
+        let lch = color::Lcha::new(lightness,foreground.chroma,foreground.hue,1.0);
+        color::Rgba::from(lch)
+    };
+    let towards_black  = relative_luminance(background) > 0.18;
+    let target         = if towards_black {0.0} else {1.0};
+    let mut closer     = foreground.lightness;
+    let mut further    = target;
+    for _ in 0..LIGHTNESS_SEARCH_STEPS {
+        let middle = (closer + further) / 2.0;
+        if contrast_ratio(with_lightness(middle),background) < required { closer  = middle }
+        else                                                            { further = middle }
+    }
+    with_lightness(further)
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::builtin;
+
+    #[test]
+    fn computing_contrast_ratio() {
+        let black = color::Rgba::new(0.0,0.0,0.0,1.0);
+        let white = color::Rgba::new(1.0,1.0,1.0,1.0);
+        let gray  = color::Rgba::new(0.5,0.5,0.5,1.0);
+        assert!((contrast_ratio(black,white) - 21.0).abs() < 0.01);
+        assert!((contrast_ratio(white,black) - 21.0).abs() < 0.01);
+        assert!((contrast_ratio(gray,gray) - 1.0).abs() < 0.01);
+        assert!((contrast_ratio(gray,white) - 3.98).abs() < 0.01);
+        let transparent_black = color::Rgba::new(0.0,0.0,0.0,0.0);
+        assert!((contrast_ratio(transparent_black,white) - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn resolving_references() {
+        let theme = Theme::new();
+        theme.set("base.color",color::Rgba::new(1.0,0.0,0.0,1.0));
+        theme.set("text.color",Path::from("base.color"));
+        theme.set("cycle.a",Path::from("cycle.b"));
+        theme.set("cycle.b",Path::from("cycle.a"));
+        let resolved = ResolvedTheme::new(&theme);
+        assert_eq!(resolved.color("text.color"), Some(color::Rgba::new(1.0,0.0,0.0,1.0)));
+        assert_eq!(resolved.color("cycle.a"), None);
+        assert_eq!(resolved.color("missing"), None);
+    }
+
+    #[test]
+    fn builtin_themes_are_readable() {
+        for (name,theme) in builtin::all() {
+            let issues = audit(&theme,&text_contrast_pairs(),AA_RATIO);
+            assert!(issues.is_empty(), "Theme {} has contrast issues: {:?}", name, issues);
+        }
+    }
+
+    #[test]
+    fn deriving_high_contrast_theme() {
+        const TEXT       : StaticPath = StaticPath::new("text");
+        const BACKGROUND : StaticPath = StaticPath::new("background");
+        const MISSING    : StaticPath = StaticPath::new("missing");
+        let pairs = [ContrastPair::new(TEXT,BACKGROUND),ContrastPair::new(MISSING,BACKGROUND)];
+        let theme = Theme::new();
+        theme.set(TEXT,color::Lcha::new(0.6,0.5,0.3,0.8));
+        theme.set(BACKGROUND,color::Rgba::new(0.9,0.9,0.9,1.0));
+
+        let issues = audit(&theme,&pairs,AAA_RATIO);
+        assert_eq!(issues.len(), 2);
+        assert!(matches!(&issues[0], Issue::LowContrast {foreground,..} if foreground == "text"));
+        assert_eq!(issues[1], Issue::MissingColor {path:"missing".to_owned()});
+
+        let derived = high_contrast(&theme,&pairs,AAA_RATIO);
+        let issues  = audit(&derived,&pairs[..1],AAA_RATIO);
+        assert!(issues.is_empty(), "{:?}", issues);
+        let ratio = contrast_ratio(ResolvedTheme::new(&derived).color(TEXT).unwrap(),
+            color::Rgba::new(0.9,0.9,0.9,1.0));
+        assert!(ratio < AAA_RATIO + 0.5, "The lightness change should be minimal, got {}.", ratio);
+        assert!(!audit(&theme,&pairs[..1],AAA_RATIO).is_empty(), "The source theme is unchanged.");
+    }
+}
@@ -0,0 +1,334 @@
+//! Themes defined in files, loaded at runtime and layered on top of the builtin themes.
+//!
+//! A theme file is a JSON object mapping style paths to values. Paths can be given either as
+//! nested objects or as dot-separated keys, so the following definitions are equivalent:
+//!
+//! ```json
+//! { "graph_editor" : { "node" : { "background" : "rgba(1.0,1.0,1.0,1.0)" } } }
+//! { "graph_editor.node.background" : "rgba(1.0,1.0,1.0,1.0)" }
+//! ```
+//!
+//! The value can be a number, a color in any format supported by `data::color` (like
+//! `"lcha(0.5,0.0,0.0,1.0)"`), or a path of another style (like `"graph_editor.node.background"`),
+//! which makes the style an expression referring to it. Only the paths defined by the registered
+//! themes are accepted.
+//!
+//! The themes loaded from files are registered as `Layer`s, enabled on top of the builtin theme in
+//! the order of their precedence.
+
+use ensogl_core::prelude::*;
+
+use ensogl_core::display::style::Change;
+use ensogl_core::display::style::Path;
+use ensogl_core::display::style::Value;
+use ensogl_core::display::style::theme;
+use serde_json as json;
+
+
+
+// =============
+// === Layer ===
+// =============
+
+/// A theme file layered on top of the builtin theme. The layers are listed in the order of
+/// increasing precedence, so the project's theme overrides the styles of the user's theme.
+#[derive(Clone,Copy,Debug,Eq,Hash,PartialEq)]
+pub enum Layer {
+    /// The theme file in the user's configuration directory, applied to all projects.
+    User,
+    /// The theme file in the project's root directory.
+    Project,
+}
+
+impl Layer {
+    /// All layers, in the order of increasing precedence.
+    pub const ALL : [Layer;2] = [Layer::User,Layer::Project];
+
+    /// The name under which the theme loaded from the layer's file is registered.
+    pub fn theme_name(self) -> &'static str {
+        match self {
+            Self::User    => "user",
+            Self::Project => "project",
+        }
+    }
+}
+
+impl Default for Layer {
+    fn default() -> Self {
+        Self::Project
+    }
+}
+
+
+
+// ==============
+// === Errors ===
+// ==============
+
+#[allow(missing_docs)]
+#[derive(Clone,Debug,Fail)]
+#[fail(display="The theme file is not a valid JSON: {}.", message)]
+pub struct InvalidJson { message:String }
+
+#[allow(missing_docs)]
+#[derive(Clone,Copy,Debug,Fail)]
+#[fail(display="The theme file must contain an object mapping style paths to values.")]
+pub struct NotAnObject;
+
+/// An entry of the theme file which was skipped, because it could not be applied.
+#[allow(missing_docs)]
+#[derive(Clone,Debug,Eq,Fail,PartialEq)]
+pub enum InvalidEntry {
+    #[fail(display="Unknown style path \"{}\".", path)]
+    UnknownPath {path:String},
+    #[fail(display="Invalid value {} of style \"{}\".", value, path)]
+    InvalidValue {path:String, value:String},
+}
+
+
+
+// =================
+// === ThemeFile ===
+// =================
+
+/// A parsed theme file.
+#[derive(Clone,Debug)]
+pub struct ThemeFile {
+    /// The theme with all the valid entries of the file.
+    pub theme : theme::Theme,
+    /// The entries which were skipped, because they could not be applied.
+    pub invalid_entries : Vec<InvalidEntry>,
+}
+
+impl ThemeFile {
+    /// Parse the theme file contents. The entries which are not valid are skipped and reported in
+    /// `invalid_entries`, so an error in one style does not discard the whole file.
+    pub fn parse(code:&str, known_paths:&HashSet<String>) -> FallibleResult<Self> {
+        let invalid_json  = |error:json::Error| InvalidJson {message:error.to_string()};
+        let object        = match json::from_str(code).map_err(invalid_json)? {
+            json::Value::Object(object) => object,
+            _                           => return Err(NotAnObject.into()),
+        };
+        let theme         = theme::Theme::new();
+        let mut invalid   = Vec::new();
+        let mut add_entry = |path:String, value:&json::Value| {
+            match Self::parse_value(&path,value,known_paths) {
+                Ok(value)  => { theme.set(path,value); }
+                Err(error) => invalid.push(error),
+            }
+        };
+        for (key,value) in &object {
+            Self::visit_entries(key.clone(),value,&mut add_entry);
+        }
+        Ok(Self {theme,invalid_entries:invalid})
+    }
+
+    fn visit_entries
+    (path:String, value:&json::Value, f:&mut impl FnMut(String,&json::Value)) {
+        match value {
+            json::Value::Object(object) => for (key,value) in object {
+                Self::visit_entries(format!("{}.{}",path,key),value,f)
+            }
+            _ => f(path,value),
+        }
+    }
+
+    fn parse_value
+    (path:&str, value:&json::Value, known_paths:&HashSet<String>) -> Result<Value,InvalidEntry> {
+        let invalid_value = || {
+            let path  = path.to_owned();
+            let value = value.to_string();
+            InvalidEntry::InvalidValue {path,value}
+        };
+        if !known_paths.contains(path) {
+            return Err(InvalidEntry::UnknownPath {path:path.to_owned()})
+        }
+        match value {
+            json::Value::Number(number) => number.as_f64().map(|n| Value::from(n as f32)),
+            json::Value::String(text)   => Value::try_from(text.clone()).ok().or_else(||
+                known_paths.contains(text).as_some_from(|| Path::from(text).into())
+            ),
+            _ => None,
+        }.ok_or_else(invalid_value)
+    }
+}
+
+/// All style paths defined by the themes registered in the manager, in the dot-separated form.
+/// The themes loaded from the files are skipped, so they can not define new paths.
+pub fn known_paths(manager:&theme::Manager) -> HashSet<String> {
+    let layers = Layer::ALL.iter().map(|layer| layer.theme_name()).collect::<HashSet<_>>();
+    let names  = manager.keys().into_iter().filter(|name| !layers.contains(name.as_str()));
+    let themes = names.filter_map(|name| manager.get(&name));
+    themes.flat_map(|theme| theme.values().into_iter().map(|(path,_)| path)).collect()
+}
+
+
+
+// ===============
+// === Loading ===
+// ===============
+
+/// Register the theme of the given layer. If the layer's theme is already registered, it is
+/// replaced, and the theme manager refreshes only the styles which differ between the two.
+/// Returns these differences.
+pub fn load(manager:&theme::Manager, layer:Layer, theme:&theme::Theme) -> Vec<Change> {
+    let name   = layer.theme_name();
+    let staged = format!("{}.staged",name);
+    if manager.get(name).is_none() {
+        manager.register(name,theme::Theme::new());
+    }
+    manager.register(staged.as_str(),theme.deep_clone());
+    let changes = manager.diff(name,&staged);
+    manager.remove(staged.as_str());
+    if !changes.is_empty() {
+        manager.register(name,theme.deep_clone());
+    }
+    changes
+}
+
+/// Enable the `base` theme with the registered layers on top of it. The styles defined in the
+/// layers override the ones of the base theme, according to the layers precedence.
+pub fn enable_layered(manager:&theme::Manager, base:&str) {
+    let layers = Layer::ALL.iter().map(|layer| layer.theme_name());
+    let layers = layers.filter(|name| manager.get(name).is_some());
+    manager.set_enabled(std::iter::once(base).chain(layers));
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::builtin;
+
+    use ensogl_core::data::color;
+    use ensogl_core::display::style;
+    use ensogl_core::display::style::data::DataMatch;
+
+    fn manager() -> theme::Manager {
+        let manager = theme::Manager::new();
+        builtin::light::register(&manager);
+        builtin::dark::register(&manager);
+        manager
+    }
+
+    fn parse(manager:&theme::Manager, code:&str) -> ThemeFile {
+        ThemeFile::parse(code,&known_paths(manager)).unwrap()
+    }
+
+    fn value(theme:&theme::Theme, path:&str) -> Option<Value> {
+        theme.values().into_iter().find(|(p,_)| p == path).map(|(_,value)| value)
+    }
+
+    fn number(theme:&theme::Theme, path:&str) -> Option<f32> {
+        match value(theme,path)? {
+            Value::Data(data) => data.number(),
+            _                 => None,
+        }
+    }
+
+    #[test]
+    fn parsing_theme_file() {
+        let code = r#"{
+            "graph_editor" : {
+                "node" : {
+                    "background" : "rgba(1.0,0.0,0.0,1.0)",
+                    "unknown"    : 1.0
+                }
+            },
+            "text.size"                   : 14,
+            "application.background"      : "graph_editor.node.background",
+            "graph_editor.node.selection" : "not a color",
+            "shadow.size"                 : [1,2]
+        }"#;
+        let manager    = manager();
+        let file       = parse(&manager,code);
+        let background = value(&file.theme,"graph_editor.node.background");
+        let red        = color::Rgba::new(1.0,0.0,0.0,1.0);
+        assert_eq!(file.theme.values().len(), 3);
+        assert_eq!(number(&file.theme,"text.size"), Some(14.0));
+        assert_eq!(background, Some(red.into()));
+        match value(&file.theme,"application.background") {
+            Some(Value::Expression(expr)) => {
+                assert_eq!(expr.args, vec![Path::from("graph_editor.node.background")]);
+            }
+            other => panic!("Expected a reference expression, got {:?}.",other),
+        }
+
+        let invalid_value = |path:&str, value:&str| {
+            let path  = path.to_owned();
+            let value = value.to_owned();
+            InvalidEntry::InvalidValue {path,value}
+        };
+        let unknown_path = |path:&str| InvalidEntry::UnknownPath {path:path.to_owned()};
+        let mut invalid  = file.invalid_entries;
+        invalid.sort_by_key(|entry| entry.to_string());
+        assert_eq!(invalid, vec!
+            [ invalid_value("graph_editor.node.selection","\"not a color\"")
+            , invalid_value("shadow.size","[1,2]")
+            , unknown_path("graph_editor.node.unknown")
+            ]);
+
+        assert!(ThemeFile::parse("{",&known_paths(&manager)).is_err());
+        assert!(ThemeFile::parse("[]",&known_paths(&manager)).is_err());
+    }
+
+    #[test]
+    fn paths_of_all_registered_themes_are_known() {
+        let manager = theme::Manager::new();
+        let custom  = theme::Theme::new();
+        custom.set("custom.size",1.0);
+        manager.register("custom",custom);
+        let user = theme::Theme::new();
+        user.set("user.size",1.0);
+        manager.register(Layer::User.theme_name(),user);
+        builtin::light::register(&manager);
+        let light_paths = builtin::light::theme().values().into_iter().map(|(path,_)| path);
+        let mut expected : HashSet<String> = light_paths.collect();
+        expected.insert("custom.size".into());
+        assert_eq!(known_paths(&manager),expected);
+    }
+
+    #[test]
+    fn project_layer_overrides_user_layer() {
+        let sheet   = style::Sheet::new();
+        let manager = theme::Manager::from(&sheet);
+        builtin::light::register(&manager);
+        let user    = parse(&manager,r#"{"text.size":14,"shadow.size":20}"#).theme;
+        let project = parse(&manager,r#"{"text.size":16}"#).theme;
+        load(&manager,Layer::Project,&project);
+        load(&manager,Layer::User,&user);
+        enable_layered(&manager,builtin::light::NAME);
+        let number = |path:&str| sheet.value(path).and_then(|data| data.number());
+        assert_eq!(number("text.size"), Some(16.0));
+        assert_eq!(number("shadow.size"), Some(20.0));
+    }
+
+    #[test]
+    fn reloading_theme() {
+        let manager = manager();
+        let layer   = Layer::Project;
+        let first   = parse(&manager,r#"{"text.size":14,"shadow.size":20}"#).theme;
+        let changes = load(&manager,layer,&first);
+        assert_eq!(changes.len(), 2);
+
+        let second  = parse(&manager,r#"{"text.size":16,"shadow.size":20}"#).theme;
+        let changes = load(&manager,layer,&second);
+        let changes = changes.into_iter().map(|c| (c.path,c.value)).collect_vec();
+        assert_eq!(changes, vec![(Path::from("text.size"),Some(16.0.into()))]);
+        let theme = manager.get(layer.theme_name()).unwrap();
+        assert_eq!(number(&theme,"text.size"), Some(16.0));
+        assert!(manager.keys().iter().all(|name| !name.ends_with(".staged")));
+
+        let changes = load(&manager,layer,&theme::Theme::new());
+        assert_eq!(changes.len(), 2);
+        assert!(changes.iter().all(|change| change.value.is_none()));
+        let theme = manager.get(layer.theme_name()).unwrap();
+        assert_eq!(number(&theme,"text.size"), None);
+    }
+}
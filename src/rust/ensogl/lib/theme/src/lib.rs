@@ -12,6 +12,9 @@
 
 use ensogl_core::prelude::ImString;
 
+pub mod audit;
+pub mod file;
+
 
 
 // ==============
@@ -76,6 +79,11 @@ macro_rules! _define_themes_wrappers_and_literals {
         pub mod builtin {
             use super::*;
             $(_define_theme_wrapper_and_literals! {$name $id $body})*
+
+            /// All builtin themes with their names.
+            pub fn all() -> Vec<(&'static str,ensogl_core::display::style::theme::Theme)> {
+                vec![$(($name::NAME,$name::theme())),*]
+            }
         }
     }
 }
@@ -95,16 +103,24 @@ macro_rules! _define_theme_wrapper_and_literals {
             use ensogl_core::data::color::Rgba;
             use ensogl_core::display::style::theme;
 
-            /// Registers the theme in the application.
-            pub fn register(theme_manager:impl AsRef<theme::Manager>) {
+            /// The name under which the theme is registered.
+            pub const NAME : &str = stringify!($name);
+
+            /// Creates the theme definition.
+            pub fn theme() -> theme::Theme {
                 let $name = theme::Theme::new();
                 _define_theme_literals!{$id $name [] $($t)*}
-                theme_manager.as_ref().register(stringify!($name),$name);
+                $name
+            }
+
+            /// Registers the theme in the application.
+            pub fn register(theme_manager:impl AsRef<theme::Manager>) {
+                theme_manager.as_ref().register(NAME,theme());
             }
 
             /// Enables the current theme.
             pub fn enable(theme_manager:impl AsRef<theme::Manager>) {
-                theme_manager.as_ref().set_enabled(&[stringify!($name)]);
+                theme_manager.as_ref().set_enabled(&[NAME]);
             }
         }
     };
@@ -234,15 +250,23 @@ define_themes! { [light:0, dark:1]
             gap_between_panels = 0.0, 0.0;
         }
         project_list {
-            width      = 202.0 , 202.0;
+            width  = 202.0 , 202.0;
+            height = 421.0, 421.0;
+        }
+
+        undo_history {
+            width  = 302.0 , 302.0;
+            height = 421.0, 421.0;
+        }
+
+        symbol_panel {
+            width  = 402.0 , 402.0;
+            height = 421.0, 421.0;
+        }
+
+        list_panel {
             padding    = 16.0, 16.0;
-            height     = 421.0, 421.0;
             background = Rgba(0.992,0.996,1.0,1.0), Rgba(0.182,0.188,0.196,1.0);
-            text       = widget::list_view::text, widget::list_view::text;
-            text {
-                size    = 12.0, 12.0;
-                padding = 6.0 , 6.0 ;
-            }
             bar {
                 height      = 45.0, 45.0;
                 border_size = 1.0, 1.0;
@@ -350,6 +374,7 @@ define_themes! { [light:0, dark:1]
                         Integer.hue = 0.68 , 0.68;
                         Number.hue = 0.68 , 0.68;
                         Text.hue = 0.22 , 0.217;
+                        Array.container = 0.0 , 0.0;
                     }
                 }
             }
@@ -37,6 +37,7 @@ ensogl::read_args! {
         wasm_url             : String,
         wasm_glue_url        : String,
         node_labels          : bool,
+        new_searcher         : bool,
         crash_report_host    : String,
         no_data_gathering    : bool,
         is_in_cloud          : bool,
@@ -87,6 +87,10 @@ trait API {
     #[MethodInput=AcquireCapabilityInput,rpc_name="capability/acquire"]
     fn acquire_capability(&self, method:String, register_options:RegisterOptions) -> ();
 
+    /// Release capability permission.
+    #[MethodInput=ReleaseCapabilityInput,rpc_name="capability/release"]
+    fn release_capability(&self, registration:CapabilityRegistration) -> ();
+
     /// Open the specified file. If no user has write lock on the opened file, the write lock
     /// capability is granted to the caller.
     #[MethodInput=OpenTextFileInput,rpc_name="text/openFile"]
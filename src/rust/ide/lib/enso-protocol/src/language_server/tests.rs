@@ -70,6 +70,53 @@ fn test_file_event_notification() {
     }
 }
 
+#[test]
+fn test_text_did_change_notification() {
+    let mut fixture = setup_language_server();
+    let mut events  = Box::pin(fixture.client.events());
+    events.expect_pending();
+
+    let root_id   = uuid::Uuid::parse_str("00000000-0000-0000-0000-000000000000");
+    let root_id   = root_id.expect("Couldn't parse uuid.");
+    let start     = Position{line:0, character:0};
+    let end       = Position{line:0, character:3};
+    let file_edit = FileEdit {
+        path        : Path{root_id,segments:vec!["Main.enso".into()]},
+        edits       : vec![TextEdit {range:TextRange{start,end}, text:"bar".into()}],
+        old_version : Sha3_224::new(b"foo"),
+        new_version : Sha3_224::new(b"bar"),
+    };
+    let notification = json!({
+        "jsonrpc" : "2.0",
+        "method"  : "text/didChange",
+        "params"  : {
+            "edits" : [{
+                "path" : {
+                    "rootId"   : "00000000-0000-0000-0000-000000000000",
+                    "segments" : ["Main.enso"]
+                },
+                "edits" : [{
+                    "range" : {
+                        "start" : {"line":0, "character":0},
+                        "end"   : {"line":0, "character":3}
+                    },
+                    "text" : "bar"
+                }],
+                "oldVersion" : Sha3_224::new(b"foo"),
+                "newVersion" : Sha3_224::new(b"bar")
+            }]
+        }
+    });
+    fixture.transport.mock_peer_text_message(notification.to_string());
+    fixture.executor.run_until_stalled();
+
+    if let Event::Notification(n) = events.expect_next() {
+        assert_eq!(n, Notification::TextDidChange(TextDidChange {edits:vec![file_edit]}));
+    } else {
+        panic!("expected notification event");
+    }
+}
+
 /// This function tests making a request using language server. It
 /// * creates FM client and uses `make_request` to make a request,
 /// * checks that request is made for `expected_method`,
@@ -305,6 +352,69 @@ fn test_acquire_capability() {
     );
 }
 
+#[test]
+fn test_release_capability() {
+    let root_id      = uuid::Uuid::parse_str("00000000-0000-0000-0000-000000000000");
+    let root_id      = root_id.expect("Couldn't parse uuid.");
+    let path         = Path{root_id,segments:vec!["Main.enso".into()]};
+    let registration = CapabilityRegistration::create_can_edit_text_file(path);
+    test_request(
+        |client| client.release_capability(&registration),
+        "capability/release",
+        json!({
+            "registration" : {
+                "method"          : "text/canEdit",
+                "registerOptions" : {
+                    "path" : {
+                        "rootId"   : "00000000-0000-0000-0000-000000000000",
+                        "segments" : ["Main.enso"]
+                    }
+                }
+            }
+        }),
+        json!(null),
+        ()
+    );
+}
+
+#[test]
+fn test_capability_force_released_notification() {
+    let mut fixture = setup_language_server();
+    let mut events  = Box::pin(fixture.client.events());
+
+    let root_id      = uuid::Uuid::parse_str("00000000-0000-0000-0000-000000000000");
+    let root_id      = root_id.expect("Couldn't parse uuid.");
+    let path         = Path{root_id,segments:vec!["Main.enso".into()]};
+    let registration = CapabilityRegistration::create_can_edit_text_file(path.clone());
+    let notification = json!({
+        "jsonrpc" : "2.0",
+        "method"  : "capability/forceReleased",
+        "params"  : {
+            "registration" : {
+                "method"          : "text/canEdit",
+                "registerOptions" : {
+                    "path" : {
+                        "rootId"   : "00000000-0000-0000-0000-000000000000",
+                        "segments" : ["Main.enso"]
+                    }
+                }
+            }
+        }
+    });
+    fixture.transport.mock_peer_text_message(notification.to_string());
+    fixture.executor.run_until_stalled();
+
+    if let Event::Notification(n) = events.expect_next() {
+        let expected = registration.clone();
+        assert_eq!(n, Notification::CapabilityForceReleased {registration:expected});
+    } else {
+        panic!("expected notification event");
+    }
+    assert_eq!(registration.can_edit_text_file_path(), Some(&path));
+    let tree_updates = CapabilityRegistration::create_receives_tree_updates(path);
+    assert_eq!(tree_updates.can_edit_text_file_path(), None);
+}
+
 
 #[test]
 fn test_computed_value_update() {
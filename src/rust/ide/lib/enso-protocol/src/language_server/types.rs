@@ -116,6 +116,11 @@ pub enum Notification {
     #[serde(rename = "file/event")]
     FileEvent(FileEvent),
 
+    /// Sent from the server to the client to inform about edits made by other clients to a file
+    /// opened by this client.
+    #[serde(rename = "text/didChange")]
+    TextDidChange(TextDidChange),
+
     /// Sent from the server to the client to inform about new information for certain expressions
     /// becoming available. This notification is superseded by executionContext/expressionUpdates.
     #[serde(rename = "executionContext/expressionValuesComputed")]
@@ -148,6 +153,17 @@ pub enum Notification {
     #[allow(missing_docs)]
     #[serde(rename = "file/rootRemoved")]
     ContentRootRemoved {id:Uuid},
+
+    /// Sent from the server to the client to inform that it was granted a capability.
+    #[allow(missing_docs)]
+    #[serde(rename = "capability/granted")]
+    CapabilityGranted {registration:CapabilityRegistration},
+
+    /// Sent from the server to the client to inform that it lost a capability, e.g. because
+    /// another client acquired it.
+    #[allow(missing_docs)]
+    #[serde(rename = "capability/forceReleased")]
+    CapabilityForceReleased {registration:CapabilityRegistration},
 }
 
 /// Sent from the server to the client to inform about a failure during execution of an execution
@@ -299,6 +315,17 @@ pub enum FileEventKind {
 }
 
 
+// === TextDidChange ===
+
+/// The `text/didChange` notification parameters.
+#[derive(Clone,Debug,PartialEq)]
+#[derive(Serialize,Deserialize)]
+#[allow(missing_docs)]
+pub struct TextDidChange {
+    pub edits : Vec<FileEdit>,
+}
+
+
 
 // ======================
 // === FileAttributes ===
@@ -700,6 +727,9 @@ pub enum StackItem {
 // === CapabilityRegistration ===
 // ==============================
 
+/// The method name of the capability allowing editing a text file.
+pub const CAN_EDIT_TEXT_FILE : &str = "text/canEdit";
+
 /// `CapabilityRegistration` is used to keep track of permissions granting.
 #[derive(Hash, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all="camelCase")]
@@ -721,11 +751,19 @@ impl CapabilityRegistration {
 
     /// Create "text/canEdit" capability for path.
     pub fn create_can_edit_text_file(path:Path) -> Self {
-        let method           = "text/canEdit".to_string();
+        let method           = CAN_EDIT_TEXT_FILE.to_string();
         let register_options = RegisterOptions::Path {path};
         CapabilityRegistration {method,register_options}
     }
 
+    /// If this is the "text/canEdit" capability, return the path of the file it allows editing.
+    pub fn can_edit_text_file_path(&self) -> Option<&Path> {
+        match &self.register_options {
+            RegisterOptions::Path {path} if self.method == CAN_EDIT_TEXT_FILE => Some(path),
+            _                                                               => None,
+        }
+    }
+
     /// Create "executionContext/canModify" capability for path.
     pub fn create_can_modify_execution_context(context_id:Uuid) -> Self {
         let method = "executionContext/canModify".to_string();
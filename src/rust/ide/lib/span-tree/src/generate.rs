@@ -310,13 +310,33 @@ impl<T:Payload> SpanTreeGenerator<T> for ast::prefix::Chain {
     }
 }
 
+/// The name of the argument, if the AST is a named argument (like `name=value`). Only the
+/// assignments without spaces around `=` are named arguments, as the spaced ones are bindings.
+fn argument_name(ast:&Ast) -> Option<String> {
+    let infix      = ast::opr::to_assignment(ast)?;
+    let no_spacing = infix.loff == 0 && infix.roff == 0;
+    let name       = ast::identifier::as_var(&infix.larg)?;
+    no_spacing.as_some_from(|| name.to_owned())
+}
+
+/// Take the parameter filled by an argument from the not yet filled `params`. The named argument
+/// fills the parameter of the same name, while the positional one fills the first not filled
+/// parameter.
+fn take_param(params:&mut Vec<ArgumentInfo>, name:Option<&String>) -> Option<ArgumentInfo> {
+    let index = match name {
+        Some(name) => params.iter().position(|param| param.name.as_ref() == Some(name))?,
+        None       => (!params.is_empty()).as_some(0)?,
+    };
+    Some(params.remove(index))
+}
+
 fn generate_node_for_prefix_chain<T:Payload>
 (this:&ast::prefix::Chain, kind:node::Kind, context:&impl Context)
 -> FallibleResult<Node<T>> {
     let base             = ApplicationBase::new(&this.func);
     let invocation_info  = this.id().and_then(|id| context.call_info(id, base.function_name));
     let known_args       = invocation_info.is_some();
-    let mut known_params = base.prefix_params(invocation_info);
+    let mut known_params = base.prefix_params(invocation_info).collect_vec();
     let prefix_arity     = this.args.len().max(known_params.len());
 
     use ast::crumbs::PrefixCrumb::*;
@@ -327,10 +347,12 @@ fn generate_node_for_prefix_chain<T:Payload>
         let node     = node?;
         let is_first = i == 0;
         let is_last  = i + 1 == prefix_arity;
-        let arg_kind = if is_first && !base.has_target {
+        let name     = argument_name(&arg.sast.wrapped);
+        let arg_kind = if is_first && !base.has_target && name.is_none() {
             node::Kind::from(node::Kind::this().with_removable(removable))
         } else {
-            node::Kind::from(node::Kind::argument().with_removable(removable))
+            let argument = node::Kind::argument().with_removable(removable);
+            node::Kind::from(argument.with_name(name.clone()))
         };
 
         let mut gen = ChildGenerator::default();
@@ -341,7 +363,7 @@ fn generate_node_for_prefix_chain<T:Payload>
         }
         let arg_ast      = arg.sast.wrapped.clone_ref();
         let arg_child  : &mut node::Child<T>   = gen.generate_ast_node(Located::new(Arg,arg_ast),arg_kind,context)?;
-        if let Some(info) = known_params.next() {
+        if let Some(info) = take_param(&mut known_params,name.as_ref()) {
             arg_child.node.set_argument_info(info)
         }
         if !known_args {
@@ -356,7 +378,7 @@ fn generate_node_for_prefix_chain<T:Payload>
         })
     })?;
 
-    Ok(generate_expected_arguments(ret,kind,this.args.len(),known_params))
+    Ok(generate_expected_arguments(ret,kind,this.args.len(),known_params.into_iter()))
 }
 
 
@@ -1009,6 +1031,31 @@ mod test {
         assert_eq!(tree,expected);
     }
 
+    #[wasm_bindgen_test]
+    fn generating_span_tree_for_named_arguments() {
+        let parser     = Parser::new_or_panic();
+        let param      = |name:&str| ArgumentInfo::new(Some(name.to_owned()),None);
+        let parameters = vec![param("this"),param("arg1"),param("arg2")];
+
+        let ast             = parser.parse_line_ast("foo arg2=bar here").unwrap();
+        let invocation_info = CalledMethodInfo {parameters:parameters.clone()};
+        let ctx             = MockContext::new_single(ast.id.unwrap(),invocation_info);
+        let tree            = SpanTree::new(&ast,&ctx).unwrap() : SpanTree;
+        let children        = tree.root_ref().chain_children_iter().collect_vec();
+        match children.as_slice() {
+            [_func,named,positional,expected] => {
+                assert!(named.is_argument());
+                assert_eq!(named.name()                , Some(&"arg2".to_owned()));
+                assert_eq!(named.argument_info()       , Some(parameters[2].clone()));
+                assert!(positional.is_argument());
+                assert_eq!(positional.argument_info()  , Some(parameters[0].clone()));
+                assert!(expected.is_expected_argument());
+                assert_eq!(expected.argument_info()    , Some(parameters[1].clone()));
+            },
+            sth_else => panic!("There should be 4 chain children, found: {}",sth_else.len()),
+        }
+    }
+
     fn segment_body_crumbs(index:usize, pattern_crumb:&Vec<PatternMatchCrumb>) -> ast::crumbs::MatchCrumb {
         let val = ast::crumbs::SegmentMatchCrumb::Body {val:pattern_crumb.clone()};
         ast::crumbs::MatchCrumb::Segs {val,index}
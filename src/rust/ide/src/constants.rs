@@ -22,6 +22,14 @@ pub const PROJECTS_MAIN_MODULE:&str = "Main";
 /// Visualization folder where IDE can look for user-defined visualizations per project.
 pub const VISUALIZATION_DIRECTORY:&str = "visualization";
 
+/// The file in the project's root directory with the project's theme, applied on top of the
+/// builtin theme and the user's theme. See [`ensogl_theme::file`] for its format.
+pub const THEME_FILE:&str = "theme.json";
+
+/// The path of the file with the user's theme, relative to the user's home directory. It is
+/// applied to all projects, on top of the builtin theme.
+pub const USER_THEME_FILE:&[&str] = &["enso","theme.json"];
+
 /// How many times IDE will try to attach initial visualization on loading project.
 ///
 /// This is necessary, because the request will be timing out for a while, until the stdlib
@@ -35,4 +43,7 @@ pub mod keywords {
 
     /// The "void" atom returned by function meant to not return any argument.
     pub const NOTHING:&str = "Nothing";
+
+    /// A keyword starting the type declaration.
+    pub const TYPE:&str = "type";
 }
@@ -12,6 +12,7 @@ pub mod text;
 pub mod project;
 pub mod visualization;
 pub mod searcher;
+pub mod symbol_index;
 pub mod upload;
 
 pub use graph::Handle           as Graph;
@@ -22,6 +23,7 @@ pub use project::Project;
 pub use text::Handle            as Text;
 pub use visualization::Handle   as Visualization;
 pub use searcher::Searcher;
+pub use symbol_index::Handle   as SymbolIndex;
 
 
 
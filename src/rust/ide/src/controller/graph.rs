@@ -713,6 +713,23 @@ impl Handle {
         self.set_expression_ast(connection.destination.node, updated_expression)
     }
 
+    /// Set the value of node's argument, given as code, replacing the whole argument expression.
+    /// The `port` is the argument's port in the node's input span tree. If the argument is not
+    /// applied yet (the port is a placeholder), it is inserted.
+    pub fn set_argument_value
+    (&self, port:&Endpoint, value:impl Str, context:&impl SpanTreeContext) -> FallibleResult {
+        let _transaction_guard = self.get_or_open_transaction("Set argument value");
+        let value_ast          = self.parser.parse_line_ast(value.as_ref())?;
+        let node               = self.node_info(port.node)?;
+        let info               = EndpointInfo::new(port,node.expression(),context)?;
+        let updated_expression = if info.port()?.node.is_insertion_point() {
+            info.set(value_ast)
+        } else {
+            info.set_ast(value_ast)
+        }?;
+        self.set_expression_ast(port.node,updated_expression)
+    }
+
     /// Obtain the definition information for this graph from the module's AST.
     pub fn definition(&self) -> FallibleResult<definition::ChildDefinition> {
         let module_ast = self.module.ast();
@@ -796,6 +813,19 @@ impl Handle {
         Ok(())
     }
 
+    /// Removes the node with given Id together with the module's imports which are no longer used.
+    /// Both changes are made in a single undo-redo transaction.
+    pub fn remove_node_and_unused_imports(&self, id:ast::Id) -> FallibleResult {
+        let _transaction_guard = self.get_or_open_transaction("Remove node");
+        self.remove_node(id)?;
+        let analyzer   = controller::module::imports::Analyzer::new(&self.suggestion_db);
+        let mut module = module::Info {ast:self.module.ast()};
+        if analyzer.optimize(&mut module,&self.parser) {
+            self.module.update_ast(module.ast)?;
+        }
+        Ok(())
+    }
+
     /// Sets the given's node expression.
     pub fn set_expression(&self, id:ast::Id, expression_text:impl Str) -> FallibleResult {
         info!(self.logger, "Setting node {id} expression to `{expression_text.as_ref()}`");
@@ -1375,6 +1405,21 @@ main =
         })
     }
 
+    #[wasm_bindgen_test]
+    fn removing_node_with_unused_imports() {
+        let mut test   = Fixture::set_up();
+        let mut entry  = data::suggestion_entry_foo();
+        entry.module   = module::QualifiedName::from_text("Standard.Geo").unwrap();
+        entry.name     = "point".to_owned();
+        test.data.code = "import Standard.Geo\n\nmain =\n    p = Geo.point 1 2\n    print 2".into();
+        test.data.suggestions.insert(1,entry);
+        test.run(|graph| async move {
+            let (node,_) = graph.nodes().unwrap().expect_tuple();
+            graph.remove_node_and_unused_imports(node.info.id()).unwrap();
+            model::module::test::expect_code(&*graph.module,"main =\n    print 2");
+        })
+    }
+
     #[wasm_bindgen_test]
     fn graph_controller_connections_listing() {
         let mut test  = Fixture::set_up();
@@ -1475,6 +1520,22 @@ main =
         }
     }
 
+    #[wasm_bindgen_test]
+    fn graph_controller_set_argument_value() {
+        let mut test   = Fixture::set_up();
+        test.data.code = "main =\n    foo 1\n    bar = 2 + _".into();
+        test.run(|graph| async move {
+            let (node0,node1) = graph.nodes().unwrap().expect_tuple();
+            let context       = &span_tree::generate::context::Empty;
+            let number_arg    = Endpoint::new(node0.info.id(),vec![2]);
+            let text_arg      = Endpoint::new(node1.info.id(),vec![4]);
+            graph.set_argument_value(&number_arg,"5",context).unwrap();
+            graph.set_argument_value(&text_arg,"\"text\"",context).unwrap();
+            let expected = "main =\n    foo 5\n    bar = 2 + \"text\"";
+            assert_eq!(graph.definition().unwrap().ast.repr(), expected);
+        })
+    }
+
     #[wasm_bindgen_test]
     fn graph_controller_create_connection_reordering() {
         let mut test  = Fixture::set_up();
@@ -17,6 +17,7 @@ use span_tree::generate::context::CalledMethodInfo;
 
 pub use crate::controller::graph::Connection;
 pub use crate::controller::graph::Connections;
+pub use crate::controller::graph::Endpoint;
 
 
 
@@ -278,6 +279,13 @@ impl Handle {
     pub fn disconnect(&self, connection:&Connection) -> FallibleResult {
         self.graph.borrow().disconnect(connection,self)
     }
+
+    /// Set the value of node's argument, given as code.
+    ///
+    /// See [`controller::Graph::set_argument_value`].
+    pub fn set_argument_value(&self, port:&Endpoint, value:impl Str) -> FallibleResult {
+        self.graph.borrow().set_argument_value(port,value,self)
+    }
 }
 
 
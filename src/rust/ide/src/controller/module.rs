@@ -1,5 +1,7 @@
 //! Module Controller.
 
+pub mod imports;
+
 use crate::prelude::*;
 
 use crate::double_representation::text::apply_code_change_to_id_map;
@@ -147,6 +149,17 @@ impl Handle {
         self.modify(|info| info.remove_import(&import))?
     }
 
+    /// Remove the unused and duplicated imports and sort the remaining ones. See
+    /// [`imports::Analyzer`] for the rules telling which imports are used.
+    pub fn optimize_imports(&self, database:&model::SuggestionDatabase) -> FallibleResult {
+        let analyzer   = imports::Analyzer::new(database);
+        let mut module = self.module_info();
+        if analyzer.optimize(&mut module,&self.parser) {
+            self.model.update_ast(module.ast)?;
+        }
+        Ok(())
+    }
+
     /// Retrieve a vector describing all import declarations currently present in the module.
     pub fn imports(&self) -> Vec<module::ImportInfo> {
         let module = self.module_info();
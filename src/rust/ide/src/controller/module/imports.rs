@@ -0,0 +1,196 @@
+//! The analysis of the module's imports, telling which of them are needed by the module's code.
+
+use crate::prelude::*;
+
+use crate::double_representation::alias_analysis;
+use crate::double_representation::identifier::NormalizedName;
+use crate::double_representation::module;
+use crate::double_representation::module::ImportInfo;
+use crate::model::suggestion_database::entry::Scope;
+use crate::model::SuggestionDatabase;
+
+use parser::Parser;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The modules whose imports are never considered unused, even if the code does not refer to
+/// them, because they are needed by the visualizations' preprocessors.
+pub const IMPLICITLY_USED_MODULES:&[&str] = &[controller::project::VISUALIZATION_MODULE];
+
+
+
+// ================
+// === Analyzer ===
+// ================
+
+/// The analyzer of the module's imports.
+///
+/// An import is considered used if the module's code refers to the imported module's name or to
+/// any name the Suggestion Database knows to be defined in the imported module or its submodules.
+/// The imports of the modules the database knows nothing about are always considered used.
+#[derive(Clone,Debug,Default)]
+pub struct Analyzer {
+    /// The names defined in each module, keyed by the segments of the module's qualified name.
+    defined_names : HashMap<Vec<String>,HashSet<NormalizedName>>,
+}
+
+impl Analyzer {
+    /// Create an analyzer knowing the modules described by the Suggestion Database.
+    pub fn new(database:&SuggestionDatabase) -> Self {
+        let mut defined_names = HashMap::<_,HashSet<_>>::new();
+        for (_,entry) in database.entries() {
+            if entry.scope == Scope::Everywhere {
+                let mut module = entry.module.clone();
+                module.remove_main_module_segment();
+                let segments = module.segments().map(ToString::to_string).collect_vec();
+                defined_names.entry(segments).or_default().insert(NormalizedName::new(&entry.name));
+            }
+        }
+        Self {defined_names}
+    }
+
+    /// The names the module's code refers to, not counting the import declarations.
+    pub fn used_names(module:&module::Info) -> HashSet<NormalizedName> {
+        let mut code = module.clone();
+        remove_import_lines(&mut code);
+        let usage = alias_analysis::analyze_crumbable(code.ast.shape());
+        usage.used.into_iter().map(|name| name.item).collect()
+    }
+
+    /// Check if the import is needed by the code referring to the given names.
+    pub fn is_used(&self, import:&ImportInfo, used_names:&HashSet<NormalizedName>) -> bool {
+        let target           = import.target.join(ast::opr::predefined::ACCESS);
+        let is_implicit      = IMPLICITLY_USED_MODULES.contains(&target.as_str());
+        let module_name      = import.target.last().map(NormalizedName::new);
+        let is_name_used     = module_name.map_or(false, |name| used_names.contains(&name));
+        let mut provided     = self.defined_names.iter().filter_map(|(module,names)| {
+            module.starts_with(&import.target).as_some(names)
+        }).peekable();
+        let is_known         = provided.peek().is_some();
+        let are_entries_used = provided.any(|names| !names.is_disjoint(used_names));
+        is_implicit || is_name_used || !is_known || are_entries_used
+    }
+
+    /// The imports needed by the module, without duplicates and in lexicographic order.
+    pub fn required_imports(&self, module:&module::Info) -> Vec<ImportInfo> {
+        let used_names = Self::used_names(module);
+        let imports    = module.iter_imports().filter(|import| self.is_used(import,&used_names));
+        imports.sorted_by(|lhs,rhs| lhs.target.cmp(&rhs.target)).dedup().collect()
+    }
+
+    /// Remove the unused and duplicated imports of the module and sort the remaining ones. The
+    /// import declarations are placed at the module's beginning.
+    ///
+    /// Returns `false` if the imports were already optimal and the module was left untouched.
+    pub fn optimize(&self, module:&mut module::Info, parser:&Parser) -> bool {
+        let required = self.required_imports(module);
+        let current  = module.iter_imports().collect_vec();
+        if current == required {
+            false
+        } else {
+            let removed_at_beginning = module.enumerate_imports().any(|(crumb,_)| {
+                crumb.line_index == 0
+            });
+            remove_import_lines(module);
+            if required.is_empty() && removed_at_beginning {
+                remove_leading_empty_lines(module);
+            }
+            for import in required {
+                module.add_import(parser,import);
+            }
+            true
+        }
+    }
+}
+
+fn remove_import_lines(module:&mut module::Info) {
+    let lines = module.enumerate_imports().map(|(crumb,_)| crumb.line_index).collect_vec();
+    for index in lines.into_iter().rev() {
+        // The index was just obtained from the module, so it cannot be out of bounds.
+        let _ = module.remove_line(index);
+    }
+}
+
+fn remove_leading_empty_lines(module:&mut module::Info) {
+    let is_first_empty = |module:&module::Info| module.ast.lines.first().map(|l| l.elem.is_none());
+    while module.ast.lines.len() > 1 && is_first_empty(module) == Some(true) {
+        let _ = module.remove_line(0);
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::model::suggestion_database::entry::Kind;
+    use crate::model::suggestion_database::Entry;
+
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    fn entry(module:&str, name:&str) -> Entry {
+        Entry {
+            kind               : Kind::Method,
+            module             : module::QualifiedName::from_text(module).unwrap(),
+            name               : name.to_owned(),
+            arguments          : vec![],
+            return_type        : "Any".to_owned(),
+            documentation_html : None,
+            self_type          : None,
+            scope              : Scope::Everywhere,
+        }
+    }
+
+    fn analyzer() -> Analyzer {
+        let entries = vec!
+            [ entry("Standard.Base.Main"       ,"print")
+            , entry("Standard.Table.Data.Table","new")
+            , entry("Standard.Geo.Main"        ,"point")
+            , entry("Standard.Visualization"   ,"prepare")
+            ];
+        let entries  = entries.into_iter().enumerate().collect::<HashMap<_,_>>();
+        let database = SuggestionDatabase::new_from_entries(Logger::new("Test"),&entries);
+        Analyzer::new(&database)
+    }
+
+    #[wasm_bindgen_test]
+    fn optimizing_imports() {
+        struct Case {code:&'static str, expected:&'static str}
+        let cases =
+            [ Case { code     : "import Standard.Geo\nimport Standard.Base\nimport Foo.Unknown\n\
+                                 import Standard.Base\n\nmain = 2.print"
+                   , expected : "import Foo.Unknown\nimport Standard.Base\n\nmain = 2.print"
+                   }
+            , Case { code     : "import Standard.Table\nimport Standard.Geo\n\nmain = Table.new"
+                   , expected : "import Standard.Table\n\nmain = Table.new"
+                   }
+            , Case { code     : "import Standard.Geo\n\nmain = point 1 2"
+                   , expected : "import Standard.Geo\n\nmain = point 1 2"
+                   }
+            , Case { code     : "import Standard.Visualization\n\nmain = 1"
+                   , expected : "import Standard.Visualization\n\nmain = 1"
+                   }
+            , Case { code     : "import Standard.Geo\n\nmain = 1"
+                   , expected : "main = 1"
+                   }
+            ];
+        let parser   = Parser::new_or_panic();
+        let analyzer = analyzer();
+        for case in &cases {
+            let ast        = parser.parse_module(case.code,default()).unwrap();
+            let mut module = module::Info {ast};
+            let changed    = analyzer.optimize(&mut module,&parser);
+            assert_eq!(module.ast.repr(), case.expected);
+            assert_eq!(changed, case.code != case.expected);
+        }
+    }
+}
@@ -3,19 +3,49 @@
 use crate::prelude::*;
 
 use crate::controller::graph::executed::Notification as GraphNotification;
+use crate::constants::LANGUAGE_FILE_EXTENSION;
+use crate::constants::SOURCE_DIRECTORY;
 use crate::controller::ide::StatusNotificationPublisher;
+use crate::double_representation::identifier::ReferentName;
+use crate::double_representation::module::ImportInfo;
 use crate::double_representation::project;
 use crate::model::module::QualifiedName;
 use crate::model::traits::*;
 
 use enso_frp::web::platform;
 use enso_frp::web::platform::Platform;
+use enso_protocol::language_server::FileSystemObject;
 use enso_protocol::language_server::MethodPointer;
 use enso_protocol::language_server::Path;
 use parser::Parser;
 
 
 
+// ==============
+// === Errors ===
+// ==============
+
+#[allow(missing_docs)]
+#[derive(Clone,Debug,Fail)]
+#[fail(display="The module {} already exists.", _0)]
+pub struct ModuleAlreadyExists(pub String);
+
+#[allow(missing_docs)]
+#[derive(Clone,Debug,Fail)]
+#[fail(display="The module {} is the project's main module, so it cannot be moved or deleted.", _0)]
+pub struct MainModuleRequired(pub String);
+
+#[allow(missing_docs)]
+#[derive(Clone,Debug,Fail)]
+#[fail(display="The module {} cannot be deleted, because it is imported in: {}.",
+    module, importers)]
+pub struct ModuleStillImported {
+    pub module    : String,
+    pub importers : String,
+}
+
+
+
 // =================
 // === Constants ===
 // =================
@@ -40,6 +70,9 @@ pub const MINIMUM_EDITION_SUPPORTED : &str = "2021.12";
 /// created using engine's Project Picker service.
 pub const INITIAL_MODULE_NAME:&str = "Main";
 
+/// The module imported in the initial module for the sake of the visualizations' preprocessors.
+pub const VISUALIZATION_MODULE:&str = "Standard.Visualization";
+
 /// Name of the main definition.
 ///
 /// This is the definition whose graph will be opened on IDE start.
@@ -86,6 +119,32 @@ pub fn package_yaml_path(project_name:&str) -> String {
     }
 }
 
+/// List the paths of all the project's modules, i.e. the files with the language extension in the
+/// source directory and its subdirectories.
+pub async fn module_paths(project:&model::Project) -> FallibleResult<Vec<model::module::Path>> {
+    let json_rpc    = project.json_rpc();
+    let root_id     = project.project_content_root_id();
+    let mut pending = vec![Path::new(root_id,&[SOURCE_DIRECTORY])];
+    let mut modules = Vec::new();
+    while let Some(directory) = pending.pop() {
+        for object in json_rpc.file_list(&directory).await?.paths {
+            match object {
+                FileSystemObject::Directory {name,path} => pending.push(path.append_im(name)),
+                FileSystemObject::File {name,path} => {
+                    let path     = path.append_im(name);
+                    let is_valid = path.extension() == Some(LANGUAGE_FILE_EXTENSION);
+                    let module   = is_valid.and_option_from(|| {
+                        model::module::Path::from_file_path(path).ok()
+                    });
+                    modules.extend(module);
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(modules)
+}
+
 
 // ==============
 // === Handle ===
@@ -145,7 +204,7 @@ impl Project {
         Self::add_main_if_missing(project.qualified_name(), &main_module_model, &method, &parser)?;
 
         let mut info = main_module_model.info();
-        info.add_module_import(&project.qualified_module_name(&module_path), &project.parser(), &QualifiedName::from_text("Standard.Visualization").unwrap());
+        info.add_module_import(&project.qualified_module_name(&module_path), &project.parser(), &QualifiedName::from_text(VISUALIZATION_MODULE).unwrap());
         main_module_model.update_ast(info.ast)?;
 
         // Here, we should be relatively certain (except race conditions in case of multiple
@@ -238,6 +297,185 @@ impl Project {
 }
 
 
+// === Module Management ===
+
+impl Project {
+    /// Create a new module file containing the default main method. Fails if the module already
+    /// exists.
+    pub async fn create_module
+    (&self, id:&model::module::Id) -> FallibleResult<model::module::Path> {
+        let path = model::module::Path::from_id(self.model.project_content_root_id(),id);
+        self.check_not_existing(&path).await?;
+        self.model.json_rpc().write_file(path.file_path(),&default_main_module_code()).await?;
+        Ok(path)
+    }
+
+    /// Rename the module, keeping it in the same parent module. See [`Self::move_module`].
+    pub async fn rename_module
+    (&self, path:&model::module::Path, new_name:ReferentName)
+    -> FallibleResult<model::module::Path> {
+        let parents = path.id().parent_segments().to_vec();
+        let id      = model::module::Id::new(parents.into_iter().chain(std::iter::once(new_name)));
+        self.move_module(path,&id).await
+    }
+
+    /// Move the module under a new name, fixing the imports of the module in the other project
+    /// modules and the code referring to the module's members through its name.
+    ///
+    /// Only the module's file is moved: its submodules stay in place.
+    pub async fn move_module
+    (&self, path:&model::module::Path, new_id:&model::module::Id)
+    -> FallibleResult<model::module::Path> {
+        let new_path = model::module::Path::from_id(self.model.project_content_root_id(),new_id);
+        self.check_not_main(path)?;
+        self.check_not_existing(&new_path).await?;
+        let old_name  = self.module_name(path);
+        let new_name  = self.module_name(&new_path);
+        let importers = self.importers_of(&old_name).await?;
+        info!(self.logger, "Moving module {old_name} to {new_name}.");
+        self.model.json_rpc().move_file(path.file_path(),new_path.file_path()).await?;
+        self.model.forget_module(path.clone());
+        let parser     = self.model.parser();
+        let import     = ImportInfo::from_qualified_name(&old_name);
+        let new_prefix = new_path.module_name();
+        for importer in importers {
+            let here = self.module_name(importer.path());
+            importer.update(&self.model,|info| {
+                // The qualifiers are renamed first, as they are checked against the old import.
+                info.rename_qualifier(&import,new_prefix.as_str())?;
+                while info.remove_import(&import).is_ok() {}
+                info.add_module_import(&here,&parser,&new_name);
+                Ok(())
+            }).await?;
+        }
+        Ok(new_path)
+    }
+
+    /// Delete the module's file. Fails if any other project module still imports it.
+    pub async fn delete_module(&self, path:&model::module::Path) -> FallibleResult {
+        self.check_not_main(path)?;
+        let name      = self.module_name(path);
+        let importers = self.importers_of(&name).await?;
+        if importers.is_empty() {
+            info!(self.logger, "Deleting module {name}.");
+            self.model.json_rpc().delete_file(path.file_path()).await?;
+            self.model.forget_module(path.clone());
+            Ok(())
+        } else {
+            let module    = name.to_string();
+            let importers = importers.iter().map(|importer| self.module_name(importer.path()));
+            let importers = importers.join(", ");
+            Err(ModuleStillImported {module,importers}.into())
+        }
+    }
+
+    /// Get the project modules, other than the module itself, which import the module.
+    ///
+    /// The modules are not opened: the code of the modules which are not opened yet is read from
+    /// their files.
+    async fn importers_of(&self, name:&QualifiedName) -> FallibleResult<Vec<ModuleSource>> {
+        let root_id       = self.model.project_content_root_id();
+        let import        = ImportInfo::from_qualified_name(name);
+        let itself        = model::module::Path::from_name(root_id,name);
+        let mut importers = Vec::new();
+        for path in module_paths(&self.model).await? {
+            if path != itself {
+                let module = ModuleSource::get(&self.model,path).await?;
+                if module.info().iter_imports().any(|imported| imported == import) {
+                    importers.push(module);
+                }
+            }
+        }
+        Ok(importers)
+    }
+
+    async fn check_not_existing(&self, path:&model::module::Path) -> FallibleResult {
+        if self.model.json_rpc().file_exists(path.file_path()).await?.exists {
+            Err(ModuleAlreadyExists(self.module_name(path).to_string()).into())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_not_main(&self, path:&model::module::Path) -> FallibleResult {
+        if path.id() == main_module_id() {
+            Err(MainModuleRequired(self.module_name(path).to_string()).into())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn module_name(&self, path:&model::module::Path) -> QualifiedName {
+        path.qualified_module_name(self.model.qualified_name())
+    }
+}
+
+
+
+// ====================
+// === ModuleSource ===
+// ====================
+
+/// The code of a project module: the opened module's model, or the contents of the module's file
+/// if the module is not opened.
+#[derive(Clone,Debug)]
+enum ModuleSource {
+    Opened(model::Module),
+    File {
+        path   : model::module::Path,
+        source : model::module::Content,
+    },
+}
+
+impl ModuleSource {
+    async fn get
+    (project:&model::Project, path:model::module::Path) -> FallibleResult<ModuleSource> {
+        match project.loaded_module(path.clone()) {
+            Some(module) => Ok(Self::Opened(module)),
+            None => {
+                let contents = project.json_rpc().read_file(path.file_path()).await?.contents;
+                let source   = project.parser().parse_with_metadata(contents)?;
+                Ok(Self::File {path,source})
+            }
+        }
+    }
+
+    fn path(&self) -> &model::module::Path {
+        match self {
+            Self::Opened(module) => module.path(),
+            Self::File {path,..} => path,
+        }
+    }
+
+    fn info(&self) -> double_representation::module::Info {
+        match self {
+            Self::Opened(module)   => module.info(),
+            Self::File {source,..} => double_representation::module::Info {ast:source.ast.clone()},
+        }
+    }
+
+    /// Update the module's code, through the model if the module is opened, or by writing the
+    /// module's file otherwise.
+    async fn update
+    ( self
+    , project : &model::Project
+    , f       : impl FnOnce(&mut double_representation::module::Info) -> FallibleResult
+    ) -> FallibleResult {
+        let mut info = self.info();
+        f(&mut info)?;
+        match self {
+            Self::Opened(module) => module.update_ast(info.ast),
+            Self::File {path,mut source} => {
+                source.ast   = info.ast;
+                let contents = String::try_from(&source)?;
+                project.json_rpc().write_file(path.file_path(),&contents).await?;
+                Ok(())
+            }
+        }
+    }
+}
+
+
 
 // =============
 // === Tests ===
@@ -248,7 +486,52 @@ mod tests {
     use super::*;
 
     use crate::executor::test_utils::TestWithLocalPoolExecutor;
+    use crate::test::mock::data;
 
+    use enso_protocol::language_server;
+    use enso_protocol::language_server::response;
+    use json_rpc::expect_call;
+
+    const MAIN_CODE:&str = "import mock_namespace.Mock_Project.Lib.Util\n\nmain = Util.greet 1";
+    const UTIL_CODE:&str = "greet x = x";
+
+    fn module_path(segments:&[&str]) -> model::module::Path {
+        model::module::Path::from_name_segments(data::ROOT_ID,segments).unwrap()
+    }
+
+    /// Create a project controller of the mock project containing the `Main` module with the given
+    /// code and the `Lib.Util` module. Only the `Main` module is opened.
+    fn project_with_modules
+    (main_code:&str, json_client:language_server::MockClient) -> Project {
+        let logger     = Logger::new("test");
+        let parser     = parser::Parser::new_or_panic();
+        let src        = Path::new(data::ROOT_ID,&["src"]);
+        let lib        = Path::new(data::ROOT_ID,&["src","Lib"]);
+        let main       = module_path(&["Main"]);
+        let util       = module_path(&["Lib","Util"]);
+        let src_list   = vec!
+            [ FileSystemObject::new_file(main.file_path().clone()).unwrap()
+            , FileSystemObject::new_directory(lib.clone()).unwrap()
+            ];
+        let lib_list   = vec![FileSystemObject::new_file(util.file_path().clone()).unwrap()];
+        let list       = |paths| Ok(response::FileList {paths});
+        expect_call!(json_client.file_list(path=src) => list(src_list));
+        expect_call!(json_client.file_list(path=lib) => list(lib_list));
+
+        let ast         = parser.parse_module(main_code,default()).unwrap();
+        let main        = model::module::Plain::new(&logger,main,ast,default(),default());
+        let main        = Rc::new(main) as model::Module;
+        let mut project = model::project::MockAPI::new();
+        let json_rpc    = language_server::Connection::new_mock_rc(json_client);
+        model::project::test::expect_qualified_name(&mut project,&data::project_qualified_name());
+        model::project::test::expect_parser(&mut project,&parser);
+        model::project::test::expect_root_id(&mut project,data::ROOT_ID);
+        model::project::test::expect_json_rpc(&mut project,json_rpc);
+        model::project::test::expect_module(&mut project,main.clone_ref());
+        model::project::test::expect_loaded_modules(&mut project,vec![main]);
+        project.expect_forget_module().withf(move |path| path == &util).returning(|_| ());
+        Project::new(Rc::new(project),default())
+    }
 
     #[test]
     fn main_module_id_test() {
@@ -291,4 +574,45 @@ mod tests {
         expect_intact("here.main = 5");
         expect_intact(&format!("{}.main = 5",module_name));
     }
+
+    #[wasm_bindgen_test]
+    fn renaming_module() {
+        let mut test    = TestWithLocalPoolExecutor::set_up();
+        let json_client = language_server::MockClient::default();
+        let util        = module_path(&["Lib","Util"]);
+        let helpers     = module_path(&["Lib","Helpers"]);
+        let exists      = |exists| Ok(response::FileExists {exists});
+        let from        = util.file_path().clone();
+        let to          = helpers.file_path().clone();
+        expect_call!(json_client.file_exists(path=to.clone()) => exists(false));
+        expect_call!(json_client.move_file(from=from,to=to)    => Ok(()));
+        let project  = project_with_modules(MAIN_CODE,json_client);
+        let new_name = ReferentName::new("Helpers").unwrap();
+        let moved    = test.expect_completion(project.rename_module(&util,new_name)).unwrap();
+        assert_eq!(moved, helpers);
+
+        let main     = project.model.module(module_path(&["Main"]));
+        let main     = test.expect_completion(main).unwrap();
+        let expected = "import mock_namespace.Mock_Project.Lib.Helpers\n\nmain = Helpers.greet 1";
+        assert_eq!(main.ast().repr(), expected);
+    }
+
+    #[wasm_bindgen_test]
+    fn deleting_module() {
+        let mut test = TestWithLocalPoolExecutor::set_up();
+        let util     = module_path(&["Lib","Util"]);
+
+        let project = project_with_modules(MAIN_CODE,default());
+        let result  = test.expect_completion(project.delete_module(&util));
+        assert!(result.is_err());
+
+        let json_client = language_server::MockClient::default();
+        expect_call!(json_client.delete_file(path=util.file_path().clone()) => Ok(()));
+        let project = project_with_modules("main = 1",json_client);
+        test.expect_completion(project.delete_module(&util)).unwrap();
+
+        let project = project_with_modules(MAIN_CODE,default());
+        let result  = test.expect_completion(project.delete_module(&module_path(&["Main"])));
+        assert!(result.is_err());
+    }
 }
@@ -1,5 +1,7 @@
 //! This module contains all structures related to Searcher Controller.
 pub mod action;
+pub mod arguments;
+pub mod hierarchy;
 
 use crate::prelude::*;
 
@@ -21,6 +23,7 @@ use crate::notification;
 
 use data::text::TextLocation;
 use enso_protocol::language_server;
+use enso_protocol::language_server::SuggestionId;
 use flo_stream::Subscriber;
 use parser::Parser;
 
@@ -61,6 +64,13 @@ pub struct NotASuggestion {
     index : usize,
 }
 
+#[allow(missing_docs)]
+#[derive(Copy,Clone,Debug,Fail)]
+#[fail(display="No method in the suggestion hierarchy with the id {}.", id)]
+pub struct NoSuchHierarchyMethod {
+    id : hierarchy::NodeId,
+}
+
 #[allow(missing_docs)]
 #[derive(Debug,Fail)]
 #[fail(display="An action \"{}\" is not supported: {}", action_label, reason)]
@@ -90,7 +100,9 @@ pub struct CannotCommitExpression {
 #[derive(Copy,Clone,Debug,Eq,PartialEq)]
 pub enum Notification {
     /// A new Suggestion list is available.
-    NewActionList
+    NewActionList,
+    /// The suggestion hierarchy was invalidated, so its previously retrieved nodes are outdated.
+    NewHierarchy,
 }
 
 
@@ -183,6 +195,9 @@ pub struct ParsedInput {
     pub expression : Option<ast::Shifted<ast::prefix::Chain>>,
     /// An offset between expression and pattern.
     pub pattern_offset : usize,
+    /// If the user is typing a named argument (like `b=` in `foo a=1 b=`), this is its name. The
+    /// name, together with `=`, precedes the pattern in the input.
+    pub argument_name : Option<String>,
     /// The part of input being a function/argument which is still typed by user. It is used
     /// for filtering actions.
     pub pattern : String,
@@ -201,13 +216,22 @@ impl ParsedInput {
         input.push('a');
         let ast        = parser.parse_line_ast(input.trim_start())?;
         let mut prefix = ast::prefix::Chain::from_ast_non_strict(&ast);
+        let last_port  = arguments::argument_ports(&prefix).pop();
         if let Some(last_arg) = prefix.args.pop() {
-            let mut last_arg_repr = last_arg.sast.wrapped.repr();
-            last_arg_repr.pop();
+            let (argument_name,value) = match last_port {
+                Some(port) => match port.argument {
+                    arguments::AppliedArgument::Named(name) => (Some(name),port.value),
+                    arguments::AppliedArgument::Positional  => (None,port.value),
+                },
+                None => (None,last_arg.sast.wrapped),
+            };
+            let mut pattern = value.repr();
+            pattern.pop();
             Ok(ParsedInput {
                 expression     : Some(ast::Shifted::new(leading_spaces,prefix)),
                 pattern_offset : last_arg.sast.off,
-                pattern        : last_arg_repr,
+                argument_name,
+                pattern,
             })
         } else {
             let mut func_repr = prefix.func.repr();
@@ -215,6 +239,7 @@ impl ParsedInput {
             Ok(ParsedInput {
                 expression     : None,
                 pattern_offset : leading_spaces,
+                argument_name  : None,
                 pattern        : func_repr
             })
         }
@@ -225,6 +250,7 @@ impl ParsedInput {
         ParsedInput {
             expression     : Some(ast::Shifted::new(default(),prefix)),
             pattern_offset : 0,
+            argument_name  : None,
             pattern        : default(),
         }
     }
@@ -238,15 +264,36 @@ impl ParsedInput {
     }
 
     /// Get the picked fragment from the Searcher's input.
+    ///
+    /// For named arguments only the value is returned, without the argument name.
     pub fn completed_fragment(&self,fragment:CompletedFragmentId) -> Option<String> {
         use CompletedFragmentId::*;
         match (fragment,&self.expression) {
             (_              ,None)       => None,
             (Function       ,Some(expr)) => Some(expr.func.repr()),
-            (Argument{index},Some(expr)) => Some(expr.args.get(index)?.sast.wrapped.repr()),
+            (Argument{index},Some(expr)) => {
+                let port = arguments::argument_ports(expr).into_iter().nth(index)?;
+                Some(port.value.repr())
+            }
         }
     }
 
+    /// The arguments already applied in the expression part of the input.
+    pub fn applied_arguments(&self) -> Vec<arguments::AppliedArgument> {
+        let ports = self.expression.iter().flat_map(|expr| arguments::argument_ports(expr));
+        ports.map(|port| port.argument).collect()
+    }
+
+    /// The argument being typed in the pattern part of the input. Returns [`None`] if the user
+    /// is typing the function.
+    pub fn typed_argument(&self) -> Option<arguments::AppliedArgument> {
+        self.expression.as_ref()?;
+        Some(match &self.argument_name {
+            Some(name) => arguments::AppliedArgument::Named(name.clone()),
+            None       => arguments::AppliedArgument::Positional,
+        })
+    }
+
     /// Get the user action basing of this input (see `UserAction` docs).
     pub fn user_action(&self) -> UserAction {
         use UserAction::*;
@@ -287,6 +334,10 @@ impl HasRepr for ParsedInput {
     fn repr(&self) -> String {
         let mut repr = self.expression.as_ref().map_or("".to_string(), HasRepr::repr);
         repr.extend(itertools::repeat_n(' ',self.pattern_offset));
+        if let Some(name) = &self.argument_name {
+            repr.push_str(name);
+            repr.push('=');
+        }
         repr.push_str(&self.pattern);
         repr
     }
@@ -356,6 +407,113 @@ impl ThisNode {
 
 
 
+// ==================
+// === TargetPort ===
+// ==================
+
+/// Information about an input port which is going to be fed with the value of the node created by
+/// Searcher, e.g. when the Searcher was opened by dropping an edge detached from this port.
+///
+/// The type expected by the port is used to suggest methods producing values of that type.
+#[derive(Clone,Debug)]
+pub struct TargetPort {
+    /// The port's location.
+    pub endpoint:controller::graph::Endpoint,
+    /// The type of argument expected by the port, taken from the span tree's [`ArgumentInfo`]. It
+    /// is [`None`] if the type is unknown.
+    ///
+    /// [`ArgumentInfo`]: span_tree::ArgumentInfo
+    pub expected_type:Option<String>,
+}
+
+impl TargetPort {
+    /// Retrieve information about the given port, including its expected type.
+    pub fn new(endpoint:controller::graph::Endpoint, graph:&controller::ExecutedGraph) -> Self {
+        let expected_type = (|| {
+            let node = graph.graph().node_info(endpoint.node).ok()?;
+            let tree = span_tree::SpanTree::new(node.expression(),graph).ok()?;
+            let port = tree.get_node(&endpoint.port).ok()?;
+            port.tp().cloned()
+        })();
+        TargetPort {endpoint,expected_type}
+    }
+}
+
+
+
+// =======================
+// === CompletionQuery ===
+// =======================
+
+/// The group of suggestions received from the Language Server. The groups are displayed as separate
+/// categories on the action list.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub enum CompletionGroup {
+    /// Suggestions requested without distinguishing their relation to connected ports.
+    All,
+    /// Suggestions taking the value of "this" node as their "this" argument.
+    ConsumingSourceType,
+    /// Suggestions returning the type expected by the target port.
+    ProducingTargetType,
+}
+
+impl CompletionGroup {
+    /// The name of the category on the action list containing the suggestions of this group.
+    fn category_name(self, this_type:Option<&str>, return_type:Option<&str>) -> String {
+        let short_name = |tp:Option<&str>| {
+            let name = tp.and_then(|tp| tp.rsplit(ast::opr::predefined::ACCESS).next());
+            name.unwrap_or("Any").to_owned()
+        };
+        match self {
+            Self::All                 => "Libraries".to_owned(),
+            Self::ConsumingSourceType => format!("Consumes {}",short_name(this_type)),
+            Self::ProducingTargetType => format!("Produces {}",short_name(return_type)),
+        }
+    }
+}
+
+/// The result of a single `search/completion` request.
+pub type CompletionResult = json_rpc::Result<language_server::response::Completion>;
+
+/// Parameters of a single `search/completion` request with the group its results belong to.
+#[allow(missing_docs)]
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct CompletionQuery {
+    pub group       : CompletionGroup,
+    pub self_type   : Option<String>,
+    pub return_type : Option<String>,
+}
+
+impl CompletionQuery {
+    /// Make queries for suggestions applicable to "this" value of given type and returning one of
+    /// given types.
+    ///
+    /// When both the "this" type and return types are known (e.g. when the searcher was opened for
+    /// a new node between two ports), the suggestions consuming the "this" value and those
+    /// producing the expected type are requested separately, so they can be grouped on the list.
+    /// Otherwise, we fall back to querying with only the known types as filters.
+    pub fn for_types(this_type:Option<&String>, return_types:&[String]) -> Vec<Self> {
+        use CompletionGroup::*;
+        let query = |group,self_type:Option<&String>,return_type:Option<&String>| CompletionQuery {
+            group,
+            self_type   : self_type.cloned(),
+            return_type : return_type.cloned(),
+        };
+        match (this_type,return_types) {
+            (this_type,[]) => vec![query(All,this_type,None)],
+            (None,types)   => types.iter().map(|tp| query(All,None,Some(tp))).collect(),
+            (Some(this_type),types) => {
+                let both      = types.iter().map(|tp| query(ConsumingSourceType,Some(this_type),Some(tp)));
+                let consuming = std::iter::once(query(ConsumingSourceType,Some(this_type),None));
+                let producing = types.iter().map(|tp| query(ProducingTargetType,None,Some(tp)));
+                both.chain(consuming).chain(producing).collect()
+            }
+        }
+    }
+}
+
+
+
 // ===========================
 // === Searcher Controller ===
 // ===========================
@@ -379,6 +537,9 @@ pub enum Mode {
 pub struct FragmentAddedByPickingSuggestion {
     pub id                : CompletedFragmentId,
     pub picked_suggestion : action::Suggestion,
+    /// The type of value the suggestion was applied to, used for gathering usage statistics. See
+    /// [`Data::input_type`].
+    pub input_type        : Option<String>,
 }
 
 impl FragmentAddedByPickingSuggestion {
@@ -405,6 +566,13 @@ pub struct Data {
     /// All fragments of input which were added by picking suggestions. If the fragment will be
     /// changed by user, it will be removed from this list.
     pub fragments_added_by_picking:Vec<FragmentAddedByPickingSuggestion>,
+    /// The type of value the suggestions from the current action list would be applied to: the
+    /// type of "this" argument or the expected type of the argument. Used for gathering and
+    /// applying usage statistics (see [`model::suggestion_database::usage`]).
+    pub input_type:Option<String>,
+    /// The hierarchy of suggestions browsed in the new Searcher GUI. It is built from the
+    /// Suggestion Database when requested for the first time.
+    pub hierarchy:Option<Rc<hierarchy::Hierarchy>>,
 }
 
 impl Data {
@@ -430,6 +598,7 @@ impl Data {
             let fragment = FragmentAddedByPickingSuggestion {
                 id                : CompletedFragmentId::Function,
                 picked_suggestion : action::Suggestion::FromDatabase(entry),
+                input_type        : None,
             };
             // This is meant to work with single function calls (without "this" argument).
             // In other case we should know what the function is from the engine, as the function
@@ -438,7 +607,9 @@ impl Data {
         });
         let mut fragments_added_by_picking = Vec::<FragmentAddedByPickingSuggestion>::new();
         initial_fragment.for_each(|f| fragments_added_by_picking.push(f));
-        Ok(Data {input, actions: suggestions,fragments_added_by_picking})
+        let input_type = default();
+        let hierarchy  = default();
+        Ok(Data {input, actions: suggestions,fragments_added_by_picking,input_type,hierarchy})
     }
 
     fn find_picked_fragment(&self, id:CompletedFragmentId)
@@ -471,6 +642,7 @@ pub struct Searcher {
     language_server  : Rc<language_server::Connection>,
     ide              : controller::Ide,
     this_arg         : Rc<Option<ThisNode>>,
+    target_port      : Rc<Option<TargetPort>>,
     position_in_code : Immutable<TextLocation>,
 }
 
@@ -483,12 +655,17 @@ impl Searcher {
     , method         : language_server::MethodPointer
     , mode           : Mode
     , selected_nodes : Vec<double_representation::node::Id>
+    , target_port    : Option<controller::graph::Endpoint>
     ) -> FallibleResult<Self> {
         let graph = controller::ExecutedGraph::new(&parent,project.clone_ref(),method).await?;
-        Self::new_from_graph_controller(parent,ide,project,graph,mode,selected_nodes)
+        Self::new_from_graph_controller(parent,ide,project,graph,mode,selected_nodes,target_port)
     }
 
     /// Create new Searcher Controller, when you have Executed Graph Controller handy.
+    ///
+    /// The first of `selected_nodes` will be used as "this" argument of the new node. If
+    /// `target_port` is given, the searcher will prefer suggestions returning the type expected by
+    /// that port.
     pub fn new_from_graph_controller
     ( parent         : impl AnyLogger
     , ide            : controller::Ide
@@ -496,6 +673,7 @@ impl Searcher {
     , graph          : controller::ExecutedGraph
     , mode           : Mode
     , selected_nodes : Vec<double_representation::node::Id>
+    , target_port    : Option<controller::graph::Endpoint>
     ) -> FallibleResult<Self> {
         let logger   = Logger::new_sub(parent,"Searcher Controller");
         let database = project.suggestion_db();
@@ -509,8 +687,10 @@ impl Searcher {
         let def_span   = double_representation::module::definition_span(&module_ast,&def_id)?;
         let position   = TextLocation::convert_span(module_ast.repr(),&def_span).end;
         let this_arg   = Rc::new(matches!(mode, Mode::NewNode{..}).and_option_from(|| ThisNode::new(selected_nodes,&graph.graph())));
+        let target     = matches!(mode, Mode::NewNode{..}).and_option(target_port);
+        let target_port = Rc::new(target.map(|endpoint| TargetPort::new(endpoint,&graph)));
         let ret        = Self {
-            logger,graph,this_arg,ide,
+            logger,graph,this_arg,target_port,ide,
             data             : Rc::new(RefCell::new(data)),
             notifier         : default(),
             mode             : Immutable(mode),
@@ -519,14 +699,37 @@ impl Searcher {
             position_in_code : Immutable(position),
         };
         ret.reload_list();
+        ret.setup_hierarchy_invalidation();
         Ok(ret)
     }
 
+    /// Drop the cached hierarchy whenever the Suggestion Database changes, so it is built anew
+    /// from the updated entries. The `NewHierarchy` notification is emitted if the hierarchy was
+    /// already built, as its nodes may be displayed.
+    fn setup_hierarchy_invalidation(&self) {
+        let weak     = Rc::downgrade(&self.data);
+        let notifier = self.notifier.clone_ref();
+        executor::global::spawn_stream_handler(weak,self.database.subscribe(),move |_,data| {
+            let was_built = data.borrow_mut().hierarchy.take().is_some();
+            let notifier  = notifier.clone_ref();
+            async move {
+                if was_built {
+                    notifier.publish(Notification::NewHierarchy).await;
+                }
+            }
+        });
+    }
+
     /// Return true if user is currently filtering entries (the input has non-empty _pattern_ part).
     pub fn is_filtering(&self) -> bool {
         !self.data.borrow().input.pattern.is_empty()
     }
 
+    /// The _pattern_ part of the current input, which is used to filter the suggestions.
+    pub fn pattern(&self) -> String {
+        self.data.borrow().input.pattern.clone()
+    }
+
     /// Subscribe to controller's notifications.
     pub fn subscribe(&self) -> Subscriber<Notification> {
         self.notifier.subscribe()
@@ -546,10 +749,13 @@ impl Searcher {
         let parsed_input = ParsedInput::new(new_input,self.ide.parser())?;
         let old_expr     = self.data.borrow().input.expression.repr();
         let new_expr     = parsed_input.expression.repr();
+        let old_arg_name = self.data.borrow().input.argument_name.clone();
+        let new_arg_name = parsed_input.argument_name.clone();
 
         self.data.borrow_mut().input = parsed_input;
         self.invalidate_fragments_added_by_picking();
-        let expression_changed = old_expr != new_expr;
+        // The named argument determines the expected argument type as much as the expression.
+        let expression_changed = old_expr != new_expr || old_arg_name != new_arg_name;
         if expression_changed {
             debug!(self.logger, "Reloading list.");
             self.reload_list();
@@ -577,14 +783,21 @@ impl Searcher {
     ///
     /// This function should be called when user do the _use as suggestion_ action as a code
     /// suggestion (see struct documentation). The picked suggestion will be remembered, and the
-    /// searcher's input will be updated and returned by this function.
+    /// searcher's input will be updated and returned by this function. If the user was typing
+    /// a named argument, the suggestion's code becomes the argument's value.
     pub fn  use_suggestion
     (&self, picked_suggestion: action::Suggestion) -> FallibleResult<String> {
         info!(self.logger, "Picking suggestion: {picked_suggestion:?}");
         let id                = self.data.borrow().input.next_completion_id();
-        let picked_completion = FragmentAddedByPickingSuggestion {id,picked_suggestion};
+        let input_type        = self.data.borrow().input_type.clone();
+        let picked_completion = FragmentAddedByPickingSuggestion {id,picked_suggestion,input_type};
         let code_to_insert    = self.code_to_insert(&picked_completion).code;
         debug!(self.logger, "Code to insert: \"{code_to_insert}\"");
+        let argument_name     = self.data.borrow().input.argument_name.clone();
+        let code_to_insert    = match argument_name {
+            Some(name) => iformat!("{name}={code_to_insert}"),
+            None       => code_to_insert,
+        };
         let added_ast         = self.ide.parser().parse_line_ast(&code_to_insert)?;
         let pattern_offset    = self.data.borrow().input.pattern_offset;
         let new_expression    = match self.data.borrow_mut().input.expression.take() {
@@ -604,6 +817,7 @@ impl Searcher {
         let new_parsed_input = ParsedInput {
             expression     : Some(new_expression),
             pattern_offset : 1,
+            argument_name  : None,
             pattern        : "".to_string()
         };
         let new_input = new_parsed_input.repr();
@@ -682,6 +896,28 @@ impl Searcher {
         self.execute_action(action.clone_ref())
     }
 
+    /// The hierarchy of all suggestions, browsed in the column view of the new Searcher GUI.
+    ///
+    /// The hierarchy is built from the Suggestion Database on the first call, and rebuilt after the
+    /// database changes.
+    pub fn hierarchy(&self) -> Rc<hierarchy::Hierarchy> {
+        let built = self.data.borrow().hierarchy.clone();
+        built.unwrap_or_else(|| {
+            let hierarchy = Rc::new(hierarchy::Hierarchy::from_database(&self.database));
+            self.data.borrow_mut().hierarchy = Some(hierarchy.clone_ref());
+            hierarchy
+        })
+    }
+
+    /// Use the method represented by the given node of [`Self::hierarchy`] as a suggestion. See
+    /// [`Self::use_suggestion`].
+    pub fn use_hierarchy_node_as_suggestion(&self, id:hierarchy::NodeId) -> FallibleResult<String> {
+        let hierarchy  = self.hierarchy();
+        let suggestion = hierarchy.get(id).and_then(|node| node.suggestion.clone());
+        let suggestion = suggestion.ok_or(NoSuchHierarchyMethod {id})?;
+        self.use_suggestion(suggestion)
+    }
+
     /// Check if the first fragment in the input (i.e. the one representing the called function)
     /// is still unmodified.
     ///
@@ -718,7 +954,7 @@ impl Searcher {
 
         // We add the required imports before we create the node/edit its content. This way, we
         // avoid an intermediate state where imports would already be in use but not yet available.
-        match *self.mode {
+        let result = match *self.mode {
             Mode::NewNode {position} => {
                 self.add_required_imports()?;
                 let (expression,intended_method) = expr_and_method();
@@ -741,7 +977,12 @@ impl Searcher {
                 }))?;
                 Ok(node_id)
             }
+        };
+        // The usage is recorded only when the node was actually committed.
+        if result.is_ok() {
+            self.record_usage_of_picked_suggestions();
         }
+        result
     }
 
     /// Adds an example to the graph.
@@ -796,6 +1037,16 @@ impl Searcher {
     }
 
 
+    /// Update the usage statistics with all the suggestions picked into the committed input.
+    fn record_usage_of_picked_suggestions(&self) {
+        let data = self.data.borrow();
+        for fragment in &data.fragments_added_by_picking {
+            if let Some(method) = fragment.picked_suggestion.method_id() {
+                self.database.record_usage(&method,fragment.input_type.as_deref());
+            }
+        }
+    }
+
     fn add_required_imports(&self) -> FallibleResult {
         let data_borrowed = self.data.borrow();
         let fragments     = data_borrowed.fragments_added_by_picking.iter();
@@ -819,9 +1070,8 @@ impl Searcher {
     fn reload_list(&self) {
         let this_type    = self.this_arg_type_for_next_completion();
         let return_types = match self.data.borrow().input.next_completion_id() {
-            CompletedFragmentId::Function         => vec![],
-            CompletedFragmentId::Argument {index} =>
-                self.return_types_for_argument_completion(index),
+            CompletedFragmentId::Function         => self.return_types_for_function_completion(),
+            CompletedFragmentId::Argument {..}    => self.return_types_for_argument_completion(),
         };
         self.gather_actions_from_engine(this_type,return_types,None);
         self.data.borrow_mut().actions = Actions::Loading;
@@ -844,21 +1094,56 @@ impl Searcher {
         }
     }
 
+    /// Get the type that suggestions for the function completion should return.
+    ///
+    /// This is the type expected by the target port, if the new node is going to be connected to
+    /// any. Returns empty list if there is no target port or its type is unknown.
+    fn return_types_for_function_completion(&self) -> Vec<String> {
+        let target_port = self.target_port.deref().as_ref();
+        target_port.and_then(|port| port.expected_type.clone()).into_iter().collect()
+    }
+
     /// Get the type that suggestions for the next completion should return.
     ///
     /// Generally this corresponds to the type of the currently filled function argument. Returns
     /// empty list if no type could be determined.
-    fn return_types_for_argument_completion(&self, arg_index:usize) -> Vec<String> {
+    fn return_types_for_argument_completion(&self) -> Vec<String> {
+        let expected = self.expected_arguments().into_iter();
+        expected.map(|argument| argument.parameter.repr_type).collect()
+    }
+
+    /// The parameter of the intended function which will be filled by the currently typed
+    /// argument. See [`arguments`] module for details of matching arguments with parameters.
+    ///
+    /// If the function was not picked from suggestions, the first function matching the input is
+    /// considered. Returns [`None`] if the user is not typing an argument.
+    pub fn expected_argument(&self) -> Option<arguments::ExpectedArgument> {
+        self.expected_arguments().into_iter().next()
+    }
+
+    /// The parameters which will be filled by the currently typed argument, one for each function
+    /// which may be called in the input (see [`Self::possible_function_calls`]), or only for the
+    /// intended function if it was picked.
+    fn expected_arguments(&self) -> Vec<arguments::ExpectedArgument> {
+        let input = self.data.borrow().input.clone();
+        let typed = match input.typed_argument() {
+            Some(typed) => typed,
+            None        => return default(),
+        };
         let suggestions = if let Some(intended) = self.intended_function_suggestion() {
             std::iter::once(intended).collect()
         } else {
             self.possible_function_calls()
         };
+        // The `this` argument is applied implicitly when the node is created from selected node,
+        // or explicitly when the method is accessed (like `foo.bar`).
+        let implicit_this = self.this_arg.is_some() as usize + self.has_this_argument() as usize;
+        let positional    = arguments::AppliedArgument::Positional;
+        let implicit_args = itertools::repeat_n(positional,implicit_this);
+        let applied       = implicit_args.chain(input.applied_arguments()).collect_vec();
         suggestions.into_iter().filter_map(|suggestion| {
-            let arg_index = arg_index + if self.this_arg.is_some()  { 1 } else { 0 };
-            let arg_index = arg_index + if self.has_this_argument() { 1 } else { 0 };
-            let parameter = suggestion.argument_types().into_iter().nth(arg_index)?;
-            Some(parameter)
+            let parameters = suggestion.arguments();
+            arguments::expected_argument(&parameters,applied.iter().cloned(),&typed)
         }).collect()
     }
 
@@ -873,33 +1158,45 @@ impl Searcher {
         let position                = self.position_in_code.deref().into();
         let this                    = self.clone_ref();
         let return_types            = return_types.into_iter().collect_vec();
-        let return_types_for_engine = if return_types.is_empty() { vec![None] }
-            else {return_types.iter().cloned().map(Some).collect()};
         executor::global::spawn(async move {
             let this_type = this_type.await;
             info!(this.logger,"Requesting new suggestion list. Type of `this` is {this_type:?}.");
-            let requests  = return_types_for_engine.into_iter().map(|return_type| {
-                info!(this.logger, "Requesting suggestions for returnType {return_type:?}.");
+            let queries   = CompletionQuery::for_types(this_type.as_ref(),&return_types);
+            let groups    = queries.iter().map(|query| query.group).collect_vec();
+            let requests  = queries.into_iter().map(|query| {
+                let CompletionQuery {self_type,return_type,..} = query;
+                info!(this.logger, "Requesting suggestions for selfType {self_type:?} and \
+                    returnType {return_type:?}.");
                 let file = graph.module.path().file_path();
-                ls.completion(file,&position,&this_type,&return_type,&tags)
+                ls.completion(file,&position,&self_type,&return_type,&tags)
             });
             let responses = futures::future::join_all(requests).await;
+            let responses = groups.into_iter().zip(responses).collect_vec();
             info!(this.logger,"Received suggestions from Language Server.");
-            let new_list = match this.make_action_list(responses,this_type,return_types) {
-                Ok(list)   => Actions::Loaded {list:Rc::new(list)},
+            let input_type = this_type.clone().or_else(|| return_types.first().cloned());
+            let new_list   = match this.make_action_list(responses,this_type,return_types) {
+                Ok(list)   => {
+                    this.apply_usage_statistics(&list,input_type.as_deref());
+                    Actions::Loaded {list:Rc::new(list)}
+                }
                 Err(error) => Actions::Error(Rc::new(error))
             };
-            this.data.borrow_mut().actions = new_list;
+            this.data.borrow_mut().actions    = new_list;
+            this.data.borrow_mut().input_type = input_type;
             this.notifier.publish(Notification::NewActionList).await;
         });
     }
 
     /// Process multiple completion responses from the engine into a single list of suggestion.
+    ///
+    /// The responses of [`CompletionGroup::All`] are put into a single "Libraries" category, while
+    /// other groups get a category each. A suggestion appearing in many groups is listed only in
+    /// the first one.
     fn make_action_list
     ( &self
-    , completion_responses : Vec<json_rpc::Result<language_server::response::Completion>>
-    , _this_type           : Option<String>
-    , _return_types        : Vec<String>,
+    , completion_responses : Vec<(CompletionGroup,CompletionResult)>
+    , this_type            : Option<String>
+    , return_types         : Vec<String>,
     ) -> FallibleResult<action::List> {
         let creating_new_node             = matches!(self.mode.deref(), Mode::NewNode{..});
         let should_add_additional_entries = creating_new_node && self.this_arg.is_none();
@@ -917,31 +1214,79 @@ impl Searcher {
             let create_project = action::ProjectManagement::CreateNewProject;
             category.add_action(Action::ProjectManagement(create_project));
         }
+        if let Some(default) = self.expected_argument_default() {
+            let mut root_cat = actions.add_root_category("Arguments",default_icon.clone_ref());
+            let category     = root_cat.add_category("Default Values",default_icon.clone_ref());
+            category.add_action(Action::Suggestion(action::Suggestion::DefaultArgument(default)));
+        }
         let mut libraries_root_cat = actions.add_root_category("Libraries",libraries_icon.clone_ref());
         if should_add_additional_entries {
             let examples_cat = libraries_root_cat.add_category("Examples",default_icon.clone_ref());
             examples_cat.extend(self.database.iterate_examples().map(Action::Example));
         }
-        let libraries_cat = libraries_root_cat.add_category("Libraries",libraries_icon.clone_ref());
-        if should_add_additional_entries {
-            Self::add_enso_project_entries(&libraries_cat)?;
-        }
-        for response in completion_responses {
-            let response = response?;
-            let entries  = response.results.iter().filter_map(|id| {
-                self.database.lookup(*id)
-                    .map(|entry| Action::Suggestion(action::Suggestion::FromDatabase(entry)))
-                    .handle_err(|e| {
-                        error!(self.logger,"Response provided a suggestion ID that cannot be \
-                        resolved: {e}.")
-                    })
-            });
-            libraries_cat.extend(entries);
+        let is_grouped = completion_responses.iter().any(|(g,_)| *g != CompletionGroup::All);
+        if is_grouped {
+            use CompletionGroup::*;
+            let this_type   = this_type.as_deref();
+            let return_type = return_types.first().map(AsRef::as_ref);
+            let mut added   = HashSet::new();
+            let mut groups  = vec![(ConsumingSourceType,vec![]),(ProducingTargetType,vec![])];
+            for (group,response) in completion_responses {
+                let results = response?.results;
+                if let Some((_,entries)) = groups.iter_mut().find(|(g,_)| *g == group) {
+                    let not_added = results.into_iter().filter(|id| added.insert(*id));
+                    entries.extend(not_added.filter_map(|id| self.suggestion_action(id)));
+                }
+            }
+            for (group,entries) in groups {
+                let name     = group.category_name(this_type,return_type);
+                let category = libraries_root_cat.add_category(name,libraries_icon.clone_ref());
+                category.extend(entries);
+            }
+        } else {
+            let libraries_cat = libraries_root_cat.add_category("Libraries",libraries_icon.clone_ref());
+            if should_add_additional_entries {
+                Self::add_enso_project_entries(&libraries_cat)?;
+            }
+            for (_,response) in completion_responses {
+                let response = response?;
+                let entries  = response.results.iter().filter_map(|id| self.suggestion_action(*id));
+                libraries_cat.extend(entries);
+            }
         }
-
         Ok(actions.build())
     }
 
+    /// The default value of the currently expected argument (see [`Self::expected_argument`]).
+    fn expected_argument_default(&self) -> Option<Rc<action::DefaultArgument>> {
+        let expected  = self.expected_argument()?;
+        let parameter = expected.parameter.name;
+        let code      = expected.parameter.default_value?;
+        Some(Rc::new(action::DefaultArgument {parameter,code}))
+    }
+
+    /// Create an action for the suggestion with given id, or [`None`] if the suggestion is not
+    /// present in the database.
+    fn suggestion_action(&self, id:SuggestionId) -> Option<Action> {
+        self.database.lookup(id)
+            .map(|entry| Action::Suggestion(action::Suggestion::FromDatabase(entry)))
+            .handle_err(|e| {
+                error!(self.logger,"Response provided a suggestion ID that cannot be \
+                resolved: {e}.")
+            })
+    }
+
+    /// Order the actions on the list taking into account how often and how recently they were
+    /// picked for the value of given type.
+    fn apply_usage_statistics(&self, list:&action::List, input_type:Option<&str>) {
+        list.update_usage_scores(|action| match action {
+            Action::Suggestion(suggestion) => suggestion.method_id().map_or(0.0, |method| {
+                self.database.usage_score(&method,input_type)
+            }),
+            _ => 0.0,
+        });
+    }
+
     fn possible_function_calls(&self) -> Vec<action::Suggestion> {
         let opt_result = || {
             let call_ast = self.data.borrow().input.expression.as_ref()?.func.clone_ref();
@@ -1136,6 +1481,7 @@ pub mod test {
     use crate::test::mock::data::MODULE_NAME;
     use crate::test::mock::data::project_qualified_name;
 
+    use enso_protocol::language_server::types::SuggestionDatabaseUpdatesEvent;
     use enso_protocol::language_server::types::test::value_update_with_type;
     use json_rpc::expect_call;
     use utils::test::traits::*;
@@ -1241,6 +1587,7 @@ pub mod test {
                 mode             : Immutable(Mode::NewNode {position:default()}),
                 language_server  : language_server::Connection::new_mock_rc(client),
                 this_arg         : Rc::new(this),
+                target_port      : default(),
                 position_in_code : Immutable(end_of_code),
             };
             let entry1 = model::suggestion_database::Entry {
@@ -1416,6 +1763,68 @@ pub mod test {
         }
     }
 
+    /// Test checks that when the new node is going to be connected between the "this" node and
+    /// a port of known type, the suggestions are requested and grouped separately for consuming the
+    /// "this" type and producing the expected type.
+    #[wasm_bindgen_test]
+    fn loading_list_for_connected_ports() {
+        let mock_type = crate::test::mock::data::TYPE_NAME;
+        let Fixture{mut test,mut searcher,..} = Fixture::new_custom(|data,client| {
+            data.change_main_body("2+2");
+            data.selected_node = true;
+            data.expect_completion(client,Some(mock_type),Some("Text"),&[1]);
+            data.expect_completion(client,Some(mock_type),None,&[1,2]);
+            data.expect_completion(client,None,Some("Text"),&[1,9]);
+        });
+        let endpoint         = controller::graph::Endpoint::default();
+        let expected_type    = Some("Text".to_owned());
+        searcher.target_port = Rc::new(Some(TargetPort {endpoint,expected_type}));
+        searcher.reload_list();
+        let this_node_id = searcher.this_arg.deref().as_ref().unwrap().id;
+        let update       = value_update_with_type(this_node_id,mock_type);
+        searcher.graph.computed_value_info_registry().apply_updates(vec![update]);
+        test.run_until_stalled();
+
+        let actions    = searcher.actions();
+        let list       = actions.list().unwrap();
+        let categories = list.root_categories().flat_map(|(id,_)| list.subcategories_of(id));
+        let categories = categories.collect_vec();
+        let names      = categories.iter().map(|(_,category)| category.name.to_string());
+        let short_type = mock_type.rsplit(ast::opr::predefined::ACCESS).next().unwrap();
+        let consuming  = format!("Consumes {}",short_type);
+        let producing  = "Produces Text";
+        let expected   = vec!["All Search Result",consuming.as_str(),producing];
+        assert_eq!(names.collect_vec(), expected);
+        let category_id = |name:&str| {
+            let category = categories.iter().find(|(_,category)| category.name == name);
+            category.unwrap().0
+        };
+        assert_eq!(list.actions_of(category_id(&consuming)).count(), 2);
+        assert_eq!(list.actions_of(category_id(producing)).count(), 1);
+    }
+
+    #[test]
+    fn completion_queries_for_types() {
+        use CompletionGroup::*;
+        let query = |group,self_type:Option<&str>,return_type:Option<&str>| CompletionQuery {
+            group,
+            self_type   : self_type.map(ToOwned::to_owned),
+            return_type : return_type.map(ToOwned::to_owned),
+        };
+        let this_type    = "Number".to_owned();
+        let return_types = vec!["Text".to_owned()];
+        assert_eq!(CompletionQuery::for_types(None,&[]), vec![query(All,None,None)]);
+        assert_eq!(CompletionQuery::for_types(Some(&this_type),&[]),
+            vec![query(All,Some("Number"),None)]);
+        assert_eq!(CompletionQuery::for_types(None,&return_types),
+            vec![query(All,None,Some("Text"))]);
+        assert_eq!(CompletionQuery::for_types(Some(&this_type),&return_types), vec!
+            [ query(ConsumingSourceType,Some("Number"),Some("Text"))
+            , query(ConsumingSourceType,Some("Number"),None)
+            , query(ProducingTargetType,None,Some("Text"))
+            ]);
+    }
+
     #[wasm_bindgen_test]
     fn arguments_suggestions_for_picked_method() {
         let mut fixture = Fixture::new_custom(|data,client| {
@@ -1428,6 +1837,40 @@ pub mod test {
         assert!(!searcher.actions().is_loading());
     }
 
+    #[wasm_bindgen_test]
+    fn using_hierarchy_node_as_suggestion() {
+        let Fixture{test:_test,searcher,entry4,..} = Fixture::new_custom(|data,client| {
+            data.expect_completion(client,None,Some("String"),&[]);
+        });
+        let hierarchy   = searcher.hierarchy();
+        let library     = hierarchy.children_nodes(None).find(|(_,n)| n.name.as_str() == "test.Test");
+        let (library,_) = library.unwrap();
+        let (module,)   = hierarchy.children(Some(library)).iter().copied().expect_tuple();
+        let (tp,)       = hierarchy.children(Some(module)).iter().copied().expect_tuple();
+        let (method,)   = hierarchy.children(Some(tp)).iter().copied().expect_tuple();
+        assert!(Rc::ptr_eq(&hierarchy,&searcher.hierarchy()));
+        assert!(searcher.use_hierarchy_node_as_suggestion(tp).is_err());
+
+        let new_input = searcher.use_hierarchy_node_as_suggestion(method).unwrap();
+        assert_eq!(new_input, "Test.testMethod1 ");
+        let data      = searcher.data.borrow();
+        let (picked,) = data.fragments_added_by_picking.iter().expect_tuple();
+        assert!(are_same(&picked.picked_suggestion,&entry4));
+    }
+
+    #[wasm_bindgen_test]
+    fn rebuilding_hierarchy_after_database_update() {
+        let Fixture{mut test,searcher,..} = Fixture::new();
+        let hierarchy = searcher.hierarchy();
+        assert!(Rc::ptr_eq(&hierarchy,&searcher.hierarchy()));
+        let mut notifications = searcher.subscribe().boxed_local();
+        let update = SuggestionDatabaseUpdatesEvent {updates:vec![], current_version:2};
+        searcher.database.apply_update_event(update);
+        test.run_until_stalled();
+        assert_eq!(notifications.expect_next(), Notification::NewHierarchy);
+        assert!(!Rc::ptr_eq(&hierarchy,&searcher.hierarchy()));
+    }
+
     #[wasm_bindgen_test]
     fn arguments_suggestions_for_picked_function() {
         let mut fixture = Fixture::new_custom(|data,client| {
@@ -1444,6 +1887,51 @@ pub mod test {
         searcher.set_input("testFunction2 'foo' 10 ".to_owned()).unwrap();
     }
 
+    #[wasm_bindgen_test]
+    fn arguments_suggestions_for_named_arguments() {
+        let mut fixture = Fixture::new_custom(|data,client| {
+            data.expect_completion(client,None,Some("Text"),&[]);   // First arg suggestion.
+            data.expect_completion(client,None,Some("Number"),&[]); // `num_arg` suggestion.
+            data.expect_completion(client,None,Some("Text"),&[]);   // `text_arg` suggestion.
+        });
+        let Fixture{searcher,entry9,..} = &mut fixture;
+        searcher.use_suggestion(action::Suggestion::FromDatabase(entry9.clone_ref())).unwrap();
+        searcher.set_input("testFunction2 num_arg=".to_owned()).unwrap();
+        let expected = searcher.expected_argument().unwrap();
+        assert_eq!(expected.index         , 1);
+        assert_eq!(expected.parameter.name, "num_arg");
+        searcher.set_input("testFunction2 num_arg=10 ".to_owned()).unwrap();
+        let expected = searcher.expected_argument().unwrap();
+        assert_eq!(expected.index         , 0);
+        assert_eq!(expected.parameter.name, "text_arg");
+    }
+
+    #[wasm_bindgen_test]
+    fn suggesting_default_argument_value() {
+        let mut fixture = Fixture::new_custom(|data,client| {
+            data.expect_completion(client,None,Some("Text"),&[]);   // First arg suggestion.
+            data.expect_completion(client,None,Some("Number"),&[]); // `num_arg` suggestion.
+            data.expect_completion(client,None,Some("Text"),&[]);   // After picking the default.
+        });
+        let Fixture{test,searcher,entry9,..} = &mut fixture;
+        let mut entry = (**entry9).clone();
+        entry.arguments[1].has_default   = true;
+        entry.arguments[1].default_value = Some("42".to_owned());
+        searcher.database.put_entry(11,entry);
+        let entry = searcher.database.lookup(11).unwrap();
+        searcher.use_suggestion(action::Suggestion::FromDatabase(entry)).unwrap();
+        searcher.set_input("testFunction2 num_arg=".to_owned()).unwrap();
+        test.run_until_stalled();
+
+        let actions       = searcher.actions();
+        let list          = actions.list().unwrap();
+        let is_default    = |action:&Action| action.to_string() == "42 (default)";
+        let default_entry = list.actions().find(|(_,entry)| is_default(&entry.action));
+        let (index,_)     = default_entry.expect("The default value should be suggested.");
+        let new_input     = searcher.use_as_suggestion(index).unwrap();
+        assert_eq!(new_input, "testFunction2 num_arg=42 ");
+    }
+
     #[wasm_bindgen_test]
     fn non_picked_function_arg_suggestions() {
         let mut fixture = Fixture::new_custom(|data,client| {
@@ -1597,12 +2085,31 @@ pub mod test {
         assert_eq!(args_reprs(&expression) , vec![" bar".to_string()]);
         assert_eq!(parsed.pattern_offset,   1);
         assert_eq!(parsed.pattern.as_str(), "(baz ");
+
+        let input  = "foo a=1 b=";
+        let parsed = ParsedInput::new(input.to_string(),&parser).unwrap();
+        let expression = parsed.expression.as_ref().unwrap();
+        assert_eq!(expression.func.repr()  , "foo");
+        assert_eq!(args_reprs(&expression) , vec![" a=1".to_string()]);
+        assert_eq!(parsed.pattern_offset   , 1);
+        assert_eq!(parsed.argument_name    , Some("b".to_string()));
+        assert_eq!(parsed.pattern.as_str() , "");
+        assert_eq!(parsed.repr()           , input);
+        let applied = parsed.applied_arguments();
+        assert_eq!(applied, vec![arguments::AppliedArgument::Named("a".to_string())]);
+
+        let input  = "foo a=1 b=ba";
+        let parsed = ParsedInput::new(input.to_string(),&parser).unwrap();
+        assert_eq!(parsed.argument_name   , Some("b".to_string()));
+        assert_eq!(parsed.pattern.as_str(), "ba");
+        assert_eq!(parsed.repr()          , input);
     }
 
     fn are_same(action:&action::Suggestion, entry:&Rc<model::suggestion_database::Entry>) -> bool {
         match action {
             action::Suggestion::FromDatabase(lhs) => Rc::ptr_eq(lhs,entry),
             action::Suggestion::Hardcoded(_)      => false,
+            action::Suggestion::DefaultArgument(_) => false,
         }
     }
 
@@ -1764,6 +2271,7 @@ pub mod test {
             let picked_method = FragmentAddedByPickingSuggestion {
                 id                : CompletedFragmentId::Function,
                 picked_suggestion : action::Suggestion::FromDatabase(entry.clone_ref()),
+                input_type        : None,
             };
             with(searcher.data.borrow_mut(), |mut data| {
                 data.fragments_added_by_picking.push(picked_method);
@@ -1799,7 +2307,8 @@ pub mod test {
         let parser        = Parser::new_or_panic();
         let picked_method = FragmentAddedByPickingSuggestion {
             id                : CompletedFragmentId::Function,
-            picked_suggestion : action::Suggestion::FromDatabase(entry4),
+            picked_suggestion : action::Suggestion::FromDatabase(entry4.clone_ref()),
+            input_type        : None,
         };
         with(searcher.data.borrow_mut(), |mut data| {
             data.fragments_added_by_picking.push(picked_method);
@@ -1820,6 +2329,9 @@ pub mod test {
             name            : "testMethod1".to_string(),
         });
         assert_eq!(node2.metadata.unwrap().intended_method, expected_intended_method);
+        let picked_method = entry4.method_id().unwrap();
+        let usage_score   = searcher.database.usage_score(&picked_method,None);
+        assert!(usage_score > 0.0);
 
         // Edit existing node.
         searcher.mode = Immutable(Mode::EditNode {node_id:node1.info.id()});
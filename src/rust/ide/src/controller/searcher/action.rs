@@ -2,6 +2,7 @@
 use crate::prelude::*;
 
 use crate::double_representation::module;
+use crate::model::suggestion_database::entry::Argument;
 use crate::model::suggestion_database::entry::CodeToInsert;
 use crate::model::module::MethodId;
 
@@ -9,6 +10,17 @@ pub mod hardcoded;
 
 
 
+// =================
+// === Constants ===
+// =================
+
+/// How much the usage score (see [`model::suggestion_database::usage`]) affects the entry ordering
+/// compared to the match score. The usage score is in range [0.0,1.0), while the match score grows
+/// by at most 1.0 with each matched letter of the pattern.
+pub const USAGE_SCORE_WEIGHT:f32 = 1.0;
+
+
+
 // ==============
 // === Action ===
 // ==============
@@ -20,6 +32,8 @@ pub enum Suggestion {
     FromDatabase(Rc<model::suggestion_database::Entry>),
     /// The one of the hard-coded suggestion.
     Hardcoded(Rc<hardcoded::Suggestion>),
+    /// The default value of the argument the user is about to type.
+    DefaultArgument(Rc<DefaultArgument>),
 }
 
 impl Suggestion {
@@ -31,7 +45,11 @@ impl Suggestion {
             Suggestion::Hardcoded(s) => CodeToInsert {
                 code    : s.code.to_owned(),
                 imports : s.imports.iter().cloned().collect()
-            }
+            },
+            Suggestion::DefaultArgument(s) => CodeToInsert {
+                code    : s.code.clone(),
+                imports : default(),
+            },
         }
     }
 
@@ -42,6 +60,22 @@ impl Suggestion {
                 suggestion.arguments.iter().map(|a| a.repr_type.clone()).collect(),
             Suggestion::Hardcoded(suggestion) =>
                 suggestion.argument_types.iter().map(|t| t.into()).collect(),
+            Suggestion::DefaultArgument(_) => default(),
+        }
+    }
+
+    /// Return the expected arguments to be added after picking the suggestion, with their names
+    /// and default values if known.
+    pub fn arguments(&self) -> Vec<Argument> {
+        match self {
+            Suggestion::FromDatabase(suggestion) => suggestion.arguments.clone(),
+            _ => self.argument_types().into_iter().map(|repr_type| Argument {
+                repr_type,
+                name          : default(),
+                is_suspended  : false,
+                has_default   : false,
+                default_value : None,
+            }).collect(),
         }
     }
 
@@ -50,6 +84,7 @@ impl Suggestion {
         match self {
             Suggestion::FromDatabase(s) => s.documentation_html.as_ref().map(AsRef::<str>::as_ref),
             Suggestion::Hardcoded   (s) => s.documentation_html,
+            Suggestion::DefaultArgument(_) => None,
         }
     }
 
@@ -59,10 +94,20 @@ impl Suggestion {
         match self {
             Suggestion::FromDatabase(s) => s.method_id(),
             Suggestion::Hardcoded   (s) => s.method_id.clone(),
+            Suggestion::DefaultArgument(_) => None,
         }
     }
 }
 
+/// The default value of a method's parameter, suggested when the user is about to type the
+/// argument for that parameter.
+#[allow(missing_docs)]
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct DefaultArgument {
+    pub parameter : String,
+    pub code      : String,
+}
+
 /// Action of adding example code.
 pub type Example = Rc<model::suggestion_database::Example>;
 
@@ -119,6 +164,8 @@ impl Display for Action {
                 }
             Self::Suggestion(Suggestion::Hardcoded(suggestion)) =>
                 Display::fmt(&suggestion.name,f),
+            Self::Suggestion(Suggestion::DefaultArgument(argument)) =>
+                write!(f,"{} (default)",argument.code),
             Self::Example(example) =>
                 write!(f,"Example: {}", example.name),
             Self::ProjectManagement(ProjectManagement::CreateNewProject) =>
@@ -198,11 +245,13 @@ impl Eq for MatchInfo {}
 
 /// The single list entry.
 #[allow(missing_docs)]
-#[derive(Clone,Debug,Eq,PartialEq)]
+#[derive(Clone,Debug,PartialEq)]
 pub struct ListEntry {
-    pub category   : CategoryId,
-    pub match_info : MatchInfo,
-    pub action     : Action,
+    pub category    : CategoryId,
+    pub match_info  : MatchInfo,
+    pub action      : Action,
+    /// How often and how recently the entry's action was picked by the user, in range [0.0,1.0).
+    pub usage_score : f32,
 }
 
 impl ListEntry {
@@ -222,13 +271,29 @@ impl ListEntry {
     /// Returns true if the entry matches current filtering pattern.
     pub fn matches(&self) -> bool { matches!(self.match_info, MatchInfo::Matches {..}) }
 
+    /// The score used for ordering entries of the same category: the match score blended with
+    /// the usage score.
+    pub fn score(&self) -> f32 {
+        let match_score = match &self.match_info {
+            MatchInfo::Matches {subsequence} => subsequence.score,
+            MatchInfo::DoesNotMatch          => 0.0,
+        };
+        match_score + self.usage_score * USAGE_SCORE_WEIGHT
+    }
+
     /// The ordering on the action list: first, are the matched entries are gathered on the top of
-    /// the list, then sorted by categories, and those of same category are ordered by match score
-    /// (the best matches are first).
+    /// the list, then sorted by categories, and those of same category are ordered by their score
+    /// (the best matches and most used actions are first). See also [`ListEntry::score`].
     pub fn ordering_on_list(&self, rhs:&Self) -> std::cmp::Ordering {
         self.matches().cmp(&rhs.matches()).reverse()
             .then_with(|| self.category.cmp(&rhs.category))
-            .then_with(|| self.match_info.cmp(&rhs.match_info).reverse())
+            .then_with(|| Self::compare_scores(self.score(),rhs.score()).reverse())
+    }
+
+    /// Compare scores, treating NaNs as the lowest values (see also
+    /// [`fuzzly::Subsequence::compare_scores`]).
+    fn compare_scores(lhs:f32, rhs:f32) -> std::cmp::Ordering {
+        lhs.partial_cmp(&rhs).unwrap_or_else(|| rhs.is_nan().cmp(&lhs.is_nan()))
     }
 }
 
@@ -290,6 +355,18 @@ impl List {
         self.update_sorting();
     }
 
+    /// Update the usage scores of all entries with the given function and re-order the entries
+    /// accordingly. See [`ListEntry::usage_score`].
+    pub fn update_usage_scores(&self, usage_score:impl Fn(&Action) -> f32) {
+        {
+            let mut entries_mut = self.entries.borrow_mut();
+            for entry in entries_mut.iter_mut() {
+                entry.usage_score = usage_score(&entry.action);
+            }
+        }
+        self.update_sorting();
+    }
+
     /// Length of the actions list.
     pub fn len(&self) -> usize { self.entries.borrow().len() }
 
@@ -430,8 +507,9 @@ impl<'a> CategoryBuilder<'a> {
         let built_list = &self.list_builder.built_list;
         let category   = self.category_id;
         built_list.entries.borrow_mut().extend(iter.into_iter().map(|action| {
-            let match_info = MatchInfo::Matches {subsequence:default()};
-            ListEntry{category,match_info,action}
+            let match_info  = MatchInfo::Matches {subsequence:default()};
+            let usage_score = default();
+            ListEntry{category,match_info,action,usage_score}
         }));
     }
 }
@@ -475,10 +553,11 @@ impl ListWithSearchResultBuilder {
 
     fn make_searcher_result_entries(&self) -> Vec<ListEntry> {
         self.internal.built_list.entries.borrow().iter().map(|entry| {
-            let match_info = MatchInfo::Matches {subsequence:default()};
-            let action     = entry.action.clone_ref();
-            let category   = self.search_result_category;
-            ListEntry {category,match_info,action}
+            let match_info  = MatchInfo::Matches {subsequence:default()};
+            let action      = entry.action.clone_ref();
+            let category    = self.search_result_category;
+            let usage_score = entry.usage_score;
+            ListEntry {category,match_info,action,usage_score}
         }).collect()
     }
 }
@@ -0,0 +1,155 @@
+//! Matching the arguments typed in the Searcher input with the parameters of the called method.
+//!
+//! Enso allows passing arguments both by position and by name (like `foo a=1 b=2`). The named
+//! arguments fill the parameters with the same name, while the positional ones fill the
+//! consecutive parameters which were not filled yet.
+use crate::prelude::*;
+
+use crate::model::suggestion_database::entry::Argument;
+
+use ast::crumbs::TraversableAst;
+use span_tree::SpanTree;
+use span_tree::generate::context;
+
+
+
+// =======================
+// === AppliedArgument ===
+// =======================
+
+/// An argument applied to the called method in the Searcher input.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub enum AppliedArgument {
+    /// The argument passed by position.
+    Positional,
+    /// The argument passed by name.
+    Named(String),
+}
+
+/// The port of an argument applied in the Searcher input.
+#[derive(Clone,Debug)]
+pub struct ArgumentPort {
+    /// The way the argument is applied.
+    pub argument : AppliedArgument,
+    /// The argument's value. For named arguments, it does not contain the name.
+    pub value : Ast,
+}
+
+/// Get the ports of the arguments applied in the given prefix chain.
+///
+/// The arguments are read from the span tree ports: the named arguments (like `name=value`) are
+/// the argument ports having a name, and their value is the argument port of their own subtree.
+pub fn argument_ports(chain:&ast::prefix::Chain) -> Vec<ArgumentPort> {
+    let ast  = chain.clone().into_ast();
+    let tree = match SpanTree::<()>::new(&ast,&context::Empty) {
+        Ok(tree) => tree,
+        Err(_)   => return default(),
+    };
+    let ports = tree.root_ref().chain_children_iter();
+    let ports = ports.filter(|port| port.is_this() || port.is_argument());
+    ports.filter_map(|port| {
+        let (argument,value_port) = match port.name() {
+            Some(name) => {
+                let value = port.clone().children_iter().filter(|child| child.is_argument());
+                (AppliedArgument::Named(name.clone()),value.last().unwrap_or(port))
+            }
+            None => (AppliedArgument::Positional,port),
+        };
+        let value = ast.get_traversing(&value_port.ast_crumbs).ok()?.clone_ref();
+        Some(ArgumentPort {argument,value})
+    }).collect()
+}
+
+
+
+// ========================
+// === ExpectedArgument ===
+// ========================
+
+/// The method's parameter which will be filled by the argument typed next.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct ExpectedArgument {
+    /// The index of the parameter on the method's argument list.
+    pub index : usize,
+    /// The parameter description from the Suggestion Database.
+    pub parameter : Argument,
+}
+
+/// Check which of the `parameters` are filled by the given applied arguments.
+///
+/// The arguments naming non-existent parameters and the positional arguments exceeding the
+/// parameters count are ignored.
+pub fn filled_parameters
+(parameters:&[Argument], arguments:impl IntoIterator<Item=AppliedArgument>) -> Vec<bool> {
+    let mut filled = vec![false;parameters.len()];
+    for argument in arguments {
+        if let Some(index) = parameter_index(parameters,&filled,&argument) {
+            filled[index] = true;
+        }
+    }
+    filled
+}
+
+/// Find the parameter which will be filled by the `next` argument, applied after the given
+/// `arguments`. Returns [`None`] if there is no such parameter (e.g. all are already filled).
+pub fn expected_argument
+( parameters : &[Argument]
+, arguments  : impl IntoIterator<Item=AppliedArgument>
+, next       : &AppliedArgument
+) -> Option<ExpectedArgument> {
+    let filled    = filled_parameters(parameters,arguments);
+    let index     = parameter_index(parameters,&filled,next)?;
+    let parameter = parameters[index].clone();
+    Some(ExpectedArgument {index,parameter})
+}
+
+fn parameter_index
+(parameters:&[Argument], filled:&[bool], argument:&AppliedArgument) -> Option<usize> {
+    match argument {
+        AppliedArgument::Named(name) => parameters.iter().position(|p| &p.name == name),
+        AppliedArgument::Positional  => filled.iter().position(|is_filled| !is_filled),
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use AppliedArgument::*;
+
+    fn parameter(name:&str) -> Argument {
+        Argument {
+            name          : name.to_owned(),
+            repr_type     : "Any".to_owned(),
+            is_suspended  : false,
+            has_default   : false,
+            default_value : None,
+        }
+    }
+
+    #[test]
+    fn matching_arguments_with_parameters() {
+        let parameters = vec![parameter("this"),parameter("a"),parameter("b"),parameter("c")];
+        let named      = |name:&str| Named(name.to_owned());
+
+        let filled = filled_parameters(&parameters,vec![Positional,named("b"),Positional]);
+        assert_eq!(filled, vec![true,true,true,false]);
+        let filled = filled_parameters(&parameters,vec![named("x"),named("c"),named("c")]);
+        assert_eq!(filled, vec![false,false,false,true]);
+
+        let expected = |args:Vec<AppliedArgument>, next| {
+            expected_argument(&parameters,args,&next).map(|arg| arg.parameter.name)
+        };
+        assert_eq!(expected(vec![Positional],Positional)             , Some("a".to_owned()));
+        assert_eq!(expected(vec![Positional,named("a")],Positional)  , Some("b".to_owned()));
+        assert_eq!(expected(vec![Positional],named("c"))             , Some("c".to_owned()));
+        assert_eq!(expected(vec![Positional],named("x"))             , None);
+        assert_eq!(expected(vec![Positional;4],Positional)           , None);
+    }
+}
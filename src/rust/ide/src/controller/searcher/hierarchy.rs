@@ -0,0 +1,302 @@
+//! The hierarchy of suggestions browsed in the column view of the new Searcher GUI.
+//!
+//! All methods from the Suggestion Database are organized in a tree of four levels: libraries,
+//! their modules, the types defined in those modules and finally the methods of these types.
+use crate::prelude::*;
+
+use crate::controller::searcher::action;
+use crate::double_representation::module;
+use crate::double_representation::project;
+use crate::model::suggestion_database;
+use crate::model::suggestion_database::entry::Kind;
+
+use std::collections::BTreeMap;
+
+
+
+// ============
+// === Node ===
+// ============
+
+/// The identifier of the node in [`Hierarchy`]: the index on its node list.
+pub type NodeId = usize;
+
+/// The hierarchy level of a node. All children of a node are on the next level.
+#[allow(missing_docs)]
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+pub enum Level {
+    Library,Module,Type,Method
+}
+
+/// A single node of the [`Hierarchy`].
+#[derive(Clone,Debug)]
+pub struct Node {
+    /// The hierarchy level of this node.
+    pub level : Level,
+    /// The label displayed in the Searcher.
+    pub name : ImString,
+    /// The HTML documentation of the node, if available.
+    pub documentation_html : Option<String>,
+    /// The suggestion represented by this node. Present only for [`Level::Method`] nodes.
+    pub suggestion : Option<action::Suggestion>,
+    /// The nodes on the next level.
+    pub children : Vec<NodeId>,
+}
+
+impl Node {
+    fn new(level:Level, name:impl Into<ImString>, documentation_html:Option<String>) -> Self {
+        let name       = name.into();
+        let suggestion = default();
+        let children   = default();
+        Self {level,name,documentation_html,suggestion,children}
+    }
+
+    /// Check if the node is a leaf of the hierarchy, i.e. a method which may be picked by the user.
+    pub fn is_leaf(&self) -> bool {
+        self.level == Level::Method
+    }
+}
+
+
+
+// =================
+// === Hierarchy ===
+// =================
+
+/// The hierarchy of all methods from the Suggestion Database.
+///
+/// On each level the nodes are sorted by their names.
+#[derive(Clone,Debug,Default)]
+pub struct Hierarchy {
+    nodes : Vec<Node>,
+    roots : Vec<NodeId>,
+}
+
+impl Hierarchy {
+    /// Build the hierarchy from the given suggestion entries.
+    ///
+    /// Only methods, atoms and modules are taken into account: local variables and functions are
+    /// not visible outside their scope, so they would not fit into the library structure.
+    pub fn new(entries:impl IntoIterator<Item=Rc<suggestion_database::Entry>>) -> Self {
+        let mut libraries = Libraries::default();
+        for entry in entries {
+            libraries.add_entry(entry);
+        }
+        libraries.into_hierarchy()
+    }
+
+    /// Build the hierarchy from all entries in the Suggestion Database.
+    pub fn from_database(database:&model::SuggestionDatabase) -> Self {
+        Self::new(database.entries().into_iter().map(|(_,entry)| entry))
+    }
+
+    /// Get the node by its id.
+    pub fn get(&self, id:NodeId) -> Option<&Node> {
+        self.nodes.get(id)
+    }
+
+    /// The children of a given node. If `parent` is [`None`], returns the library nodes.
+    pub fn children(&self, parent:Option<NodeId>) -> &[NodeId] {
+        let children = match parent {
+            Some(id) => self.nodes.get(id).map(|node| &node.children),
+            None     => Some(&self.roots),
+        };
+        children.map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Iterate over the children of a given node together with their ids. See [`Self::children`].
+    pub fn children_nodes(&self, parent:Option<NodeId>) -> impl Iterator<Item=(NodeId,&Node)> {
+        self.children(parent).iter().filter_map(move |id| Some((*id,self.nodes.get(*id)?)))
+    }
+
+    /// Number of all nodes in the hierarchy.
+    pub fn len(&self) -> usize { self.nodes.len() }
+
+    /// Check if the hierarchy has no nodes.
+    pub fn is_empty(&self) -> bool { self.nodes.is_empty() }
+
+    fn add_node(&mut self, node:Node, parent:Option<NodeId>) -> NodeId {
+        let id = self.nodes.len();
+        self.nodes.push(node);
+        match parent {
+            Some(parent) => self.nodes[parent].children.push(id),
+            None         => self.roots.push(id),
+        }
+        id
+    }
+}
+
+
+
+// ===============
+// === Builder ===
+// ===============
+
+/// The methods and documentation of a single type, gathered before building the [`Hierarchy`].
+#[derive(Debug,Default)]
+struct TypeContent {
+    documentation_html : Option<String>,
+    methods            : Vec<Rc<suggestion_database::Entry>>,
+}
+
+/// The types and documentation of a single module, gathered before building the [`Hierarchy`].
+#[derive(Debug,Default)]
+struct ModuleContent {
+    documentation_html : Option<String>,
+    types              : BTreeMap<String,TypeContent>,
+}
+
+/// The gathered suggestion entries, grouped by their libraries, modules and types.
+#[derive(Debug,Default)]
+struct Libraries {
+    libraries : BTreeMap<project::QualifiedName,BTreeMap<module::QualifiedName,ModuleContent>>,
+}
+
+impl Libraries {
+    fn module_mut(&mut self, module:&module::QualifiedName) -> &mut ModuleContent {
+        let library = self.libraries.entry(module.project_name.clone()).or_default();
+        library.entry(module.clone()).or_default()
+    }
+
+    fn add_entry(&mut self, entry:Rc<suggestion_database::Entry>) {
+        match entry.kind {
+            Kind::Module => {
+                let module = self.module_mut(&entry.module);
+                module.documentation_html = entry.documentation_html.clone();
+            }
+            Kind::Atom => {
+                let module = self.module_mut(&entry.module);
+                let tp     = module.types.entry(entry.name.clone()).or_default();
+                tp.documentation_html = entry.documentation_html.clone();
+            }
+            Kind::Method => if let Some(self_type) = &entry.self_type {
+                let type_name = self_type.name.clone();
+                let module    = self.module_mut(&entry.module);
+                module.types.entry(type_name).or_default().methods.push(entry);
+            }
+            Kind::Function | Kind::Local => {}
+        }
+    }
+
+    fn into_hierarchy(self) -> Hierarchy {
+        let mut hierarchy = Hierarchy::default();
+        for (library_name,modules) in self.libraries {
+            let library = Node::new(Level::Library,library_name.to_string(),None);
+            let library = hierarchy.add_node(library,None);
+            for (module_name,module_content) in modules {
+                let ModuleContent {documentation_html,types} = module_content;
+                let name   = Self::module_label(&module_name);
+                let module = Node::new(Level::Module,name,documentation_html);
+                let module = hierarchy.add_node(module,Some(library));
+                for (type_name,type_content) in types {
+                    let TypeContent {documentation_html,mut methods} = type_content;
+                    let tp = Node::new(Level::Type,type_name,documentation_html);
+                    let tp = hierarchy.add_node(tp,Some(module));
+                    methods.sort_by(|lhs,rhs| lhs.name.cmp(&rhs.name));
+                    for entry in methods {
+                        let name          = entry.name.clone();
+                        let documentation = entry.documentation_html.clone();
+                        let mut method    = Node::new(Level::Method,name,documentation);
+                        method.suggestion = Some(action::Suggestion::FromDatabase(entry));
+                        hierarchy.add_node(method,Some(tp));
+                    }
+                }
+            }
+        }
+        hierarchy
+    }
+
+    /// The module is labeled with its path inside the library, or the project name in case of the
+    /// library's main module.
+    fn module_label(module:&module::QualifiedName) -> String {
+        if module.id().segments().is_empty() {
+            module.name().to_string()
+        } else {
+            module.id().to_string()
+        }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::double_representation::tp;
+    use crate::model::suggestion_database::Entry;
+    use crate::model::suggestion_database::entry::Scope;
+
+    fn entry(kind:Kind, module:&str, name:&str, self_type:Option<&str>) -> Rc<Entry> {
+        Rc::new(Entry {
+            kind,
+            module             : module::QualifiedName::from_text(module).unwrap(),
+            name               : name.to_owned(),
+            arguments          : vec![],
+            return_type        : "Any".to_owned(),
+            documentation_html : Some(format!("<p>{}</p>",name)),
+            self_type          : self_type.map(|tp| tp::QualifiedName::from_text(tp).unwrap()),
+            scope              : Scope::Everywhere,
+        })
+    }
+
+    fn names<'a>(hierarchy:&'a Hierarchy, parent:Option<NodeId>) -> Vec<&'a str> {
+        hierarchy.children_nodes(parent).map(|(_,node)| node.name.as_str()).collect()
+    }
+
+    #[test]
+    fn building_hierarchy() {
+        let text_module  = "Standard.Base.Data.Text";
+        let text_type    = "Standard.Base.Data.Text.Text";
+        let table_module = "Standard.Table.Data.Table";
+        let table_type   = "Standard.Table.Data.Table.Table";
+        let entries = vec![
+            entry(Kind::Method  , text_module , "length"    , Some(text_type)),
+            entry(Kind::Method  , text_module , "characters", Some(text_type)),
+            entry(Kind::Atom    , text_module , "Text"      , None),
+            entry(Kind::Module  , text_module , "Text"      , None),
+            entry(Kind::Method  , table_module, "new"       , Some(table_module)),
+            entry(Kind::Method  , table_module, "at"        , Some(table_type)),
+            entry(Kind::Local   , table_module, "local"     , None),
+            entry(Kind::Function, table_module, "function"  , None),
+        ];
+        let hierarchy = Hierarchy::new(entries);
+        assert_eq!(names(&hierarchy,None), vec!["Standard.Base","Standard.Table"]);
+
+        let base        = hierarchy.children(None)[0];
+        let text_module = hierarchy.children(Some(base))[0];
+        assert_eq!(names(&hierarchy,Some(base)), vec!["Data.Text"]);
+        let module_docs = hierarchy.get(text_module).unwrap().documentation_html.as_deref();
+        assert_eq!(module_docs, Some("<p>Text</p>"));
+
+        let text_type = hierarchy.children(Some(text_module))[0];
+        assert_eq!(names(&hierarchy,Some(text_module)), vec!["Text"]);
+        assert_eq!(names(&hierarchy,Some(text_type)), vec!["characters","length"]);
+        let length = hierarchy.get(hierarchy.children(Some(text_type))[1]).unwrap();
+        assert!(length.is_leaf());
+        assert_eq!(length.level, Level::Method);
+        assert!(length.suggestion.is_some());
+
+        let table        = hierarchy.children(None)[1];
+        let table_module = hierarchy.children(Some(table))[0];
+        assert_eq!(names(&hierarchy,Some(table_module)), vec!["Table"]);
+        let table_type = hierarchy.children(Some(table_module))[0];
+        assert_eq!(names(&hierarchy,Some(table_type)), vec!["at","new"]);
+        assert_eq!(hierarchy.len(), 10);
+    }
+
+    #[test]
+    fn hierarchy_of_main_module() {
+        let entries   = vec![entry(Kind::Method,"local.Project","main",Some("local.Project.Main"))];
+        let hierarchy = Hierarchy::new(entries);
+        let library   = hierarchy.children(None)[0];
+        let module    = hierarchy.children(Some(library))[0];
+        assert_eq!(names(&hierarchy,Some(library)), vec!["Project"]);
+        assert_eq!(names(&hierarchy,Some(module)), vec!["Main"]);
+        assert!(hierarchy.children(Some(hierarchy.len())).is_empty());
+    }
+}
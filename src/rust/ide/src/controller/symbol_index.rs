@@ -0,0 +1,294 @@
+//! The Symbol Index Controller.
+//!
+//! The controller keeps the project's [`model::SymbolIndex`] up to date with the Suggestion
+//! Database and the module files, and resolves symbols under nodes and code locations.
+
+use crate::prelude::*;
+
+use crate::constants::LANGUAGE_FILE_EXTENSION;
+use crate::controller::FilePath;
+use crate::model::module;
+use crate::model::symbol_index::Reference;
+use crate::model::symbol_index::Symbol;
+use crate::model::symbol_index::SymbolId;
+
+use data::text::Index;
+use enso_protocol::language_server;
+use enso_protocol::language_server::FileEventKind;
+
+
+
+// ==============
+// === Handle ===
+// ==============
+
+/// The Symbol Index Controller.
+///
+/// On creation the index contains only the symbols known to the Suggestion Database. To index the
+/// project modules, [`Handle::rebuild`] should be called; afterwards the modules are reindexed when
+/// their files change.
+#[derive(Clone,CloneRef,Debug)]
+pub struct Handle {
+    logger  : Logger,
+    project : model::Project,
+    index   : Rc<model::SymbolIndex>,
+    /// The ASTs of the opened modules as they were last indexed. A module is reindexed only when
+    /// its current AST is a different one, i.e. the module was changed since.
+    indexed : Rc<RefCell<HashMap<module::QualifiedName,Ast>>>,
+}
+
+impl Handle {
+    /// Create a controller of the project's symbol index.
+    pub fn new(parent:impl AnyLogger, project:model::Project) -> Self {
+        let logger = Logger::new_sub(parent,"Symbol Index");
+        let index   = Rc::new(model::SymbolIndex::new());
+        let indexed = default();
+        index.update_suggestions(&project.suggestion_db());
+        let this = Self {logger,project,index,indexed};
+        this.setup_suggestion_database_handling();
+        this.setup_file_event_handling();
+        this
+    }
+
+    fn setup_suggestion_database_handling(&self) {
+        let database = self.project.suggestion_db();
+        let stream   = database.subscribe();
+        let weak     = Rc::downgrade(&self.index);
+        executor::global::spawn_stream_handler(weak,stream,move |_,index| {
+            index.update_suggestions(&database);
+            futures::future::ready(())
+        });
+    }
+
+    fn setup_file_event_handling(&self) {
+        let stream  = self.project.subscribe();
+        let weak    = Rc::downgrade(&self.index);
+        let logger  = self.logger.clone_ref();
+        let project = self.project.clone_ref();
+        let indexed = self.indexed.clone_ref();
+        executor::global::spawn_stream_handler(weak,stream,move |notification,index| {
+            if let model::project::Notification::FileEvent(event) = notification {
+                let logger  = logger.clone_ref();
+                let project = project.clone_ref();
+                let indexed = indexed.clone_ref();
+                Self {logger,project,index,indexed}.file_changed(event);
+            }
+            futures::future::ready(())
+        });
+    }
+
+    fn file_changed(&self, event:language_server::FileEvent) {
+        let is_module = event.path.extension() == Some(LANGUAGE_FILE_EXTENSION);
+        let path      = is_module.and_option_from(|| module::Path::from_file_path(event.path).ok());
+        if let Some(path) = path {
+            match event.kind {
+                FileEventKind::Removed => {
+                    let name = self.module_name(&path);
+                    self.indexed.borrow_mut().remove(&name);
+                    self.index.remove_module(&name);
+                }
+                _ => self.reindex_file(path),
+            }
+        }
+    }
+
+    fn reindex_file(&self, path:module::Path) {
+        let this = self.clone_ref();
+        executor::global::spawn(async move {
+            if let Err(err) = this.index_file(path.file_path()).await {
+                error!(this.logger,"Cannot index the module {path}: {err}");
+            }
+        });
+    }
+
+    /// Index all the modules of the project, reading their contents from the Language Server.
+    ///
+    /// The modules which cannot be read or parsed are skipped, so they do not prevent indexing the
+    /// rest of the project. Fails only if the list of modules cannot be obtained.
+    pub async fn rebuild(&self) -> FallibleResult {
+        let modules = controller::project::module_paths(&self.project).await?;
+        let count   = modules.len();
+        info!(self.logger,"Indexing {count} modules.");
+        for path in modules {
+            if let Err(err) = self.index_file(path.file_path()).await {
+                error!(self.logger,"Cannot index the module {path}: {err}");
+            }
+        }
+        Ok(())
+    }
+
+    async fn index_file(&self, path:&FilePath) -> FallibleResult {
+        let module   = module::Path::from_file_path(path.clone())?;
+        let contents = self.project.json_rpc().read_file(path).await?.contents;
+        let source   = self.project.parser().parse_with_metadata::<module::Metadata>(contents)?;
+        let name     = self.module_name(&module);
+        // The file content replaces what was indexed from the opened module.
+        self.indexed.borrow_mut().remove(&name);
+        self.index.update_module(name,&source.ast);
+        Ok(())
+    }
+
+    fn module_name(&self, path:&module::Path) -> module::QualifiedName {
+        path.qualified_module_name(self.project.qualified_name())
+    }
+
+    /// Get the definition of the method called in the node.
+    ///
+    /// The method reported by the execution is preferred; if the node was not computed yet, the
+    /// called method is resolved statically from the node's expression.
+    pub fn definition_of_node
+    (&self, graph:&controller::ExecutedGraph, node:double_representation::node::Id)
+    -> Option<Symbol> {
+        let registry = graph.computed_value_info_registry();
+        let called   = registry.get(&node).and_then(|info| info.method_call);
+        let entry    = called.and_then(|id| self.project.suggestion_db().lookup(id).ok());
+        let from_ls  = entry.and_then(|entry| self.index.symbol(&SymbolId::from_entry(&entry)?));
+        from_ls.or_else(|| {
+            let graph     = graph.graph();
+            let module    = self.indexed_module(&graph.module);
+            let node      = graph.node(node).ok()?;
+            let reference = self.index.reference_in(&module,node.info.expression())?;
+            self.index.definition_of(&reference)
+        })
+    }
+
+    /// Get the definition of the symbol referred at the given index of the module code.
+    pub fn definition_at(&self, module:&model::Module, index:Index) -> Option<Symbol> {
+        let module = self.indexed_module(module);
+        self.index.definition_of(&self.index.reference_at(&module,index)?)
+    }
+
+    /// Find all the references which may refer to the symbol.
+    pub fn references_to(&self, symbol:&SymbolId) -> Vec<Reference> {
+        self.index.references_to(symbol)
+    }
+
+    /// Find the symbols whose names fuzzy-match the pattern, the best matches first.
+    pub fn search(&self, pattern:&str) -> Vec<Symbol> {
+        self.index.search(pattern)
+    }
+
+    /// Reindex the opened module if it was changed since it was last indexed, as its content may be
+    /// newer than the file's, and return its name.
+    fn indexed_module(&self, module:&model::Module) -> module::QualifiedName {
+        let name       = self.module_name(module.path());
+        let ast        = module.ast();
+        let mut cache  = self.indexed.borrow_mut();
+        let is_current = cache.get(&name).map_or(false, |indexed| {
+            Rc::ptr_eq(&indexed.wrapped,&ast.ast().wrapped)
+        });
+        if !is_current {
+            self.index.update_module(name.clone(),&ast);
+            cache.insert(name.clone(),ast.into());
+        }
+        name
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::executor::test_utils::TestWithLocalPoolExecutor;
+    use crate::notification;
+    use crate::test::mock::data;
+
+    use enso_protocol::language_server::FileSystemObject;
+    use enso_protocol::language_server::response;
+    use flo_stream::Subscriber;
+    use json_rpc::error::RpcError;
+    use json_rpc::expect_call;
+
+    const MAIN_CODE:&str = "import mock_namespace.Mock_Project.Lib.Util\n\nmain = Util.greet 1";
+    const UTIL_CODE:&str = "greet x = x";
+
+    fn main_path() -> FilePath { FilePath::new(data::ROOT_ID,&["src","Main.enso"]) }
+    fn util_path() -> FilePath { FilePath::new(data::ROOT_ID,&["src","Lib","Util.enso"]) }
+    fn broken_path() -> FilePath { FilePath::new(data::ROOT_ID,&["src","Broken.enso"]) }
+
+    fn mock_project
+    (logger:&Logger, subscriber:Subscriber<model::project::Notification>)
+    -> model::project::MockAPI {
+        let mut project = model::project::MockAPI::new();
+        let database    = Rc::new(model::SuggestionDatabase::new_empty(logger));
+        model::project::test::expect_qualified_name(&mut project,&data::project_qualified_name());
+        model::project::test::expect_parser(&mut project,&parser::Parser::new_or_panic());
+        model::project::test::expect_root_id(&mut project,data::ROOT_ID);
+        model::project::test::expect_suggestion_db(&mut project,database);
+        project.expect_subscribe().return_once_st(move || subscriber);
+        project
+    }
+
+    #[wasm_bindgen_test]
+    fn indexing_project_modules() {
+        let mut test      = TestWithLocalPoolExecutor::set_up();
+        let logger        = Logger::new("test");
+        let src           = FilePath::new(data::ROOT_ID,&["src"]);
+        let lib           = FilePath::new(data::ROOT_ID,&["src","Lib"]);
+        let json_client   = language_server::MockClient::default();
+        let notifications = notification::Publisher::default();
+        let src_list      = vec!
+            [ FileSystemObject::new_file(main_path()).unwrap()
+            , FileSystemObject::new_directory(lib.clone()).unwrap()
+            , FileSystemObject::new_file(src.append_im("notes.txt")).unwrap()
+            , FileSystemObject::new_file(broken_path()).unwrap()
+            ];
+        let lib_list = vec![FileSystemObject::new_file(util_path()).unwrap()];
+        let read     = |code:&str| Ok(response::Read {contents:code.to_owned()});
+        let list     = |paths| Ok(response::FileList {paths});
+        expect_call!(json_client.file_list(path=src)         => list(src_list));
+        expect_call!(json_client.file_list(path=lib)         => list(lib_list));
+        expect_call!(json_client.read_file(path=main_path()) => read(MAIN_CODE));
+        expect_call!(json_client.read_file(path=util_path()) => read(UTIL_CODE));
+        expect_call!(json_client.read_file(path=broken_path()) => Err(RpcError::LostConnection));
+
+        let json_rpc    = language_server::Connection::new_mock_rc(json_client);
+        let mut project = mock_project(&logger,notifications.subscribe());
+        model::project::test::expect_json_rpc(&mut project,json_rpc);
+        let handle = Handle::new(&logger,Rc::new(project));
+
+        test.expect_completion(handle.rebuild()).unwrap();
+        let found = handle.search("greet");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id.to_string(), "Util.greet");
+        assert!(found[0].definition.is_some());
+        let references = handle.references_to(&found[0].id);
+        assert_eq!(references.len(), 1);
+        let main = module::Path::from_file_path(main_path()).unwrap();
+        let main = main.qualified_module_name(data::project_qualified_name());
+        assert_eq!(references[0].module, main);
+
+        let event = language_server::FileEvent {path:util_path(), kind:FileEventKind::Removed};
+        notifications.notify(model::project::Notification::FileEvent(event));
+        test.run_until_stalled();
+        assert!(handle.search("greet").is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn reindexing_changed_opened_module() {
+        let _test         = TestWithLocalPoolExecutor::set_up();
+        let logger        = Logger::new("test");
+        let notifications = notification::Publisher::default();
+        let project       = mock_project(&logger,notifications.subscribe());
+        let handle        = Handle::new(&logger,Rc::new(project));
+        let module        = model::module::test::plain_from_code("foo x = x\nmain = here.foo 1");
+        let call          = Index::new("foo x = x\nmain = here.".len());
+
+        let found = handle.definition_at(&module,call).unwrap();
+        assert_eq!(found.id.name, "foo");
+        assert_eq!(handle.definition_at(&module,call), Some(found));
+
+        let parser = parser::Parser::new_or_panic();
+        let ast    = parser.parse_module("bar x = x\nmain = here.bar 1",default()).unwrap();
+        module.update_ast(ast).unwrap();
+        let found = handle.definition_at(&module,call).unwrap();
+        assert_eq!(found.id.name, "bar");
+        assert!(handle.search("foo").is_empty());
+    }
+}
@@ -3,6 +3,7 @@
 use crate::prelude::*;
 
 use crate::constants::keywords::HERE;
+use crate::constants::keywords::TYPE;
 use crate::constants::PROJECTS_MAIN_MODULE;
 use crate::double_representation::definition;
 use crate::double_representation::definition::DefinitionProvider;
@@ -14,6 +15,8 @@ use crate::double_representation::project;
 use crate::double_representation::tp;
 
 use ast::crumbs::ChildAst;
+use ast::crumbs::Crumbable;
+use ast::crumbs::InfixCrumb;
 use ast::crumbs::ModuleCrumb;
 use ast::known;
 use ast::BlockLine;
@@ -503,6 +506,38 @@ impl Info {
         }
     }
 
+    /// Replace the name qualifying the accesses to the `import`ed module, like `Util` in
+    /// `Util.greet`, with `new` in all the module's lines other than import declarations.
+    ///
+    /// Used when an imported module is renamed, as the code refers to the module's members
+    /// through the last segment of its name. Nothing is renamed if the name does not resolve to
+    /// the given import: when the import is missing, when another import ends with the same
+    /// segment, or when the module declares a type of this name.
+    pub fn rename_qualifier(&mut self, import:&ImportInfo, new:&str) -> FallibleResult {
+        let old = match import.target.last() {
+            Some(old) => old,
+            None      => return Ok(()),
+        };
+        let imports      = self.iter_imports().collect_vec();
+        let is_imported  = imports.contains(import);
+        let same_name    = |other:&&ImportInfo| other.target.last().contains(&old);
+        let is_ambiguous = imports.iter().filter(same_name).any(|other| other != import);
+        let lines        = self.ast.shape().enumerate().map(|(_,ast)| ast);
+        let is_shadowed  = lines.filter_map(declared_type_name).any(|name| name == *old);
+        if !is_imported || is_ambiguous || is_shadowed {
+            return Ok(())
+        }
+        let lines   = self.ast.shape().enumerate();
+        let lines   = lines.filter(|(_,ast)| ImportInfo::from_ast(ast).is_none());
+        let renamed = lines.map(|(crumb,ast)| Ok((crumb,rename_qualifier(ast,old,new)?)));
+        let renamed = renamed.collect::<FallibleResult<Vec<_>>>()?;
+        for (crumb,ast) in renamed {
+            let shape = self.ast.shape().set(&crumb,ast)?;
+            self.ast.update_shape(|current| *current = shape);
+        }
+        Ok(())
+    }
+
     /// Place the line with given AST in the module's body.
     ///
     /// Unlike `add_line` (which is more low-level) will introduce empty lines around introduced
@@ -647,6 +682,32 @@ impl TryFrom<definition::ChildDefinition> for ChildDefinition {
 // === Module Utilities ===
 // ========================
 
+/// Replace the constructor name `old` with `new` wherever it is the left operand of an access.
+fn rename_qualifier(ast:&Ast, old:&str, new:&str) -> FallibleResult<Ast> {
+    let mut result = ast.clone();
+    for (crumb,child) in ast.enumerate() {
+        result = result.set(&crumb,rename_qualifier(child,old,new)?)?;
+    }
+    let is_old = |ast:&Ast| known::Cons::try_from(ast).map_or(false, |cons| cons.name == old);
+    match ast::opr::to_access(&result) {
+        Some(access) if is_old(&access.larg) => {
+            let qualifier = Ast::new(ast::Cons {name:new.into()}, access.larg.id);
+            result.set(&InfixCrumb::LeftOperand.into(),qualifier)
+        }
+        _ => Ok(result),
+    }
+}
+
+/// If the AST is a type declaration (like `type Util`), returns the declared type's name.
+fn declared_type_name(ast:&Ast) -> Option<String> {
+    let macro_match = known::Match::try_from(ast).ok()?;
+    let keyword     = ast::identifier::name(&macro_match.segs.head.head)?;
+    if keyword != TYPE {
+        return None
+    }
+    ast.repr().split_whitespace().nth(1).map(ToOwned::to_owned)
+}
+
 /// Looks up graph in the module.
 pub fn get_definition
 (ast:&known::Module, id:&definition::Id) -> FallibleResult<definition::DefinitionInfo> {
@@ -812,6 +873,30 @@ mod tests {
         info.expect_code("import Bar.Gar");
     }
 
+    #[wasm_bindgen_test]
+    fn renaming_qualifier() {
+        let parser   = parser::Parser::new_or_panic();
+        let import   = ImportInfo::from_target_str("Foo.Util");
+        let code     = "import Foo.Util\n\nmain =\n    x = Util.greet Util.name\n    Utils.greet x";
+        let ast      = parser.parse_module(code,default()).unwrap();
+        let mut info = Info {ast};
+        info.rename_qualifier(&import,"Helpers").unwrap();
+        let expected = "import Foo.Util\n\nmain =\n    x = Helpers.greet Helpers.name\n    \
+            Utils.greet x";
+        info.expect_code(expected);
+
+        // The qualifier is left intact when it does not resolve to the import.
+        let not_renamed = |code:&str| {
+            let ast      = parser.parse_module(code,default()).unwrap();
+            let mut info = Info {ast};
+            info.rename_qualifier(&import,"Helpers").unwrap();
+            info.expect_code(code);
+        };
+        not_renamed("import Bar.Util\n\nmain = Util.greet");
+        not_renamed("import Foo.Util\nimport Bar.Util\n\nmain = Util.greet");
+        not_renamed("import Foo.Util\n\ntype Util\n\nmain = Util.greet");
+    }
+
     #[wasm_bindgen_test]
     fn implicit_method_resolution() {
         let parser         = parser::Parser::new_or_panic();
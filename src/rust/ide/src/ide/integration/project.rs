@@ -11,6 +11,8 @@ use crate::controller::graph::Connections;
 use crate::controller::graph::NodeTrees;
 use crate::controller::searcher::action::MatchInfo;
 use crate::controller::searcher::Actions;
+use crate::controller::searcher::action::hardcoded;
+use crate::controller::searcher::hierarchy;
 use crate::controller::upload;
 use crate::controller::upload::NodeFromDroppedFileHandler;
 use crate::ide::integration::file_system::FileProvider;
@@ -25,16 +27,21 @@ use crate::model::execution_context::VisualizationId;
 use crate::model::execution_context::VisualizationUpdateData;
 use crate::model::module::ProjectMetadata;
 use crate::model::suggestion_database;
+use crate::model::symbol_index::Reference;
+use crate::model::symbol_index::Symbol;
 use crate::model::traits::*;
 
 use analytics;
 use bimap::BiMap;
 use enso_data::text::TextChange;
 use enso_frp as frp;
+use enso_protocol::language_server;
+use enso_protocol::language_server::CapabilityRegistration;
 use enso_protocol::language_server::ExpressionUpdatePayload;
 use ensogl::display::traits::*;
 use ensogl_gui_components::file_browser::model::AnyFolderContent;
 use ensogl_gui_components::list_view;
+use ensogl_theme::file::Layer as ThemeLayer;
 use ensogl_web::drop;
 use ide_view::graph_editor;
 use ide_view::graph_editor::component::node;
@@ -43,8 +50,9 @@ use ide_view::graph_editor::EdgeEndpoint;
 use ide_view::graph_editor::GraphEditor;
 use ide_view::graph_editor::SharedHashMap;
 use ide_view::searcher::entry::AnyModelProvider;
+use ide_view::searcher::new::DirectoryContent;
 use ide_view::searcher::new::Icon;
-use ide_view::open_dialog;
+use ide_view::list_panel;
 use utils::iter::split_by_predicate;
 use futures::future::LocalBoxFuture;
 use ide_view::searcher::entry::GlyphHighlightedLabel;
@@ -164,6 +172,10 @@ const DEFAULT_NODE_X_POSITION   : f32 = -100.0;
 /// node was added by editing text.
 const DEFAULT_NODE_Y_POSITION   : f32 =  200.0;
 
+/// The status bar message shown when the definition of the symbol under the code cursor or of the
+/// selected node's method is not known.
+const NO_DEFINITION_MESSAGE:&str = "The definition of the symbol was not found.";
+
 /// Default node position -- acts as a starting points for laying out nodes with no position defined
 /// in the metadata.
 pub fn default_node_position() -> Vector2 {
@@ -203,6 +215,7 @@ struct Model {
     searcher                : RefCell<Option<controller::Searcher>>,
     project                 : model::Project,
     main_module             : model::Module,
+    symbol_index            : controller::SymbolIndex,
     node_views              : RefCell<BiMap<ast::Id,graph_editor::NodeId>>,
     node_view_by_expression : RefCell<HashMap<ast::Id,graph_editor::NodeId>>,
     expression_views        : RefCell<HashMap<graph_editor::NodeId,graph_editor::component::node::Expression>>,
@@ -213,6 +226,10 @@ struct Model {
     error_visualizations    : VisualizationMap,
     prompt_was_shown        : Cell<bool>,
     displayed_project_list  : CloneRefCell<ProjectsToOpen>,
+    displayed_undo_history  : CloneRefCell<UndoHistoryEntries>,
+    displayed_symbols       : CloneRefCell<SymbolPanelEntries>,
+    /// Dropping the sender stops following the access mode of the previously displayed module.
+    access_mode_tracking    : RefCell<Option<futures::channel::oneshot::Sender<()>>>,
 }
 
 
@@ -354,16 +371,39 @@ impl Integration {
         }
 
 
+        // === Undo History ===
+
+        let undo_history = model.view.undo_history();
+        frp::extend! { network
+            history_is_shown <- project_frp.undo_history_shown.filter(|v| *v);
+            history_chosen   <- undo_history.chosen_entry.filter_map(|e| *e);
+            eval_ history_is_shown (    model.undo_history_opened_in_ui());
+            eval  history_chosen   ((e) model.undo_history_entry_chosen_in_ui(*e));
+        }
+
+
+        // === Symbols Panel ===
+
+        let symbol_panel = model.view.symbol_panel();
+        frp::extend! { network
+            symbol_chosen <- symbol_panel.chosen_entry.filter_map(|e| *e);
+            eval_ project_frp.find_references (model.find_references_in_ui());
+            eval  project_frp.symbol_query    ((query) model.symbol_query_changed_in_ui(query));
+            eval  symbol_chosen               ((e) model.symbol_entry_chosen_in_ui(*e));
+        }
+
+
         // === Searcher 2.0 ===
 
         let searcher = model.view.searcher().new_frp();
         frp::extend! { network
-            eval searcher.list_directory ([model,searcher](crumbs) {
-                for (id,entry) in model.get_category_content(crumbs) {
-                    let mut new_crumbs     = crumbs.clone();
-                    Rc::make_mut(&mut new_crumbs).push(id);
-                    searcher.directory_content(new_crumbs,entry);
-                }
+            eval searcher.list_directory ([model,searcher](path) {
+                let content = model.hierarchy_directory_content(path);
+                searcher.directory_content(path.clone_ref(),content);
+            });
+            eval searcher.highlight ([model,searcher](path) {
+                let documentation = model.hierarchy_documentation(path);
+                searcher.set_documentation(documentation.unwrap_or_default());
             });
         }
 
@@ -386,7 +426,9 @@ impl Integration {
         let searcher_opened           = Self::ui_action(&model,searcher_opened_in_ui              ,inv);
         let node_editing              = Self::ui_action(&model,node_editing_in_ui                 ,inv);
         let node_expression_set       = Self::ui_action(&model,Model::node_expression_set_in_ui   ,inv);
+        let node_argument_value_set   = Self::ui_action(&model,Model::node_argument_value_set_in_ui,inv);
         let used_as_suggestion        = Self::ui_action(&model,Model::used_as_suggestion_in_ui    ,inv);
+        let hierarchy_node_picked     = Self::ui_action(&model,Model::hierarchy_node_picked_in_ui ,inv);
         let node_editing_committed    = Self::ui_action(&model,Model::node_editing_committed_in_ui,inv);
         let node_editing_aborted      = Self::ui_action(&model,Model::node_editing_aborted_in_ui  ,inv);
         let visualization_path_changed = Self::ui_action(&model,Model::visualization_path_changed_in_ui  ,inv);
@@ -423,7 +465,9 @@ impl Integration {
             _action <- editor_outs.node_being_edited        .map2(&is_hold,node_editing);
             _action <- project_frp.searcher_opened          .map2(&is_hold,searcher_opened);
             _action <- editor_outs.node_expression_set      .map2(&is_hold,node_expression_set);
+            _action <- editor_outs.node_argument_value_set  .map2(&is_hold,node_argument_value_set);
             _action <- searcher_frp.used_as_suggestion      .map2(&is_hold,used_as_suggestion);
+            _action <- searcher_frp.new_view_entry_picked   .map2(&is_hold,hierarchy_node_picked);
             _action <- project_frp.editing_committed        .map2(&is_hold,node_editing_committed);
             _action <- project_frp.editing_aborted          .map2(&is_hold,node_editing_aborted);
             _action <- editor_outs.enabled_visualization_path.map2(&is_hold,visualization_path_changed);
@@ -444,6 +488,13 @@ impl Integration {
             eval_ project_frp.save_module       (model.module_saved_in_ui());
             eval_ project_frp.undo              (model.undo_in_ui());
             eval_ project_frp.redo              (model.redo_in_ui());
+            eval_ project_frp.edit_requested    (model.edit_requested_in_ui());
+            eval_ project_frp.edit_released     (model.edit_released_in_ui());
+            eval_ project_frp.go_to_definition  (model.go_to_definition_in_ui());
+            eval_ project_frp.reset_suggestion_usage  (model.suggestion_usage_reset_in_ui());
+            eval_ project_frp.toggle_suggestion_usage (model.suggestion_usage_toggled_in_ui());
+            eval_ project_frp.toggle_import_optimization
+                (model.import_optimization_toggled_in_ui());
         }
 
         frp::extend! { network
@@ -467,6 +518,7 @@ impl Integration {
         ret.connect_frp_to_graph_controller_notifications(handle_graph_notification.trigger);
         ret.connect_frp_text_controller_notifications(handle_text_notification.trigger);
         ret.setup_handling_project_notifications();
+        ret.setup_handling_undo_history_changes();
         ret.show_initial_visualizations();
         ret
     }
@@ -485,17 +537,27 @@ impl Integration {
         let stream     = self.model.project.subscribe();
         let logger     = self.model.logger.clone_ref();
         let status_bar = self.model.view.status_bar().clone_ref();
-        self.spawn_sync_stream_handler(stream, move |notification,_| {
+        self.spawn_sync_stream_handler(stream, move |notification,model| {
             info!(logger,"Processing notification {notification:?}");
-            let message = match notification {
-                model::project::Notification::ConnectionLost(_) =>
-                    crate::BACKEND_DISCONNECTED_MESSAGE,
-            };
-            let message = ide_view::status_bar::event::Label::from(message);
-            status_bar.add_event(message);
+            match notification {
+                model::project::Notification::ConnectionLost(_) => {
+                    let message = crate::BACKEND_DISCONNECTED_MESSAGE;
+                    let message = ide_view::status_bar::event::Label::from(message);
+                    status_bar.add_event(message);
+                }
+                model::project::Notification::FileEvent(event) => model.file_changed(&event),
+                model::project::Notification::ExternalModuleChangeRejected {reason,..} => {
+                    status_bar.add_event(ide_view::status_bar::event::Label::new(reason));
+                }
+            }
         })
     }
 
+    fn setup_handling_undo_history_changes(&self) {
+        let stream = self.model.project.urm().repository.subscribe();
+        self.spawn_sync_stream_handler(stream, move |_,model| model.reload_undo_history())
+    }
+
     fn show_initial_visualizations(&self) {
         let logger     = self.model.logger.clone_ref();
         info!(logger,"Attaching initially opened visualization");
@@ -580,16 +642,25 @@ impl Model {
         let searcher                = default();
         let prompt_was_shown        = default();
         let displayed_project_list  = default();
+        let displayed_undo_history  = default();
+        let displayed_symbols       = default();
+        let access_mode_tracking    = default();
+        let symbol_index            = controller::SymbolIndex::new(&logger,project.clone_ref());
         let this                    = Model
-            {logger,view,graph,text,ide,searcher,project,main_module,node_views
+            {logger,view,graph,text,ide,searcher,project,main_module,symbol_index,node_views
             ,node_view_by_expression,expression_views,expression_types,connection_views,code_view
-            ,visualizations,error_visualizations,prompt_was_shown,displayed_project_list};
+            ,visualizations,error_visualizations,prompt_was_shown,displayed_project_list
+            ,displayed_undo_history,displayed_symbols,access_mode_tracking};
 
         this.view.graph().frp.remove_all_nodes();
         this.view.status_bar().clear_all();
         this.init_project_name();
         this.init_crumbs();
+        this.init_suggestion_usage();
         this.load_visualizations();
+        this.load_theme_files();
+        this.build_symbol_index();
+        this.track_module_access_mode();
         if let Err(err) = this.refresh_graph_view() {
             error!(this.logger,"Error while initializing graph editor: {err}.");
         }
@@ -599,6 +670,27 @@ impl Model {
         this
     }
 
+    /// Restore the statistics of picked suggestions stored in project's metadata.
+    fn init_suggestion_usage(&self) {
+        let get_usage  = |metadata:&ProjectMetadata| metadata.suggestion_usage.clone();
+        let statistics = self.main_module.with_project_metadata(get_usage);
+        self.project.suggestion_db().set_usage_statistics(statistics);
+    }
+
+    /// Store the current statistics of picked suggestions in project's metadata.
+    fn store_suggestion_usage(&self) -> FallibleResult {
+        let statistics = self.project.suggestion_db().usage_statistics();
+        self.main_module.update_project_metadata(|metadata| {
+            metadata.suggestion_usage = statistics;
+        })
+    }
+
+    fn store_suggestion_usage_or_log(&self) {
+        if let Err(err) = self.store_suggestion_usage() {
+            error!(self.logger, "Failed to store suggestion usage statistics: {err}");
+        }
+    }
+
     fn load_visualizations(&self) {
         let logger       = self.logger.clone_ref();
         let controller   = self.project.visualization().clone_ref();
@@ -619,6 +711,114 @@ impl Model {
             info!(logger, "Visualizations Initialized.");
         });
     }
+
+    fn build_symbol_index(&self) {
+        let logger       = self.logger.clone_ref();
+        let symbol_index = self.symbol_index.clone_ref();
+        executor::global::spawn(async move {
+            match symbol_index.rebuild().await {
+                Ok(())   => info!(logger, "Symbol Index built."),
+                Err(err) => error!(logger, "Error while building the symbol index: {err}"),
+            }
+        });
+    }
+
+    /// Display the access mode of the module of the currently displayed graph and follow its
+    /// changes. Should be called again whenever the graph is switched to another module.
+    fn track_module_access_mode(&self) {
+        let module         = self.graph.graph().module;
+        let read_only      = |mode| mode == model::module::AccessMode::ReadOnly;
+        let (stop,stopped) = futures::channel::oneshot::channel::<()>();
+        self.access_mode_tracking.replace(Some(stop));
+        self.view.frp.set_read_only(read_only(module.access_mode()));
+        let view    = self.view.clone_ref();
+        let changes = module.subscribe_access_mode().take_until(stopped);
+        executor::global::spawn(changes.for_each(move |mode| {
+            view.frp.set_read_only(read_only(mode));
+            futures::future::ready(())
+        }));
+    }
+}
+
+
+// === Theme Files ===
+
+impl Model {
+    /// The path of the given theme file layer, if its content root is available.
+    fn theme_file_path(&self, layer:ThemeLayer) -> Option<language_server::Path> {
+        match layer {
+            ThemeLayer::Project => {
+                let root_id = self.project.project_content_root_id();
+                Some(language_server::Path::new(root_id,&[crate::constants::THEME_FILE]))
+            }
+            ThemeLayer::User => {
+                let home = self.project.content_roots().into_iter().find_map(|root| match *root {
+                    language_server::ContentRoot::Home {id} => Some(id),
+                    _                                       => None,
+                });
+                home.map(|id| language_server::Path::new(id,crate::constants::USER_THEME_FILE))
+            }
+        }
+    }
+
+    /// Load the theme files of all layers, and start watching their directories, so the themes
+    /// are reloaded whenever the files change.
+    fn load_theme_files(&self) {
+        for layer in ThemeLayer::ALL.iter().copied() {
+            if let Some(path) = self.theme_file_path(layer) {
+                self.watch_theme_file(layer,&path);
+                self.reload_theme_file(layer);
+            }
+        }
+    }
+
+    fn watch_theme_file(&self, layer:ThemeLayer, path:&language_server::Path) {
+        let logger     = self.logger.clone_ref();
+        let json_rpc   = self.project.json_rpc();
+        let directory  = path.parent().unwrap_or_else(|| path.clone());
+        let capability = CapabilityRegistration::create_receives_tree_updates(directory);
+        let method     = capability.method;
+        let options    = capability.register_options;
+        executor::global::spawn(async move {
+            if let Err(err) = json_rpc.acquire_capability(&method,&options).await {
+                warning!(logger,"Cannot watch the directory of the {layer:?} theme file, the \
+                    theme will not be reloaded on changes: {err}");
+            }
+        });
+    }
+
+    fn reload_theme_file(&self, layer:ThemeLayer) {
+        let logger   = self.logger.clone_ref();
+        let json_rpc = self.project.json_rpc();
+        let path     = self.theme_file_path(layer);
+        let view     = self.view.clone_ref();
+        executor::global::spawn(async move {
+            let path     = if let Some(path) = path { path } else { return };
+            let exists   = json_rpc.file_exists(&path).await.map(|response| response.exists);
+            let contents = match exists {
+                Ok(true)  => json_rpc.read_file(&path).await.map(|file| Some(file.contents)),
+                Ok(false) => Ok(None),
+                Err(err)  => Err(err),
+            };
+            match contents {
+                Ok(Some(code)) => view.set_theme_file((layer,code)),
+                Ok(None)       => view.reset_theme_file(layer),
+                Err(err)       => error!(logger,"Cannot read the {layer:?} theme file: {err}"),
+            }
+        });
+    }
+
+    fn file_changed(&self, event:&language_server::FileEvent) {
+        let is_theme_file = |layer:&ThemeLayer| {
+            self.theme_file_path(*layer).contains(&event.path)
+        };
+        if let Some(layer) = ThemeLayer::ALL.iter().copied().find(is_theme_file) {
+            match event.kind {
+                language_server::FileEventKind::Removed => self.view.reset_theme_file(layer),
+                _                                       => self.reload_theme_file(layer),
+            }
+        }
+    }
 }
 
 
@@ -885,6 +1085,7 @@ impl Model {
             pattern             : node.info.pattern().map(|t|t.repr()),
             code                : node.info.expression().repr(),
             whole_expression_id : node.info.expression().id ,
+            argument_choices    : self.argument_choices(&trees.inputs),
             input_span_tree     : trees.inputs,
             output_span_tree    : trees.outputs.unwrap_or_else(default)
         };
@@ -910,6 +1111,21 @@ impl Model {
         }
     }
 
+    /// The possible values of the node's arguments, displayed in the drop-down widgets on the input
+    /// ports. These are the atoms of the argument's type, given as code accessing the atom on its
+    /// module, like `Ordering.Less`. The widgets compare them with the arguments' code, so it must
+    /// be the same form the widgets insert.
+    fn argument_choices
+    (&self, input_span_tree:&span_tree::SpanTree) -> HashMap<span_tree::Crumbs,Vec<String>> {
+        let suggestion_db = self.project.suggestion_db();
+        input_span_tree.root_ref().leaf_iter().filter_map(|port| {
+            let atoms  = suggestion_db.lookup_atoms_of_type(port.node.tp()?);
+            let values = atoms.iter().map(|atom| format!("{}.{}",atom.module.name(),atom.name));
+            let values = values.collect_vec();
+            (!values.is_empty()).as_some((port.crumbs.clone(),values))
+        }).collect()
+    }
+
     /// Like `refresh_computed_info` but for multiple expressions.
     fn refresh_computed_infos(&self, expressions_to_refresh:&[ExpressionId]) -> FallibleResult {
         debug!(self.logger, "Refreshing type information for IDs: {expressions_to_refresh:?}.");
@@ -1062,43 +1278,32 @@ impl Model {
 // === Updating Searcher View ===
 
 impl Model {
-    pub fn get_category_content
-    (&self, crumbs:&[usize]) -> Vec<(usize,ide_view::searcher::new::Entry)> {
-        let maybe_actions = self.searcher.borrow().as_ref().map(controller::Searcher::actions);
-        let is_filtering  = self.searcher.borrow().contains_if(controller::Searcher::is_filtering);
-        let maybe_list    = maybe_actions.as_ref().and_then(|actions| actions.list());
-        if let Some(list)  = maybe_list {
-            match crumbs.len() {
-                0 => {
-                    // We skip the first "All Search Result" category if we're not currently
-                    // filtering
-                    let skipped_categories = if is_filtering {0} else {1};
-                    list.root_categories().skip(skipped_categories).map(|(id,cat)|
-                        (id,ide_view::searcher::new::Entry {
-                            label     : cat.name.to_string().into(),
-                            is_folder : Immutable(true),
-                            icon      : Icon(cat.icon.clone_ref()),
-                        })
-                    ).collect()
-                },
-                1 => list.subcategories_of(*crumbs.last().unwrap()).map(|(id,cat)|
-                    (id,ide_view::searcher::new::Entry {
-                        label     : cat.name.to_string().into(),
-                        is_folder : Immutable(true),
-                        icon      : Icon(cat.icon.clone_ref()),
-                    })
-                ).collect(),
-                2 => list.actions_of(*crumbs.last().unwrap()).map(|(id,action)|
-                    (id,ide_view::searcher::new::Entry {
-                        label     : action.action.to_string().into(),
-                        is_folder : Immutable(false),
-                        icon      : Icon(action.action.icon())
-                    })
-                ).collect(),
-                _ => vec![],
-            }
-        } else {
-            vec![]
+    /// The content of the suggestion hierarchy node at the given path, to be displayed in the
+    /// new Searcher view. The empty path denotes the libraries level.
+    fn hierarchy_directory_content(&self, path:&[usize]) -> DirectoryContent<usize> {
+        let hierarchy = self.searcher.borrow().as_ref().map(controller::Searcher::hierarchy);
+        let content   = hierarchy.map_or_else(default, |hierarchy| {
+            let nodes = hierarchy.children_nodes(path.last().copied());
+            nodes.map(|(id,node)| (id,Self::hierarchy_entry(node))).collect()
+        });
+        Rc::new(content)
+    }
+
+    /// The documentation of the suggestion hierarchy node at the end of the given path.
+    fn hierarchy_documentation(&self, path:&[usize]) -> Option<String> {
+        let hierarchy = self.searcher.borrow().as_ref().map(controller::Searcher::hierarchy)?;
+        let node      = hierarchy.get(*path.last()?)?;
+        node.documentation_html.clone()
+    }
+
+    fn hierarchy_entry(node:&hierarchy::Node) -> ide_view::searcher::new::Entry {
+        let icon = hardcoded::ICONS.with(|icons| {
+            if node.is_leaf() { icons.default.clone_ref() } else { icons.libraries.clone_ref() }
+        });
+        ide_view::searcher::new::Entry {
+            label     : node.name.clone_ref(),
+            is_folder : Immutable(!node.is_leaf()),
+            icon      : Icon(icon),
         }
     }
 }
@@ -1134,6 +1339,7 @@ impl Model {
         self.view.graph().frp.deselect_all_nodes.emit(&());
         self.push_crumb(local_call);
         self.request_detaching_all_visualizations();
+        self.track_module_access_mode();
         self.refresh_graph_view()
     }
 
@@ -1142,6 +1348,7 @@ impl Model {
         analytics::remote_log_event("integration::node_exited");
         self.view.graph().frp.deselect_all_nodes.emit(&());
         self.request_detaching_all_visualizations();
+        self.track_module_access_mode();
         self.refresh_graph_view()?;
         self.pop_crumb();
         let id = self.get_displayed_node_id(id)?;
@@ -1233,9 +1440,6 @@ impl Model {
                                 { actions,user_action,intended_function};
                             self.view.searcher().set_actions(Rc::new(provider));
 
-                            // the Searcher 2.0
-                            self.view.searcher().new_frp().reset();
-
                             // Usually we want to select first entry and display docs for it
                             // But not when user finished typing function or argument.
                             let starting_typing = user_action == UserAction::StartingTypingArgument;
@@ -1249,7 +1453,16 @@ impl Model {
                         },
                     };
                 }
-            })
+            }),
+            // The node ids of the rebuilt hierarchy differ from the displayed ones, so the column
+            // view is reset and lists the new top-level directory.
+            Notification::NewHierarchy => with(self.searcher.borrow(), |searcher| {
+                if let Some(searcher) = &*searcher {
+                    let view = self.view.searcher().new_frp();
+                    view.reset();
+                    view.set_filter(ImString::new(searcher.pattern()));
+                }
+            }),
         }
     }
 }
@@ -1267,8 +1480,14 @@ impl Model {
         debug!(self.logger, "Removing node.");
         let id = self.get_controller_node_id(*node)?;
         self.node_views.borrow_mut().remove_by_left(&id);
-        self.graph.graph().remove_node(id)?;
-        Ok(())
+        let optimize_imports = self.main_module.with_project_metadata(|metadata| {
+            metadata.optimize_imports_on_node_removal
+        });
+        if optimize_imports {
+            self.graph.graph().remove_node_and_unused_imports(id)
+        } else {
+            self.graph.graph().remove_node(id)
+        }
     }
 
     fn node_selected_in_ui
@@ -1320,10 +1539,20 @@ impl Model {
         self.expression_views.borrow_mut().insert(*displayed_id,code_and_trees);
         if let Some(searcher) = searcher.as_ref() {
             searcher.set_input(expression.clone())?;
+            self.view.searcher().new_frp().set_filter(ImString::new(searcher.pattern()));
         }
         Ok(())
     }
 
+    fn node_argument_value_set_in_ui
+    (&self, (displayed_id,crumbs,value):&(graph_editor::NodeId,span_tree::Crumbs,String))
+    -> FallibleResult {
+        debug!(self.logger, "Setting argument {crumbs:?} of node {displayed_id} to {value}.");
+        let node = self.get_controller_node_id(*displayed_id)?;
+        let port = controller::graph::Endpoint::new(node,crumbs.clone());
+        self.graph.set_argument_value(&port,value)
+    }
+
     fn searcher_opened_in_ui(weak_self:Weak<Self>)
     -> impl Fn(&Self,&graph_editor::NodeId) -> FallibleResult {
         move |this,displayed_id| {
@@ -1331,7 +1560,15 @@ impl Model {
             let position  = node_view.map(|node| node.position().xy());
             let position  = position.map(|vector| model::module::Position{vector});
             let mode      = controller::searcher::Mode::NewNode {position};
-            this.setup_searcher_controller(&weak_self,mode)
+            let graph     = &this.view.graph().model;
+            let source    = graph.sources_of_edges_with_detached_targets().into_iter().next();
+            let target    = graph.targets_of_edges_with_detached_sources().into_iter().next();
+            let source    = source.and_then(|s| this.get_controller_node_id(s.node_id).ok());
+            let target    = target.and_then(|t| {
+                let node = this.get_controller_node_id(t.node_id).ok()?;
+                Some(controller::graph::Endpoint::new(node,t.port))
+            });
+            this.setup_searcher_controller(&weak_self,mode,source,target)
         }
     }
 
@@ -1355,7 +1592,7 @@ impl Model {
                     },
                     Err(other) => return Err(other.into()),
                 };
-                this.setup_searcher_controller(&weak_self,mode)?;
+                this.setup_searcher_controller(&weak_self,mode,None,None)?;
             } else {
                 debug!(this.logger, "Finishing node editing.");
             }
@@ -1379,6 +1616,19 @@ impl Model {
         Ok(())
     }
 
+    fn hierarchy_node_picked_in_ui(&self, id:&hierarchy::NodeId) -> FallibleResult {
+        debug!(self.logger, "Using suggestion hierarchy node {id} as suggestion.");
+        let graph_frp      = &self.view.graph().frp;
+        let error          = || MissingSearcherController;
+        let searcher       = self.searcher.borrow().clone().ok_or_else(error)?;
+        let error          = || GraphEditorInconsistency;
+        let edited_node    = graph_frp.output.node_being_edited.value().ok_or_else(error)?;
+        let code           = searcher.use_hierarchy_node_as_suggestion(*id)?;
+        let code_and_trees = node::Expression::new_plain(code);
+        graph_frp.input.set_node_expression.emit(&(edited_node,code_and_trees));
+        Ok(())
+    }
+
     fn node_editing_committed_in_ui
     (&self, (displayed_id,entry_id):&(graph_editor::NodeId,Option<ide_view::searcher::entry::Id>))
     -> FallibleResult {
@@ -1393,6 +1643,9 @@ impl Model {
         } else {
             searcher.commit_node().map(Some)
         };
+        if result.is_ok() {
+            self.store_suggestion_usage_or_log();
+        }
         match result {
             Ok(Some(node_id)) => {
                 // Node editor wants to decide whether node is selected after adding/updating.
@@ -1564,6 +1817,181 @@ impl Model {
         });
     }
 
+    fn edit_requested_in_ui(&self) {
+        debug!(self.logger, "Editing of the module requested in UI.");
+        let logger     = self.logger.clone_ref();
+        let status_bar = self.view.status_bar().clone_ref();
+        let request    = self.graph.graph().module.request_write_access();
+        executor::global::spawn(async move {
+            if let Err(err) = request.await {
+                error!(logger, "Failed to acquire the write lock of the module: {err}");
+                let message = format!("Cannot edit the module: {}", err);
+                status_bar.add_event(ide_view::status_bar::event::Label::new(message));
+            }
+        });
+    }
+
+    fn edit_released_in_ui(&self) {
+        debug!(self.logger, "Releasing the module's write lock requested in UI.");
+        let logger     = self.logger.clone_ref();
+        let status_bar = self.view.status_bar().clone_ref();
+        let request    = self.graph.graph().module.release_write_access();
+        executor::global::spawn(async move {
+            if let Err(err) = request.await {
+                error!(logger, "Failed to release the write lock of the module: {err}");
+                let message = format!("Cannot release the module: {}", err);
+                status_bar.add_event(ide_view::status_bar::event::Label::new(message));
+            }
+        });
+    }
+
+    fn go_to_definition_in_ui(&self) {
+        debug!(self.logger, "Going to definition requested in UI.");
+        let in_code = self.view.code_editor().is_visible.value();
+        let symbol  = self.pointed_symbol();
+        match symbol {
+            Some(symbol) => {
+                let span = symbol.definition.as_ref().map(|definition| definition.span);
+                if !self.show_in_code_editor(&symbol.id.module,span) {
+                    // The definition is not in the displayed module, but the method called in the
+                    // selected node may still be entered.
+                    let node       = (!in_code).and_option_from(|| self.selected_node());
+                    let definition = symbol.method_pointer();
+                    match node.zip(definition) {
+                        Some((call,definition)) => {
+                            let local_call = LocalCall{call,definition};
+                            if let Err(err) = self.expression_entered_in_ui(&Some(local_call)) {
+                                error!(self.logger, "Going to definition failed: {err}");
+                            }
+                        }
+                        None => self.show_symbol_not_displayed(&symbol.id.to_string()),
+                    }
+                }
+            }
+            None => self.show_status_message(NO_DEFINITION_MESSAGE),
+        }
+    }
+
+    fn find_references_in_ui(&self) {
+        debug!(self.logger, "Finding references requested in UI.");
+        match self.pointed_symbol() {
+            Some(symbol) => {
+                let references = self.symbol_index.references_to(&symbol.id);
+                let caption    = format!("References to {} ({})",symbol.id,references.len());
+                let displayed  = self.displayed_module_name();
+                let code       = self.code_view.get();
+                let entries    = SymbolPanelEntries::from_references(references,&displayed,&code);
+                self.set_symbol_panel_entries(entries);
+                self.view.show_references(caption);
+            }
+            None => self.show_status_message(NO_DEFINITION_MESSAGE),
+        }
+    }
+
+    fn symbol_query_changed_in_ui(&self, query:&str) {
+        debug!(self.logger, "Searching for symbols matching \"{query}\".");
+        let symbols = self.symbol_index.search(query);
+        self.set_symbol_panel_entries(SymbolPanelEntries::from_symbols(symbols));
+    }
+
+    fn symbol_entry_chosen_in_ui(&self, entry_id:list_view::entry::Id) {
+        if let Some(entry) = self.displayed_symbols.get().get(entry_id) {
+            debug!(self.logger, "Going to the symbol panel entry {entry:?}.");
+            if !self.show_in_code_editor(&entry.module,entry.span) {
+                self.show_symbol_not_displayed(&entry.label);
+            }
+        }
+    }
+
+    fn set_symbol_panel_entries(&self, entries:SymbolPanelEntries) {
+        self.displayed_symbols.set(entries.clone_ref());
+        self.view.symbol_panel().set_entries(AnyModelProvider::new(entries));
+    }
+
+    /// The symbol the user points at: the one under the Code Editor cursor if the editor is
+    /// shown, or the method called in the selected node otherwise.
+    fn pointed_symbol(&self) -> Option<Symbol> {
+        if self.view.code_editor().is_visible.value() {
+            let location = self.view.code_editor().text_area().newest_cursor_location()?;
+            let offset   = self.code_view.get().byte_offset_of_location_snapped(location);
+            let index    = data::text::Index::new(offset.value.max(0) as usize);
+            self.symbol_index.definition_at(&self.graph.graph().module,index)
+        } else {
+            let node = self.selected_node()?;
+            self.symbol_index.definition_of_node(&self.graph,node)
+        }
+    }
+
+    fn selected_node(&self) -> Option<double_representation::node::Id> {
+        let selected = self.view.graph().model.nodes.all_selected();
+        selected.iter().find_map(|id| self.get_controller_node_id(*id).ok())
+    }
+
+    /// Show the Code Editor with the cursor at the span's beginning. Returns `false` if the span
+    /// is not known or is not in the displayed module, in which case nothing is done.
+    fn show_in_code_editor
+    (&self, module:&model::module::QualifiedName, span:Option<data::text::Span>) -> bool {
+        let displayed = self.displayed_module_name();
+        let span      = span.filter(|_| module == &displayed);
+        if let Some(span) = span {
+            let offset   = ensogl_text::Bytes(span.index.value as i32);
+            let location = self.code_view.get().location_of_byte_offset_snapped(offset);
+            self.view.code_editor().show();
+            self.view.code_editor().text_area().set_cursor(location);
+        }
+        span.is_some()
+    }
+
+    fn displayed_module_name(&self) -> model::module::QualifiedName {
+        self.project.qualified_module_name(self.graph.graph().module.path())
+    }
+
+    fn show_symbol_not_displayed(&self, symbol:&str) {
+        let message = format!("Cannot show {}: it is not in the displayed module.",symbol);
+        self.show_status_message(message);
+    }
+
+    fn show_status_message(&self, message:impl Into<String>) {
+        let message = ide_view::status_bar::event::Label::new(message.into());
+        self.view.status_bar().add_event(message);
+    }
+
+    fn suggestion_usage_reset_in_ui(&self) {
+        debug!(self.logger, "Resetting suggestion usage statistics requested in UI.");
+        self.project.suggestion_db().reset_usage_statistics();
+        self.store_suggestion_usage_or_log();
+        self.show_status_message("The statistics of picked suggestions were reset.");
+    }
+
+    fn suggestion_usage_toggled_in_ui(&self) {
+        let suggestion_db = self.project.suggestion_db();
+        let enabled       = !suggestion_db.is_usage_statistics_enabled();
+        debug!(self.logger, "Setting suggestion usage statistics enabled to {enabled} in UI.");
+        suggestion_db.set_usage_statistics_enabled(enabled);
+        self.store_suggestion_usage_or_log();
+        let message = if enabled { "Suggestions are ranked by the statistics of their usage." }
+                      else       { "The statistics of picked suggestions are disabled." };
+        self.show_status_message(message);
+    }
+
+    fn import_optimization_toggled_in_ui(&self) {
+        let result = self.main_module.update_project_metadata(|metadata| {
+            let enabled = !metadata.optimize_imports_on_node_removal;
+            metadata.optimize_imports_on_node_removal = enabled;
+        });
+        if let Err(err) = result {
+            error!(self.logger, "Failed to store the import optimization setting: {err}");
+            return
+        }
+        let enabled = self.main_module.with_project_metadata(|metadata| {
+            metadata.optimize_imports_on_node_removal
+        });
+        debug!(self.logger, "Set import optimization on node removal to {enabled} in UI.");
+        let message = if enabled { "The unused imports are removed together with the nodes." }
+                      else       { "The imports are kept when removing the nodes." };
+        self.show_status_message(message);
+    }
+
     fn undo_in_ui(&self) {
         debug!(self.logger, "Undo triggered in UI.");
         if let Err(e) = self.project.urm().undo() {
@@ -1578,6 +2006,33 @@ impl Model {
         }
     }
 
+    fn undo_history_opened_in_ui(&self) {
+        debug!(self.logger, "Undo history opened in UI.");
+        self.reload_undo_history();
+    }
+
+    fn undo_history_entry_chosen_in_ui(&self, entry_id:list_view::entry::Id) {
+        let target = self.displayed_undo_history.get().target_by_index(entry_id);
+        if let Some(target) = target {
+            debug!(self.logger, "Restoring the undo history entry {target:?}.");
+            let urm    = self.project.urm();
+            let result = match target {
+                HistoryTarget::Position(position) => urm.jump_to(position),
+                HistoryTarget::Branch(index)      => urm.switch_branch(index),
+            };
+            if let Err(e) = result {
+                error!(self.logger, "Restoring the undo history entry failed: {e}");
+            }
+        }
+    }
+
+    fn reload_undo_history(&self) {
+        let now     = ensogl_system_web::time_from_start();
+        let entries = UndoHistoryEntries::new(&self.project.urm().history(),now);
+        self.displayed_undo_history.set(entries.clone_ref());
+        self.view.undo_history().set_entries(AnyModelProvider::new(entries));
+    }
+
     fn resolve_visualization_context
     (&self, context:&visualization::instance::ContextModule)
     -> FallibleResult<model::module::QualifiedName> {
@@ -1879,15 +2334,26 @@ impl Model {
         Ok(())
     }
 
+    /// Create a new Searcher controller.
+    ///
+    /// The `source_node` is the node whose output edge is being dragged, it will be used as the
+    /// "this" argument instead of the selected nodes. The `target_port` is the input port of the
+    /// edge being dragged, the new node is expected to feed it.
     fn setup_searcher_controller
-    (&self, weak_self:&Weak<Self>, mode:controller::searcher::Mode) -> FallibleResult {
+    ( &self
+    , weak_self   : &Weak<Self>
+    , mode        : controller::searcher::Mode
+    , source_node : Option<ast::Id>
+    , target_port : Option<controller::graph::Endpoint>
+    ) -> FallibleResult {
         let selected_nodes = self.view.graph().model.nodes.all_selected().iter().filter_map(|id| {
             self.get_controller_node_id(*id).ok()
-        }).collect_vec();
+        });
+        let this_nodes = source_node.into_iter().chain(selected_nodes).collect_vec();
         let controller = self.graph.clone_ref();
         let ide        = self.ide.clone_ref();
         let searcher   = controller::Searcher::new_from_graph_controller
-            (&self.logger,ide,&self.project,controller,mode,selected_nodes)?;
+            (&self.logger,ide,&self.project,controller,mode,this_nodes,target_port)?;
         executor::global::spawn(searcher.subscribe().for_each(f!([weak_self](notification) {
             if let Some(this) = weak_self.upgrade() {
                 this.handle_searcher_notification(notification);
@@ -1948,6 +2414,9 @@ impl SuggestionsProviderForView {
             Suggestion::Hardcoded(suggestion) => {
                 format!("{}\n\nNo documentation available", suggestion.name)
             }
+            Suggestion::DefaultArgument(argument) => {
+                format!("The default value of `{}` argument", argument.parameter)
+            }
         };
         let parser = parser::DocParser::new();
         match parser {
@@ -2042,11 +2511,162 @@ impl ProjectsToOpen {
     }
 }
 
-impl list_view::entry::ModelProvider<open_dialog::project_list::Entry> for ProjectsToOpen {
+impl list_view::entry::ModelProvider<list_panel::Entry> for ProjectsToOpen {
     fn entry_count(&self) -> usize { self.projects.len() }
 
     fn get(&self, id:list_view::entry::Id)
-    -> Option<<open_dialog::project_list::Entry as list_view::Entry> ::Model> {
+    -> Option<<list_panel::Entry as list_view::Entry> ::Model> {
         Some(<[controller::ide::ProjectMetadata]>::get(&self.projects,id)?.name.clone().into())
     }
 }
+
+
+
+// =============================
+// === Undo History Provider ===
+// =============================
+
+/// The action performed when an entry of the Undo History panel is chosen.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+enum HistoryTarget {
+    /// Jump to the given position of the current history line.
+    Position(usize),
+    /// Switch to the alternative future with the given index.
+    Branch(usize),
+}
+
+#[derive(Clone,Debug)]
+struct HistoryEntry {
+    label  : String,
+    target : HistoryTarget,
+}
+
+/// The entries of the Undo History panel: the current history line, the oldest first, followed by
+/// the alternative futures.
+#[derive(Clone,CloneRef,Debug,Default)]
+struct UndoHistoryEntries {
+    entries : Rc<Vec<HistoryEntry>>
+}
+
+impl UndoHistoryEntries {
+    fn new(history:&model::undo_redo::History, now:f64) -> Self {
+        let position = history.position();
+        let marker   = |index:usize| if index == position { "●" } else { " " };
+        let initial  = HistoryEntry {
+            label  : format!("{} Opened project",marker(0)),
+            target : HistoryTarget::Position(0),
+        };
+        let actions  = history.done.iter().chain(&history.undone).enumerate();
+        let actions  = actions.map(|(index,frame)| HistoryEntry {
+            label  : format!("{} {}",marker(index + 1),Self::describe(frame,now)),
+            target : HistoryTarget::Position(index + 1),
+        });
+        let branches = history.branches.iter().enumerate().filter_map(|(index,branch)| {
+            let next        = branch.frames.first()?;
+            let count       = branch.frames.len();
+            let fork        = branch.fork_depth;
+            let description = Self::describe(next,now);
+            Some(HistoryEntry {
+                label  : format!("⑂ {} [{} actions after step {}]",description,count,fork),
+                target : HistoryTarget::Branch(index),
+            })
+        });
+        let entries = std::iter::once(initial).chain(actions).chain(branches).collect();
+        Self {entries:Rc::new(entries)}
+    }
+
+    fn describe(frame:&model::undo_redo::FrameInfo, now:f64) -> String {
+        let modules = frame.modules.iter().map(|id| id.to_string()).join(", ");
+        let age     = Self::format_age(now - frame.timestamp);
+        if modules.is_empty() { format!("{} ({})",frame.name,age) }
+        else                  { format!("{} in {} ({})",frame.name,modules,age) }
+    }
+
+    fn format_age(milliseconds:f64) -> String {
+        let seconds = (milliseconds / 1000.0).max(0.0) as u64;
+        match seconds {
+            0..=4     => "just now".to_owned(),
+            5..=59    => format!("{} s ago",seconds),
+            60..=3599 => format!("{} min ago",seconds / 60),
+            _         => format!("{} h ago",seconds / 3600),
+        }
+    }
+
+    fn target_by_index(&self, index:usize) -> Option<HistoryTarget> {
+        self.entries.get(index).map(|entry| entry.target)
+    }
+}
+
+impl list_view::entry::ModelProvider<list_panel::Entry> for UndoHistoryEntries {
+    fn entry_count(&self) -> usize { self.entries.len() }
+
+    fn get(&self, id:list_view::entry::Id)
+    -> Option<<list_panel::Entry as list_view::Entry> ::Model> {
+        Some(self.entries.get(id)?.label.clone().into())
+    }
+}
+
+
+
+// ==============================
+// === Symbols Panel Provider ===
+// ==============================
+
+/// A place in the project code listed in the Symbols panel.
+#[derive(Clone,Debug)]
+struct SymbolEntry {
+    label  : String,
+    module : model::module::QualifiedName,
+    /// The place to put the code cursor at. It is `None` if the code of the symbol is not known.
+    span   : Option<data::text::Span>,
+}
+
+/// The entries of the Symbols panel: the symbols found by name or the references to a symbol.
+#[derive(Clone,CloneRef,Debug,Default)]
+struct SymbolPanelEntries {
+    entries : Rc<Vec<SymbolEntry>>
+}
+
+impl SymbolPanelEntries {
+    fn from_symbols(symbols:Vec<Symbol>) -> Self {
+        let entries = symbols.into_iter().map(|symbol| SymbolEntry {
+            label  : format!("{} ({:?})",symbol.id,symbol.kind),
+            module : symbol.id.module.clone(),
+            span   : symbol.definition.map(|definition| definition.span),
+        });
+        Self {entries:Rc::new(entries.collect())}
+    }
+
+    /// The references' line numbers are computed from the displayed module's code, so are given
+    /// only for the references in the displayed module.
+    fn from_references
+    ( references     : Vec<Reference>
+    , displayed      : &model::module::QualifiedName
+    , displayed_code : &ensogl_text::Text
+    ) -> Self {
+        let entries = references.into_iter().map(|reference| {
+            let offset = ensogl_text::Bytes(reference.span.index.value as i32);
+            let label  = if &reference.module == displayed {
+                let line = displayed_code.location_of_byte_offset_snapped(offset).line;
+                format!("{} at line {}",reference.name,line.as_usize() + 1)
+            } else {
+                format!("{} in {}",reference.name,reference.module)
+            };
+            SymbolEntry {label,module:reference.module,span:Some(reference.span)}
+        });
+        Self {entries:Rc::new(entries.collect())}
+    }
+
+    fn get(&self, index:usize) -> Option<SymbolEntry> {
+        self.entries.get(index).cloned()
+    }
+}
+
+impl list_view::entry::ModelProvider<list_panel::Entry> for SymbolPanelEntries {
+    fn entry_count(&self) -> usize { self.entries.len() }
+
+    fn get(&self, id:list_view::entry::Id)
+    -> Option<<list_panel::Entry as list_view::Entry> ::Model> {
+        Some(self.entries.get(id)?.label.clone().into())
+    }
+}
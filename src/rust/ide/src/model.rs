@@ -24,12 +24,14 @@ pub mod module;
 pub mod project;
 pub mod registry;
 pub mod suggestion_database;
+pub mod symbol_index;
 pub mod undo_redo;
 
 pub use execution_context::ExecutionContext;
 pub use module::Module;
 pub use project::Project;
 pub use suggestion_database::SuggestionDatabase;
+pub use symbol_index::SymbolIndex;
 
 /// A module with commonly used traits to mass import.
 pub mod traits {
@@ -59,6 +59,12 @@ pub enum ModulePathViolation {
     WrongFileExtension,
 }
 
+/// Failed attempt to edit a module which is in the read-only mode.
+#[derive(Clone,Debug,Fail)]
+#[fail(display="The module {} is read-only, because another client is editing it. Request \
+    editing to modify it.", _0)]
+pub struct ReadOnly(pub String);
+
 
 
 // ============
@@ -252,6 +258,26 @@ impl From<Path> for Id {
 
 
 
+// ==================
+// === AccessMode ===
+// ==================
+
+/// Describes whether the module may be edited.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub enum AccessMode {
+    /// The module can be read and edited.
+    ReadWrite,
+    /// The module can be only read, e.g. because another client holds its write lock. All the
+    /// attempts to edit it fail with [`ReadOnly`] error.
+    ReadOnly,
+}
+
+impl Default for AccessMode {
+    fn default() -> Self { Self::ReadWrite }
+}
+
+
+
 // ====================
 // === Notification ===
 // ====================
@@ -323,6 +349,13 @@ pub struct ProjectMetadata {
     /// The execution context of the displayed graph editor.
     #[serde(default,deserialize_with="utils::serde::deserialize_or_default")]
     pub call_stack : Vec<model::execution_context::LocalCall>,
+    /// The statistics of suggestions picked in Searcher, used for ranking suggestions.
+    #[serde(default,deserialize_with="utils::serde::deserialize_or_default")]
+    pub suggestion_usage : model::suggestion_database::usage::Statistics,
+    /// Whether the imports which are no longer used should be removed together with the removed
+    /// nodes. Toggled by the user with the `toggle_import_optimization` project view command.
+    #[serde(default,deserialize_with="utils::serde::deserialize_or_default")]
+    pub optimize_imports_on_node_removal : bool,
 }
 
 /// Metadata that belongs to ide.
@@ -537,6 +570,31 @@ pub trait API:Debug+model::undo_redo::Aware {
     (&self, fun:Box<dyn FnOnce(&mut ProjectMetadata) + '_>) -> FallibleResult;
 
 
+// === Access ===
+
+    /// Get the current access mode of the module.
+    fn access_mode(&self) -> AccessMode {
+        AccessMode::ReadWrite
+    }
+
+    /// Subscribe for notifications about access mode changes.
+    fn subscribe_access_mode(&self) -> StaticBoxStream<AccessMode> {
+        futures::stream::empty().boxed_local()
+    }
+
+    /// Request the permission to edit the module. Once granted, the module switches to the
+    /// [`AccessMode::ReadWrite`] mode.
+    fn request_write_access(&self) -> StaticBoxFuture<FallibleResult> {
+        futures::future::ready(Ok(())).boxed_local()
+    }
+
+    /// Give up the permission to edit the module, so other clients can acquire it. The module
+    /// switches to the [`AccessMode::ReadOnly`] mode.
+    fn release_write_access(&self) -> StaticBoxFuture<FallibleResult> {
+        futures::future::ready(Ok(())).boxed_local()
+    }
+
+
 // === Utils ===
 
     /// Get the module's identifier.
@@ -4,23 +4,86 @@ use crate::prelude::*;
 
 use crate::double_representation::definition::DefinitionInfo;
 use crate::double_representation::graph::Id;
+use crate::double_representation::text::apply_code_change_to_id_map;
 use crate::model::module::API;
+use crate::model::module::AccessMode;
 use crate::model::module::NotificationKind;
 use crate::model::module::Content;
 use crate::model::module::Notification;
 use crate::model::module::NodeMetadata;
 use crate::model::module::Path;
 use crate::model::module::ProjectMetadata;
+use crate::model::module::ReadOnly;
+use crate::notification;
 
 use ast::IdMap;
 use data::text::TextChange;
 use data::text::TextLocation;
 use enso_protocol::types::Sha3_224;
 use enso_protocol::language_server;
+use enso_protocol::language_server::CapabilityRegistration;
+use enso_protocol::language_server::FileEdit;
 use enso_protocol::language_server::TextEdit;
 use flo_stream::Subscriber;
+use futures::channel::mpsc;
+use futures::channel::oneshot;
 use parser::api::SourceFile;
 use parser::Parser;
+use std::collections::VecDeque;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The maximum number of Language Server content versions remembered in [`KnownVersions`].
+const KNOWN_VERSIONS_LIMIT : usize = 32;
+
+
+
+// ==============
+// === Errors ===
+// ==============
+
+/// An error indicating that a change made outside the IDE could not be merged with the local
+/// changes of the module. The local version is kept in such case.
+#[allow(missing_docs)]
+#[derive(Clone,Debug,Fail)]
+#[fail(display="The external changes of module {} conflict with the local ones and were \
+    discarded.", path)]
+pub struct Conflict { path:String }
+
+#[allow(missing_docs)]
+#[derive(Clone,Debug,Fail)]
+#[fail(display="The module {} is not synchronized with the Language Server.", path)]
+pub struct NotSynchronized { path:String }
+
+
+
+// ====================
+// === RemoteChange ===
+// ====================
+
+/// A change of the module made outside this IDE instance.
+#[derive(Clone,Debug)]
+pub enum RemoteChange {
+    /// The edits made by another Language Server client, as received in `text/didChange`.
+    Edits(FileEdit),
+    /// The module file was modified on disk. Contains the new content of the file.
+    FileModified(String),
+}
+
+/// The remote change with the channel for replying with the result of applying it.
+type RemoteChangeRequest = (RemoteChange,oneshot::Sender<FallibleResult>);
+
+/// An event handled by the task synchronizing the module with the Language Server.
+#[derive(Debug)]
+enum Event {
+    LocalChange(Notification),
+    RemoteChange(RemoteChangeRequest),
+    AccessModeChanged(AccessMode),
+}
 
 
 
@@ -106,6 +169,65 @@ impl LanguageServerContent {
 }
 
 
+// === KnownVersions ===
+
+/// The recent versions of the Language Server content, used to recognize the file modifications
+/// caused by saving them. Saves are done in order, so once a version is seen on disk, all the older
+/// ones are forgotten. At most [`KNOWN_VERSIONS_LIMIT`] versions which were not seen are kept.
+#[derive(Clone,Debug,Default)]
+struct KnownVersions {
+    versions : VecDeque<Sha3_224>,
+}
+
+impl KnownVersions {
+    /// Remember the new version, unless it is the newest one already.
+    fn insert(&mut self, digest:&Sha3_224) {
+        if self.versions.back() != Some(digest) {
+            self.versions.push_back(digest.clone());
+            if self.versions.len() > KNOWN_VERSIONS_LIMIT {
+                self.versions.pop_front();
+            }
+        }
+    }
+
+    /// Check whether the file content is one of the known versions. If so, the older versions are
+    /// forgotten.
+    fn acknowledge(&mut self, digest:&Sha3_224) -> bool {
+        let index = self.versions.iter().position(|version| version == digest);
+        if let Some(index) = index {
+            self.versions.drain(..index);
+        }
+        index.is_some()
+    }
+}
+
+
+
+// ===================
+// === AccessState ===
+// ===================
+
+/// The access mode of the module with the notifications about its changes. It depends on whether
+/// this client holds the write lock (the `text/canEdit` capability) of the module file.
+#[derive(Debug,Default)]
+struct AccessState {
+    mode          : Cell<AccessMode>,
+    notifications : notification::Publisher<AccessMode>,
+}
+
+impl AccessState {
+    fn new(mode:AccessMode) -> Self {
+        Self {mode:Cell::new(mode),notifications:default()}
+    }
+
+    fn set(&self, mode:AccessMode) {
+        if self.mode.replace(mode) != mode {
+            self.notifications.notify(mode);
+        }
+    }
+}
+
+
 
 // ===========================
 // === Synchronized Module ===
@@ -116,12 +238,19 @@ impl LanguageServerContent {
 /// This struct owns  `model::Module`, load the state during creation and updates LS about all
 /// changes done to it. On drop the module is closed in Language Server.
 ///
+/// The module can be edited only when this client holds the write lock of the module file.
+/// Otherwise it is in [`AccessMode::ReadOnly`] mode, and all edits done through the module API
+/// fail.
+///
 /// See also (enso protocol documentation)
 /// [https://github.com/enso-org/enso/blob/main/docs/language-server/protocol-language-server.md].
 #[derive(Debug)]
 pub struct Module {
     model           : model::module::Plain,
     language_server : Rc<language_server::Connection>,
+    parser          : Parser,
+    remote_changes  : mpsc::UnboundedSender<RemoteChangeRequest>,
+    access          : Rc<AccessState>,
     logger          : Logger,
 }
 
@@ -144,28 +273,86 @@ impl Module {
         info!(logger, "Opening module {file_path}");
         let opened = language_server.client.open_text_file(&file_path).await?;
         info!(logger, "Read content of the module {path}, digest is {opened.current_version:?}");
+        let access_mode = if opened.write_capability.is_some() {
+            AccessMode::ReadWrite
+        } else {
+            warning!(logger, "The module {path} is opened in read-only mode.");
+            AccessMode::ReadOnly
+        };
         let end_of_file = TextLocation::at_document_end(&opened.content);
+        let ls_source   = SourceFile::new(opened.content.clone());
         // TODO[ao] We should not fail here when metadata are malformed, but discard them and set
         //  default instead.
         let source  = parser.parse_with_metadata(opened.content)?;
         let digest  = opened.current_version;
         let summary = ContentSummary {digest,end_of_file};
         let model   = model::module::Plain::new(&logger,path,source.ast,source.metadata,repository);
-        let this    = Rc::new(Module {model,language_server,logger});
+        let access  = Rc::new(AccessState::new(access_mode));
+        let (remote_changes,remote_changes_receiver) = mpsc::unbounded();
+        let this    = Rc::new(Module {model,language_server,parser,remote_changes,access,logger});
         let content = this.model.serialized_content()?;
-        let first_invalidation = this.full_invalidation(&summary,content);
-        executor::global::spawn(Self::runner(this.clone_ref(),summary,first_invalidation));
+        // Without the write lock we cannot update the Language Server content, so we just take it
+        // as it is.
+        let first_invalidation = match access_mode {
+            AccessMode::ReadWrite => this.full_invalidation(&summary,content).boxed_local(),
+            AccessMode::ReadOnly  => {
+                let ls_content = ParsedContentSummary::from_source(&ls_source);
+                futures::future::ready(Ok(ls_content)).boxed_local()
+            }
+        };
+        let runner  = Self::runner(this.clone_ref(),summary,first_invalidation,
+            remote_changes_receiver);
+        executor::global::spawn(runner);
         Ok(this)
     }
 
     /// Create a module mock.
-    pub fn mock(model:model::module::Plain) -> Rc<Self> {
+    pub fn mock(model:model::module::Plain, parser:Parser) -> Rc<Self> {
         let logger = Logger::new(iformat!("Mocked Module {model.path()}"));
         let client = language_server::MockClient::default();
         client.expect.close_text_file(|_| Ok(()));
         // We don't expect any other call, because we don't execute `runner()`.
-        let language_server = language_server::Connection::new_mock_rc(client);
-        Rc::new(Module{model,language_server,logger})
+        let language_server  = language_server::Connection::new_mock_rc(client);
+        let (remote_changes,_) = mpsc::unbounded();
+        let access             = default();
+        Rc::new(Module{model,language_server,parser,remote_changes,access,logger})
+    }
+
+    /// Apply the change of the module made outside this IDE instance, e.g. by another Language
+    /// Server client or by a text editor modifying the module file.
+    ///
+    /// Fails with [`Conflict`] if there are local changes not yet sent to the Language Server. In
+    /// such case the local version of the module is kept.
+    pub fn apply_remote_change(&self, change:RemoteChange) -> impl Future<Output=FallibleResult> {
+        let (sender,receiver) = oneshot::channel();
+        let sent              = self.remote_changes.unbounded_send((change,sender)).is_ok();
+        let not_synchronized  = NotSynchronized {path:self.path().to_string()};
+        async move {
+            match receiver.await {
+                Ok(result) if sent => result,
+                _                  => Err(not_synchronized.into()),
+            }
+        }
+    }
+
+    /// Set the access mode, e.g. after the write lock was granted to or taken from this client.
+    pub fn set_access_mode(&self, mode:AccessMode) {
+        info!(self.logger, "Setting the access mode to {mode:?}.");
+        self.access.set(mode)
+    }
+
+    fn write_capability(&self) -> CapabilityRegistration {
+        CapabilityRegistration::create_can_edit_text_file(self.path().file_path().clone())
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.access.mode.get() == AccessMode::ReadOnly
+    }
+
+    /// Fail with [`ReadOnly`] error if the module cannot be edited.
+    fn check_writable(&self) -> FallibleResult {
+        if self.is_read_only() { Err(ReadOnly(self.path().to_string()).into()) }
+        else                   { Ok(()) }
     }
 }
 
@@ -195,28 +382,34 @@ impl API for Module {
     }
 
     fn update_whole(&self, content:Content) -> FallibleResult {
+        self.check_writable()?;
         self.model.update_whole(content)
     }
 
     fn update_ast(&self, ast: ast::known::Module) -> FallibleResult {
+        self.check_writable()?;
         self.model.update_ast(ast)
     }
 
     fn apply_code_change
     (&self, change:TextChange, parser:&Parser, new_id_map:IdMap) -> FallibleResult {
+        self.check_writable()?;
         self.model.apply_code_change(change,parser,new_id_map)
     }
 
     fn set_node_metadata(&self, id:ast::Id, data:NodeMetadata) -> FallibleResult {
+        self.check_writable()?;
         self.model.set_node_metadata(id,data)
     }
 
     fn remove_node_metadata(&self, id:ast::Id) -> FallibleResult<NodeMetadata> {
+        self.check_writable()?;
         self.model.remove_node_metadata(id)
     }
 
     fn with_node_metadata
     (&self, id:ast::Id, fun:Box<dyn FnOnce(&mut NodeMetadata) + '_>) -> FallibleResult {
+        self.check_writable()?;
         self.model.with_node_metadata(id,fun)
     }
 
@@ -226,8 +419,41 @@ impl API for Module {
 
     fn boxed_update_project_metadata
     (&self, fun:Box<dyn FnOnce(&mut ProjectMetadata) + '_>) -> FallibleResult {
+        self.check_writable()?;
         self.model.boxed_update_project_metadata(fun)
     }
+
+    fn access_mode(&self) -> AccessMode {
+        self.access.mode.get()
+    }
+
+    fn subscribe_access_mode(&self) -> StaticBoxStream<AccessMode> {
+        self.access.notifications.subscribe().boxed_local()
+    }
+
+    fn request_write_access(&self) -> StaticBoxFuture<FallibleResult> {
+        let registration    = self.write_capability();
+        let language_server = self.language_server.clone_ref();
+        let access          = self.access.clone_ref();
+        async move {
+            let method  = &registration.method;
+            let options = &registration.register_options;
+            language_server.acquire_capability(method,options).await?;
+            access.set(AccessMode::ReadWrite);
+            Ok(())
+        }.boxed_local()
+    }
+
+    fn release_write_access(&self) -> StaticBoxFuture<FallibleResult> {
+        let registration    = self.write_capability();
+        let language_server = self.language_server.clone_ref();
+        let access          = self.access.clone_ref();
+        async move {
+            language_server.release_capability(&registration).await?;
+            access.set(AccessMode::ReadOnly);
+            Ok(())
+        }.boxed_local()
+    }
 }
 
 
@@ -236,25 +462,54 @@ impl API for Module {
 impl Module {
     /// The asynchronous task scheduled during struct creation which listens for all module changes
     /// and send proper updates to Language Server.
+    ///
+    /// The task also applies the changes made outside the IDE, so they are handled in order with
+    /// the local ones.
     async fn runner
     ( self               : Rc<Self>
     , initial_ls_content : ContentSummary
-    , first_invalidation : impl Future<Output=FallibleResult<ParsedContentSummary>>) {
+    , first_invalidation : impl Future<Output=FallibleResult<ParsedContentSummary>>
+    , remote_changes     : mpsc::UnboundedReceiver<RemoteChangeRequest>) {
         let first_invalidation = first_invalidation.await;
         let mut ls_content     = self.new_ls_content_info(initial_ls_content,first_invalidation);
-        let mut subscriber     = self.model.subscribe();
+        let mut known_versions = KnownVersions::default();
+        let local_changes      = self.model.subscribe().map(Event::LocalChange);
+        let remote_changes     = remote_changes.map(Event::RemoteChange);
+        let access_changes     = self.access.notifications.subscribe();
+        let access_changes     = access_changes.map(Event::AccessModeChanged);
+        let changes            = futures::stream::select(local_changes,remote_changes);
+        let mut events         = futures::stream::select(changes,access_changes);
         let weak               = Rc::downgrade(&self);
         drop(self);
 
         loop {
-            let notification = subscriber.next().await;
-            let this = weak.upgrade();
-            match (notification,this) {
-                (Some(notification),Some(this)) => {
+            known_versions.insert(&ls_content.summary().digest);
+            let event = events.next().await;
+            let this  = weak.upgrade();
+            match (event,this) {
+                (Some(Event::LocalChange(notification)),Some(this)) => {
                     debug!(this.logger,"Processing a notification: {notification:?}");
                     let result = this.handle_notification(&ls_content,notification).await;
                     ls_content = this.new_ls_content_info(ls_content.summary().clone(),result)
                 }
+                (Some(Event::RemoteChange((change,reply))),Some(this)) => {
+                    debug!(this.logger,"Processing a remote change: {change:?}");
+                    let (new_content,result) = match change {
+                        RemoteChange::Edits(edit) =>
+                            this.handle_remote_edit(ls_content,edit).await,
+                        RemoteChange::FileModified(text) =>
+                            this.handle_file_modification(ls_content,text,&mut known_versions),
+                    };
+                    ls_content = new_content;
+                    // The requester is not obliged to wait for the result.
+                    let _ = reply.send(result);
+                }
+                (Some(Event::AccessModeChanged(AccessMode::ReadWrite)),Some(this)) => {
+                    debug!(this.logger,"Synchronizing the module after getting the write lock.");
+                    let result = this.resynchronize(&ls_content).await;
+                    ls_content = this.new_ls_content_info(ls_content.summary().clone(),result)
+                }
+                (Some(Event::AccessModeChanged(AccessMode::ReadOnly)),Some(_)) => {}
                 _ => break,
             }
         }
@@ -287,8 +542,18 @@ impl Module {
         let Notification{new_file,kind} = notification;
         debug!(self.logger,"Handling notification: {content:?}.");
         match content {
+            // Without the write lock the Language Server content cannot be changed. The module
+            // is synchronized with it once the lock is granted.
+            LanguageServerContent::Synchronized(summary) if self.is_read_only() =>
+                Ok(summary.clone()),
+            LanguageServerContent::Desynchronized(_) if self.is_read_only() =>
+                Err(ReadOnly(self.path().to_string()).into()),
             LanguageServerContent::Desynchronized(summary) =>
                 self.full_invalidation(summary,new_file).await,
+            // E.g. the module was just updated with the Language Server content after a remote
+            // change.
+            LanguageServerContent::Synchronized(summary) if summary.source == new_file.content =>
+                Ok(summary.clone()),
             LanguageServerContent::Synchronized(summary) => match kind {
                 NotificationKind::Invalidate =>
                     self.partial_invalidation(summary,new_file).await,
@@ -316,6 +581,16 @@ impl Module {
         }
     }
 
+    /// Send to Language Server the changes of the module done while we did not hold the write
+    /// lock, like the metadata regenerated after applying a remote change.
+    async fn resynchronize
+    (&self, content:&LanguageServerContent) -> FallibleResult<ParsedContentSummary> {
+        let new_file     = self.model.serialized_content()?;
+        let kind         = NotificationKind::Invalidate;
+        let notification = Notification {new_file,kind};
+        self.handle_notification(content,notification).await
+    }
+
     /// Send update to Language Server with the entire file content. Returns the new content summary
     /// of Language Server state.
     fn full_invalidation
@@ -395,6 +670,125 @@ impl Module {
     }
 }
 
+
+// === Applying Remote Changes ===
+
+impl Module {
+    /// Apply the edits made by another Language Server client. Returns the new Language Server
+    /// content summary and the result reported to the change requester.
+    ///
+    /// If there are local changes not yet sent to the Language Server, the conflict is resolved by
+    /// overwriting the remote edits with the local version of the module.
+    async fn handle_remote_edit
+    (&self, ls_content:LanguageServerContent, edit:FileEdit)
+    -> (LanguageServerContent,FallibleResult) {
+        let summary = match ls_content {
+            LanguageServerContent::Synchronized(summary) if summary.digest == edit.old_version =>
+                summary,
+            ls_content => {
+                // We don't know the content the edits were made to, so the new Language Server
+                // content cannot be reproduced. We can only hope that the next full invalidation
+                // will succeed.
+                warning!(self.logger,"Received edits of unknown version {edit.old_version}.");
+                let end_of_file = ls_content.summary().end_of_file;
+                let summary     = ContentSummary {digest:edit.new_version,end_of_file};
+                return (LanguageServerContent::Desynchronized(summary),Err(self.conflict()))
+            }
+        };
+        let new_content = apply_text_edits(&summary.source,&edit.edits);
+        let new_summary = ParsedContentSummary::from_source(&SourceFile::new(new_content));
+        if new_summary.digest != edit.new_version {
+            warning!(self.logger,"The edits do not lead to the declared version \
+                {edit.new_version}.");
+            let end_of_file = new_summary.end_of_file;
+            let summary     = ContentSummary {digest:edit.new_version,end_of_file};
+            return (LanguageServerContent::Desynchronized(summary),Err(self.conflict()))
+        }
+        match self.model.serialized_content() {
+            Ok(local) if local.content == summary.source || self.is_read_only() => {
+                let result = self.apply_external_content(&summary,new_summary.source.clone());
+                (LanguageServerContent::Synchronized(new_summary),result)
+            }
+            Ok(local) => {
+                warning!(self.logger,"Remote edits conflict with the local changes.");
+                let ls_summary = new_summary.summary.clone();
+                let result     = self.full_invalidation(&ls_summary,local).await;
+                let ls_content = self.new_ls_content_info(ls_summary,result);
+                (ls_content,Err(self.conflict()))
+            }
+            Err(error) => (LanguageServerContent::Synchronized(new_summary),Err(error)),
+        }
+    }
+
+    /// Apply the new content of the module file modified on disk. The updated module is then
+    /// synchronized with the Language Server like after any local change.
+    ///
+    /// The modifications resulting in one of the `known_versions` of the Language Server content
+    /// are ignored, as they are the module saves.
+    fn handle_file_modification
+    (&self, ls_content:LanguageServerContent, text:String, known_versions:&mut KnownVersions)
+    -> (LanguageServerContent,FallibleResult) {
+        let result = if known_versions.acknowledge(&Sha3_224::new(text.as_bytes())) {
+            Ok(())
+        } else {
+            match &ls_content {
+                LanguageServerContent::Synchronized(summary) => {
+                    match self.model.serialized_content() {
+                        Ok(local) if local.content == summary.source || self.is_read_only() =>
+                            self.apply_external_content(summary,text),
+                        Ok(_)      => Err(self.conflict()),
+                        Err(error) => Err(error),
+                    }
+                }
+                LanguageServerContent::Desynchronized(_) => Err(self.conflict()),
+            }
+        };
+        (ls_content,result)
+    }
+
+    /// Update the module with the content changed outside the IDE, assuming that the module was
+    /// in sync with the `old` content before.
+    ///
+    /// If only the code was changed (like text editors do), the id map from the file is outdated.
+    /// In such case the ids of the current AST are adjusted to the code change instead, so the
+    /// nodes keep their ids and metadata.
+    fn apply_external_content
+    (&self, old:&ParsedContentSummary, new_content:String) -> FallibleResult {
+        let new_file          = SourceFile::new(new_content);
+        let old_code          = old.code_slice();
+        let new_code          = new_file.code_slice();
+        let same_id_map       = old.id_map_slice() == new_file.id_map_slice();
+        let same_metadata     = old.metadata_slice() == new_file.metadata_slice();
+        let only_code_changed = same_id_map && same_metadata && old_code != new_code;
+        let mut content:Content = self.parser.parse_with_metadata(new_file.content.clone())?;
+        if only_code_changed {
+            let edit       = TextEdit::from_prefix_postfix_differences(old_code,new_code);
+            let change     = text_change(old_code,&edit);
+            let mut id_map = self.model.ast().id_map();
+            apply_code_change_to_id_map(&mut id_map,&change,old_code);
+            content.ast = self.parser.parse(new_code.to_owned(),id_map)?.try_into()?;
+        }
+        self.model.update_whole(content)
+    }
+
+    fn conflict(&self) -> failure::Error {
+        Conflict {path:self.path().to_string()}.into()
+    }
+}
+
+/// Convert the Language Server's edit of the given text to the text change.
+fn text_change(text:&str, edit:&TextEdit) -> TextChange {
+    let start = TextLocation::from(edit.range.start).to_index(text);
+    let end   = TextLocation::from(edit.range.end).to_index(text);
+    TextChange::replace(start..end,edit.text.clone())
+}
+
+/// Apply the Language Server's edits to the text. The edits are applied one after another, so
+/// each one refers to the text resulting from applying the previous ones.
+fn apply_text_edits(text:&str, edits:&[TextEdit]) -> String {
+    edits.iter().fold(text.to_owned(), |text,edit| text_change(&text,edit).applied(&text))
+}
+
 impl Drop for Module {
     fn drop(&mut self) {
         let file_path       = self.path().file_path().clone();
@@ -436,13 +830,12 @@ pub mod test {
     use crate::test::Runner;
 
     use data::text;
-    use data::text::TextChange;
-    use enso_protocol::language_server::FileEdit;
     use enso_protocol::language_server::MockClient;
     use enso_protocol::language_server::Position;
     use enso_protocol::language_server::TextRange;
     use json_rpc::error::RpcError;
     use utils::test::ExpectTuple;
+    use utils::test::stream::StreamTestExt;
     use wasm_bindgen_test::wasm_bindgen_test;
 
 
@@ -559,15 +952,8 @@ pub mod test {
         }
     }
 
-    fn apply_edit(code:&str, edit:&TextEdit) -> String {
-        let start = TextLocation::from(edit.range.start).to_index(code);
-        let end   = TextLocation::from(edit.range.end).to_index(code);
-        data::text::TextChange::replace(start..end,edit.text.clone()).applied(code)
-    }
-
     fn apply_edits(code:impl Into<String>, file_edit:&FileEdit) -> String {
-        let initial = code.into();
-        file_edit.edits.iter().fold(initial, |content,edit| apply_edit(&content,edit))
+        apply_text_edits(&code.into(),&file_edit.edits)
     }
 
 
@@ -666,4 +1052,146 @@ pub mod test {
         };
         Runner::run(test);
     }
+
+    #[wasm_bindgen_test]
+    fn handling_remote_changes() {
+        let initial_code = "main =\n    println \"Hello World!\"";
+        let mut data     = crate::test::mock::Unified::new();
+        data.set_code(initial_code);
+        let edit_handler = LsClientSetup::new_for_mock_data(&data);
+        let mut fixture  = data.fixture_customize(|data,client,_| {
+            data.expect_opening_module(client);
+            data.expect_closing_module(client);
+            // Opening module and metadata generation.
+            edit_handler.expect_full_invalidation(client);
+            // The id map adjusted after the remote code change.
+            edit_handler.expect_some_edit(client, |edits| {
+                assert!(edits.edits.iter().all(|edit| !edit.text.contains("Hi")));
+                Ok(())
+            });
+        });
+        let module = fixture.synchronized_module();
+        fixture.run_until_stalled();
+        let println_id = |module:&Module| {
+            let id_map = module.ast().id_map();
+            let span   = |span:&data::text::Span| span.index.value == 11 && span.size.value == 7;
+            id_map.vec.iter().find(|(s,_)| span(s)).map(|(_,id)| *id)
+        };
+        let initial_id = println_id(&module);
+        assert!(initial_id.is_some());
+
+        // Code edited by another client, without updating the id map.
+        let old_content = edit_handler.current_ls_content.get();
+        let range       = TextRange {
+            start : Position {line:1, character:13},
+            end   : Position {line:1, character:25},
+        };
+        let edits       = vec![TextEdit {range,text:"Hi".to_owned()}];
+        let new_content = apply_text_edits(&old_content,&edits);
+        let file_edit   = FileEdit {
+            path        : module.path().file_path().clone(),
+            old_version : Sha3_224::new(old_content.as_bytes()),
+            new_version : Sha3_224::new(new_content.as_bytes()),
+            edits,
+        };
+        edit_handler.current_ls_version.set(file_edit.new_version.clone());
+        edit_handler.current_ls_content.set(new_content.clone());
+        let result = module.apply_remote_change(RemoteChange::Edits(file_edit));
+        fixture.executor.expect_completion(result).unwrap();
+        fixture.run_until_stalled();
+        assert_eq!(module.ast().repr(), "main =\n    println \"Hi\"");
+        assert_eq!(println_id(&module), initial_id);
+
+        // The module saved with the known content.
+        let result = module.apply_remote_change(RemoteChange::FileModified(new_content));
+        fixture.executor.expect_completion(result).unwrap();
+        assert_eq!(module.ast().repr(), "main =\n    println \"Hi\"");
+
+        // Edits of the content we don't know.
+        let range     = TextRange {start:Position {line:0, character:0}, end:range.start};
+        let file_edit = FileEdit {
+            path        : module.path().file_path().clone(),
+            old_version : Sha3_224::new("unknown".as_bytes()),
+            new_version : Sha3_224::new("unknown 2".as_bytes()),
+            edits       : vec![TextEdit {range,text:"".to_owned()}],
+        };
+        let result = module.apply_remote_change(RemoteChange::Edits(file_edit));
+        let error  = fixture.executor.expect_completion(result).unwrap_err();
+        assert!(error.downcast_ref::<Conflict>().is_some());
+        assert_eq!(module.ast().repr(), "main =\n    println \"Hi\"");
+    }
+
+    #[wasm_bindgen_test]
+    fn forgetting_saved_versions() {
+        let version      = |n:usize| Sha3_224::new(n.to_string().as_bytes());
+        let mut versions = KnownVersions::default();
+        for n in 0..5 {
+            versions.insert(&version(n));
+            versions.insert(&version(n));
+        }
+        assert_eq!(versions.versions.len(), 5);
+        assert!(versions.acknowledge(&version(2)));
+        assert_eq!(versions.versions.len(), 3);
+        assert!(!versions.acknowledge(&version(1)));
+        assert!(versions.acknowledge(&version(2)));
+
+        for n in 5..KNOWN_VERSIONS_LIMIT + 10 {
+            versions.insert(&version(n));
+        }
+        assert_eq!(versions.versions.len(), KNOWN_VERSIONS_LIMIT);
+        assert!(!versions.acknowledge(&version(2)));
+        assert!(versions.acknowledge(&version(KNOWN_VERSIONS_LIMIT + 9)));
+        assert_eq!(versions.versions.len(), 1);
+    }
+
+    #[wasm_bindgen_test]
+    fn editing_read_only_module() {
+        let initial_code = "main =\n    println \"Hello World!\"";
+        let mut data     = crate::test::mock::Unified::new();
+        data.set_code(initial_code);
+        let edit_handler = LsClientSetup::new_for_mock_data(&data);
+        let mut fixture  = data.fixture_customize(|data,client,_| {
+            let path            = data.module_path.file_path().clone();
+            let current_version = Sha3_224::new(initial_code.as_bytes());
+            let open_response   = language_server::response::OpenTextFile {
+                write_capability : None,
+                content          : initial_code.to_owned(),
+                current_version,
+            };
+            expect_call!(client.open_text_file(path=path.clone()) => Ok(open_response));
+            data.expect_closing_module(client);
+            let registration     = CapabilityRegistration::create_can_edit_text_file(path);
+            let method           = registration.method.clone();
+            let register_options = registration.register_options.clone();
+            expect_call!(client.acquire_capability(method,register_options) => Ok(()));
+            // The metadata generated while reading the module are sent with the write lock.
+            edit_handler.expect_some_edit(client, |_| Ok(()));
+            edit_handler.expect_some_edit(client, |edits| {
+                assert!(edits.edits.iter().any(|edit| edit.text.contains("Test")));
+                Ok(())
+            });
+            expect_call!(client.release_capability(registration) => Ok(()));
+        });
+        let module = fixture.synchronized_module();
+        fixture.run_until_stalled();
+        assert_eq!(module.access_mode(), AccessMode::ReadOnly);
+        let new_ast = data.parser.parse_module("main =\n    println \"Test\"",default()).unwrap();
+        let error   = module.update_ast(new_ast.clone()).unwrap_err();
+        assert!(error.downcast_ref::<ReadOnly>().is_some());
+        assert_eq!(module.ast().repr(), initial_code);
+
+        let mut access_modes = module.subscribe_access_mode();
+        let result           = module.request_write_access();
+        fixture.executor.expect_completion(result).unwrap();
+        fixture.run_until_stalled();
+        assert_eq!(access_modes.expect_next(), AccessMode::ReadWrite);
+        assert_eq!(module.access_mode(), AccessMode::ReadWrite);
+        module.update_ast(new_ast).unwrap();
+        fixture.run_until_stalled();
+
+        let result = module.release_write_access();
+        fixture.executor.expect_completion(result).unwrap();
+        assert_eq!(access_modes.expect_next(), AccessMode::ReadOnly);
+        assert_eq!(module.access_mode(), AccessMode::ReadOnly);
+    }
 }
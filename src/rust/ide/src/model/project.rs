@@ -66,6 +66,17 @@ pub trait API:Debug {
     fn module<'a>
     (&'a self, path:crate::model::module::Path) -> BoxFuture<'a,FallibleResult<model::Module>>;
 
+    /// Returns the model of the module if it is already opened. Unlike `module`, never opens the
+    /// module's file.
+    fn loaded_module(&self, path:crate::model::module::Path) -> Option<model::Module>;
+
+    /// Removes the module from the opened modules, so the next `module` call opens it anew. Should
+    /// be called when the module's file was moved or deleted.
+    ///
+    /// The project releases its handles to the module, so the module's file gets closed once the
+    /// handles held elsewhere are dropped.
+    fn forget_module(&self, path:crate::model::module::Path);
+
     /// Creates a new execution context with given definition as a root; and registers the context
     /// for receiving update.
     #[allow(clippy::needless_lifetimes)] // Note: Needless lifetimes
@@ -164,10 +175,17 @@ pub type Synchronized = synchronized::Project;
 // ====================
 
 /// Notification emitted by the project model.
-#[derive(Clone,Copy,Debug,PartialEq)]
+#[derive(Clone,Debug,PartialEq)]
 pub enum Notification {
     /// One of the backend connections has been lost.
-    ConnectionLost(BackendConnection)
+    ConnectionLost(BackendConnection),
+    /// A file in the path watched by the Language Server was changed. The paths are watched once
+    /// the `file/receivesTreeUpdates` capability is acquired for them.
+    FileEvent(language_server::FileEvent),
+    /// A change of the opened module, made outside this IDE instance, could not be applied. The
+    /// module keeps its local version.
+    #[allow(missing_docs)]
+    ExternalModuleChangeRejected {path:model::module::Path, reason:String},
 }
 
 /// Denotes one of backend connections used by a project.
@@ -205,6 +223,14 @@ pub mod test {
             .returning_st(move |_path| ready(Ok(module.clone_ref())).boxed_local());
     }
 
+    /// Sets up loaded module expectation on the mock project, returning the given modules for
+    /// their paths and `None` for other paths.
+    pub fn expect_loaded_modules(project:&mut MockAPI, modules:Vec<model::Module>) {
+        project.expect_loaded_module().returning_st(move |path| {
+            modules.iter().find(|module| module.path() == &path).map(CloneRef::clone_ref)
+        });
+    }
+
     /// Sets up execution context expectation on the mock project, returning a given context.
     pub fn expect_execution_ctx(project:&mut MockAPI, ctx:model::ExecutionContext) {
         let ctx2 = ctx.clone_ref();
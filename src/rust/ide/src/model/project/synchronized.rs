@@ -147,6 +147,87 @@ impl ContentRoots {
 }
 
 
+// ===========================
+// === RemoteModuleChanges ===
+// ===========================
+
+type ModuleRegistry = model::registry::Registry<module::Path,module::Synchronized>;
+
+/// Applies the changes made outside this IDE instance to the opened modules. The changes which
+/// could not be applied are reported with project notifications.
+#[derive(Clone,Debug)]
+struct RemoteModuleChanges {
+    logger          : Logger,
+    module_registry : Weak<ModuleRegistry>,
+    language_server : Weak<language_server::Connection>,
+    notifications   : notification::Publisher<model::project::Notification>,
+}
+
+impl RemoteModuleChanges {
+    fn opened_module(&self, path:&language_server::Path) -> Option<Rc<module::Synchronized>> {
+        let path = module::Path::from_file_path(path.clone()).ok()?;
+        self.module_registry.upgrade()?.get_loaded(&path)
+    }
+
+    /// Apply the edits done by another Language Server client.
+    fn apply_edits(&self, edit:language_server::FileEdit) {
+        if let Some(module) = self.opened_module(&edit.path) {
+            let change = module::synchronized::RemoteChange::Edits(edit);
+            crate::executor::global::spawn(self.apply(module,change));
+        }
+    }
+
+    /// Reload the opened module whose file was modified on disk.
+    fn reload(&self, path:language_server::Path) {
+        let module          = self.opened_module(&path);
+        let language_server = self.language_server.upgrade();
+        if let (Some(module),Some(language_server)) = (module,language_server) {
+            let this = self.clone();
+            crate::executor::global::spawn(async move {
+                match language_server.read_file(&path).await {
+                    Ok(response) => {
+                        let change = module::synchronized::RemoteChange::FileModified
+                            (response.contents);
+                        this.apply(module,change).await
+                    }
+                    Err(error) => error!(this.logger,"Failed to read the modified module file \
+                        {path}: {error}"),
+                }
+            });
+        }
+    }
+
+    /// Update the access mode of the opened module whose write lock was granted to or taken from
+    /// this client.
+    fn set_access_mode
+    (&self, registration:&language_server::CapabilityRegistration, mode:module::AccessMode) {
+        let path   = registration.can_edit_text_file_path();
+        let module = path.and_then(|path| self.opened_module(path));
+        if let Some(module) = module {
+            module.set_access_mode(mode);
+        }
+    }
+
+    fn apply
+    (&self, module:Rc<module::Synchronized>, change:module::synchronized::RemoteChange)
+    -> impl Future<Output=()> {
+        let logger        = self.logger.clone_ref();
+        let notifications = self.notifications.clone_ref();
+        async move {
+            if let Err(error) = module.apply_remote_change(change).await {
+                let path   = module.path().clone_ref();
+                let reason = error.to_string();
+                warning!(logger,"Failed to apply the external change of module {path}: {reason}");
+                let notification = model::project::Notification::ExternalModuleChangeRejected
+                    {path,reason};
+                notifications.notify(notification);
+            }
+        }
+    }
+}
+
+
+
 // =============
 // === Model ===
 // =============
@@ -219,7 +300,7 @@ pub struct Project {
     pub project_manager     : Option<Rc<dyn project_manager::API>>,
     pub language_server_rpc : Rc<language_server::Connection>,
     pub language_server_bin : Rc<binary::Connection>,
-    pub module_registry     : Rc<model::registry::Registry<module::Path,module::Synchronized>>,
+    pub module_registry     : Rc<ModuleRegistry>,
     pub execution_contexts  : Rc<ExecutionContextsRegistry>,
     pub visualization       : controller::Visualization,
     pub suggestion_db       : Rc<SuggestionDatabase>,
@@ -382,6 +463,12 @@ impl Project {
         let weak_execution_contexts = Rc::downgrade(&self.execution_contexts);
         let weak_suggestion_db      = Rc::downgrade(&self.suggestion_db);
         let weak_content_roots      = Rc::downgrade(&self.content_roots);
+        let remote_module_changes   = RemoteModuleChanges {
+            logger          : self.logger.clone_ref(),
+            module_registry : Rc::downgrade(&self.module_registry),
+            language_server : Rc::downgrade(&self.language_server_rpc),
+            notifications   : self.notifications.clone_ref(),
+        };
         move |event| {
             debug!(logger, "Received an event from the json-rpc protocol: {event:?}");
             use enso_protocol::language_server::Event;
@@ -420,6 +507,25 @@ impl Project {
                         content_roots.remove(id);
                     }
                 }
+                Event::Notification(Notification::FileEvent(event)) => {
+                    if event.kind == language_server::FileEventKind::Modified {
+                        remote_module_changes.reload(event.path.clone());
+                    }
+                    publisher.notify(model::project::Notification::FileEvent(event));
+                }
+                Event::Notification(Notification::TextDidChange(update)) => {
+                    for edit in update.edits {
+                        remote_module_changes.apply_edits(edit);
+                    }
+                }
+                Event::Notification(Notification::CapabilityGranted {registration}) => {
+                    let mode = module::AccessMode::ReadWrite;
+                    remote_module_changes.set_access_mode(&registration,mode);
+                }
+                Event::Notification(Notification::CapabilityForceReleased {registration}) => {
+                    let mode = module::AccessMode::ReadOnly;
+                    remote_module_changes.set_access_mode(&registration,mode);
+                }
                 Event::Closed => {
                     error!(logger,"Lost JSON-RPC connection with the Language Server!");
                     let which        = model::project::BackendConnection::LanguageServerJson;
@@ -507,6 +613,19 @@ impl model::project::API for Project {
         }.boxed_local()
     }
 
+    fn loaded_module(&self, path:module::Path) -> Option<model::Module> {
+        let model:model::Module = self.module_registry.get_loaded(&path)?;
+        Some(model)
+    }
+
+    fn forget_module(&self, path:module::Path) {
+        info!(self.logger,"Forgetting module {path}");
+        if let Some(module) = self.module_registry.get_loaded(&path) {
+            self.urm.module_closed(module);
+        }
+        self.module_registry.remove(&path);
+    }
+
     fn create_execution_context
     (&self, root_definition:MethodPointer) -> BoxFuture<FallibleResult<model::ExecutionContext>> {
         async move {
@@ -641,6 +760,80 @@ mod test {
         });
     }
 
+    #[wasm_bindgen_test]
+    fn forward_file_events() {
+        use crate::model::project::Notification;
+        use enso_protocol::language_server::FileEvent;
+        use enso_protocol::language_server::FileEventKind;
+
+        let mut f      = Fixture::new(|_| {}, |_|{});
+        let mut events = f.project.subscribe().boxed_local();
+        let root_id    = f.project.project_content_root_id();
+        let path       = language_server::Path::new(root_id,&["theme.json"]);
+        let event      = FileEvent {path,kind:FileEventKind::Modified};
+        let json_event = language_server::Event::Notification(
+            language_server::Notification::FileEvent(event.clone()));
+        f.json_events_sender.unbounded_send(json_event).unwrap();
+        f.test.run_until_stalled();
+        assert_eq!(events.expect_next(), Notification::FileEvent(event));
+    }
+
+    #[wasm_bindgen_test]
+    fn reject_remote_edits_of_unknown_version() {
+        use crate::model::project::Notification;
+
+        let path      = module::Path::from_mock_module_name("TestModule");
+        let file_path = path.file_path().clone();
+        let mut f     = Fixture::new(|ls_json| {
+            mock_calls_for_opening_text_file(ls_json,file_path.clone(),"2+2");
+        }, |_|{});
+        let mut events = f.project.subscribe().boxed_local();
+        let module     = f.project.module(path.clone_ref()).boxed_local();
+        let _module    = f.test.expect_completion(module).unwrap();
+
+        let range      = language_server::TextRange {
+            start : language_server::Position {line:0, character:0},
+            end   : language_server::Position {line:0, character:1},
+        };
+        let edit       = language_server::FileEdit {
+            path        : file_path,
+            edits       : vec![language_server::TextEdit {range,text:"3".into()}],
+            old_version : Sha3_224::new(b"unknown"),
+            new_version : Sha3_224::new(b"unknown 2"),
+        };
+        let update     = language_server::TextDidChange {edits:vec![edit]};
+        let json_event = language_server::Event::Notification(
+            language_server::Notification::TextDidChange(update));
+        f.json_events_sender.unbounded_send(json_event).unwrap();
+        f.test.run_until_stalled();
+        match events.expect_next() {
+            Notification::ExternalModuleChangeRejected {path:rejected,..} =>
+                assert_eq!(rejected, path),
+            other => panic!("Unexpected notification {:?}.", other),
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn losing_write_lock_of_module() {
+        let path      = module::Path::from_mock_module_name("TestModule");
+        let file_path = path.file_path().clone();
+        let mut f     = Fixture::new(|ls_json| {
+            mock_calls_for_opening_text_file(ls_json,file_path.clone(),"2+2");
+        }, |_|{});
+        let module = f.project.module(path.clone_ref()).boxed_local();
+        let module = f.test.expect_completion(module).unwrap();
+        assert_eq!(module.access_mode(), module::AccessMode::ReadWrite);
+
+        let registration = CapabilityRegistration::create_can_edit_text_file(file_path);
+        let json_event   = language_server::Event::Notification(
+            language_server::Notification::CapabilityForceReleased {registration});
+        f.json_events_sender.unbounded_send(json_event).unwrap();
+        f.test.run_until_stalled();
+        assert_eq!(module.access_mode(), module::AccessMode::ReadOnly);
+        let error = module.remove_node_metadata(default()).unwrap_err();
+        assert!(error.downcast_ref::<module::ReadOnly>().is_some());
+    }
+
     #[wasm_bindgen_test]
     fn obtain_module_controller() {
         let path         = module::Path::from_mock_module_name("TestModule");
@@ -661,6 +854,23 @@ mod test {
         });
     }
 
+    #[wasm_bindgen_test]
+    fn forgetting_module_releases_its_handles() {
+        let path      = module::Path::from_mock_module_name("TestModule");
+        let file_path = path.file_path().clone();
+        let mut f     = Fixture::new(|ls_json| {
+            mock_calls_for_opening_text_file(ls_json,file_path,"2+2");
+        }, |_|{});
+        let module = f.project.module(path.clone_ref()).boxed_local();
+        let module = Rc::downgrade(&f.test.expect_completion(module).unwrap());
+        assert!(module.upgrade().is_some());
+
+        f.project.forget_module(path.clone_ref());
+        f.test.run_until_stalled();
+        assert!(module.upgrade().is_none());
+        assert!(f.project.loaded_module(path).is_none());
+    }
+
     fn mock_calls_for_opening_text_file
     (client:&language_server::MockClient, path:language_server::Path, content:&str) {
         let content          = content.to_string();
@@ -111,6 +111,21 @@ where K : Clone + Eq + Hash {
         }
     }
 
+    /// Get item under the key if it is already loaded. Unlike `get_or_load`, this function does
+    /// not wait for the item being loaded by another task.
+    pub fn get_loaded(&self, key:&K) -> Option<Rc<V>> {
+        match self.registry.borrow_mut().get(key) {
+            Some(Entry::Loaded(state)) => Some(state),
+            _                          => None,
+        }
+    }
+
+    /// Remove the item from the registry. The handles to the item remain valid, but the next
+    /// `get_or_load` call will load the item anew.
+    pub fn remove(&self, key:&K) {
+        self.registry.borrow_mut().remove(key);
+    }
+
     async fn get(&self, key:&K) -> Result<Option<Rc<V>>,LoadingError> {
         loop {
             let entry = self.registry.borrow_mut().get(key);
@@ -185,6 +200,30 @@ mod test {
         });
     }
 
+    #[test]
+    fn removing_module() {
+        let mut test = TestWithLocalPoolExecutor::set_up();
+        test.run_task(async move {
+            let line     = ast::Ast::infix_var("a", "+", "b");
+            let ast      = ast::Ast::one_line_module(line).try_into().unwrap();
+            let path     = ModulePath::from_mock_module_name("Test");
+            let logger   = Logger::new("Test");
+            let state    = model::module::Plain::new(logger,path.clone(),ast,default(),default());
+            let state    = Rc::new(state);
+            let registry = Registry::default();
+
+            let loader = async move { Ok(state) };
+            let module = registry.get_or_load(path.clone(),loader).await.unwrap();
+            registry.remove(&path);
+            assert!(registry.get_loaded(&path).is_none());
+
+            let reloaded = module.clone_ref();
+            let loader   = async move { Ok(reloaded) };
+            registry.get_or_load(path.clone(),loader).await.unwrap();
+            assert!(registry.get_loaded(&path).is_some());
+        });
+    }
+
     #[test]
     fn getting_module_during_load() {
         let line      = ast::Ast::infix_var("a", "+", "b");
@@ -1,6 +1,7 @@
 //! The module contains all structures for representing suggestions and their database.
 pub mod entry;
 pub mod example;
+pub mod usage;
 
 use crate::prelude::*;
 
@@ -61,6 +62,7 @@ pub struct SuggestionDatabase {
     entries       : RefCell<HashMap<entry::Id,Rc<Entry>>>,
     examples      : RefCell<Vec<Rc<Example>>>,
     version       : Cell<SuggestionsDatabaseVersion>,
+    usage         : RefCell<usage::Statistics>,
     notifications : notification::Publisher<Notification>,
 }
 
@@ -71,8 +73,9 @@ impl SuggestionDatabase {
         let entries       = default();
         let examples      = default();
         let version       = default();
+        let usage         = default();
         let notifications = default();
-        Self {logger,entries,examples,version,notifications}
+        Self {logger,entries,examples,version,usage,notifications}
     }
 
     /// Create a database filled with entries provided by the given iterator.
@@ -110,6 +113,7 @@ impl SuggestionDatabase {
             entries       : RefCell::new(entries),
             examples      : RefCell::new(examples),
             version       : Cell::new(response.current_version),
+            usage         : default(),
             notifications : default()
         }
     }
@@ -124,6 +128,11 @@ impl SuggestionDatabase {
         self.entries.borrow().get(&id).cloned().ok_or(NoSuchEntry(id))
     }
 
+    /// Get all entries stored in the database together with their ids.
+    pub fn entries(&self) -> Vec<(entry::Id,Rc<Entry>)> {
+        self.entries.borrow().iter().map(|(id,entry)| (*id,entry.clone_ref())).collect()
+    }
+
     /// Apply the update event to the database.
     pub fn apply_update_event(&self, event:SuggestionDatabaseUpdatesEvent) {
         for update in event.updates {
@@ -200,6 +209,25 @@ impl SuggestionDatabase {
         })
     }
 
+    /// Search the database for Atom entries of the given type, sorted by their names.
+    ///
+    /// The atoms of a type are defined in the module representing the type, so an atom is of the
+    /// type `tp` if `tp` is the atom's module, like `Standard.Base.Data.Ordering`, or the type named
+    /// after the module, like `Standard.Base.Data.Ordering.Ordering`.
+    pub fn lookup_atoms_of_type(&self, tp:impl Str) -> Vec<Rc<Entry>> {
+        let tp         = tp.as_ref();
+        let is_of_type = |entry:&Entry| {
+            let module      = entry.module.to_string();
+            let module_type = format!("{}.{}",module,entry.module.name());
+            tp == module || tp == module_type
+        };
+        let mut atoms = self.entries.borrow().values().filter(|entry| {
+            entry.kind == Kind::Atom && is_of_type(entry)
+        }).cloned().collect_vec();
+        atoms.sort_by(|lhs,rhs| lhs.name.cmp(&rhs.name));
+        atoms
+    }
+
     /// An iterator over all examples gathered from suggestions.
     ///
     /// If the database was modified during iteration, the iterator does not panic, but may return
@@ -209,6 +237,42 @@ impl SuggestionDatabase {
         indices.filter_map(move |i| self.examples.borrow().get(i).cloned())
     }
 
+    /// Record that the user picked a suggestion of given method. See [`usage::Statistics`].
+    pub fn record_usage(&self, method:&MethodId, input_type:Option<&str>) {
+        self.usage.borrow_mut().record_pick(method,input_type)
+    }
+
+    /// The usage score of given method in the context of given input type. See
+    /// [`usage::Statistics::score`].
+    pub fn usage_score(&self, method:&MethodId, input_type:Option<&str>) -> f32 {
+        self.usage.borrow().score(method,input_type)
+    }
+
+    /// Get a copy of the current usage statistics, e.g. to store them in the project's metadata.
+    pub fn usage_statistics(&self) -> usage::Statistics {
+        self.usage.borrow().clone()
+    }
+
+    /// Replace the usage statistics, e.g. with ones restored from the project's metadata.
+    pub fn set_usage_statistics(&self, statistics:usage::Statistics) {
+        *self.usage.borrow_mut() = statistics;
+    }
+
+    /// Remove all gathered usage statistics.
+    pub fn reset_usage_statistics(&self) {
+        self.usage.borrow_mut().reset()
+    }
+
+    /// Enable or disable gathering usage statistics and using them for ranking suggestions.
+    pub fn set_usage_statistics_enabled(&self, enabled:bool) {
+        self.usage.borrow_mut().enabled = enabled;
+    }
+
+    /// Check if the usage statistics are gathered and used for ranking suggestions.
+    pub fn is_usage_statistics_enabled(&self) -> bool {
+        self.usage.borrow().enabled
+    }
+
     /// Put the entry to the database. Using this function likely breaks the synchronization between
     /// Language Server and IDE, and should be used only in tests.
     #[cfg(test)]
@@ -548,4 +612,35 @@ mod test {
         assert_eq!(db.lookup(3).unwrap().arguments[2].name, "NewArg");
         assert_eq!(db.version.get(), 8);
     }
+
+    #[test]
+    fn looking_up_atoms_of_type() {
+        let atom = |id, name:&str, module:&str| {
+            let suggestion = language_server::types::SuggestionEntry::Atom {
+                name               : name.to_owned(),
+                module             : module.to_owned(),
+                arguments          : vec![],
+                return_type        : format!("{}.{}",module,name),
+                documentation      : None,
+                documentation_html : None,
+                external_id        : None,
+            };
+            SuggestionsDatabaseEntry {id,suggestion}
+        };
+        let response = language_server::response::GetSuggestionDatabase {
+            entries         : vec!
+                [ atom(1,"Less"   ,"Standard.Base.Data.Ordering")
+                , atom(2,"Greater","Standard.Base.Data.Ordering")
+                , atom(3,"Nothing","Standard.Base.Nothing")
+                ],
+            current_version : 1,
+        };
+        let db    = SuggestionDatabase::from_ls_response(response);
+        let names = |tp| db.lookup_atoms_of_type(tp).iter().map(|e| e.name.clone()).collect_vec();
+        assert_eq!(names("Standard.Base.Data.Ordering")         , vec!["Greater","Less"]);
+        assert_eq!(names("Standard.Base.Data.Ordering.Ordering"), vec!["Greater","Less"]);
+        assert_eq!(names("Standard.Base.Nothing")               , vec!["Nothing"]);
+        assert!(names("Standard.Base.Data.Ordering.Less").is_empty());
+        assert!(names("Ordering").is_empty());
+    }
 }
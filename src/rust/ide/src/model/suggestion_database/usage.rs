@@ -0,0 +1,271 @@
+//! A module with the statistics of suggestions picked by the user. They are used to rank the
+//! Searcher's suggestion list, so the methods used often and recently are listed first.
+use crate::prelude::*;
+
+use crate::model::module::MethodId;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The factor by which the score of every method is multiplied each time any suggestion is picked.
+/// It makes the recently picked methods more important than the ones used long ago.
+pub const DECAY_PER_PICK:f32 = 0.97;
+
+/// The maximum number of records kept in statistics. When exceeded, the records with the lowest
+/// score are dropped.
+pub const MAX_RECORDS:usize = 512;
+
+/// How much the score gathered regardless of the input type contributes to the score of a method
+/// picked in the context of specific input type.
+pub const ANY_INPUT_TYPE_WEIGHT:f32 = 0.5;
+
+
+
+// ==============
+// === Record ===
+// ==============
+
+/// The usage record of a single method in the context of a single input type.
+#[derive(Clone,Debug,Deserialize,PartialEq,Serialize)]
+pub struct Record {
+    /// The picked method.
+    pub method : MethodId,
+    /// The type of the value the suggestion was applied to (e.g. the type of selected node), or
+    /// [`None`] for the record gathering picks regardless of the input type.
+    pub input_type : Option<String>,
+    /// The score at the moment of the last pick. See [`Statistics::score`] for details.
+    pub score : f32,
+    /// The number of all picks recorded in [`Statistics`] at the moment of the last pick.
+    pub last_pick : u64,
+}
+
+impl Record {
+    fn is_about(&self, method:&MethodId, input_type:Option<&str>) -> bool {
+        &self.method == method && self.input_type.as_deref() == input_type
+    }
+}
+
+
+
+// ==================
+// === Statistics ===
+// ==================
+
+/// The Suggestion Usage Statistics.
+///
+/// For each method picked in Searcher it keeps a score growing with each pick and decaying when
+/// other suggestions are picked. The scores are kept separately for each input type and for all
+/// picks regardless of the input type.
+///
+/// The structure is serializable, as it is designed to be stored in the project's metadata.
+#[derive(Clone,Debug,Deserialize,PartialEq,Serialize)]
+pub struct Statistics {
+    /// If false, no picks will be recorded and all scores will be 0.0.
+    #[serde(default="enabled_by_default")]
+    pub enabled : bool,
+    /// The number of all recorded picks. Serves as a clock for the score decay.
+    #[serde(default)]
+    picks : u64,
+    #[serde(default,deserialize_with="utils::serde::deserialize_or_default")]
+    records : Vec<Record>,
+}
+
+fn enabled_by_default() -> bool { true }
+
+impl Default for Statistics {
+    fn default() -> Self {
+        let enabled = enabled_by_default();
+        let picks   = default();
+        let records = default();
+        Self {enabled,picks,records}
+    }
+}
+
+impl Statistics {
+    /// Record that the user picked given method, applying it to the value of given type.
+    ///
+    /// Does nothing if the statistics are disabled.
+    pub fn record_pick(&mut self, method:&MethodId, input_type:Option<&str>) {
+        if self.enabled {
+            self.picks += 1;
+            self.bump(method,None);
+            if input_type.is_some() {
+                self.bump(method,input_type);
+            }
+            self.drop_excessive_records();
+        }
+    }
+
+    /// The usage score of the given method in the context of given input type.
+    ///
+    /// The score is in range [0.0,1.0): it is 0.0 for methods which were never picked, and
+    /// approaches 1.0 for methods picked many times recently.
+    pub fn score(&self, method:&MethodId, input_type:Option<&str>) -> f32 {
+        if self.enabled {
+            let any_type = self.raw_score(method,None);
+            let raw      = match input_type {
+                Some(tp) => self.raw_score(method,Some(tp)) + any_type * ANY_INPUT_TYPE_WEIGHT,
+                None     => any_type,
+            };
+            raw / (raw + 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Remove all the gathered statistics.
+    pub fn reset(&mut self) {
+        self.picks = 0;
+        self.records.clear();
+    }
+
+    /// Number of the stored records.
+    pub fn len(&self) -> usize { self.records.len() }
+
+    /// Check if there are no records stored.
+    pub fn is_empty(&self) -> bool { self.records.is_empty() }
+
+    fn raw_score(&self, method:&MethodId, input_type:Option<&str>) -> f32 {
+        let record = self.records.iter().find(|r| r.is_about(method,input_type));
+        record.map_or(0.0, |r| self.decayed_score(r))
+    }
+
+    fn decayed_score(&self, record:&Record) -> f32 {
+        let picks_since = self.picks.saturating_sub(record.last_pick).min(i32::MAX as u64);
+        record.score * DECAY_PER_PICK.powi(picks_since as i32)
+    }
+
+    fn bump(&mut self, method:&MethodId, input_type:Option<&str>) {
+        let score     = self.raw_score(method,input_type) + 1.0;
+        let last_pick = self.picks;
+        if let Some(record) = self.records.iter_mut().find(|r| r.is_about(method,input_type)) {
+            record.score     = score;
+            record.last_pick = last_pick;
+        } else {
+            let method     = method.clone();
+            let input_type = input_type.map(ToOwned::to_owned);
+            self.records.push(Record {method,input_type,score,last_pick});
+        }
+    }
+
+    fn drop_excessive_records(&mut self) {
+        if self.records.len() > MAX_RECORDS {
+            let mut records = std::mem::take(&mut self.records);
+            records.sort_by(|lhs,rhs| {
+                let lhs = self.decayed_score(lhs);
+                let rhs = self.decayed_score(rhs);
+                rhs.partial_cmp(&lhs).unwrap_or(std::cmp::Ordering::Equal)
+            });
+            records.truncate(MAX_RECORDS);
+            self.records = records;
+        }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::model::module::QualifiedName;
+    use crate::model::module::TypeQualifiedName;
+
+    fn method(name:&str) -> MethodId {
+        MethodId {
+            module          : QualifiedName::from_text("Standard.Base.Main").unwrap(),
+            defined_on_type : TypeQualifiedName::from_text("Standard.Base.Main").unwrap(),
+            name            : name.to_owned(),
+        }
+    }
+
+    #[test]
+    fn recording_picks() {
+        let mut stats = Statistics::default();
+        let foo       = method("foo");
+        let bar       = method("bar");
+        assert_eq!(stats.score(&foo,None), 0.0);
+
+        stats.record_pick(&foo,None);
+        assert!(stats.score(&foo,None) > 0.0);
+        assert_eq!(stats.score(&bar,None), 0.0);
+
+        let foo_after_one_pick = stats.score(&foo,None);
+        stats.record_pick(&foo,None);
+        assert!(stats.score(&foo,None) > foo_after_one_pick);
+        assert!(stats.score(&foo,None) < 1.0);
+    }
+
+    #[test]
+    fn scores_decay_with_other_picks() {
+        let mut stats = Statistics::default();
+        let foo       = method("foo");
+        let bar       = method("bar");
+        stats.record_pick(&foo,None);
+        stats.record_pick(&bar,None);
+        assert!(stats.score(&bar,None) > stats.score(&foo,None));
+        stats.record_pick(&foo,None);
+        assert!(stats.score(&foo,None) > stats.score(&bar,None));
+    }
+
+    #[test]
+    fn scores_per_input_type() {
+        let mut stats = Statistics::default();
+        let foo       = method("foo");
+        let bar       = method("bar");
+        stats.record_pick(&foo,Some("Standard.Base.Data.Text.Text"));
+        stats.record_pick(&bar,Some("Standard.Table.Data.Table.Table"));
+        let text_ctx = Some("Standard.Base.Data.Text.Text");
+        assert!(stats.score(&foo,text_ctx) > stats.score(&bar,text_ctx));
+        assert!(stats.score(&bar,None) > stats.score(&foo,None));
+        assert_eq!(stats.len(), 4);
+    }
+
+    #[test]
+    fn disabling_and_resetting() {
+        let mut stats = Statistics::default();
+        let foo       = method("foo");
+        stats.record_pick(&foo,None);
+        stats.enabled = false;
+        assert_eq!(stats.score(&foo,None), 0.0);
+        stats.record_pick(&foo,None);
+        stats.enabled = true;
+        assert_eq!(stats.len(), 1);
+        stats.reset();
+        assert!(stats.is_empty());
+        assert_eq!(stats.score(&foo,None), 0.0);
+    }
+
+    #[test]
+    fn limiting_records_count() {
+        let mut stats = Statistics::default();
+        let favourite = method("favourite");
+        for _ in 0..10 {
+            stats.record_pick(&favourite,None);
+        }
+        for i in 0..MAX_RECORDS {
+            stats.record_pick(&method(&format!("method{}",i)),None);
+        }
+        assert_eq!(stats.len(), MAX_RECORDS);
+        assert!(stats.score(&favourite,None) > 0.0);
+    }
+
+    #[test]
+    fn deserializing_missing_fields() {
+        let stats:Statistics = serde_json::from_str("{}").unwrap();
+        assert_eq!(stats, Statistics::default());
+        let serialized       = serde_json::to_string(&stats).unwrap();
+        let deserialized     = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(stats, deserialized);
+    }
+}
@@ -0,0 +1,561 @@
+//! A project-wide index of symbols: the methods and atoms defined in the project and libraries,
+//! together with the references to them found in the project code. It is used for navigating the
+//! code: going to definitions, finding references and searching symbols by name.
+//!
+//! The symbols are gathered from two sources: the Suggestion Database, which knows all the symbols
+//! but not their positions in code, and the parsed project modules, which give the exact locations
+//! of the definitions and references.
+
+use crate::prelude::*;
+
+use crate::double_representation::definition;
+use crate::double_representation::definition::DefinitionProvider;
+use crate::double_representation::module;
+use crate::double_representation::tp;
+use crate::model::suggestion_database::Entry;
+use crate::model::suggestion_database::entry::Kind;
+use crate::model::suggestion_database::entry::Scope;
+use crate::model::SuggestionDatabase;
+
+use ast::crumbs::Crumb;
+use ast::crumbs::InfixCrumb;
+use ast::crumbs::TraversableAst;
+use ast::opr::predefined::ACCESS;
+use data::text::Index;
+use data::text::Span;
+use enso_protocol::language_server::MethodPointer;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The names which, used as a method call target, refer to the module where the call is written.
+pub const CURRENT_MODULE_KEYWORDS : &[&str] = &["here","this"];
+
+
+
+// ==============
+// === Symbol ===
+// ==============
+
+/// Identifies a symbol in the project.
+#[derive(Clone,Debug,Eq,Hash,PartialEq)]
+pub struct SymbolId {
+    /// The module where the symbol is defined.
+    pub module : module::QualifiedName,
+    /// The name of the type the symbol is a method of, or `None` for the symbols defined on the
+    /// module itself. Only the last segment of the type name is stored, as this is how the types
+    /// are referred to in the code.
+    pub target : Option<String>,
+    /// The name of the symbol.
+    pub name   : String,
+}
+
+impl SymbolId {
+    /// The id of the symbol described by the Suggestion Database entry. Returns `None` for the
+    /// entries which are not visible outside their definitions, like local variables.
+    pub fn from_entry(entry:&Entry) -> Option<Self> {
+        let is_global = entry.scope == Scope::Everywhere && entry.kind != Kind::Module;
+        is_global.as_some_from(|| {
+            let module_type = tp::QualifiedName::from(entry.module.clone());
+            let target      = entry.self_type.as_ref().filter(|tp| **tp != module_type);
+            let target      = target.map(|tp| tp.name.clone());
+            let module      = entry.module.clone();
+            let name        = entry.name.clone();
+            SymbolId {module,target,name}
+        })
+    }
+}
+
+impl Display for SymbolId {
+    fn fmt(&self, f:&mut fmt::Formatter) -> fmt::Result {
+        match &self.target {
+            Some(target) => write!(f, "{}.{}", target, self.name),
+            None         => write!(f, "{}.{}", self.module.name(), self.name),
+        }
+    }
+}
+
+/// The location of a symbol definition in the module code.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct Definition {
+    /// The id of the definition, which can be used to open its graph.
+    pub id   : definition::Id,
+    /// The span of the definition name in the module code.
+    pub span : Span,
+}
+
+/// A symbol defined in the project or a library.
+#[derive(Clone,Debug,Eq,PartialEq)]
+#[allow(missing_docs)]
+pub struct Symbol {
+    pub id         : SymbolId,
+    pub kind       : Kind,
+    /// The fully qualified type the symbol is a method of, if known.
+    pub self_type  : Option<tp::QualifiedName>,
+    /// The definition in the module code. It is `None` if the symbol's module was not indexed,
+    /// e.g. because it belongs to a library, or if the symbol is not defined by a binding, like
+    /// atoms.
+    pub definition : Option<Definition>,
+}
+
+impl Symbol {
+    /// The pointer to the method defined by this symbol, which can be used to enter the method's
+    /// graph. Returns `None` if the symbol is not a method or its type is not known.
+    pub fn method_pointer(&self) -> Option<MethodPointer> {
+        let self_type = self.self_type.as_ref().filter(|_| self.kind == Kind::Method)?;
+        Some(MethodPointer {
+            module          : self.id.module.to_string(),
+            defined_on_type : self_type.to_string(),
+            name            : self.id.name.clone(),
+        })
+    }
+}
+
+
+
+// =================
+// === Reference ===
+// =================
+
+/// Describes what a referenced name is accessed on.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub enum Qualifier {
+    /// The name is not accessed on anything, like the `Foo` atom.
+    None,
+    /// The name is accessed on the module where the reference is written, like in `here.foo`.
+    CurrentModule,
+    /// The name is accessed on a module or type, like in `Module.foo` or `Text.length`.
+    Name(String),
+    /// The name is accessed on an arbitrary expression, like in `x.foo`. The actual target is
+    /// known only when the code is evaluated.
+    Expression,
+}
+
+/// A place in the project code referring to some symbol.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct Reference {
+    /// The module where the reference is written.
+    pub module    : module::QualifiedName,
+    /// The referenced name.
+    pub name      : String,
+    #[allow(missing_docs)]
+    pub qualifier : Qualifier,
+    /// The span of the referenced name in the module code.
+    pub span      : Span,
+    /// The id of the whole referencing expression, e.g. the `Module.foo` access.
+    pub id        : Option<ast::Id>,
+}
+
+impl Reference {
+    /// Check if the given module code index points at the referenced name.
+    pub fn contains(&self, index:Index) -> bool {
+        self.span.index <= index && index < self.span.end()
+    }
+}
+
+
+
+// ===================
+// === ModuleIndex ===
+// ===================
+
+/// The symbols defined in a single module and the references it contains.
+#[derive(Clone,Debug,Default)]
+struct ModuleIndex {
+    imports     : Vec<module::QualifiedName>,
+    definitions : Vec<(SymbolId,Definition)>,
+    references  : Vec<Reference>,
+}
+
+impl ModuleIndex {
+    fn new(name:&module::QualifiedName, ast:&ast::known::Module) -> Self {
+        let info        = module::Info {ast:ast.clone_ref()};
+        let mut skipped = Vec::new();
+        let imports     = info.enumerate_imports().filter_map(|(crumb,import)| {
+            skipped.extend(ast.ast().span_of_descendent_at(&[Crumb::Module(crumb)]).ok());
+            import.qualified_name().ok()
+        }).collect();
+        let definitions = ast.def_iter().filter_map(|child| {
+            let mut head_crumbs = child.crumbs.clone();
+            head_crumbs.push(Crumb::Infix(InfixCrumb::LeftOperand));
+            skipped.push(ast.ast().span_of_descendent_at(&head_crumbs).ok()?);
+            let def_name   = &child.item.name.item;
+            let crumbs     = child.crumbs.iter().chain(&child.item.name.crumbs);
+            let crumbs     = crumbs.chain(&def_name.name.crumbs).cloned().collect_vec();
+            let span       = ast.ast().span_of_descendent_at(&crumbs).ok()?;
+            let target     = def_name.extended_target.last().map(|target| target.item.clone());
+            let target     = target.filter(|target| name.name() != *target);
+            let module     = name.clone();
+            let symbol     = SymbolId {module,target,name:def_name.name.item.clone()};
+            let id         = definition::Id::new_single_crumb(def_name.clone());
+            Some((symbol,Definition {id,span}))
+        }).collect();
+        let references = collect_references(name,ast.ast(),&skipped);
+        Self {imports,definitions,references}
+    }
+}
+
+/// Find the references in the module code, except the `skipped` spans. The definition heads
+/// (bindings' left-hand sides) should be skipped, so the definitions are not counted as references
+/// to themselves.
+fn collect_references
+(module:&module::QualifiedName, ast:&Ast, skipped:&[Span]) -> Vec<Reference> {
+    let mut references = Vec::<Reference>::new();
+    // The names being parts of accesses, which were already handled with the whole access.
+    let mut qualified  = Vec::<Span>::new();
+    ast::traverse_with_span(ast, |span,ast| {
+        let is_skipped = skipped.iter().any(|head| head.index <= span.index
+            && span.end() <= head.end());
+        if is_skipped || qualified.contains(&span) {
+            return
+        }
+        let access = ast::opr::to_specific_infix(ast,ACCESS);
+        let child  = |crumb| {
+            let child = ast.span_of_child_at(&Crumb::Infix(crumb)).ok()?;
+            Some(Span::new(Index::new(span.index.value + child.index.value),child.size))
+        };
+        if let Some(access) = access {
+            let larg = child(InfixCrumb::LeftOperand);
+            let rarg = child(InfixCrumb::RightOperand);
+            let name = identifier_name(&access.rarg);
+            if let (Some(larg),Some(rarg),Some(name)) = (larg,rarg,name) {
+                qualified.push(rarg);
+                let qualifier = match access.larg.shape() {
+                    ast::Shape::Var(var) if CURRENT_MODULE_KEYWORDS.contains(&var.name.as_str()) =>
+                        Qualifier::CurrentModule,
+                    ast::Shape::Cons(cons) => {
+                        qualified.push(larg);
+                        Qualifier::Name(cons.name.clone())
+                    }
+                    // A qualified type or module name, like `Base.Text`.
+                    _ => match ast::opr::to_specific_infix(&access.larg,ACCESS) {
+                        Some(target) => match target.rarg.shape() {
+                            ast::Shape::Cons(cons) => Qualifier::Name(cons.name.clone()),
+                            _                      => Qualifier::Expression,
+                        }
+                        None => Qualifier::Expression,
+                    }
+                };
+                let module = module.clone();
+                let id     = ast.id;
+                references.push(Reference {module,name,qualifier,span:rarg,id});
+            }
+        } else if let ast::Shape::Cons(cons) = ast.shape() {
+            let module    = module.clone();
+            let name      = cons.name.clone();
+            let qualifier = Qualifier::None;
+            references.push(Reference {module,name,qualifier,span,id:ast.id});
+        }
+    });
+    references
+}
+
+/// The name of the variable or referent, i.e. the names which may refer to symbols.
+fn identifier_name(ast:&Ast) -> Option<String> {
+    match ast.shape() {
+        ast::Shape::Var(var)   => Some(var.name.clone()),
+        ast::Shape::Cons(cons) => Some(cons.name.clone()),
+        _                      => None,
+    }
+}
+
+
+
+// ===================
+// === SymbolIndex ===
+// ===================
+
+/// The project-wide index of symbols.
+///
+/// The index is meant to be shared, so it implements the internal mutability pattern. It is not
+/// updated automatically: the owner should feed it with the changes of the Suggestion Database
+/// and the modules.
+#[derive(Debug,Default)]
+pub struct SymbolIndex {
+    suggestions : RefCell<HashMap<SymbolId,Rc<Entry>>>,
+    modules     : RefCell<HashMap<module::QualifiedName,ModuleIndex>>,
+}
+
+impl SymbolIndex {
+    /// Create an empty index.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Replace the symbols known from the Suggestion Database with the database's current
+    /// entries.
+    pub fn update_suggestions(&self, database:&SuggestionDatabase) {
+        let entries = database.entries().into_iter();
+        let symbols = entries.filter_map(|(_,entry)| {
+            SymbolId::from_entry(&entry).map(|id| (id,entry))
+        });
+        *self.suggestions.borrow_mut() = symbols.collect();
+    }
+
+    /// Index the definitions and references of the module, replacing its previous version.
+    pub fn update_module(&self, name:module::QualifiedName, ast:&ast::known::Module) {
+        let index = ModuleIndex::new(&name,ast);
+        self.modules.borrow_mut().insert(name,index);
+    }
+
+    /// Remove the module's definitions and references from the index.
+    pub fn remove_module(&self, name:&module::QualifiedName) {
+        self.modules.borrow_mut().remove(name);
+    }
+
+    /// Get the symbol with the given id.
+    pub fn symbol(&self, id:&SymbolId) -> Option<Symbol> {
+        let entry      = self.suggestions.borrow().get(id).cloned();
+        let modules    = self.modules.borrow();
+        let module     = modules.get(&id.module);
+        let definition = module.and_then(|module| {
+            module.definitions.iter().find(|(symbol,_)| symbol == id).map(|(_,def)| def.clone())
+        });
+        match entry {
+            Some(entry) => {
+                let kind      = entry.kind;
+                let self_type = entry.self_type.clone();
+                Some(Symbol {id:id.clone(),kind,self_type,definition})
+            }
+            // The symbols found in code but not yet known to the Suggestion Database are methods,
+            // as the only definitions we discover in code are the module-level bindings.
+            None => definition.map(|definition| {
+                let module_type = id.target.is_none().as_some_from(|| id.module.clone().into());
+                let kind        = Kind::Method;
+                Symbol {id:id.clone(),kind,self_type:module_type,definition:Some(definition)}
+            }),
+        }
+    }
+
+    /// Get all the symbols.
+    pub fn symbols(&self) -> Vec<Symbol> {
+        let ids = self.symbol_ids();
+        ids.iter().filter_map(|id| self.symbol(id)).collect()
+    }
+
+    fn symbol_ids(&self) -> HashSet<SymbolId> {
+        let suggestions = self.suggestions.borrow();
+        let modules     = self.modules.borrow();
+        let defined     = modules.values().flat_map(|module| &module.definitions);
+        let defined     = defined.map(|(id,_)| id.clone());
+        suggestions.keys().cloned().chain(defined).collect()
+    }
+
+    /// Get the symbols which the reference may refer to.
+    ///
+    /// The references qualified with arbitrary expressions (like `x.foo`) may refer to a method of
+    /// any type, so all the methods with the given name are returned.
+    pub fn resolve(&self, reference:&Reference) -> Vec<Symbol> {
+        let name      = &reference.name;
+        let in_module = |module:module::QualifiedName| {
+            SymbolId {module,target:None,name:name.clone()}
+        };
+        let candidates = match &reference.qualifier {
+            Qualifier::CurrentModule => vec![in_module(reference.module.clone())],
+            Qualifier::None          => self.visible_modules(reference).map(in_module).collect(),
+            Qualifier::Name(target)  => {
+                let modules = self.visible_modules(reference);
+                let modules = modules.filter(|module| module.name() == *target).map(in_module);
+                let methods = self.symbol_ids().into_iter().filter(|id| {
+                    id.target.contains(target) && &id.name == name
+                });
+                modules.chain(methods).collect()
+            }
+            Qualifier::Expression => self.symbol_ids().into_iter().filter(|id| {
+                id.target.is_some() && &id.name == name
+            }).collect(),
+        };
+        candidates.into_iter().unique().filter_map(|id| self.symbol(&id)).collect()
+    }
+
+    /// The module where the reference is written and the modules it imports.
+    fn visible_modules
+    (&self, reference:&Reference) -> impl Iterator<Item=module::QualifiedName> {
+        let modules = self.modules.borrow();
+        let imports = modules.get(&reference.module).map(|module| module.imports.clone());
+        std::iter::once(reference.module.clone()).chain(imports.unwrap_or_default())
+    }
+
+    /// Get the symbol the reference refers to. If the target of the reference is not known
+    /// statically and there are many methods it may refer to, `None` is returned.
+    pub fn definition_of(&self, reference:&Reference) -> Option<Symbol> {
+        let candidates   = self.resolve(reference);
+        let is_ambiguous = candidates.len() > 1 && reference.qualifier == Qualifier::Expression;
+        if is_ambiguous { None } else { candidates.into_iter().next() }
+    }
+
+    /// Get the reference at the given index of the module code.
+    pub fn reference_at(&self, module:&module::QualifiedName, index:Index) -> Option<Reference> {
+        let modules    = self.modules.borrow();
+        let references = &modules.get(module)?.references;
+        let containing = references.iter().filter(|reference| reference.contains(index));
+        containing.min_by_key(|reference| reference.span.size).cloned()
+    }
+
+    /// Get the outermost reference in the expression, e.g. the called method in the node
+    /// expression.
+    pub fn reference_in
+    (&self, module:&module::QualifiedName, expression:&Ast) -> Option<Reference> {
+        let modules    = self.modules.borrow();
+        let references = &modules.get(module)?.references;
+        let mut ids    = expression.iter_recursive().filter_map(|ast| ast.id);
+        ids.find_map(|id| references.iter().find(|reference| reference.id == Some(id))).cloned()
+    }
+
+    /// Find all the references which may refer to the symbol in the indexed modules.
+    pub fn references_to(&self, symbol:&SymbolId) -> Vec<Reference> {
+        let references = self.modules.borrow().values().flat_map(|module| {
+            module.references.iter().filter(|reference| reference.name == symbol.name).cloned()
+        }).collect_vec();
+        references.into_iter().filter(|reference| {
+            self.resolve(reference).iter().any(|candidate| &candidate.id == symbol)
+        }).collect()
+    }
+
+    /// Find the symbols whose names fuzzy-match the pattern, the best matches first. The names are
+    /// matched together with the type or module they are defined on, e.g. `Text.length`.
+    pub fn search(&self, pattern:&str) -> Vec<Symbol> {
+        let matched = self.symbols().into_iter().filter_map(|symbol| {
+            let text   = symbol.id.to_string();
+            let metric = fuzzly::metric::default();
+            let found  = fuzzly::matches(&text,pattern);
+            found.and_option_from(|| fuzzly::find_best_subsequence(&text,pattern,metric))
+                .map(|subsequence| (subsequence,symbol))
+        });
+        let mut matched = matched.collect_vec();
+        matched.sort_by(|(lhs,_),(rhs,_)| rhs.compare_scores(lhs));
+        matched.into_iter().map(|(_,symbol)| symbol).collect()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use parser::Parser;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    const LIBRARY_CODE : &str = "foo x = x + 1\n\nText.shout = this + \"!\"";
+    const MAIN_CODE    : &str = "import local.Project.Library\n\nmain =\n    \
+        a = Library.foo 2\n    b = \"hi\".shout\n    here.bar a\n\nbar x = Point x 1";
+
+    fn module_name(name:&str) -> module::QualifiedName {
+        module::QualifiedName::from_text(format!("local.Project.{}",name)).unwrap()
+    }
+
+    fn index() -> SymbolIndex {
+        let parser = Parser::new_or_panic();
+        let index  = SymbolIndex::new();
+        let atom   = Entry {
+            name               : "Point".to_owned(),
+            kind               : Kind::Atom,
+            module             : module_name("Main"),
+            arguments          : vec![],
+            return_type        : "Point".to_owned(),
+            documentation_html : None,
+            self_type          : None,
+            scope              : Scope::Everywhere,
+        };
+        let local = Entry {
+            name  : "a".to_owned(),
+            kind  : Kind::Local,
+            scope : Scope::InModule {range:default()..=default()},
+            ..atom.clone()
+        };
+        let entries  = vec![(1,atom),(2,local)];
+        let entries  = entries.iter().map(|(id,entry)| (id,entry));
+        let database = SuggestionDatabase::new_from_entries(Logger::new("Test"),entries);
+        let library  = parser.parse_module(LIBRARY_CODE,default()).unwrap();
+        let main     = parser.parse_module(MAIN_CODE,default()).unwrap();
+        index.update_suggestions(&database);
+        index.update_module(module_name("Library"),&library);
+        index.update_module(module_name("Main"),&main);
+        index
+    }
+
+    fn id(module:&str, target:Option<&str>, name:&str) -> SymbolId {
+        let module = module_name(module);
+        let target = target.map(ToOwned::to_owned);
+        SymbolId {module,target,name:name.to_owned()}
+    }
+
+    fn index_of(code:&str, text:&str) -> Index {
+        Index::new(code.find(text).unwrap())
+    }
+
+    #[wasm_bindgen_test]
+    fn indexing_symbols() {
+        let index   = index();
+        let symbols = index.symbols().into_iter().map(|symbol| symbol.id).collect::<HashSet<_>>();
+        let expected = [id("Library",None,"foo"),id("Library",Some("Text"),"shout"),
+            id("Main",None,"main"),id("Main",None,"bar"),id("Main",None,"Point")];
+        assert_eq!(symbols, expected.iter().cloned().collect());
+
+        let foo = index.symbol(&id("Library",None,"foo")).unwrap();
+        assert_eq!(foo.kind, Kind::Method);
+        let definition = foo.definition.clone().unwrap();
+        assert_eq!(definition.id, definition::Id::new_plain_name("foo"));
+        assert_eq!(definition.span.index, index_of(LIBRARY_CODE,"foo"));
+        let library = module_name("Library").to_string();
+        let pointer = MethodPointer {
+            module          : library.clone(),
+            defined_on_type : library,
+            name            : "foo".to_owned(),
+        };
+        assert_eq!(foo.method_pointer(), Some(pointer));
+        let shout = index.symbol(&id("Library",Some("Text"),"shout")).unwrap();
+        assert_eq!(shout.method_pointer(), None);
+        assert_eq!(shout.definition.unwrap().span.index, index_of(LIBRARY_CODE,"shout"));
+        let point = index.symbol(&id("Main",None,"Point")).unwrap();
+        assert_eq!(point.kind, Kind::Atom);
+        assert_eq!(point.definition, None);
+    }
+
+    #[wasm_bindgen_test]
+    fn going_to_definition() {
+        let index      = index();
+        let main       = module_name("Main");
+        let definition = |text:&str| {
+            let reference = index.reference_at(&main,index_of(MAIN_CODE,text))?;
+            index.definition_of(&reference).map(|symbol| symbol.id)
+        };
+        assert_eq!(definition("foo 2"), Some(id("Library",None,"foo")));
+        assert_eq!(definition("shout"), Some(id("Library",Some("Text"),"shout")));
+        assert_eq!(definition("bar a"), Some(id("Main",None,"bar")));
+        assert_eq!(definition("Point"), Some(id("Main",None,"Point")));
+        assert_eq!(definition("Library.foo"), None);
+        assert_eq!(definition("bar x"), None);
+    }
+
+    #[wasm_bindgen_test]
+    fn finding_references() {
+        let index      = index();
+        let references = index.references_to(&id("Main",None,"bar"));
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].span.index, index_of(MAIN_CODE,"bar a"));
+        assert_eq!(references[0].qualifier, Qualifier::CurrentModule);
+        let references = index.references_to(&id("Library",Some("Text"),"shout"));
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].qualifier, Qualifier::Expression);
+        assert!(index.references_to(&id("Main",None,"main")).is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn searching_symbols() {
+        let index   = index();
+        let results = index.search("libfoo").into_iter().map(|symbol| symbol.id).collect_vec();
+        assert_eq!(results, vec![id("Library",None,"foo")]);
+        let results = index.search("shout").into_iter().map(|symbol| symbol.id).collect_vec();
+        assert_eq!(results, vec![id("Library",Some("Text"),"shout")]);
+        assert!(index.search("nothing").is_empty());
+    }
+}
@@ -3,6 +3,9 @@
 use crate::prelude::*;
 
 use crate::controller;
+use crate::notification;
+
+use flo_stream::Subscriber;
 
 
 
@@ -35,6 +38,16 @@ pub struct NoFrameToPop(Stack);
 #[fail(display = "The module {} is not accessible.",_0)]
 pub struct MissingModuleHandle(model::module::Id);
 
+#[allow(missing_docs)]
+#[derive(Debug,Clone,Copy,Eq,Fail,PartialEq)]
+#[fail(display = "There is no position {} in the undo-redo history.",_0)]
+pub struct NoSuchPosition(usize);
+
+#[allow(missing_docs)]
+#[derive(Debug,Clone,Copy,Eq,Fail,PartialEq)]
+#[fail(display = "There is no branch with index {} in the undo-redo history.",_0)]
+pub struct NoSuchBranch(usize);
+
 
 
 // ==============
@@ -78,9 +91,10 @@ pub struct Transaction {
 impl Transaction {
     /// Create a new transaction, that will add to the given's repository undo stack on destruction.
     pub fn new(urm:&Rc<Repository>, name:String) -> Self {
+        let timestamp = ensogl_system_web::time_from_start();
         Self {
             logger  : Logger::new_sub(&urm.logger,"Transaction"),
-            frame   : RefCell::new(Frame{name,..default()}),
+            frame   : RefCell::new(Frame{name,timestamp,..default()}),
             urm     : Rc::downgrade(urm),
             ignored : default(),
         }
@@ -88,7 +102,7 @@ impl Transaction {
 
     /// Get the transaction name.
     ///
-    /// Currently the name serves only debugging purposes.
+    /// The name is displayed in the undo-redo history.
     pub fn name(&self) -> String {
         self.frame.borrow().name.clone()
     }
@@ -126,8 +140,7 @@ impl Drop for Transaction {
         if let Some(urm) = self.urm.upgrade() {
             if !self.ignored.get() {
                 info!(self.logger, "Transaction '{self.name()}' will create a new frame.");
-                urm.push_to(Stack::Undo, self.frame.borrow().clone());
-                urm.clear(Stack::Redo);
+                urm.push_new_frame(self.frame.borrow().clone());
             } else {
                 info!(self.logger, "Dropping the ignored transaction '{self.name()}' without \
                 pushing a frame to repository.")
@@ -155,6 +168,16 @@ pub struct Frame {
     pub graph : Option<controller::graph::Id>,
     /// Snapshots of content for all edited modules.
     pub snapshots : BTreeMap<model::module::Id, model::module::Content>,
+    /// Time when the transaction that created this frame was opened, in milliseconds since the
+    /// application start.
+    pub timestamp : f64,
+}
+
+impl Frame {
+    /// Ids of all modules edited in this frame.
+    pub fn modules(&self) -> impl Iterator<Item=&model::module::Id> {
+        self.snapshots.keys()
+    }
 }
 
 impl Display for Frame {
@@ -183,6 +206,29 @@ pub enum Stack {
     Redo,
 }
 
+/// An alternative future: the redo stack which was set aside when a new action was made after
+/// undoing.
+///
+/// The branches form a tree: the alternative futures forked off the branch's own frames are kept
+/// inside the branch, and become reachable once the branch is switched to.
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct Branch {
+    /// The length of the undo stack at the moment the branch was forked.
+    pub fork_depth : usize,
+    /// The redo stack of the branch, the next frame to redo is the last.
+    pub frames : Vec<Frame>,
+    /// The branches forked off this branch's frames, ordered by the time of forking. Their fork
+    /// depths are greater than the branch's one.
+    pub branches : Vec<Branch>,
+}
+
+/// Notification emitted by the [`Repository`].
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub enum Notification {
+    /// The undo or redo stack, or the branches, were changed.
+    HistoryChanged,
+}
+
 /// The inner state of the Und-Redo repository.
 #[derive(Debug,Default)]
 pub struct Data {
@@ -190,6 +236,8 @@ pub struct Data {
     pub undo: Vec<Frame>,
     /// Redo stack.
     pub redo: Vec<Frame>,
+    /// Alternative futures forked off the undo and redo stacks, ordered by the time of forking.
+    pub branches: Vec<Branch>,
     /// Currently open transaction (if `Some` and alive).
     pub current_transaction : Option<Weak<Transaction>>,
 }
@@ -203,8 +251,9 @@ pub struct Data {
 /// can be shared with no consequence on project state.
 #[derive(Debug)]
 pub struct Repository {
-    logger : Logger,
-    data : RefCell<Data>,
+    logger        : Logger,
+    data          : RefCell<Data>,
+    notifications : notification::Publisher<Notification>,
 }
 
 
@@ -218,11 +267,21 @@ impl Repository {
     /// Create a new repository.
     pub fn new(parent:impl AnyLogger) -> Self {
         Self {
-            logger : Logger::new_sub(parent,"Repository"),
-            data   : default(),
+            logger        : Logger::new_sub(parent,"Repository"),
+            data          : default(),
+            notifications : default(),
         }
     }
 
+    /// Subscribe for the notifications about the history changes.
+    pub fn subscribe(&self) -> Subscriber<Notification> {
+        self.notifications.subscribe()
+    }
+
+    fn notify_history_changed(&self) {
+        self.notifications.notify(Notification::HistoryChanged);
+    }
+
     /// Get the currently open transaction. [`None`] if there is none.
     pub fn current_transaction(&self) -> Option<Rc<Transaction>> {
         self.data.borrow().current_transaction.as_ref().and_then(Weak::upgrade)
@@ -288,19 +347,80 @@ impl Repository {
     fn push_to(&self, stack:Stack, frame:Frame) {
         debug!(self.logger, "Pushing to {stack} stack a new frame: {frame}");
         self.borrow_mut(stack).push(frame);
+        self.notify_history_changed();
     }
 
     /// Clear all frames from the given stack.
     fn clear(&self, stack:Stack) {
         debug!(self.logger, "Clearing {stack} stack.");
         self.borrow_mut(stack).clear();
+        self.notify_history_changed();
     }
 
-    /// Clear all frames from both undo and redo stacks.
+    /// Clear all frames from both undo and redo stacks, and all the branches.
     pub fn clear_all(&self) {
         for stack in [Stack::Undo,Stack::Redo] {
             self.clear(stack)
         };
+        self.data.borrow_mut().branches.clear();
+        self.notify_history_changed();
+    }
+
+    /// Push a frame of a newly made action onto the undo stack.
+    ///
+    /// The redo stack is not discarded but set aside as a new [`Branch`], together with the
+    /// branches forked off it.
+    fn push_new_frame(&self, frame:Frame) {
+        debug!(self.logger, "Pushing a new frame: {frame}");
+        with(self.data.borrow_mut(), |mut data| {
+            self.set_aside_redo(&mut data);
+            data.undo.push(frame);
+        });
+        self.notify_history_changed();
+    }
+
+    /// Make the given branch the current redo stack.
+    ///
+    /// The undo stack must have the branch's fork depth; the current redo stack, if not empty,
+    /// is set aside as a new branch. The branches forked off the given branch's frames become
+    /// reachable from the current stacks.
+    fn swap_branch(&self, index:usize) -> FallibleResult {
+        with(self.data.borrow_mut(), |mut data| {
+            if index < data.branches.len() {
+                let branch = data.branches.remove(index);
+                self.set_aside_redo(&mut data);
+                data.redo = branch.frames;
+                data.branches.extend(branch.branches);
+                Ok(())
+            } else {
+                Err(NoSuchBranch(index))
+            }
+        })?;
+        self.notify_history_changed();
+        Ok(())
+    }
+
+    /// Move the redo stack and the branches forked off it to a new branch forked at the current
+    /// position.
+    fn set_aside_redo(&self, data:&mut Data) {
+        if !data.redo.is_empty() {
+            let fork_depth = data.undo.len();
+            let frames     = std::mem::take(&mut data.redo);
+            let all        = std::mem::take(&mut data.branches);
+            let (branches,kept) = all.into_iter().partition(|b| b.fork_depth > fork_depth);
+            debug!(self.logger, "Setting aside the redo stack as a branch at {fork_depth}.");
+            data.branches = kept;
+            data.branches.push(Branch {fork_depth,frames,branches});
+        }
+    }
+
+    /// Get the description of the undo-redo history.
+    pub fn history(&self) -> History {
+        let data     = self.data.borrow();
+        let done     = data.undo.iter().map(FrameInfo::from).collect();
+        let undone   = data.redo.iter().rev().map(FrameInfo::from).collect();
+        let branches = data.branches.iter().map(BranchInfo::from).collect();
+        History {done,undone,branches}
     }
 
     /// Get the top frame from a given stack. [`Err`] if the stack is empty.
@@ -315,6 +435,7 @@ impl Repository {
         let frame = self.borrow_mut(stack).pop().ok_or(NoFrameToPop(stack))?;
         debug!(self.logger, "Popping a frame from {stack}. Remaining length: {self.len(stack)}. \
         Frame: {frame}");
+        self.notify_history_changed();
         Ok(frame)
     }
 
@@ -327,6 +448,80 @@ impl Repository {
 
 
 
+// ===============
+// === History ===
+// ===============
+
+/// The description of a single [`Frame`] in the undo-redo history.
+#[derive(Clone,Debug,PartialEq)]
+pub struct FrameInfo {
+    /// Name of the transaction that created the frame.
+    pub name      : String,
+    /// Modules edited in the frame.
+    pub modules   : Vec<model::module::Id>,
+    /// Time of the frame creation, in milliseconds since the application start.
+    pub timestamp : f64,
+}
+
+impl From<&Frame> for FrameInfo {
+    fn from(frame:&Frame) -> Self {
+        let name      = frame.name.clone();
+        let modules   = frame.modules().cloned().collect();
+        let timestamp = frame.timestamp;
+        Self {name,modules,timestamp}
+    }
+}
+
+/// The description of a [`Branch`] in the undo-redo history.
+#[derive(Clone,Debug,PartialEq)]
+pub struct BranchInfo {
+    /// The position in history where the branch was forked.
+    pub fork_depth : usize,
+    /// The frames of the branch, the next to redo first.
+    pub frames     : Vec<FrameInfo>,
+    /// The branches forked off the branch's frames.
+    pub branches   : Vec<BranchInfo>,
+}
+
+impl From<&Branch> for BranchInfo {
+    fn from(branch:&Branch) -> Self {
+        let fork_depth = branch.fork_depth;
+        let frames     = branch.frames.iter().rev().map(FrameInfo::from).collect();
+        let branches   = branch.branches.iter().map(BranchInfo::from).collect();
+        Self {fork_depth,frames,branches}
+    }
+}
+
+/// The undo-redo history.
+///
+/// The history is a sequence of actions, of which the first [`History::position`] are done. The
+/// position `0` is the state before any recorded action.
+#[derive(Clone,Debug,Default,PartialEq)]
+pub struct History {
+    /// The actions which may be undone, the oldest first.
+    pub done     : Vec<FrameInfo>,
+    /// The actions which may be redone, the next to redo first.
+    pub undone   : Vec<FrameInfo>,
+    /// The alternative futures forked off the current line of the history. The futures forked
+    /// off them are nested in their descriptions.
+    pub branches : Vec<BranchInfo>,
+}
+
+impl History {
+    /// The current position in the history.
+    pub fn position(&self) -> usize {
+        self.done.len()
+    }
+
+    /// The number of positions in the current line of the history, including the initial one.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.done.len() + self.undone.len() + 1
+    }
+}
+
+
+
 // ===============
 // === Manager ===
 // ===============
@@ -402,20 +597,59 @@ impl Manager {
         }
 
         let undo_transaction = Rc::try_unwrap(undo_transaction).map_err(|_| FauxTransactionLeaked)?;
-        self.repository.data.borrow_mut().redo.push(undo_transaction.frame.borrow().clone());
+        let redo_frame       = Frame {
+            name      : frame.name,
+            timestamp : frame.timestamp,
+            ..undo_transaction.frame.borrow().clone()
+        };
+        self.repository.push_to(Stack::Redo,redo_frame);
         Ok(())
     }
 
     /// Redo the last undone operation.
     pub fn redo(&self) -> FallibleResult {
-        let frame            = self.repository.data.borrow_mut().redo.pop().ok_or(NoActionToUndo)?;
+        let frame            = self.repository.pop(Stack::Redo).map_err(|_| NoActionToUndo)?;
         let redo_transaction = self.get_or_open_transaction(&frame.name);
         redo_transaction.ignore();
         self.reset_to(&frame)?;
-        self.repository.push_to(Stack::Undo,redo_transaction.frame.borrow().clone());
+        let undo_frame = Frame {
+            timestamp : frame.timestamp,
+            ..redo_transaction.frame.borrow().clone()
+        };
+        self.repository.push_to(Stack::Undo,undo_frame);
         Ok(())
     }
 
+    /// Get the description of the undo-redo history.
+    pub fn history(&self) -> History {
+        self.repository.history()
+    }
+
+    /// Undo or redo actions until the given [`History::position`] is reached.
+    pub fn jump_to(&self, position:usize) -> FallibleResult {
+        let current = self.repository.len(Stack::Undo);
+        let last    = current + self.repository.len(Stack::Redo);
+        if position > last {
+            Err(NoSuchPosition(position).into())
+        } else {
+            debug!(self.logger, "Jumping from position {current} to {position}.");
+            for _ in position..current { self.undo()? }
+            for _ in current..position { self.redo()? }
+            Ok(())
+        }
+    }
+
+    /// Switch to the alternative future described by [`History::branches`] at the given index.
+    ///
+    /// The actions are undone up to the branch's fork point, and the branch's actions become
+    /// available to redo. The actions which could be redone before become a new branch.
+    pub fn switch_branch(&self, index:usize) -> FallibleResult {
+        let fork_depth = self.repository.data.borrow().branches.get(index).map(|b| b.fork_depth);
+        let fork_depth = fork_depth.ok_or(NoSuchBranch(index))?;
+        self.jump_to(fork_depth)?;
+        self.repository.swap_branch(index)
+    }
+
     /// Restore all modules affected by the [`Frame`] to their stored state.
     fn reset_to(&self, frame:&Frame) -> FallibleResult {
         info!(self.logger,"Resetting to initial state on frame {frame}");
@@ -609,4 +843,98 @@ main =
         executed_graph.graph().set_expression(node.info.id(),"4 * 20").unwrap();
         assert!(urm.redo().is_err());
     }
+
+    #[wasm_bindgen_test]
+    fn jumping_through_history_and_branches() {
+        let mut fixture = crate::test::mock::Unified::new().fixture();
+        let Fixture{executed_graph,project,module,..} = &mut fixture;
+        let urm  = project.urm();
+        let node = executed_graph.graph().nodes().unwrap()[0].info.id();
+        let code = |expression:&str| format!("main = \n    {}",expression);
+        for expression in ["1","2","3"] {
+            executed_graph.graph().set_expression(node,expression).unwrap();
+        }
+
+        let history = urm.history();
+        assert_eq!(history.position(), 3);
+        assert_eq!(history.len(), 4);
+        assert_eq!(history.done[0].modules, vec![module.id()]);
+        assert!(history.done.iter().all(|frame| !frame.name.is_empty()));
+
+        urm.jump_to(1).unwrap();
+        assert_eq!(module.ast().to_string(), code("1"));
+        let history = urm.history();
+        assert_eq!(history.position(), 1);
+        assert_eq!(history.undone.len(), 2);
+        assert!(urm.jump_to(4).is_err());
+
+        // A new action forks the redo stack off as a branch.
+        executed_graph.graph().set_expression(node,"4").unwrap();
+        let history = urm.history();
+        assert!(history.undone.is_empty());
+        assert_eq!(history.branches.len(), 1);
+        assert_eq!(history.branches[0].fork_depth, 1);
+        assert_eq!(history.branches[0].frames.len(), 2);
+
+        urm.switch_branch(0).unwrap();
+        assert_eq!(module.ast().to_string(), code("1"));
+        urm.jump_to(3).unwrap();
+        assert_eq!(module.ast().to_string(), code("3"));
+        let history = urm.history();
+        assert_eq!(history.branches.len(), 1);
+        assert_eq!(history.branches[0].frames.len(), 1);
+
+        urm.switch_branch(0).unwrap();
+        urm.redo().unwrap();
+        assert_eq!(module.ast().to_string(), code("4"));
+        assert!(urm.switch_branch(1).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn forking_branches_of_branches() {
+        let mut fixture = crate::test::mock::Unified::new().fixture();
+        let Fixture{executed_graph,project,module,..} = &mut fixture;
+        let urm  = project.urm();
+        let node = executed_graph.graph().nodes().unwrap()[0].info.id();
+        let code = |expression:&str| format!("main = \n    {}",expression);
+        let set  = |expression:&str| executed_graph.graph().set_expression(node,expression).unwrap();
+        for expression in ["1","2","3"] { set(expression) }
+
+        // Fork `4` off after `1`, then switch back to the `1 2 3` line.
+        urm.jump_to(1).unwrap();
+        set("4");
+        urm.switch_branch(0).unwrap();
+        assert_eq!(urm.history().undone.len(), 2);
+
+        // Fork `5` off after `2`. The `4` branch, forked before, stays reachable, and the `3`
+        // branch keeps nothing of the `4` line.
+        urm.jump_to(2).unwrap();
+        set("5");
+        let history = urm.history();
+        assert_eq!(history.position(), 3);
+        assert_eq!(history.branches.len(), 2);
+        assert_eq!(history.branches[0].fork_depth, 1);
+        assert_eq!(history.branches[0].frames.len(), 1);
+        assert_eq!(history.branches[1].fork_depth, 2);
+        assert_eq!(history.branches[1].frames.len(), 1);
+        assert!(history.branches.iter().all(|branch| branch.branches.is_empty()));
+
+        // Switching to the `4` branch sets aside the `2 5` line together with the `3` branch.
+        urm.switch_branch(0).unwrap();
+        urm.redo().unwrap();
+        assert_eq!(module.ast().to_string(), code("4"));
+        let history = urm.history();
+        assert_eq!(history.branches.len(), 1);
+        assert_eq!(history.branches[0].fork_depth, 1);
+        assert_eq!(history.branches[0].branches.len(), 1);
+        assert_eq!(history.branches[0].branches[0].fork_depth, 2);
+
+        // The nested `3` branch is preserved and can be replayed on its own line.
+        urm.switch_branch(0).unwrap();
+        urm.jump_to(2).unwrap();
+        assert_eq!(module.ast().to_string(), code("2"));
+        urm.switch_branch(1).unwrap();
+        urm.redo().unwrap();
+        assert_eq!(module.ast().to_string(), code("3"));
+    }
 }
@@ -3,6 +3,7 @@
 use crate::prelude::*;
 
 use crate::component::node;
+use crate::component::type_coloring::EdgeStyle;
 
 use enso_frp as frp;
 use enso_frp;
@@ -26,6 +27,7 @@ const LINE_SHAPE_WIDTH   : f32 = LINE_WIDTH + 2.0 * PADDING;
 const LINE_SIDE_OVERLAP  : f32 = 1.0;
 const LINE_SIDES_OVERLAP : f32 = 2.0 * LINE_SIDE_OVERLAP;
 const LINE_WIDTH         : f32 = 4.0;
+const DASH_LENGTH        : f32 = 6.0;
 const ARROW_SIZE_X       : f32 = 20.0;
 const ARROW_SIZE_Y       : f32 = 20.0;
 
@@ -77,6 +79,10 @@ trait EdgeShape : display::Object {
     fn set_color(&self, color:color::Rgba);
     fn set_color_focus(&self, color:color::Rgba);
 
+    /// Set whether the shape is drawn as a dashed line. Only straight lines can be dashed, other
+    /// shapes are always solid.
+    fn set_dashed(&self, _dashed:bool) {}
+
 
     // === Hover ===
 
@@ -507,10 +513,13 @@ macro_rules! define_line { () => {
         ensogl::define_shape_system! {
             below = [joint];
             (focus_split_center:Vector2<f32>, focus_split_angle:f32, color_rgba:Vector4<f32>,
-             focus_color_rgba:Vector4<f32>) {
+             focus_color_rgba:Vector4<f32>, dash_length:f32) {
                 let width       = LINE_WIDTH.px();
                 let height      = Var::<Pixels>::from("input_size.y");
-                let shape       = Rect((width.clone(),height));
+                let dash_length = 1.px() * dash_length;
+                let dash_period = Var::<Vector2<Pixels>>::from((INFINITE.px(),&dash_length*2.0));
+                let dashes      = Rect((INFINITE.px(),dash_length)).repeat(dash_period);
+                let shape       = Rect((width.clone(),height)).intersection(dashes);
                 let color       = Var::<color::Rgba>::from(color_rgba);
                 let focus_color = Var::<color::Rgba>::from(focus_color_rgba);
 
@@ -542,6 +551,11 @@ macro_rules! define_line { () => {
                 self.focus_color_rgba.set(Vector4(color.red,color.green,color.blue,color.alpha));
             }
 
+            fn set_dashed(&self, dashed:bool) {
+                // The solid line is a single dash longer than any edge.
+                self.dash_length.set(if dashed {DASH_LENGTH} else {INFINITE});
+            }
+
             fn normal_local(&self, _:Vector2<f32>) -> Rotation2<f32> {
                 Rotation2::new(0.0)
             }
@@ -1031,6 +1045,7 @@ pub struct Frp {
     pub redraw          : frp::Source,
     pub set_disabled    : frp::Source<bool>,
     pub set_color       : frp::Source<color::Lcha>,
+    pub set_style       : frp::Source<EdgeStyle>,
 
     pub hover_position  : frp::Source<Option<Vector2<f32>>>,
     pub shape_events    : ShapeViewEventsProxy
@@ -1049,10 +1064,11 @@ impl Frp {
             def hover_position  = source();
             def set_disabled    = source();
             def set_color       = source();
+            def set_style       = source();
         }
         let shape_events = ShapeViewEventsProxy::new(network);
         Self {source_width,source_height,target_position,target_attached,source_attached,redraw
-             ,set_disabled,set_color,hover_position,shape_events}
+             ,set_disabled,set_color,set_style,hover_position,shape_events}
     }
 }
 
@@ -1175,6 +1191,7 @@ impl Edge {
 
             eval edge_color.value       ((color) model.set_color(color.into()));
             eval edge_focus_color.value ((color) model.set_focus_color(color.into()));
+            eval input.set_style        ((style) model.set_style(*style));
         }
         self
     }
@@ -1261,8 +1278,17 @@ impl EdgeModelData {
         let hover_target    = default();
 
         let scene = scene.into();
-        Self {display_object,logger,frp,front,back,joint,source_width,source_height,target_position
-             ,target_attached,source_attached,layout_state,hover_position,hover_target,scene}
+        let data  = Self {display_object,logger,frp,front,back,joint,source_width,source_height
+                         ,target_position,target_attached,source_attached,layout_state
+                         ,hover_position,hover_target,scene};
+        data.set_style(default());
+        data
+    }
+
+    /// Set the line style of the edge.
+    fn set_style(&self, style:EdgeStyle) {
+        let dashed = style == EdgeStyle::Dashed;
+        self.shapes().iter().for_each(|shape| shape.set_dashed(dashed));
     }
 
     /// Set the color of the edge.
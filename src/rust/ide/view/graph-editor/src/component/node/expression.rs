@@ -16,6 +16,9 @@ pub struct Expression {
     pub whole_expression_id : Option<ast::Id>,
     pub input_span_tree     : SpanTree,
     pub output_span_tree    : SpanTree,
+    /// The possible values of the input arguments, given as code and keyed by the argument port
+    /// crumbs. The arguments with known values are edited with drop-down widgets.
+    pub argument_choices    : HashMap<span_tree::Crumbs,Vec<String>>,
 }
 
 impl Expression {
@@ -26,7 +29,8 @@ impl Expression {
         let input_span_tree     = code.generate_tree(&span_tree::generate::context::Empty).unwrap_or_default();
         let output_span_tree    = default();
         let whole_expression_id = default();
-        Self {pattern,code,whole_expression_id,input_span_tree,output_span_tree}
+        let argument_choices    = default();
+        Self {pattern,code,whole_expression_id,input_span_tree,output_span_tree,argument_choices}
     }
 }
 
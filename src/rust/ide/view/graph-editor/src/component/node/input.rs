@@ -1,4 +1,5 @@
 pub mod area;
 pub mod port;
+pub mod widget;
 
 pub use area::Area;
@@ -21,6 +21,7 @@ use text::Text;
 use crate::Type;
 use crate::component::type_coloring;
 use crate::node::input::port;
+use crate::node::input::widget;
 use crate::node::profiling;
 use crate::node;
 use crate::view;
@@ -78,9 +79,11 @@ pub type PortRefMut<'a> = span_tree::node::RefMut<'a,port::Model>;
 pub struct Expression {
     /// Visual code representation. It can contain names of missing arguments, and thus can differ
     /// from `code`.
-    pub viz_code  : String,
-    pub code      : String,
-    pub span_tree : SpanTree,
+    pub viz_code         : String,
+    pub code             : String,
+    pub span_tree        : SpanTree,
+    /// The possible values of arguments. See [`node::Expression::argument_choices`].
+    pub argument_choices : HashMap<Crumbs,Vec<String>>,
 }
 
 impl Deref for Expression {
@@ -129,6 +132,7 @@ impl From<node::Expression> for Expression {
         let mut span_tree = t.input_span_tree.map(|_|port::Model::default());
         let mut viz_code  = String::new();
         let code          = t.code;
+        let argument_choices = t.argument_choices;
         span_tree.root_ref_mut().dfs_with_layer_data(ExprConversion::default(),|node,info| {
             let is_expected_arg       = node.is_expected_argument();
             let span                  = node.span();
@@ -156,7 +160,7 @@ impl From<node::Expression> for Expression {
             port.length      = size;
             ExprConversion::new(index)
         });
-        Self {viz_code,code,span_tree}
+        Self {viz_code,code,span_tree,argument_choices}
     }
 }
 
@@ -211,6 +215,8 @@ ensogl::define_endpoints! {
         on_port_press       (Crumbs),
         on_port_hover       (Switch<Crumbs>),
         on_port_type_change (Crumbs,Option<Type>),
+        /// The argument value (given as code) was set by the user with the port's inline widget.
+        on_port_code_update (Crumbs,String),
         on_background_press (),
         view_mode           (view::Mode),
     }
@@ -516,6 +522,8 @@ impl Area {
         let mut is_header     = true;
         let mut id_crumbs_map = HashMap::new();
         let builder           = PortLayerBuilder::empty(&self.model.ports);
+        let code              = expression.code.clone();
+        let argument_choices  = expression.argument_choices.clone();
         expression.root_ref_mut().dfs_with_layer_data(builder,|mut node,builder| {
             let is_parensed = node.is_parensed();
             let skip_opr    = if SKIP_OPERATIONS {
@@ -656,6 +664,24 @@ impl Area {
                 }
                 init_color.emit(());
                 frp.source.view_mode.emit(frp.view_mode.value());
+
+                if let Some(widget) = self.new_port_widget(port,&code,&argument_choices,width) {
+                    widget.set_position_x(width/2.0);
+                    port_shape.add_child(&widget);
+                    frp::extend! { port_network
+                        widget_init    <- source::<()>();
+                        widget_visible <- all_with4(&port.frp.set_connected,&frp.editing,
+                            &frp.ports_visible,&widget_init,
+                            |(is_connected,_),editing,ports_visible,_|
+                                !is_connected && !editing && !ports_visible
+                        );
+                        widget.set_visible <+ widget_visible;
+                        frp.source.on_port_code_update <+ widget.value_set.map(
+                            f!([crumbs](code) (crumbs.clone_ref(),code.clone())));
+                    }
+                    widget_init.emit(());
+                    port.payload_mut().widget = Some(widget);
+                }
                 port_shape.display_object().clone_ref()
             };
 
@@ -673,6 +699,27 @@ impl Area {
         *self.model.id_crumbs_map.borrow_mut() = id_crumbs_map;
     }
 
+    /// Create the inline widget for the port, if it is a leaf argument port of a type supported by
+    /// widgets and its current value can be displayed by the widget.
+    fn new_port_widget
+    (&self, port:&PortRefMut, code:&str, choices:&HashMap<Crumbs,Vec<String>>, width:f32)
+    -> Option<widget::Widget> {
+        let is_argument = port.is_argument() || port.is_expected_argument();
+        if !is_argument || !port.children.is_empty() { return None }
+        let choices  = choices.get(&port.crumbs).map(Vec::as_slice).unwrap_or_default();
+        let kind     = widget::Kind::new(port.tp().map(String::as_str),choices)?;
+        let span     = port.span();
+        let start    = span.index.value;
+        let end      = start + span.size.value;
+        // The span may not lie on the char boundaries of the code (e.g. when the span tree is
+        // out of sync with it). No widget is created then, instead of panicking on slicing.
+        let arg_code = if port.is_expected_argument() { None } else { Some(code.get(start..end)?) };
+        kind.can_edit(arg_code).as_some_from(|| {
+            let logger = &self.model.logger;
+            widget::Widget::new(logger,&self.model.app,&kind,arg_code,width)
+        })
+    }
+
     /// Initializes FRP network for every port. Please note that the networks are connected
     /// hierarchically (children get events from parents), so it is easier to init all networks
     /// this way, rather than delegate it to every port.
@@ -7,6 +7,7 @@ use ensogl::display;
 
 use crate::Type;
 use crate::node::input::area;
+use crate::node::input::widget::Widget;
 
 
 
@@ -152,6 +153,9 @@ pub struct Model {
     pub local_index     : usize,
     pub length          : usize,
     pub highlight_color : color::Lcha, // TODO needed? and other fields?
+    /// The inline widget editing the argument value. Present only on unconnected argument ports
+    /// of types supported by widgets.
+    pub widget          : Option<Widget>,
 }
 
 impl Deref for Model {
@@ -0,0 +1,490 @@
+//! Inline widgets embedded on the unconnected argument ports of the node.
+//!
+//! The widget is chosen based on the argument type: numbers are edited with a slider, booleans with
+//! a toggle button and texts with a text field. Arguments with a known set of possible values (like
+//! the atoms of the argument's type) are edited with a drop-down menu. Every widget reports the
+//! value picked by the user as Enso code, which then replaces the argument in the node expression.
+
+use crate::prelude::*;
+
+use enso_frp as frp;
+use ensogl::application::Application;
+use ensogl::data::color;
+use ensogl::display::shape::*;
+use ensogl::display::shape::StyleWatch;
+use ensogl::display;
+use ensogl_gui_components::drop_down_menu;
+use ensogl_gui_components::list_view;
+use ensogl_gui_components::selector;
+use ensogl_gui_components::selector::Bounds;
+use ensogl_gui_components::toggle_button;
+use ensogl_gui_components::toggle_button::ColorableShape;
+use ensogl_gui_components::toggle_button::ToggleButton;
+use ensogl_text as text;
+use ensogl_theme as theme;
+
+use crate::node::input::area::TEXT_SIZE;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The height of all widgets.
+pub const HEIGHT : f32 = 16.0;
+
+/// The minimal width of the widget. Ports with shorter code get widgets of this width.
+pub const MIN_WIDTH : f32 = 40.0;
+
+/// The range of values selectable with the number slider, if the current value fits in it.
+const DEFAULT_NUMBER_BOUNDS : (f32,f32) = (0.0,100.0);
+
+/// The number of fraction digits kept when setting non-integer numbers with the slider.
+const NUMBER_PRECISION : i32 = 2;
+
+
+
+// ======================
+// === Checkbox Shape ===
+// ======================
+
+/// The shape of the boolean toggle button. Its state is indicated by its color.
+pub mod checkbox {
+    use super::*;
+
+    ensogl::define_shape_system! {
+        (color_rgba:Vector4<f32>) {
+            let fill_color = Var::<color::Rgba>::from(color_rgba);
+            let width      = Var::<Pixels>::from("input_size.x");
+            let height     = Var::<Pixels>::from("input_size.y");
+            let size       = &height * 0.6;
+            let check      = Rect((&size,&size)).corners_radius(&size * 0.25);
+            let check      = check.fill(fill_color);
+            let hover_area = Rect((width,height)).fill(HOVER_COLOR);
+            (check + hover_area).into()
+        }
+    }
+
+    impl ColorableShape for DynamicShape {
+        fn set_color(&self, color:color::Rgba) {
+            self.color_rgba.set(Vector4::new(color.red,color.green,color.blue,color.alpha));
+        }
+    }
+}
+
+
+
+// ============
+// === Kind ===
+// ============
+
+/// The kind of widget used to edit an argument.
+#[derive(Clone,Debug,PartialEq)]
+pub enum Kind {
+    /// A slider. The values set for integer arguments are rounded.
+    Number {is_integer:bool},
+    /// A toggle button.
+    Boolean,
+    /// A text field.
+    Text,
+    /// A drop-down menu with the possible argument values, given as code.
+    Choice(Rc<Vec<String>>),
+}
+
+impl Kind {
+    /// Choose the widget for an argument of type `tp` which may take one of the `choices` values.
+    /// Returns [`None`] if there is no widget able to edit such argument.
+    pub fn new(tp:Option<&str>, choices:&[String]) -> Option<Self> {
+        if !choices.is_empty() {
+            return Some(Self::Choice(Rc::new(choices.to_vec())))
+        }
+        match tp?.rsplit('.').next()? {
+            "Number" | "Decimal" => Some(Self::Number {is_integer:false}),
+            "Integer"            => Some(Self::Number {is_integer:true}),
+            "Boolean"            => Some(Self::Boolean),
+            "Text"               => Some(Self::Text),
+            _                    => None,
+        }
+    }
+
+    /// Check if the widget can display the argument given as `code`. The [`None`] means that the
+    /// argument is not applied yet, which any widget can handle.
+    ///
+    /// The widgets replace the whole argument expression, so they are not displayed over arguments
+    /// which are not plain literals (like variables or method calls).
+    pub fn can_edit(&self, code:Option<&str>) -> bool {
+        match (self,code) {
+            (_                   , None)       => true,
+            (Self::Number {..}   , Some(code)) => parse_number(code).is_some(),
+            (Self::Boolean       , Some(code)) => parse_boolean(code).is_some(),
+            (Self::Text          , Some(code)) => parse_text(code).is_some(),
+            (Self::Choice(values), Some(code)) => values.iter().any(|value| value == code.trim()),
+        }
+    }
+}
+
+
+
+// ================
+// === Literals ===
+// ================
+
+/// Read the number literal. Returns [`None`] if the code is not a number literal.
+pub fn parse_number(code:&str) -> Option<f32> {
+    code.trim().parse().ok()
+}
+
+/// Print the number as Enso literal. Non-integer numbers are rounded to [`NUMBER_PRECISION`]
+/// fraction digits, but always keep the decimal point.
+pub fn number_literal(value:f32, is_integer:bool) -> String {
+    if is_integer {
+        (value.round() as i64).to_string()
+    } else {
+        let scale   = 10.0_f32.powi(NUMBER_PRECISION);
+        let rounded = (value * scale).round() / scale;
+        if rounded.fract() == 0.0 { format!("{:.1}",rounded) } else { rounded.to_string() }
+    }
+}
+
+/// Read the boolean literal. Returns [`None`] if the code is not `True` nor `False`.
+pub fn parse_boolean(code:&str) -> Option<bool> {
+    match code.trim() {
+        "True"  => Some(true),
+        "False" => Some(false),
+        _       => None,
+    }
+}
+
+/// Print the boolean as Enso literal.
+pub fn boolean_literal(value:bool) -> String {
+    if value { "True".into() } else { "False".into() }
+}
+
+/// Read the content of the text literal. Returns [`None`] if the code is not a single-line text
+/// literal.
+pub fn parse_text(code:&str) -> Option<String> {
+    let code  = code.trim();
+    let quote = code.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let inner = code.strip_prefix(quote)?.strip_suffix(quote)?;
+    let mut content = String::with_capacity(inner.len());
+    let mut chars   = inner.chars();
+    while let Some(char) = chars.next() {
+        match char {
+            '\\'            => content.push(chars.next()?),
+            c if c == quote => return None,
+            c               => content.push(c),
+        }
+    }
+    Some(content)
+}
+
+/// Print the text as Enso literal.
+pub fn text_literal(content:&str) -> String {
+    let escaped = content.replace('\\',"\\\\").replace('"',"\\\"");
+    iformat!("\"{escaped}\"")
+}
+
+/// The range of the slider displaying the given number. It is extended if the number does not fit
+/// in the default bounds.
+pub fn number_bounds(value:Option<f32>) -> Bounds {
+    let (start,end) = DEFAULT_NUMBER_BOUNDS;
+    let value       = value.unwrap_or(start);
+    Bounds::new(start.min(value),end.max(value * 2.0))
+}
+
+
+
+// ===========
+// === Frp ===
+// ===========
+
+ensogl::define_endpoints! {
+    Input {
+        /// Show or hide the widget. The widgets should be hidden when the port is connected or
+        /// the node is being edited.
+        set_visible (bool),
+    }
+    Output {
+        /// The argument value set by the user, given as code.
+        value_set (String),
+    }
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+/// The component displayed by the widget.
+#[derive(Clone,CloneRef,Debug)]
+enum View {
+    Number  {picker:selector::NumberPicker, is_integer:bool},
+    Boolean {button:ToggleButton<checkbox::DynamicShape>},
+    Text    {area:text::Area},
+    Choice  {menu:drop_down_menu::DropDownMenu, values:Rc<Vec<String>>},
+}
+
+impl display::Object for View {
+    fn display_object(&self) -> &display::object::Instance {
+        match self {
+            Self::Number  {picker,..} => picker.display_object(),
+            Self::Boolean {button}    => button.display_object(),
+            Self::Text    {area}      => area.display_object(),
+            Self::Choice  {menu,..}   => menu.display_object(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Model {
+    display_object : display::object::Instance,
+    view           : View,
+    /// The current argument value, used to report only the values which were actually changed.
+    code : RefCell<Option<String>>,
+    /// The number picked with the slider, reported once the user releases the mouse.
+    dragged_number : Cell<Option<f32>>,
+}
+
+impl Model {
+    fn set_visible(&self, visible:bool) {
+        if visible {
+            self.display_object.add_child(&self.view)
+        } else {
+            self.view.unset_parent()
+        }
+    }
+
+    /// Store the new argument value. Returns it back if it is different from the previous one.
+    fn update_code(&self, code:String) -> Option<String> {
+        let changed = self.code.borrow().as_ref() != Some(&code);
+        changed.as_some_from(|| {
+            *self.code.borrow_mut() = Some(code.clone());
+            code
+        })
+    }
+}
+
+
+
+// ==============
+// === Widget ===
+// ==============
+
+/// The widget editing a single argument. Its origin is in the center of the port.
+#[derive(Clone,CloneRef,Debug)]
+pub struct Widget {
+    pub frp : Frp,
+    model   : Rc<Model>,
+}
+
+impl Deref for Widget {
+    type Target = Frp;
+    fn deref(&self) -> &Self::Target {
+        &self.frp
+    }
+}
+
+impl Widget {
+    /// Constructor. The `code` is the current argument value, or [`None`] if the argument is not
+    /// applied yet. The widget is at least [`MIN_WIDTH`] wide.
+    pub fn new
+    (logger:impl AnyLogger, app:&Application, kind:&Kind, code:Option<&str>, width:f32) -> Self {
+        let logger         = Logger::new_sub(logger,"widget");
+        let display_object = display::object::Instance::new(&logger);
+        let size           = Vector2(width.max(MIN_WIDTH),HEIGHT);
+        let code           = code.map(|code| code.trim());
+        let styles         = StyleWatch::new(&app.display.scene().style_sheet);
+        let (view,code) = match kind {
+            Kind::Number {is_integer} => {
+                let picker = app.new_view::<selector::NumberPicker>();
+                let value  = code.and_then(parse_number);
+                let bounds = number_bounds(value);
+                picker.resize(size);
+                picker.allow_click_selection(true);
+                picker.set_bounds(bounds);
+                picker.set_value(value.unwrap_or(bounds.start));
+                let is_integer = *is_integer;
+                let code       = value.map(|value| number_literal(value,is_integer));
+                (View::Number {picker,is_integer},code)
+            }
+            Kind::Boolean => {
+                let button = ToggleButton::new(&logger);
+                let value  = code.and_then(parse_boolean);
+                let scheme = toggle_button::ColorScheme {
+                    non_toggled : Some(styles.get_color(theme::code::syntax::disabled).into()),
+                    toggled     : Some(styles.get_color(theme::code::syntax::base).into()),
+                    ..default()
+                };
+                button.set_size(Vector2(HEIGHT,HEIGHT));
+                button.set_color_scheme(&scheme);
+                button.set_visibility(true);
+                button.set_state(value.unwrap_or_default());
+                (View::Boolean {button},value.map(boolean_literal))
+            }
+            Kind::Text => {
+                let area    = app.new_view::<text::Area>();
+                let content = code.and_then(parse_text).unwrap_or_default();
+                let color   = styles.get_color(theme::code::syntax::base);
+                area.single_line(true);
+                area.set_default_color(color);
+                area.set_default_text_size(text::Size(TEXT_SIZE));
+                area.set_content(content.clone());
+                area.set_position_xy(Vector2(-size.x/2.0,TEXT_SIZE/2.0));
+                (View::Text {area},Some(text_literal(&content)))
+            }
+            Kind::Choice(values) => {
+                let menu     = drop_down_menu::DropDownMenu::new(app);
+                let selected = code.and_then(|code| values.iter().position(|v| v == code));
+                let provider = list_view::entry::AnyModelProvider::from(values.clone_ref());
+                menu.set_icon_size(Vector2(HEIGHT,HEIGHT));
+                menu.set_icon_padding(Vector2(HEIGHT/4.0,HEIGHT/4.0));
+                menu.set_menu_offset_y(HEIGHT);
+                menu.set_entries(provider);
+                menu.set_selected(selected);
+                let values   = values.clone_ref();
+                (View::Choice {menu,values},code.map(|code| code.to_owned()))
+            }
+        };
+        let code           = RefCell::new(code);
+        let dragged_number = default();
+        let model          = Model {display_object,view,code,dragged_number};
+        let frp            = Frp::new();
+        model.set_visible(true);
+        Self {frp,model:Rc::new(model)}.init(app)
+    }
+
+    /// Initialize the FRP network. Must be called after all initial values are set to the view, so
+    /// they are not reported as set by the user.
+    fn init(self, app:&Application) -> Self {
+        let network    = &self.frp.network;
+        let frp        = &self.frp;
+        let model      = &self.model;
+        let mouse      = &app.display.scene().mouse.frp;
+        let value_code = self.init_view_frp(&model.view,mouse);
+
+        frp::extend! { network
+            eval frp.set_visible ((visible) model.set_visible(*visible));
+            value_set <- value_code.filter_map(f!((code) model.update_code(code.clone())));
+            frp.source.value_set <+ value_set;
+        }
+        self
+    }
+
+    /// Connect the view's events, returning the stream of values set by the user in the view.
+    fn init_view_frp(&self, view:&View, mouse:&frp::io::Mouse) -> frp::Stream<String> {
+        let network = &self.frp.network;
+        let model   = &self.model;
+        match view {
+            View::Number {picker,is_integer} => {
+                let is_integer = *is_integer;
+                frp::extend! { network
+                    // The value is reported once the dragging is finished, so the expression is
+                    // not modified on every mouse move.
+                    eval picker.value ((value) model.dragged_number.set(Some(*value)));
+                    released   <- mouse.up.filter_map(f_!(model.dragged_number.take()));
+                    value_code <- released.map(move |value| number_literal(*value,is_integer));
+                }
+                value_code.into()
+            }
+            View::Boolean {button} => {
+                frp::extend! { network
+                    value_code <- button.state.map(|state| boolean_literal(*state));
+                }
+                value_code.into()
+            }
+            View::Text {area} => {
+                frp::extend! { network
+                    press_inside     <- mouse.down.gate(&area.hovered);
+                    press_outside    <- mouse.down.gate_not(&area.hovered);
+                    editing          <- bool(&press_outside,&press_inside).on_change();
+                    editing_finished <- editing.on_false();
+                    eval editing ((editing) area.set_focus(editing));
+                    eval_ press_inside (area.set_cursor_at_mouse_position());
+                    eval_ editing_finished (area.remove_all_cursors());
+
+                    content    <- area.content.sample(&editing_finished);
+                    value_code <- content.map(|content| text_literal(&content.to_string()));
+                }
+                value_code.into()
+            }
+            View::Choice {menu,values} => {
+                let values = values.clone_ref();
+                frp::extend! { network
+                    value_code <- menu.chosen_entry.filter_map(move |id|
+                        id.and_then(|id| values.get(id).cloned()));
+                }
+                value_code.into()
+            }
+        }
+    }
+}
+
+impl display::Object for Widget {
+    fn display_object(&self) -> &display::object::Instance {
+        &self.model.display_object
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn choosing_widget_kind() {
+        let choices = vec!["Ordering.Less".to_owned(),"Ordering.Greater".to_owned()];
+        let integer = Some(Kind::Number {is_integer:true});
+        assert_eq!(Kind::new(Some("Standard.Base.Data.Numbers.Integer"),&[]), integer);
+        assert_eq!(Kind::new(Some("Number"),&[])          , Some(Kind::Number {is_integer:false}));
+        assert_eq!(Kind::new(Some("Boolean"),&[])         , Some(Kind::Boolean));
+        assert_eq!(Kind::new(Some("Builtins.Main.Text"),&[]), Some(Kind::Text));
+        assert_eq!(Kind::new(Some("Table"),&[])           , None);
+        assert_eq!(Kind::new(None,&[])                    , None);
+        let choice = Kind::new(Some("Ordering"),&choices).unwrap();
+        assert_eq!(choice, Kind::Choice(Rc::new(choices)));
+
+        assert!(Kind::Boolean.can_edit(None));
+        assert!(Kind::Boolean.can_edit(Some(" True")));
+        assert!(!Kind::Boolean.can_edit(Some("x")));
+        assert!(Kind::Number {is_integer:true}.can_edit(Some("-12.5")));
+        assert!(!Kind::Number {is_integer:true}.can_edit(Some("foo 12")));
+        assert!(Kind::Text.can_edit(Some("'single'")));
+        assert!(!Kind::Text.can_edit(Some("\"a\" + \"b\"")));
+        assert!(choice.can_edit(Some("Ordering.Less")));
+        assert!(!choice.can_edit(Some("Ordering.Equal")));
+    }
+
+    #[test]
+    fn converting_literals() {
+        assert_eq!(number_literal(2.4,true)      , "2");
+        assert_eq!(number_literal(-2.6,true)     , "-3");
+        assert_eq!(number_literal(2.0,false)     , "2.0");
+        assert_eq!(number_literal(1.23456,false) , "1.23");
+        assert_eq!(parse_number(" 12.5 ")        , Some(12.5));
+        assert_eq!(parse_boolean("False")        , Some(false));
+        assert_eq!(boolean_literal(true)         , "True");
+
+        assert_eq!(parse_text(r#""say \"hi\"""#) , Some(r#"say "hi""#.to_owned()));
+        assert_eq!(parse_text("'it'")            , Some("it".to_owned()));
+        assert_eq!(parse_text("\"unclosed")      , None);
+        assert_eq!(text_literal(r#"a\b"c"#)      , r#""a\\b\"c""#);
+        let text = r#"back\slash "quoted""#;
+        assert_eq!(parse_text(&text_literal(text)).as_deref(), Some(text));
+    }
+
+    #[test]
+    fn extending_number_bounds() {
+        let bounds = |value| { let b = number_bounds(value); (b.start,b.end) };
+        assert_eq!(bounds(None)         , (0.0,100.0));
+        assert_eq!(bounds(Some(20.0))   , (0.0,100.0));
+        assert_eq!(bounds(Some(300.0))  , (0.0,600.0));
+        assert_eq!(bounds(Some(-5.0))   , (-5.0,100.0));
+    }
+}
@@ -7,6 +7,7 @@ use crate::Type;
 
 use ensogl::data::color;
 use ensogl::display::shape::StyleWatch;
+use ensogl::display::style::Path;
 use ensogl::display::style::data::DataMatch;
 use ensogl_theme as theme;
 use std::collections::hash_map::DefaultHasher;
@@ -14,42 +15,225 @@ use std::hash::Hasher;
 
 
 
+// ================
+// === TypeExpr ===
+// ================
+
+/// A parsed type expression, like `Vector Number` or `Map Text (Vector Integer)`.
+///
+/// The type is described by its constructor name (possibly qualified, like
+/// `Builtins.Main.Number`) and the list of its parameters. Type variables (like `a` in
+/// `Vector a`) are represented as constructors without parameters.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct TypeExpr {
+    /// The type constructor name.
+    pub constructor : String,
+    /// The parameters the constructor is applied to.
+    pub parameters : Vec<TypeExpr>,
+}
+
+impl TypeExpr {
+    /// Create a type expression with no parameters.
+    pub fn new(constructor:impl Into<String>) -> Self {
+        let constructor = constructor.into();
+        let parameters  = default();
+        Self {constructor,parameters}
+    }
+
+    /// Parse the type expression. Returns [`None`] if the code is not a constructor application
+    /// (e.g. it is a function type or is not properly parenthesized).
+    pub fn parse(code:&str) -> Option<Self> {
+        let mut tokens = tokenize(code)?.into_iter().peekable();
+        let expr       = Self::parse_application(&mut tokens)?;
+        tokens.next().is_none().as_some(expr)
+    }
+
+    fn parse_application(tokens:&mut Tokens) -> Option<Self> {
+        let mut expr = Self::parse_atom(tokens)?;
+        while matches!(tokens.peek(),Some(Token::Open) | Some(Token::Name(_))) {
+            let parameter = Self::parse_atom(tokens)?;
+            expr.parameters.push(parameter);
+        }
+        Some(expr)
+    }
+
+    fn parse_atom(tokens:&mut Tokens) -> Option<Self> {
+        match tokens.next()? {
+            Token::Name(name) => Some(Self::new(name)),
+            Token::Open       => {
+                let expr = Self::parse_application(tokens)?;
+                (tokens.next()? == Token::Close).as_some(expr)
+            }
+            Token::Close      => None,
+        }
+    }
+}
+
+impl Display for TypeExpr {
+    fn fmt(&self, f:&mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f,"{}",self.constructor)?;
+        for parameter in &self.parameters {
+            if parameter.parameters.is_empty() { write!(f," {}",parameter)?   }
+            else                               { write!(f," ({})",parameter)? }
+        }
+        Ok(())
+    }
+}
+
+
+// === Tokenizer ===
+
+#[derive(Clone,Debug,Eq,PartialEq)]
+enum Token {
+    Name(String),
+    Open,
+    Close,
+}
+
+type Tokens = std::iter::Peekable<std::vec::IntoIter<Token>>;
+
+fn tokenize(code:&str) -> Option<Vec<Token>> {
+    let is_name_char = |c:char| c.is_alphanumeric() || c == '_' || c == '.';
+    let mut tokens   = Vec::new();
+    let mut chars    = code.chars().peekable();
+    while let Some(char) = chars.next() {
+        match char {
+            '('                    => tokens.push(Token::Open),
+            ')'                    => tokens.push(Token::Close),
+            c if c.is_whitespace() => {},
+            c if is_name_char(c)   => {
+                let mut name = c.to_string();
+                while let Some(c) = chars.peek().copied().filter(|c| is_name_char(*c)) {
+                    name.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Name(name));
+            }
+            _ => return None,
+        }
+    }
+    Some(tokens)
+}
+
+
+
+// ===================
+// === StyleSource ===
+// ===================
+
+/// The source of the numeric theme values used to compute type colors. It allows testing the
+/// color resolution without the style sheet.
+pub trait StyleSource {
+    /// Get the number under the given path, if defined.
+    fn number(&self, path:&Path) -> Option<f32>;
+
+    /// Get the number under the given path, or the `fallback` if not defined.
+    fn number_or(&self, path:impl Into<Path>, fallback:f32) -> f32 {
+        self.number(&path.into()).unwrap_or(fallback)
+    }
+}
+
+impl StyleSource for StyleWatch {
+    fn number(&self, path:&Path) -> Option<f32> {
+        self.get(path).number()
+    }
+}
+
+
+
+// =================
+// === TypeColor ===
+// =================
+
+/// The style of the edges carrying values of some type.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub enum EdgeStyle {
+    /// Solid line, used for most types.
+    Solid,
+    /// Dashed line, used for container types colored by their element type.
+    Dashed,
+}
+
+impl Default for EdgeStyle {
+    fn default() -> Self {
+        Self::Solid
+    }
+}
+
+/// The result of the type color resolution.
+#[derive(Clone,Copy,Debug,PartialEq)]
+#[allow(missing_docs)]
+pub struct TypeColor {
+    pub color      : color::Lcha,
+    pub edge_style : EdgeStyle,
+}
+
+/// Resolve the color and edge style of the given type.
+///
+/// ## Parametric Types
+/// The type is parsed as a constructor applied to parameters. By default only the constructor
+/// determines the color, so `Point Float` and `Point Number` look the same, and distinct from
+/// their parameter types. Types which fail to parse are colored by their whole name.
+///
+/// ## Container Types
+/// A constructor can be marked as a container by defining the index of its element parameter in
+/// the theme, like `code::types::overriden::Builtins::Main::Array::container = 0`. Containers
+/// take the color of their element type (so `Array Number` is colored like `Number`) and use the
+/// [`EdgeStyle::Dashed`] style.
+///
+/// ## Theme Defined Colors
+/// The theme can override the hue, lightness and chroma of any constructor, like
+/// `code::types::overriden::Builtins::Main::Text::hue = 0.3`. The overrides are applied on top of
+/// the computed color, also for containers.
+pub fn resolve(tp:&str, styles:&impl StyleSource) -> TypeColor {
+    match TypeExpr::parse(tp) {
+        Some(expr) => resolve_expr(&expr,styles),
+        None       => resolve_expr(&TypeExpr::new(tp.trim()),styles),
+    }
+}
+
+/// Resolve the color and edge style of the parsed type expression. See [`resolve`].
+pub fn resolve_expr(tp:&TypeExpr, styles:&impl StyleSource) -> TypeColor {
+    let path      = constructor_path(&tp.constructor);
+    let container = styles.number(&path.sub("container"));
+    let element   = container.and_then(|index| tp.parameters.get(index as usize));
+    let base      = match element {
+        Some(element) => resolve_expr(element,styles).color,
+        None          => default_color(&tp.constructor,styles),
+    };
+    let hue        = styles.number(&path.sub("hue")).unwrap_or(base.hue);
+    let lightness  = styles.number(&path.sub("lightness")).unwrap_or(base.lightness);
+    let chroma     = styles.number(&path.sub("chroma")).unwrap_or(base.chroma);
+    let color      = color::Lch::new(lightness,chroma,hue).into();
+    let edge_style = if container.is_some() {EdgeStyle::Dashed} else {EdgeStyle::Solid};
+    TypeColor {color,edge_style}
+}
+
+fn constructor_path(constructor:&str) -> Path {
+    let types_path = theme::code::types::overriden::HERE.path();
+    types_path.into_subs(constructor.split('.'))
+}
+
+fn default_color(constructor:&str, styles:&impl StyleSource) -> color::Lch {
+    let hue       = auto_hue(constructor,styles);
+    let lightness = styles.number_or(theme::code::types::lightness,0.85);
+    let chroma    = styles.number_or(theme::code::types::chroma,0.6);
+    color::Lch::new(lightness,chroma,hue)
+}
+
+
+
 // ================================
 // === Type to Color Conversion ===
 // ================================
 
 /// Return the color that corresponds to the given type. Can be used to color edges and ports.
 ///
-/// ## Overview
-/// Currently, this is a very simple mechanism, which infers color based on the input type name.
-/// There are many ways we can improve it in the future, but it will also require Engine to develop
-/// better type inference mechanisms. For example, currently we do not get information about
-/// parametric types, so we don't know whether something is a `Vector Number`, `Vector String`, or
-/// something else. It is just `Vector` and it is equivalent to `Vector Any`.
-///
-/// ## Theme Defined Colors
-/// Please note that this function queries the currently selected theme for special colors. Themes
-/// can define color overrides, like `code::types::String::hue = 0.3` to override the selected
-/// color hue.
-///
-/// ## Future Development
-/// There are few important ideas regarding future development of this mechanism. First of all,
-/// user defining a new type should be able to mark it as a "container" type and point which of
-/// parameters should be used to obtain the color. So for example, user defining the type
-/// `Vector a` should be able to describe it as a "container", and point `a` as the type of its
-/// items. This way, `Vector Number` should be colored in similar way to `Number` (maybe with some
-/// additional "container edge style", like dashed lines). If user do not provide such
-/// parametrization, other mechanisms should be used. For example, `Point Float` and `Point Number`
-/// should have similar colors, completely distinct from their parameter types.
+/// The color is inferred from the type name, understanding parametric and container types. Please
+/// note that this function queries the currently selected theme for special colors. See
+/// [`resolve`] to learn more.
 pub fn compute(tp:&Type, styles:&StyleWatch) -> color::Lcha {
-    let types_path = theme::code::types::overriden::HERE.path();
-    let type_path  = types_path.into_subs(tp.as_str().split('.'));
-    let hue = styles.get(type_path.sub("hue")).number_or_else(|| auto_hue(tp,styles));
-    let lightness = styles.get(type_path.sub("lightness")).number_or_else(||
-        styles.get_number_or(theme::code::types::lightness,0.85));
-    let chroma = styles.get(type_path.sub("chroma")).number_or_else(||
-        styles.get_number_or(theme::code::types::chroma,0.6));
-    color::Lch::new(lightness,chroma,hue).into()
+    resolve(tp.as_str(),styles).color
 }
 
 /// Get the code color for the provided type or default code color in case the type is None.
@@ -64,12 +248,12 @@ pub fn compute_for_selection(tp:Option<&Type>, styles:&StyleWatch) -> color::Lch
     opt_color.unwrap_or_else(|| styles.get_color(theme::code::types::any::selection).into())
 }
 
-/// Computes LCH hue value based on incoming type information.
-fn auto_hue(tp:&Type, styles:&StyleWatch) -> f32 {
+/// Computes LCH hue value based on incoming type constructor name.
+fn auto_hue(constructor:&str, styles:&impl StyleSource) -> f32 {
     // Defines how many hue values we can have based on our incoming type name.
-    let hue_steps = styles.get_number_or(theme::code::types::hue_steps,512.0);
-    let hue_shift = styles.get_number_or(theme::code::types::hue_shift,0.0);
-    (hash(tp) % (hue_steps as u64)) as f32 / hue_steps + hue_shift
+    let hue_steps = styles.number_or(theme::code::types::hue_steps,512.0);
+    let hue_shift = styles.number_or(theme::code::types::hue_shift,0.0);
+    (hash(constructor) % (hue_steps as u64)) as f32 / hue_steps + hue_shift
 }
 
 /// Compute the hash of the type for use in the `compute` function.
@@ -78,3 +262,97 @@ fn hash(s:&str) -> u64 {
     s.hash(&mut hasher);
     hasher.finish()
 }
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug,Default)]
+    struct MockStyles {
+        values : Vec<(Path,f32)>,
+    }
+
+    impl MockStyles {
+        fn set(mut self, path:impl Into<Path>, value:f32) -> Self {
+            self.values.push((path.into(),value));
+            self
+        }
+
+        fn set_for(self, constructor:&str, key:&str, value:f32) -> Self {
+            self.set(constructor_path(constructor).sub(key),value)
+        }
+    }
+
+    impl StyleSource for MockStyles {
+        fn number(&self, path:&Path) -> Option<f32> {
+            self.values.iter().find(|(p,_)| p == path).map(|(_,value)| *value)
+        }
+    }
+
+    #[test]
+    fn parsing_type_expressions() {
+        let expr = |code:&str| TypeExpr::parse(code).map(|t| t.to_string());
+        assert_eq!(expr("Number")                    , Some("Number".to_owned()));
+        assert_eq!(expr(" Std.Vector  Number ")      , Some("Std.Vector Number".to_owned()));
+        assert_eq!(expr("Map Text (List Int)")       , Some("Map Text (List Int)".to_owned()));
+        assert_eq!(expr("(Vector a)")                , Some("Vector a".to_owned()));
+        assert_eq!(expr("(Map Text) a")              , Some("Map Text a".to_owned()));
+        assert_eq!(expr("a -> b")                    , None);
+        assert_eq!(expr("Vector (Number")            , None);
+        assert_eq!(expr("Vector Number)")            , None);
+        assert_eq!(expr("")                          , None);
+
+        let vector = TypeExpr::parse("Vector (Maybe Text)").unwrap();
+        assert_eq!(vector.constructor, "Vector");
+        assert_eq!(vector.parameters.len(), 1);
+        assert_eq!(vector.parameters[0].constructor, "Maybe");
+        assert_eq!(vector.parameters[0].parameters, vec![TypeExpr::new("Text")]);
+    }
+
+    #[test]
+    fn coloring_parametric_types() {
+        let styles = MockStyles::default().set_for("Vector","container",0.0);
+        let color  = |tp:&str| resolve(tp,&styles);
+
+        assert_ne!(color("Number").color, color("Text").color);
+        assert_eq!(color("Number").edge_style, EdgeStyle::Solid);
+        assert_eq!(color("Point Float").color, color("Point Number").color);
+        assert_ne!(color("Point Float").color, color("Float").color);
+        assert_eq!(color("Point Float").edge_style, EdgeStyle::Solid);
+
+        assert_eq!(color("Vector Number").color, color("Number").color);
+        assert_eq!(color("Vector (Point Float)").color, color("Point").color);
+        assert_ne!(color("Vector Number").color, color("Vector Text").color);
+        assert_eq!(color("Vector Number").edge_style, EdgeStyle::Dashed);
+        assert_eq!(color("Vector").color, resolve("Vector",&MockStyles::default()).color);
+        assert_eq!(color("Vector").edge_style, EdgeStyle::Dashed);
+        assert_ne!(color("a -> b").color, color("a").color);
+    }
+
+    #[test]
+    fn applying_theme_overrides() {
+        let styles = MockStyles::default()
+            .set(theme::code::types::lightness,0.5)
+            .set(theme::code::types::chroma,0.4)
+            .set_for("Builtins.Main.Number","hue",0.25)
+            .set_for("Builtins.Main.Text","chroma",0.1)
+            .set_for("List","container",1.0)
+            .set_for("List","lightness",0.9);
+        let color = |tp:&str| resolve(tp,&styles).color.opaque;
+
+        let number = color("Builtins.Main.Number");
+        assert_eq!((number.lightness,number.chroma,number.hue), (0.5,0.4,0.25));
+        let text = color("Builtins.Main.Text");
+        assert_eq!((text.lightness,text.chroma), (0.5,0.1));
+        let list = color("List Text Builtins.Main.Number");
+        assert_eq!((list.lightness,list.chroma,list.hue), (0.9,0.4,0.25));
+        assert_eq!(resolve("List Text",&styles).edge_style, EdgeStyle::Dashed);
+        assert_eq!(color("List Text").hue, color("List").hue);
+    }
+}
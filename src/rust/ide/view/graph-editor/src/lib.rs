@@ -557,6 +557,8 @@ ensogl::define_endpoints! {
         node_position_set         ((NodeId,Vector2)),
         node_position_set_batched ((NodeId,Vector2)),
         node_expression_set       ((NodeId,String)),
+        /// The argument value (given as code) was set with the inline widget on the input port.
+        node_argument_value_set   ((NodeId,span_tree::Crumbs,String)),
         node_comment_set          ((NodeId,String)),
         node_entered              (NodeId),
         node_exited               (),
@@ -1204,6 +1206,10 @@ impl GraphEditorModelWithNetwork {
                 input_press.emit(target);
             );
 
+            eval node.model.input.frp.on_port_code_update ([output]((crumbs,code))
+                output.source.node_argument_value_set.emit((node_id,crumbs.clone(),code.clone()))
+            );
+
             eval node.model.input.frp.on_port_hover ([model](t) {
                 let crumbs = t.on();
                 let target = crumbs.map(|c| EdgeEndpoint::new(node_id,c.clone()));
@@ -1694,6 +1700,20 @@ impl GraphEditorModel {
         self.edges.detached_target.raw.borrow().clone()
     }
 
+    /// The source endpoints of all edges being dragged from an output port, i.e. having their
+    /// targets detached.
+    pub fn sources_of_edges_with_detached_targets(&self) -> Vec<EdgeEndpoint> {
+        let edges = self.edges.detached_target.raw.borrow().clone();
+        edges.into_iter().filter_map(|edge_id| self.edge_source(edge_id)).collect()
+    }
+
+    /// The target endpoints of all edges being dragged from an input port, i.e. having their
+    /// sources detached.
+    pub fn targets_of_edges_with_detached_sources(&self) -> Vec<EdgeEndpoint> {
+        let edges = self.edges.detached_source.raw.borrow().clone();
+        edges.into_iter().filter_map(|edge_id| self.edge_target(edge_id)).collect()
+    }
+
     pub fn clear_all_detached_edges(&self) -> Vec<EdgeId>{
         let source_edges = self.edges.detached_source.mem_take();
         source_edges.iter().for_each(|edge| {self.edges.all.remove(edge);});
@@ -1813,7 +1833,9 @@ impl GraphEditorModel {
     pub fn refresh_edge_color(&self, edge_id:EdgeId, neutral_color:color::Lcha) {
         if let Some(edge) = self.edges.get_cloned_ref(&edge_id) {
             let color = self.edge_color(edge_id, neutral_color);
+            let style = self.edge_style(edge_id);
             edge.view.frp.set_color.emit(color);
+            edge.view.frp.set_style.emit(style);
         };
     }
 
@@ -1975,6 +1997,18 @@ impl GraphEditorModel {
     /// consider the edge source type to be `(a,Number)`, and target to be `(Text,a)`. These unify
     /// to `(Text,Number)`.
     fn edge_color(&self, edge_id:EdgeId, neutral_color:color::Lcha) -> color::Lcha {
+        let type_color = self.edge_type_color(edge_id);
+        type_color.map(|t| t.color).unwrap_or(neutral_color)
+    }
+
+    /// Return the line style of the edge. Edges carrying container types (see
+    /// `type_coloring::resolve`) are dashed. The type is chosen the same way as in `edge_color`.
+    fn edge_style(&self, edge_id:EdgeId) -> type_coloring::EdgeStyle {
+        let type_color = self.edge_type_color(edge_id);
+        type_color.map(|t| t.edge_style).unwrap_or_default()
+    }
+
+    fn edge_type_color(&self, edge_id:EdgeId) -> Option<type_coloring::TypeColor> {
         // FIXME : StyleWatch is unsuitable here, as it was designed as an internal tool for shape system (#795)
         let styles = StyleWatch::new(&self.scene().style_sheet);
         match self.frp.view_mode.value() {
@@ -1982,12 +2016,9 @@ impl GraphEditorModel {
                 let edge_type = self.edge_hover_type()
                     .or_else(|| self.edge_target_type(edge_id))
                     .or_else(|| self.edge_source_type(edge_id));
-                let opt_color = edge_type.map(|t|type_coloring::compute(&t,&styles));
-                opt_color.unwrap_or(neutral_color)
+                edge_type.map(|t| type_coloring::resolve(t.as_str(),&styles))
             },
-            view::Mode::Profiling => {
-                neutral_color
-            }
+            view::Mode::Profiling => None,
         }
     }
 
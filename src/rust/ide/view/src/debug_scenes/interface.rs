@@ -287,7 +287,8 @@ pub fn expression_mock_string(label:&str) -> Expression {
     let output_span_tree    = span_tree::SpanTree::default();
     let input_span_tree     = span_tree::SpanTree::new(&ast,&ctx).unwrap();
     let whole_expression_id = default();
-    Expression {pattern,code,whole_expression_id,input_span_tree,output_span_tree}
+    let argument_choices    = default();
+    Expression {pattern,code,whole_expression_id,input_span_tree,output_span_tree,argument_choices}
 }
 
 pub fn expression_mock() -> Expression {
@@ -305,7 +306,8 @@ pub fn expression_mock() -> Expression {
     let output_span_tree = span_tree::SpanTree::default();
     let input_span_tree  = span_tree::SpanTree::new(&ast,&ctx).unwrap();
     let whole_expression_id = default();
-    Expression {pattern,code,whole_expression_id,input_span_tree,output_span_tree}
+    let argument_choices    = default();
+    Expression {pattern,code,whole_expression_id,input_span_tree,output_span_tree,argument_choices}
 }
 
 pub fn expression_mock2() -> Expression {
@@ -344,7 +346,8 @@ pub fn expression_mock2() -> Expression {
         .add_empty_child(36,span_tree::node::InsertionPointType::Append)
         .build();
     let whole_expression_id = default();
-    Expression {pattern,code,whole_expression_id,input_span_tree,output_span_tree}
+    let argument_choices    = default();
+    Expression {pattern,code,whole_expression_id,input_span_tree,output_span_tree,argument_choices}
 }
 
 pub fn expression_mock3() -> Expression {
@@ -379,5 +382,6 @@ pub fn expression_mock3() -> Expression {
     let output_span_tree = span_tree::SpanTree::new(&ast,&ctx).unwrap();//span_tree::SpanTree::default();
     let input_span_tree  = span_tree::SpanTree::new(&ast,&ctx).unwrap();
     let whole_expression_id = default();
-    Expression {pattern,code,whole_expression_id,input_span_tree,output_span_tree}
+    let argument_choices    = default();
+    Expression {pattern,code,whole_expression_id,input_span_tree,output_span_tree,argument_choices}
 }
@@ -30,6 +30,7 @@
 pub mod code_editor;
 pub mod debug_scenes;
 pub mod documentation;
+pub mod list_panel;
 pub mod open_dialog;
 pub mod project;
 pub mod searcher;
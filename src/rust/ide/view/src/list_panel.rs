@@ -1,4 +1,4 @@
-//! A module containing [`ProjectList`] component and all related structures.
+//! A module containing [`ListPanel`] component and all related structures.
 
 use crate::prelude::*;
 
@@ -7,9 +7,10 @@ use ensogl_gui_components::list_view;
 use ensogl::application::Application;
 use ensogl::display;
 use ensogl::display::shape::*;
+use ensogl::display::style;
 use ensogl_text as text;
 use ensogl_gui_components::shadow;
-use ensogl_theme::application::project_list as theme;
+use ensogl_theme::application::list_panel as theme;
 
 
 
@@ -17,7 +18,7 @@ use ensogl_theme::application::project_list as theme;
 // === Entry ===
 // =============
 
-/// The entry in project list.
+/// The entry in the list panel.
 pub type Entry = list_view::entry::Label;
 
 
@@ -56,15 +57,17 @@ mod background {
 
 
 
-// ===================
-// === ProjectList ===
-// ===================
+// =================
+// === ListPanel ===
+// =================
 
-/// The Project List GUI Component.
+/// The List Panel GUI Component.
 ///
-/// This is a list of projects in a nice frame with title.
+/// This is a list of entries in a nice frame with title, like the list of projects to open. The
+/// look of all panels is defined by the `application.list_panel` theme; the size of each panel is
+/// defined by its own theme, containing `width` and `height` values.
 #[derive(Clone,CloneRef,Debug)]
-pub struct ProjectList {
+pub struct ListPanel {
     logger         : Logger,
     network        : frp::Network,
     display_object : display::object::Instance,
@@ -74,17 +77,18 @@ pub struct ProjectList {
     style_watch    : StyleWatchFrp,
 }
 
-impl Deref for ProjectList {
+impl Deref for ListPanel {
     type Target = list_view::Frp<Entry>;
 
     fn deref(&self) -> &Self::Target { &self.list.frp }
 }
 
-impl ProjectList {
-    /// Create Project List Component.
-    pub fn new(app:&Application) -> Self {
-        let logger         = Logger::new("ProjectList");
-        let network        = frp::Network::new("ProjectList");
+impl ListPanel {
+    /// Create List Panel Component with the given caption, sized by the `width` and `height`
+    /// values of the `size_theme`.
+    pub fn new(app:&Application, caption_text:&str, size_theme:style::StaticPath) -> Self {
+        let logger         = Logger::new("ListPanel");
+        let network        = frp::Network::new("ListPanel");
         let display_object = display::object::Instance::new(&logger);
         let background     = background::View::new(&logger);
         let caption        = app.new_view::<text::Area>();
@@ -93,7 +97,7 @@ impl ProjectList {
         display_object.add_child(&caption);
         display_object.add_child(&list);
         app.display.scene().layers.panel.add_exclusive(&display_object);
-        caption.set_content("Open Project");
+        caption.set_content(caption_text);
         caption.add_to_scene_layer(&app.display.scene().layers.panel_text);
         list.set_label_layer(app.display.scene().layers.panel_text.id());
 
@@ -105,8 +109,8 @@ impl ProjectList {
         }
 
         let style_watch = StyleWatchFrp::new(&app.display.scene().style_sheet);
-        let width       = style_watch.get_number(theme::width);
-        let height      = style_watch.get_number(theme::height);
+        let width       = style_watch.get_number(size_theme.path().sub("width"));
+        let height      = style_watch.get_number(size_theme.path().sub("height"));
         let bar_height  = style_watch.get_number(theme::bar::height);
         let padding     = style_watch.get_number(theme::padding);
         let color       = style_watch.get_color(theme::bar::label::color);
@@ -137,8 +141,12 @@ impl ProjectList {
 
         Self {logger,network,display_object,background,caption,list,style_watch}
     }
+
+    /// The text area displaying the panel's caption. It may be made editable, e.g. to let the user
+    /// type a filter of the entries.
+    pub fn caption(&self) -> &text::Area { &self.caption }
 }
 
-impl display::Object for ProjectList {
+impl display::Object for ListPanel {
     fn display_object(&self) -> &display::object::Instance { &self.display_object }
 }
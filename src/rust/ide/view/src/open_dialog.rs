@@ -1,8 +1,9 @@
 //! A module with [`OpenDialog`] component.
-pub mod project_list;
 
 use crate::prelude::*;
 
+use crate::list_panel::ListPanel;
+
 use enso_frp as frp;
 use ensogl::display;
 use ensogl_gui_components::file_browser::FileBrowser;
@@ -26,7 +27,7 @@ use ensogl_theme as theme;
 pub struct OpenDialog {
     logger           : Logger,
     network          : frp::Network,
-    pub project_list : project_list::ProjectList,
+    pub project_list : ListPanel,
     pub file_browser : FileBrowser,
     display_object   : display::object::Instance,
     style_watch      : StyleWatchFrp,
@@ -38,7 +39,8 @@ impl OpenDialog {
         let logger       = Logger::new("OpenDialog");
         let network      = frp::Network::new("OpenDialog");
         let style_watch  = StyleWatchFrp::new(&app.display.scene().style_sheet);
-        let project_list = project_list::ProjectList::new(app);
+        let list_theme   = theme::application::project_list::HERE;
+        let project_list = ListPanel::new(app,"Open Project",list_theme);
         let file_browser = FileBrowser::new();
         // Once FileBrowser will be implemented as component, it should be instantiated this way:
         //let file_browser   = app.new_view::<FileBrowser>();
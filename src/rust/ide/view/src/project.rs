@@ -8,6 +8,7 @@ use crate::graph_editor::component::node::Expression;
 use crate::graph_editor::component::visualization;
 use crate::graph_editor::GraphEditor;
 use crate::graph_editor::NodeId;
+use crate::list_panel::ListPanel;
 use crate::open_dialog::OpenDialog;
 use crate::searcher;
 use crate::status_bar;
@@ -24,6 +25,17 @@ use ensogl::DEPRECATED_Animation;
 use ensogl::system::web;
 use ensogl::system::web::dom;
 use ensogl_theme::Theme as Theme;
+use ensogl_theme::file::Layer as ThemeLayer;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The status bar message displayed when the opened module becomes read-only.
+const READ_ONLY_MESSAGE : &str = "The module is read-only, because another client is editing it. \
+    Press cmd+e to request editing.";
 
 
 
@@ -53,6 +65,42 @@ ensogl::define_endpoints! {
         show_prompt(),
         /// Disable the prompt. It will be hidden if currently visible.
         disable_prompt(),
+        /// Apply the theme file contents as the given layer on top of the builtin theme. See
+        /// [`ensogl_theme::file`] for the file format.
+        set_theme_file((ThemeLayer,String)),
+        /// Remove the styles of the given theme file layer.
+        reset_theme_file(ThemeLayer),
+        /// Set whether the opened module is read-only, because another client holds its write
+        /// lock.
+        set_read_only(bool),
+        /// Request the write lock of the opened module. Has effect only in the read-only mode.
+        request_edit(),
+        /// Give up the write lock of the opened module, so other clients can edit it. Has effect
+        /// only if the module is not read-only.
+        release_edit(),
+        /// Go to the definition of the symbol under the Code Editor cursor if the editor is shown,
+        /// or of the method called in the selected node otherwise.
+        go_to_definition(),
+        /// Find the references to the symbol chosen like in `go_to_definition`.
+        find_references(),
+        /// Show the Symbols panel with the list of references. The string is the panel's caption.
+        show_references(String),
+        /// Show the Symbols panel with an input for searching the project's symbols by name.
+        go_to_symbol(),
+        /// Close the Symbols panel.
+        close_symbol_panel(),
+        /// Show the Undo History panel.
+        show_undo_history(),
+        /// Close the Undo History panel.
+        close_undo_history(),
+        /// Remove the gathered statistics of the suggestions picked in the Searcher.
+        reset_suggestion_usage(),
+        /// Enable or disable gathering the statistics of the suggestions picked in the Searcher
+        /// and ranking the suggestions by them.
+        toggle_suggestion_usage(),
+        /// Enable or disable removing the imports which are no longer used together with the
+        /// removed nodes.
+        toggle_import_optimization(),
     }
 
     Output {
@@ -69,6 +117,13 @@ ensogl::define_endpoints! {
         fullscreen_visualization_shown      (bool),
         default_gap_between_nodes           (f32),
         drop_files_enabled                  (bool),
+        read_only                           (bool),
+        edit_requested                      (),
+        edit_released                       (),
+        undo_history_shown                  (bool),
+        symbol_panel_shown                  (bool),
+        /// The pattern typed in the Symbols panel opened by `go_to_symbol`.
+        symbol_query                        (String),
     }
 }
 
@@ -117,6 +172,8 @@ struct Model {
     prompt_background      : prompt_background::View,
     prompt                 : ensogl_text::Area,
     open_dialog            : Rc<OpenDialog>,
+    undo_history           : Rc<ListPanel>,
+    symbol_panel           : Rc<ListPanel>,
 }
 
 impl Model {
@@ -139,6 +196,10 @@ impl Model {
         });
         let window_control_buttons = Immutable(window_control_buttons);
         let open_dialog            = Rc::new(OpenDialog::new(app));
+        let undo_history_theme     = ensogl_theme::application::undo_history::HERE;
+        let undo_history           = Rc::new(ListPanel::new(app,"History",undo_history_theme));
+        let symbol_panel_theme     = ensogl_theme::application::symbol_panel::HERE;
+        let symbol_panel           = Rc::new(ListPanel::new(app,"Symbols",symbol_panel_theme));
         prompt_background.add_child(&prompt);
         prompt.set_content("Press the tab key to search for components.");
         scene.layers.panel.add_exclusive(&prompt_background);
@@ -155,7 +216,8 @@ impl Model {
         let app          = app.clone_ref();
         let graph_editor = Rc::new(graph_editor);
         Self{app,logger,display_object,window_control_buttons,graph_editor,searcher,code_editor
-            ,status_bar,fullscreen_vis,prompt_background,prompt,open_dialog}
+            ,status_bar,fullscreen_vis,prompt_background,prompt,open_dialog,undo_history
+            ,symbol_panel}
     }
 
     /// Sets style of IDE to the one defined by parameter `theme`.
@@ -167,15 +229,38 @@ impl Model {
     }
 
     fn set_light_style(&self) {
-        ensogl_theme::builtin::light::enable(&self.app);
+        self.enable_builtin_theme(ensogl_theme::builtin::light::NAME);
         self.set_html_style("light-theme");
     }
 
     fn set_dark_style(&self) {
-        ensogl_theme::builtin::dark::enable(&self.app);
+        self.enable_builtin_theme(ensogl_theme::builtin::dark::NAME);
         self.set_html_style("dark-theme");
     }
 
+    fn enable_builtin_theme(&self, name:&str) {
+        ensogl_theme::file::enable_layered(&self.app.themes,name);
+    }
+
+    /// Load the theme file layer from the file contents. The invalid entries of the file are
+    /// skipped and reported in the logs.
+    fn set_theme_file(&self, layer:ThemeLayer, code:&str) {
+        let known_paths = ensogl_theme::file::known_paths(&self.app.themes);
+        match ensogl_theme::file::ThemeFile::parse(code,&known_paths) {
+            Ok(file) => {
+                for entry in &file.invalid_entries {
+                    warning!(self.logger,"Skipping the {layer:?} theme entry: {entry}");
+                }
+                self.load_theme_file(layer,&file.theme);
+            }
+            Err(err) => error!(self.logger,"Cannot load the {layer:?} theme: {err}"),
+        }
+    }
+
+    fn load_theme_file(&self, layer:ThemeLayer, theme:&ensogl::display::style::theme::Theme) {
+        ensogl_theme::file::load(&self.app.themes,layer,theme);
+    }
+
     fn set_html_style(&self, style:&'static str) {
         web::with_element_by_id_or_warn(&self.logger,"root",|root| root.set_class_name(style));
     }
@@ -275,6 +360,42 @@ impl Model {
     fn hide_open_dialog(&self) {
         self.display_object.remove_child(&*self.open_dialog);
     }
+
+    fn show_undo_history(&self) {
+        self.display_object.add_child(&*self.undo_history);
+    }
+
+    fn hide_undo_history(&self) {
+        self.display_object.remove_child(&*self.undo_history);
+    }
+
+    fn show_symbol_panel(&self) {
+        self.display_object.add_child(&*self.symbol_panel);
+    }
+
+    fn hide_symbol_panel(&self) {
+        let caption = self.symbol_panel.caption();
+        caption.defocus();
+        caption.remove_all_cursors();
+        self.display_object.remove_child(&*self.symbol_panel);
+    }
+
+    /// Make the Symbols panel's caption an empty input for the searched symbol's name.
+    fn start_symbol_search(&self) {
+        let caption = self.symbol_panel.caption();
+        caption.set_content("");
+        caption.single_line(true);
+        caption.hover();
+        caption.focus();
+        caption.set_cursor_at_end();
+    }
+
+    fn show_references(&self, caption:&str) {
+        let caption_area = self.symbol_panel.caption();
+        caption_area.defocus();
+        caption_area.remove_all_cursors();
+        caption_area.set_content(caption);
+    }
 }
 
 
@@ -366,7 +487,7 @@ impl View {
 
         // FIXME[WD]: Think how to refactor it, as it needs to be done before model, as we do not
         //   want shader recompilation. Model uses styles already.
-        model.set_style(theme);
+        model.set_style(theme.clone());
         // TODO[WD]: This should not be needed after the theme switching issue is implemented.
         //   See: https://github.com/enso-org/ide/issues/795
         app.themes.update();
@@ -490,6 +611,38 @@ impl View {
             frp.source.open_dialog_shown <+ bool(&should_be_closed,&frp.show_open_dialog);
 
 
+            // === Undo History ===
+
+            // The panel stays open after choosing an entry, so the user may jump through the
+            // history a few times.
+            eval_ frp.show_undo_history (model.show_undo_history());
+            history_should_be_closed <- any(frp.close_undo_history,clicked_on_bg);
+            eval_ history_should_be_closed (model.hide_undo_history());
+
+            frp.source.undo_history_shown <+ bool(&history_should_be_closed,&frp.show_undo_history);
+
+
+            // === Symbols Panel ===
+
+            let symbol_panel = &model.symbol_panel;
+            eval_ frp.go_to_symbol    (model.start_symbol_search());
+            eval  frp.show_references ((caption) model.show_references(caption));
+            references_shown <- frp.show_references.constant(());
+            symbol_panel_shown <- any(frp.go_to_symbol,references_shown);
+            eval_ symbol_panel_shown (model.show_symbol_panel());
+            symbol_chosen <- symbol_panel.chosen_entry.filter(|entry| entry.is_some()).constant(());
+            symbols_should_be_closed <- any(frp.close_symbol_panel,clicked_on_bg,symbol_chosen);
+            eval_ symbols_should_be_closed (model.hide_symbol_panel());
+
+            frp.source.symbol_panel_shown <+ bool(&symbols_should_be_closed,&symbol_panel_shown);
+            search_stopped     <- any(references_shown,symbols_should_be_closed);
+            is_symbol_searched <- bool(&search_stopped,&frp.go_to_symbol);
+            symbol_query       <- symbol_panel.caption().content.gate(&is_symbol_searched);
+            query_reset        <- frp.go_to_symbol.constant(String::new());
+            frp.source.symbol_query <+ query_reset;
+            frp.source.symbol_query <+ symbol_query.map(|query| query.to_string());
+
+
             // === Style toggle ===
 
             let style_toggle_ev   = frp.toggle_style.clone_ref();
@@ -504,6 +657,15 @@ impl View {
             eval frp.style ((style) model.set_style(style.clone()));
 
 
+            // === Theme Files ===
+
+            eval frp.set_theme_file (((layer,code)) model.set_theme_file(*layer,code));
+            eval frp.reset_theme_file ((layer) model.load_theme_file(*layer,&default()));
+            theme_file_changed <- any_(&frp.set_theme_file,&frp.reset_theme_file);
+            style_to_refresh   <- theme_file_changed.map2(&frp.style,|_,style| style.clone());
+            eval style_to_refresh ((style) model.set_style(style.clone()));
+
+
             // === Fullscreen Visualization ===
 
             // TODO[ao]: All DOM elements in visualizations ale displayed below canvas, because
@@ -566,15 +728,28 @@ impl View {
 
             // === Disabling Navigation ===
 
-            disable_navigation           <- searcher.is_selected || frp.open_dialog_shown;
+            history_or_symbols_shown     <- frp.undo_history_shown || frp.symbol_panel_shown;
+            panel_shown                  <- frp.open_dialog_shown || history_or_symbols_shown;
+            disable_navigation           <- searcher.is_selected || panel_shown;
             graph.set_navigator_disabled <+ disable_navigation;
 
             // === Disabling Dropping ===
 
             frp.source.drop_files_enabled <+ init.constant(true);
             frp.source.drop_files_enabled <+ frp.open_dialog_shown.map(|v| !v);
+
+
+            // === Read-only Mode ===
+
+            frp.source.read_only       <+ frp.set_read_only.on_change();
+            became_read_only           <- frp.read_only.on_true();
+            read_only_info             <- became_read_only.constant(READ_ONLY_MESSAGE);
+            model.status_bar.add_event <+ read_only_info.map(|m| status_bar::event::Label::new(*m));
+            frp.source.edit_requested  <+ frp.request_edit.gate(&frp.read_only);
+            frp.source.edit_released   <+ frp.release_edit.gate_not(&frp.read_only);
         }
         init.emit(());
+        frp.source.style.emit(theme);
         std::mem::forget(prompt_visibility);
 
         Self{model,frp}
@@ -597,6 +772,12 @@ impl View {
 
     /// Open File or Project Dialog
     pub fn open_dialog(&self) -> &OpenDialog { &self.model.open_dialog }
+
+    /// Undo History Panel.
+    pub fn undo_history(&self) -> &ListPanel { &self.model.undo_history }
+
+    /// Symbols Panel, listing the found symbols or references.
+    pub fn symbol_panel(&self) -> &ListPanel { &self.model.symbol_panel }
 }
 
 impl display::Object for View {
@@ -629,6 +810,17 @@ impl application::View for View {
           , (Press   , ""                   , "cmd s"           , "save_module")
           , (Press   , ""                   , "cmd z"           , "undo")
           , (Press   , ""                   , "cmd y"           , "redo")
+          , (Press   , "read_only"          , "cmd e"           , "request_edit")
+          , (Press   , "!read_only"         , "cmd shift e"     , "release_edit")
+          , (Press   , "!is_searcher_opened", "cmd b"           , "go_to_definition")
+          , (Press   , "!is_searcher_opened", "cmd shift b"     , "find_references")
+          , (Press   , "!is_searcher_opened", "cmd shift o"     , "go_to_symbol")
+          , (Press   , "symbol_panel_shown" , "escape"          , "close_symbol_panel")
+          , (Press   , "!is_searcher_opened", "cmd shift h"     , "show_undo_history")
+          , (Press   , "undo_history_shown" , "escape"          , "close_undo_history")
+          , (Press   , ""                   , "cmd alt shift x" , "reset_suggestion_usage")
+          , (Press   , ""                   , "cmd alt shift u" , "toggle_suggestion_usage")
+          , (Press   , ""                   , "cmd alt shift m" , "toggle_import_optimization")
           ]).iter().map(|(a,b,c,d)|Self::self_shortcut_when(*a,*c,*d,*b)).collect()
     }
 }
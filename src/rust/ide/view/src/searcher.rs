@@ -9,6 +9,7 @@ use crate::prelude::*;
 
 use crate::documentation;
 
+use enso_args::ARGS;
 use enso_frp as frp;
 use ensogl::DEPRECATED_Animation;
 use ensogl::application::{Application, shortcut};
@@ -91,28 +92,36 @@ pub type Entry = list_view::entry::GlyphHighlightedLabel;
 
 #[derive(Clone,CloneRef,Debug)]
 struct Model {
-    app            : Application,
-    logger         : Logger,
-    display_object : display::object::Instance,
-    list           : ListView<Entry>,
-    new_view       : new::View<usize>,
-    documentation  : documentation::View,
-    doc_provider   : Rc<CloneRefCell<AnyDocumentationProvider>>,
+    app              : Application,
+    logger           : Logger,
+    display_object   : display::object::Instance,
+    list             : ListView<Entry>,
+    new_view         : new::View<usize>,
+    /// If true, the new column view is displayed instead of the action list. It is enabled by the
+    /// `new_searcher` startup argument.
+    is_new_view_used : bool,
+    documentation    : documentation::View,
+    doc_provider     : Rc<CloneRefCell<AnyDocumentationProvider>>,
 }
 
 impl Model {
     fn new(app:&Application) -> Self {
-        let scene          = app.display.scene();
-        let app            = app.clone_ref();
-        let logger         = Logger::new("SearcherView");
-        let display_object = display::object::Instance::new(&logger);
-        let list           = app.new_view::<ListView<Entry>>();
-        let new_view       = new::View::new();
-        let documentation  = documentation::View::new(scene);
-        let doc_provider   = default();
+        let scene            = app.display.scene();
+        let app              = app.clone_ref();
+        let logger           = Logger::new("SearcherView");
+        let display_object   = display::object::Instance::new(&logger);
+        let list             = app.new_view::<ListView<Entry>>();
+        let new_view         = app.new_view::<new::View<usize>>();
+        let documentation    = documentation::View::new(scene);
+        let doc_provider     = default();
+        let is_new_view_used = ARGS.new_searcher.unwrap_or(false);
         scene.layers.above_nodes.add_exclusive(&list);
-        display_object.add_child(&documentation);
-        display_object.add_child(&list);
+        if is_new_view_used {
+            display_object.add_child(&new_view);
+        } else {
+            display_object.add_child(&documentation);
+            display_object.add_child(&list);
+        }
 
         // FIXME: StyleWatch is unsuitable here, as it was designed as an internal tool for shape
         //  system (#795)
@@ -124,7 +133,9 @@ impl Model {
         list.set_position_x(ACTION_LIST_X);
         documentation.set_position_x(DOCUMENTATION_X);
         documentation.set_position_y(-action_list_gap);
-        Self{app,logger,display_object,list,new_view,documentation,doc_provider}
+        let new_view_top = SEARCHER_HEIGHT / 2.0 - action_list_gap;
+        new_view.set_position_xy(Vector2(-SEARCHER_WIDTH / 2.0, new_view_top));
+        Self{app,logger,display_object,list,new_view,is_new_view_used,documentation,doc_provider}
     }
 
     fn docs_for(&self, id:Option<entry::Id>) -> String {
@@ -161,6 +172,9 @@ ensogl::define_endpoints! {
         selected_entry     (Option<entry::Id>),
         used_as_suggestion (Option<entry::Id>),
         editing_committed  (Option<entry::Id>),
+        /// The entry of the new searcher view picked by the user, either to be used as a
+        /// suggestion or just before committing the editing.
+        new_view_entry_picked (usize),
         size               (Vector2<f32>),
         is_visible         (bool),
         is_selected        (bool),
@@ -227,19 +241,26 @@ impl View {
 
             is_selected               <- model.list.selected_entry.map(|e| e.is_some());
             is_enabled                <- bool(&frp.hide,&frp.show);
-            is_entry_enabled          <- is_selected && is_enabled;
+            is_list_enabled           <- is_enabled.map(f!((on) *on && !model.is_new_view_used));
+            is_entry_enabled          <- is_selected && is_list_enabled;
             displayed_doc             <- model.list.selected_entry.map(f!((id) model.docs_for(*id)));
             opt_picked_entry          <- model.list.selected_entry.sample(&frp.use_as_suggestion);
             source.used_as_suggestion <+ opt_picked_entry.gate(&is_entry_enabled);
             source.editing_committed  <+ model.list.chosen_entry.gate(&is_entry_enabled);
 
             // New searcher
-            let is_selected           =  model.new_view.focused.clone_ref();
-            selected_id               <- model.new_view.highlight.map(|id| id.last().copied());
-            opt_picked_entry          <- selected_id.sample(&frp.use_as_suggestion);
-            source.used_as_suggestion <+ opt_picked_entry.gate(&is_selected);
-            opt_chosen_id             <- model.new_view.entry_chosen.map(|id| id.last().copied());
-            source.editing_committed  <+ opt_chosen_id.gate(&is_selected);
+            let is_focused     =  model.new_view.focused.clone_ref();
+            highlighted_id     <- model.new_view.highlight.map(|id| id.last().copied());
+            picked_id          <- highlighted_id.sample(&frp.use_as_suggestion).gate(&is_focused);
+            chosen_id          <- model.new_view.entry_chosen.map(|id| id.last().copied());
+            chosen_id          <- chosen_id.gate(&is_focused);
+            // The picked entry must be handled before committing editing.
+            source.new_view_entry_picked <+ picked_id.filter_map(|id| *id);
+            source.new_view_entry_picked <+ chosen_id.filter_map(|id| *id);
+            source.editing_committed     <+ chosen_id.constant(None);
+            new_view_shown     <- frp.show.filter(f_!(model.is_new_view_used));
+            eval_ new_view_shown (model.new_view.focus());
+            eval_ frp.hide       (model.new_view.defocus());
 
             eval displayed_doc ((data) model.documentation.frp.display_documentation(data));
         };
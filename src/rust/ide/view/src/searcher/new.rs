@@ -1,7 +1,47 @@
-//! A stub module with new searcher GUI.
+//! The new Searcher GUI: a column browser of the hierarchical suggestion list.
+//!
+//! Each column displays the content of a single directory: the first column lists the top-level
+//! entries, and each next one the content of the folder highlighted in the previous column. The
+//! content of directories is requested through the `list_directory` output and provided by the
+//! `directory_content` input. The documentation of the highlighted entry is displayed next to the
+//! columns.
 
 use crate::prelude::*;
 
+use crate::documentation;
+
+use enso_frp as frp;
+use ensogl::application::Application;
+use ensogl::application::shortcut;
+use ensogl::application;
+use ensogl::display;
+use ensogl_gui_components::list_view::ListView;
+use ensogl_gui_components::list_view;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The number of displayed columns. It matches the depth of the suggestion hierarchy: libraries,
+/// modules, types and methods.
+pub const COLUMN_COUNT:usize = 4;
+/// Width of a single column in pixels.
+pub const COLUMN_WIDTH:f32 = 160.0;
+/// Height of the whole view in pixels.
+pub const HEIGHT:f32 = 184.5;
+
+const COLUMN_GAP          : f32 = 4.0;
+const DOCUMENTATION_GAP   : f32 = 15.0;
+const DOCUMENTATION_WIDTH : f32 = 320.0;
+const COLUMNS_WIDTH       : f32 = COLUMN_COUNT as f32 * (COLUMN_WIDTH + COLUMN_GAP) - COLUMN_GAP;
+/// Width of the whole view in pixels.
+pub const WIDTH:f32 = COLUMNS_WIDTH + DOCUMENTATION_GAP + DOCUMENTATION_WIDTH;
+
+/// The suffix added to labels of folder entries.
+const FOLDER_LABEL_SUFFIX:&str = " ›";
+
 
 
 // =============
@@ -17,6 +57,13 @@ pub struct Entry {
     pub icon      : Icon,
 }
 
+impl Entry {
+    fn displayed_label(&self) -> String {
+        if *self.is_folder { format!("{}{}",self.label,FOLDER_LABEL_SUFFIX) }
+        else               { self.label.to_string()                          }
+    }
+}
+
 /// The typewrapper for icon name.
 #[derive(Clone,CloneRef,Debug,Default)]
 pub struct Icon {
@@ -30,6 +77,13 @@ pub fn Icon(name:impl Into<ImString>) -> Icon {
     Icon {name}
 }
 
+/// Check if the entry's label matches the filtering pattern typed by the user: all the pattern's
+/// characters must appear in the label in the same order. The letter case is ignored.
+pub fn label_matches(label:&str, pattern:&str) -> bool {
+    let mut label_chars = label.chars().flat_map(char::to_lowercase);
+    pattern.chars().flat_map(char::to_lowercase).all(|p| label_chars.any(|l| l == p))
+}
+
 
 
 // ===========
@@ -39,28 +93,251 @@ pub fn Icon(name:impl Into<ImString>) -> Icon {
 /// A type representing path to entry in some column.
 pub type EntryPath<Id> = Rc<Vec<Id>>;
 
+/// The content of a directory: the entries' ids together with their descriptions.
+pub type DirectoryContent<Id> = Rc<Vec<(Id,Entry)>>;
+
 ensogl::define_endpoints! { <Id:(Debug+Clone+'static)>
     Input {
+        /// Clear all columns and request the top-level directory content.
         reset(),
-        directory_content (EntryPath<Id>,Entry),
+        /// Set the content of the directory with given path. The content is ignored if it is not
+        /// about the most recently requested directory in any column.
+        directory_content (EntryPath<Id>,DirectoryContent<Id>),
         set_highlight     (EntryPath<Id>),
+        /// Filter the entries of the currently active column. See [`label_matches`].
+        set_filter        (ImString),
+        set_documentation (String),
+        move_up(),
+        move_down(),
+        move_left(),
+        move_right(),
+        choose_highlighted(),
     }
 
     Output {
         list_directory (EntryPath<Id>),
         highlight      (EntryPath<Id>),
+        /// Emitted when the user chooses an entry which is not a folder.
         entry_chosen   (EntryPath<Id>),
     }
 }
 
+
+
+// ==============
+// === Column ===
+// ==============
+
+/// A single column of the view, displaying the content of one directory.
+#[derive(Clone,CloneRef,Debug)]
+struct Column<Id:Debug+Clone+'static> {
+    list      : ListView<list_view::entry::Label>,
+    /// The path of the displayed directory, or [`None`] if the column is empty.
+    directory : Rc<RefCell<Option<EntryPath<Id>>>>,
+    content   : Rc<RefCell<DirectoryContent<Id>>>,
+    /// The indices in `content` of the entries passing the filter, in the displayed order.
+    visible   : Rc<RefCell<Vec<usize>>>,
+}
+
+impl<Id:Debug+Clone+PartialEq+'static> Column<Id> {
+    fn new(app:&Application) -> Self {
+        let list      = ListView::new(app);
+        let directory = default();
+        let content   = default();
+        let visible   = default();
+        list.resize(Vector2(COLUMN_WIDTH,HEIGHT));
+        Self {list,directory,content,visible}
+    }
+
+    /// Display the given directory. The content will be set once it is received.
+    fn expect_directory(&self, directory:EntryPath<Id>) {
+        *self.directory.borrow_mut() = Some(directory);
+        self.set_content(default());
+    }
+
+    fn clear(&self) {
+        *self.directory.borrow_mut() = None;
+        self.set_content(default());
+    }
+
+    fn displays(&self, directory:&[Id]) -> bool {
+        self.directory.borrow().as_ref().contains_if(|path| path.as_slice() == directory)
+    }
+
+    fn set_content(&self, content:DirectoryContent<Id>) {
+        *self.content.borrow_mut() = content;
+        self.set_filter("");
+    }
+
+    fn set_filter(&self, pattern:&str) {
+        let content = self.content.borrow();
+        let visible = content.iter().enumerate().filter(|(_,(_,entry))| {
+            label_matches(&entry.label,pattern)
+        }).map(|(index,_)| index).collect_vec();
+        let labels  = visible.iter().map(|index| content[*index].1.displayed_label()).collect_vec();
+        *self.visible.borrow_mut() = visible;
+        self.list.set_entries(list_view::entry::AnyModelProvider::new(labels));
+    }
+
+    fn entry_count(&self) -> usize {
+        self.visible.borrow().len()
+    }
+
+    /// Get the full path and description of the displayed entry.
+    fn entry(&self, displayed_index:list_view::entry::Id) -> Option<(EntryPath<Id>,Entry)> {
+        let index      = *self.visible.borrow().get(displayed_index)?;
+        let (id,entry) = self.content.borrow().get(index)?.clone();
+        let mut path   = self.directory.borrow().as_ref()?.deref().clone();
+        path.push(id);
+        Some((Rc::new(path),entry))
+    }
+
+    /// Find the displayed index of the entry with given id.
+    fn displayed_index_of(&self, id:&Id) -> Option<list_view::entry::Id> {
+        let content = self.content.borrow();
+        self.visible.borrow().iter().position(|index| &content[*index].0 == id)
+    }
+
+    fn highlighted_entry(&self) -> Option<list_view::entry::Id> {
+        self.list.selected_entry.value()
+    }
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+#[derive(Clone,CloneRef,Debug)]
+struct Model<Id:Debug+Clone+'static> {
+    app            : Application,
+    logger         : Logger,
+    display_object : display::object::Instance,
+    columns        : Rc<Vec<Column<Id>>>,
+    documentation  : documentation::View,
+    /// The index of the column where the highlighted entry is.
+    active_column  : Rc<Cell<usize>>,
+}
+
+impl<Id:Debug+Clone+PartialEq+'static> Model<Id> {
+    fn new(app:&Application) -> Self {
+        let scene          = app.display.scene();
+        let app            = app.clone_ref();
+        let logger         = Logger::new("searcher::new::View");
+        let display_object = display::object::Instance::new(&logger);
+        let columns        = Rc::new((0..COLUMN_COUNT).map(|_| Column::new(&app)).collect_vec());
+        let documentation  = documentation::View::new(scene);
+        let active_column  = default();
+        for (index,column) in columns.iter().enumerate() {
+            let x = index as f32 * (COLUMN_WIDTH + COLUMN_GAP) + COLUMN_WIDTH / 2.0;
+            column.list.set_position_xy(Vector2(x, -HEIGHT / 2.0));
+            column.list.set_label_layer(scene.layers.above_nodes_text.id());
+            scene.layers.above_nodes.add_exclusive(&column.list);
+            display_object.add_child(&column.list);
+        }
+        let documentation_x = COLUMNS_WIDTH + DOCUMENTATION_GAP + DOCUMENTATION_WIDTH / 2.0;
+        documentation.set_position_xy(Vector2(documentation_x, -HEIGHT / 2.0));
+        documentation.visualization_frp.inputs.set_size.emit(Vector2(DOCUMENTATION_WIDTH,HEIGHT));
+        display_object.add_child(&documentation);
+        Self {app,logger,display_object,columns,documentation,active_column}
+    }
+
+    /// Clear the columns and prepare the first one for displaying the top-level directory.
+    fn reset(&self) -> EntryPath<Id> {
+        let root = Rc::new(vec![]);
+        self.clear_columns_from(0);
+        self.columns[0].expect_directory(root.clone_ref());
+        self.active_column.set(0);
+        root
+    }
+
+    fn clear_columns_from(&self, index:usize) {
+        for column in self.columns.iter().skip(index) {
+            column.clear();
+        }
+    }
+
+    fn set_directory_content(&self, directory:&EntryPath<Id>, content:&DirectoryContent<Id>) {
+        let column = self.columns.get(directory.len()).filter(|c| c.displays(directory));
+        if let Some(column) = column {
+            column.set_content(content.clone_ref());
+        } else {
+            debug!(self.logger, "Ignoring content of not displayed directory {directory:?}.");
+        }
+    }
+
+    /// Handle the change of the highlighted entry in the given column. Returns the highlighted
+    /// entry path and, if the entry is a folder, its path again as the directory to be listed in
+    /// the next column.
+    fn on_entry_highlighted
+    (&self, column_index:usize, displayed_index:list_view::entry::Id)
+    -> Option<(EntryPath<Id>,Option<EntryPath<Id>>)> {
+        let (path,entry) = self.columns.get(column_index)?.entry(displayed_index)?;
+        self.active_column.set(column_index);
+        self.clear_columns_from(column_index + 1);
+        let next_column = self.columns.get(column_index + 1);
+        let to_list     = next_column.filter(|_| *entry.is_folder).map(|column| {
+            column.expect_directory(path.clone_ref());
+            path.clone_ref()
+        });
+        Some((path,to_list))
+    }
+
+    fn active_column(&self) -> &Column<Id> {
+        &self.columns[self.active_column.get()]
+    }
+
+    fn set_highlight(&self, path:&[Id]) {
+        if let Some((id,directory)) = path.split_last() {
+            let column = self.columns.get(directory.len()).filter(|c| c.displays(directory));
+            let index  = column.and_then(|column| Some((column,column.displayed_index_of(id)?)));
+            if let Some((column,index)) = index {
+                column.list.select_entry(index);
+            }
+        }
+    }
+
+    fn set_filter(&self, pattern:&str) {
+        let column = self.active_column();
+        column.set_filter(pattern);
+        if column.entry_count() > 0 {
+            column.list.select_entry(0);
+        }
+    }
+
+    fn move_left(&self) {
+        let index = self.active_column.get();
+        if let Some(previous) = index.checked_sub(1).and_then(|i| self.columns.get(i)) {
+            if let Some(highlighted) = previous.highlighted_entry() {
+                previous.list.select_entry(highlighted);
+            }
+        }
+    }
+
+    fn move_right(&self) {
+        let next = self.columns.get(self.active_column.get() + 1);
+        if let Some(next) = next.filter(|column| column.entry_count() > 0) {
+            next.list.select_entry(0);
+        }
+    }
+}
+
+
+
+// ============
+// === View ===
+// ============
+
 /// The Searcher View gui component.
 ///
-/// Currently it contains only simple mechanism of requesting searcher content and printing it to
-/// the console.
+/// The view does not handle the searcher input: the filtering pattern should be passed by the
+/// `set_filter` input.
 #[allow(missing_docs)]
 #[derive(Clone,CloneRef,Debug)]
 pub struct View<Id:Debug+Clone+'static> {
     pub frp : Frp<Id>,
+    model   : Model<Id>,
 }
 
 impl<Id:Debug+Clone+'static> Deref for View<Id> {
@@ -69,28 +346,90 @@ impl<Id:Debug+Clone+'static> Deref for View<Id> {
     fn deref(&self) -> &Self::Target { &self.frp }
 }
 
-impl<ID:ToString+Debug+Clone+'static> View<ID> {
+impl<Id:Debug+Clone+PartialEq+'static> View<Id> {
     /// Create new searcher view.
-    pub fn new() -> Self {
-        let logger  = Logger::new("searcher::new::View");
-        let frp     = Frp::new();
-        let network = &frp.network;
-        enso_frp::extend!{ network
-            eval frp.directory_content ([logger]((crumbs,entry)) {
-                let crumbs = crumbs.iter().map(ToString::to_string).join(",");
-                info!(logger,"New Searcher Entry received: [{crumbs}] -> {entry:?}.");
-            });
-
-            frp.source.list_directory <+ frp.reset.constant(Rc::new(vec![]));
-            frp.source.list_directory <+ frp.directory_content.filter_map(|(crumbs,entry)| {
-                entry.is_folder.as_some(crumbs.clone_ref())
-            });
+    pub fn new(app:&Application) -> Self {
+        let frp   = Frp::new();
+        let model = Model::new(app);
+        Self{frp,model}.init()
+    }
+
+    fn init(self) -> Self {
+        let network = &self.frp.network;
+        let model   = &self.model;
+        let frp     = &self.frp;
+        frp::extend!{ network
+            frp.source.list_directory <+ frp.reset.map(f_!(model.reset()));
+            eval frp.directory_content (((dir,content)) model.set_directory_content(dir,content));
+            eval frp.set_highlight     ((path) model.set_highlight(path));
+            eval frp.set_filter        ((pattern) model.set_filter(pattern));
+            eval frp.set_documentation ((doc) model.documentation.frp.display_documentation(doc));
+
+            eval_ frp.move_up    (model.active_column().list.move_selection_up());
+            eval_ frp.move_down  (model.active_column().list.move_selection_down());
+            eval_ frp.move_left  (model.move_left());
+            eval_ frp.move_right (model.move_right());
+            eval_ frp.choose_highlighted (model.active_column().list.chose_selected_entry());
         }
+        for (index,column) in model.columns.iter().enumerate() {
+            frp::extend!{ network
+                highlighted_index <- column.list.selected_entry.filter_map(|entry| *entry);
+                highlighted       <- highlighted_index.filter_map(f!((entry)
+                    model.on_entry_highlighted(index,*entry)
+                ));
+                frp.source.highlight      <+ highlighted._0();
+                frp.source.list_directory <+ highlighted.filter_map(|(_,dir)| dir.clone());
+
+                chosen       <- column.list.chosen_entry.filter_map(|entry| *entry);
+                chosen_entry <- chosen.filter_map(f!((entry) column.entry(*entry)));
+                chosen_dir   <- chosen_entry.filter(|(_,entry)| *entry.is_folder);
+                eval_ chosen_dir (model.move_right());
+                frp.source.entry_chosen <+ chosen_entry.filter(|(_,e)| !*e.is_folder)._0();
+            }
+        }
+        self
+    }
+}
+
+impl<Id:Debug+Clone+'static> display::Object for View<Id> {
+    fn display_object(&self) -> &display::object::Instance { &self.model.display_object }
+}
 
-        Self{frp}
+impl<Id:Debug+Clone+'static> application::command::FrpNetworkProvider for View<Id> {
+    fn network(&self) -> &frp::Network { &self.frp.network }
+}
+
+impl<Id:Debug+Clone+PartialEq+'static> application::View for View<Id> {
+    fn label()                -> &'static str { "NewSearcher" }
+    fn new(app:&Application)  -> Self         { Self::new(app) }
+    fn app(&self)             -> &Application { &self.model.app }
+    fn default_shortcuts()    -> Vec<shortcut::Shortcut> {
+        use shortcut::ActionType::*;
+        (&[ (PressAndRepeat , "up"              , "move_up")
+          , (PressAndRepeat , "down"            , "move_down")
+          , (Press          , "alt shift left"  , "move_left")
+          , (Press          , "alt shift right" , "move_right")
+          , (Press          , "enter"           , "choose_highlighted")
+          ]).iter().map(|(a,b,c)|Self::self_shortcut_when(*a,*b,*c,"focused")).collect()
     }
 }
 
-impl<Id:ToString+Debug+Clone+'static> Default for View<Id> {
-    fn default() -> Self { Self::new() }
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matching_labels() {
+        assert!(label_matches("Standard.Base", ""));
+        assert!(label_matches("Standard.Base", "base"));
+        assert!(label_matches("Standard.Base", "StdBs"));
+        assert!(!label_matches("Standard.Base", "Table"));
+        assert!(!label_matches("length", "lengthy"));
+    }
 }
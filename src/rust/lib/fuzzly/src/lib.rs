@@ -7,7 +7,12 @@
 //!
 //! The metrics used for scoring may be adjusted by implementing `Metric` trait, or by customizing
 //! parameters of metrics defined in `metric` module.
+//!
+//! When the user may make typos in the pattern, use `find_best_subsequence_with_typos` with the
+//! `metric::extended` metric instead: it tolerates a bounded number of substituted and transposed
+//! characters, and prefers matching the initials of words.
 #![feature(option_result_contains)]
+#![feature(test)]
 
 #![warn(missing_docs)]
 #![warn(trivial_casts)]
@@ -18,9 +23,12 @@
 #![warn(missing_copy_implementations)]
 #![warn(missing_debug_implementations)]
 
+extern crate test;
+
 pub mod subsequence_graph;
 pub mod metric;
 pub mod score;
+pub mod typos;
 
 pub use enso_prelude as prelude;
 pub use metric::Metric;
@@ -28,3 +36,5 @@ pub use subsequence_graph::Graph as SubsequenceGraph;
 pub use score::Subsequence;
 pub use score::matches;
 pub use score::find_best_subsequence;
+pub use typos::Typo;
+pub use typos::find_best_subsequence_with_typos;
@@ -2,6 +2,7 @@
 use crate::prelude::*;
 
 use crate::subsequence_graph;
+use crate::typos::Typo;
 
 
 
@@ -21,6 +22,10 @@ pub trait Metric {
     /// How good is the edge on the path on the Subsequence Graph.
     fn measure_edge(&self, edge:subsequence_graph::Edge, text:&str, pattern:&str) -> f32;
 
+    /// How good is the typo on the path. Typos are made only by the typo-tolerant matcher (see
+    /// [`crate::typos`] module), so by default they are not measured at all.
+    fn measure_typo(&self, _typo:Typo, _text:&str, _pattern:&str) -> f32 { 0.0 }
+
     /// Return a new metric being a sum of this and `rhs`.
     fn sum<Rhs:Metric>(self, rhs:Rhs) -> Sum<Self,Rhs> where Self:Sized { Sum(self, rhs) }
 }
@@ -36,6 +41,12 @@ pub fn default() -> impl Metric {
     SubsequentLettersBonus::default().sum(CaseMatchBonus::default())
 }
 
+/// The metric recommended for the typo-tolerant matcher: the default metric extended with the
+/// bonus for matching word initials and the penalty for typos.
+pub fn extended() -> impl Metric {
+    default().sum(WordInitialsBonus::default()).sum(TypoPenalty::default())
+}
+
 
 
 // =======================
@@ -62,6 +73,13 @@ impl<M1:Metric, M2:Metric> Metric for Sum<M1,M2> {
         let right            = right.measure_edge(edge,text,pattern);
         left + right
     }
+
+    fn measure_typo(&self, typo:Typo, text:&str, pattern:&str) -> f32 {
+        let Self(left,right) = self;
+        let left             = left.measure_typo(typo,text,pattern);
+        let right            = right.measure_typo(typo,text,pattern);
+        left + right
+    }
 }
 
 
@@ -137,3 +155,116 @@ impl Metric for CaseMatchBonus {
 
     fn measure_edge(&self, _:subsequence_graph::Edge, _:&str, _:&str) -> f32 { 0.0 }
 }
+
+
+// === WordInitialsBonus ===
+
+/// A metric which scores the pattern chars matched with the initials of the words in text, so
+/// acronyms like "rc" prefer `read_csv` or `readCsv`. A word begins after any non-alphanumeric
+/// character (like `_`) and at each lowercase-to-uppercase boundary.
+#[derive(Copy,Clone,Debug)]
+pub struct WordInitialsBonus {
+    /// A score added for each char matched with a word initial.
+    pub bonus_per_char : f32,
+}
+
+impl Default for WordInitialsBonus {
+    fn default() -> Self {
+        WordInitialsBonus {
+            bonus_per_char : 0.5,
+        }
+    }
+}
+
+impl WordInitialsBonus {
+    /// Check if the char at given position begins a word in text.
+    pub fn is_word_initial(text:&str, position_in_text:usize) -> bool {
+        let mut chars = text.chars().skip(position_in_text.saturating_sub(1));
+        if position_in_text == 0 {
+            chars.next().map_or(false, char::is_alphanumeric)
+        } else {
+            match (chars.next(),chars.next()) {
+                (Some(prev),Some(ch)) if ch.is_alphanumeric() =>
+                    !prev.is_alphanumeric() || prev.is_lowercase() && ch.is_uppercase(),
+                _ => false,
+            }
+        }
+    }
+}
+
+impl Metric for WordInitialsBonus {
+    fn measure_vertex(&self, vertex:subsequence_graph::Vertex, text:&str, _pattern:&str) -> f32 {
+        if Self::is_word_initial(text,vertex.position_in_text) {self.bonus_per_char} else {0.0}
+    }
+
+    fn measure_edge(&self, _:subsequence_graph::Edge, _:&str, _:&str) -> f32 { 0.0 }
+}
+
+
+// === TypoPenalty ===
+
+/// A metric which lowers the score of the matches with typos, so they are listed below the exact
+/// ones.
+#[derive(Copy,Clone,Debug)]
+pub struct TypoPenalty {
+    /// The score subtracted for each pattern char substituted for other text char.
+    pub substitution_penalty : f32,
+    /// The score subtracted for each pair of adjacent chars typed in a reversed order.
+    pub transposition_penalty : f32,
+}
+
+impl Default for TypoPenalty {
+    fn default() -> Self {
+        TypoPenalty {
+            substitution_penalty  : 1.5,
+            transposition_penalty : 1.0,
+        }
+    }
+}
+
+impl Metric for TypoPenalty {
+    fn measure_vertex(&self, _:subsequence_graph::Vertex, _:&str, _:&str) -> f32 { 0.0 }
+
+    fn measure_edge(&self, _:subsequence_graph::Edge, _:&str, _:&str) -> f32 { 0.0 }
+
+    fn measure_typo(&self, typo:Typo, _text:&str, _pattern:&str) -> f32 {
+        match typo {
+            Typo::Substitution(_)  => -self.substitution_penalty,
+            Typo::Transposition(_) => -self.transposition_penalty,
+        }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::score::find_best_subsequence;
+
+    #[test]
+    fn detecting_word_initials() {
+        let initials = |text:&str| {
+            let positions = 0..text.chars().count();
+            positions.filter(|p| WordInitialsBonus::is_word_initial(text,*p)).collect_vec()
+        };
+        assert_eq!(initials("read_csv")      , vec![0,5]);
+        assert_eq!(initials("readCsv")       , vec![0,4]);
+        assert_eq!(initials("_to_JSON")      , vec![1,4]);
+        assert_eq!(initials("Table.from_rows"), vec![0,6,11]);
+    }
+
+    #[test]
+    fn matching_acronyms() {
+        let metric   = || default().sum(WordInitialsBonus::default());
+        let matching = |text| find_best_subsequence(text,"rc",metric()).unwrap();
+        assert_eq!(matching("read_csv").indices, vec![0,5]);
+        assert_eq!(matching("readCsv").indices , vec![0,4]);
+        assert!(matching("read_csv").score > matching("source").score);
+    }
+}
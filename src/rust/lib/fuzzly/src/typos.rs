@@ -0,0 +1,404 @@
+//! Typo-tolerant matching of the pattern with text.
+//!
+//! The matcher extends the subsequence matching from the `score` module: the path of matched
+//! chars may contain a bounded number of typos, i.e. the pattern chars substituted for other text
+//! chars, or pairs of adjacent chars typed in a reversed order. Thus "lenght" finds `length`. Each
+//! typo is measured by [`Metric::measure_typo`], see also [`crate::metric::TypoPenalty`].
+use crate::prelude::*;
+
+use crate::metric::Metric;
+use crate::score::Subsequence;
+use crate::subsequence_graph::Edge;
+use crate::subsequence_graph::Vertex;
+
+
+
+// ============
+// === Typo ===
+// ============
+
+/// A typo made on the path of matched chars.
+#[derive(Copy,Clone,Debug,Eq,Hash,PartialEq)]
+pub enum Typo {
+    /// The pattern char of the vertex's layer was typed instead of the text char at the vertex's
+    /// position.
+    Substitution(Vertex),
+    /// The pattern chars of the edge's layers were typed in a reversed order. The edge always
+    /// spans between adjacent text chars.
+    Transposition(Edge),
+}
+
+/// The number of typos tolerated for the given pattern by default. The shorter the pattern is, the
+/// fewer typos are allowed, as otherwise the short patterns would match almost any text.
+pub fn max_typos_for(pattern:impl Str) -> usize {
+    match pattern.as_ref().chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _     => 2,
+    }
+}
+
+
+
+// ===============
+// === Matcher ===
+// ===============
+
+/// A vertex on the path together with the number of typos made on the path so far.
+#[derive(Copy,Clone,Debug,Eq,PartialEq)]
+struct State {
+    vertex : Vertex,
+    typos  : usize,
+}
+
+/// The way the best path reaching given state arrives at it.
+#[derive(Copy,Clone,Debug)]
+enum Step {
+    /// The state is the first one on the path.
+    Start,
+    /// The path comes from the given state on the previous layer.
+    From(State),
+    /// The state ends a transposition, whose first vertex is on the previous layer and position.
+    /// The first vertex is reached from the given state, or is the first one on the path.
+    Transposed(Option<State>),
+}
+
+/// The best score of paths finishing at some specific state.
+#[derive(Copy,Clone,Debug)]
+struct StateScore {
+    value : f32,
+    step  : Step,
+}
+
+/// The dynamic programming table of [`find_best_subsequence_with_typos`].
+///
+/// The states are processed layer by layer; each state updates the scores of the states it may be
+/// followed by on the path. A substituted char must directly follow the previous matched char
+/// (unless it is the first one), which keeps the number of considered states small.
+#[derive(Debug)]
+struct Matcher<'a,M> {
+    text          : &'a str,
+    pattern       : &'a str,
+    text_chars    : Vec<char>,
+    pattern_chars : Vec<char>,
+    metric        : M,
+    max_typos     : usize,
+    scores        : Vec<Option<StateScore>>,
+}
+
+impl<'a,M:Metric> Matcher<'a,M> {
+    fn new(text:&'a str, pattern:&'a str, metric:M, max_typos:usize) -> Self {
+        let text_chars    = text.chars().collect_vec();
+        let pattern_chars = pattern.chars().collect_vec();
+        let states_count  = text_chars.len() * pattern_chars.len() * (max_typos + 1);
+        let scores        = vec![None;states_count];
+        Matcher {text,pattern,text_chars,pattern_chars,metric,max_typos,scores}
+    }
+
+    fn run(mut self) -> Option<Subsequence> {
+        self.update_successors(None,0.0);
+        for layer in 0..self.pattern_chars.len() {
+            for position_in_text in 0..self.text_chars.len() {
+                for typos in 0..=self.max_typos {
+                    let state = State {vertex:Vertex{layer,position_in_text},typos};
+                    if let Some(score) = self.score(state) {
+                        self.update_successors(Some(state),score.value);
+                    }
+                }
+            }
+        }
+        let best_end = self.best_end_state()?;
+        let score    = self.score(best_end)?.value;
+        let indices  = self.path_indices(best_end);
+        Some(Subsequence {score,indices})
+    }
+
+    fn index(&self, state:State) -> usize {
+        let State {vertex,typos} = state;
+        let vertex_index         = vertex.layer * self.text_chars.len() + vertex.position_in_text;
+        vertex_index * (self.max_typos + 1) + typos
+    }
+
+    fn score(&self, state:State) -> Option<StateScore> {
+        self.scores[self.index(state)]
+    }
+
+    fn offer(&mut self, state:State, value:f32, step:Step) {
+        if state.typos <= self.max_typos {
+            let index = self.index(state);
+            match &mut self.scores[index] {
+                Some(score) if score.value >= value => {}
+                slot                                => *slot = Some(StateScore {value,step}),
+            }
+        }
+    }
+
+    fn is_match(&self, vertex:Vertex) -> bool {
+        let text_ch    = self.text_chars[vertex.position_in_text];
+        let pattern_ch = self.pattern_chars[vertex.layer];
+        pattern_ch.eq_ignore_ascii_case(&text_ch)
+    }
+
+    /// Check if the pattern chars of the vertex's layer and the next one are present at the
+    /// vertex's position in a reversed order.
+    fn is_transposition(&self, vertex:Vertex) -> bool {
+        let Vertex {layer,position_in_text} = vertex;
+        let fits_pattern = layer + 1 < self.pattern_chars.len();
+        let fits_text    = position_in_text + 1 < self.text_chars.len();
+        fits_pattern && fits_text && !self.is_match(vertex) && {
+            let first  = Vertex {layer, position_in_text:position_in_text+1};
+            let second = Vertex {layer:layer+1, position_in_text};
+            self.is_match(first) && self.is_match(second)
+        }
+    }
+
+    /// The value of the path extended by the given vertex.
+    fn extend_path(&self, value:f32, from:Option<State>, to:Vertex) -> f32 {
+        let edge = from.map(|from| Edge {from:from.vertex, to});
+        let edge = edge.map_or(0.0, |edge| self.metric.measure_edge(edge,self.text,self.pattern));
+        value + edge + self.metric.measure_vertex(to,self.text,self.pattern)
+    }
+
+    fn measure_typo(&self, typo:Typo) -> f32 {
+        self.metric.measure_typo(typo,self.text,self.pattern)
+    }
+
+    fn update_successors(&mut self, from:Option<State>, value:f32) {
+        let layer = from.map_or(0, |state| state.vertex.layer + 1);
+        let first = from.map_or(0, |state| state.vertex.position_in_text + 1);
+        let typos = from.map_or(0, |state| state.typos);
+        let step  = from.map_or(Step::Start, Step::From);
+        if layer < self.pattern_chars.len() {
+            for position_in_text in first..self.text_chars.len() {
+                let vertex          = Vertex {layer,position_in_text};
+                let is_adjacent     = from.is_none() || position_in_text == first;
+                let is_match        = self.is_match(vertex);
+                let is_substitution = !is_match && is_adjacent;
+                if is_match || is_substitution {
+                    let value = self.extend_path(value,from,vertex);
+                    if is_match {
+                        self.offer(State {vertex,typos},value,step);
+                    } else {
+                        let value = value + self.measure_typo(Typo::Substitution(vertex));
+                        self.offer(State {vertex,typos:typos+1},value,step);
+                    }
+                }
+                if self.is_transposition(vertex) {
+                    let next  = Vertex {layer:layer+1, position_in_text:position_in_text+1};
+                    let edge  = Edge {from:vertex, to:next};
+                    let value = self.extend_path(value,from,vertex);
+                    let value = self.extend_path(value,Some(State {vertex,typos}),next);
+                    let value = value + self.measure_typo(Typo::Transposition(edge));
+                    self.offer(State {vertex:next,typos:typos+1},value,Step::Transposed(from));
+                }
+            }
+        }
+    }
+
+    fn best_end_state(&self) -> Option<State> {
+        let last_layer = self.pattern_chars.len().checked_sub(1)?;
+        let positions  = 0..self.text_chars.len();
+        let states     = positions.cartesian_product(0..=self.max_typos).map(|(position,typos)| {
+            State {vertex:Vertex{layer:last_layer,position_in_text:position},typos}
+        });
+        let scored = states.filter_map(|state| Some((state,self.score(state)?.value)));
+        let best   = scored.fold(None, |prev,(state,value)| {
+            match prev {
+                Some((_,prev_value)) if value > prev_value => Some((state,value)),
+                Some(prev)                                 => Some(prev),
+                None                                       => Some((state,value)),
+            }
+        });
+        best.map(|(state,_)| state)
+    }
+
+    fn path_indices(&self, end:State) -> Vec<usize> {
+        let mut indices = Vec::new();
+        let mut next    = Some(end);
+        while let Some(state) = next {
+            let position = state.vertex.position_in_text;
+            indices.push(position);
+            next = match self.score(state).map(|score| score.step) {
+                Some(Step::From(previous))       => Some(previous),
+                Some(Step::Transposed(previous)) => { indices.push(position - 1); previous }
+                Some(Step::Start) | None         => None,
+            };
+        }
+        indices.reverse();
+        indices
+    }
+}
+
+/// Find best subsequence in `text` which case-insensitively equals to `pattern` with at most
+/// `max_typos` typos, in terms of given `metric`.
+///
+/// Returns `None` if `text` does not match `pattern`. Empty `pattern` gives 0.0 score. The
+/// returned indices point the text chars matched with consecutive pattern chars, including the
+/// chars involved in typos. See [`max_typos_for`] for the recommended typos limit.
+///
+/// With `max_typos` equal to 0 the function finds the same matches as
+/// [`crate::find_best_subsequence`], but it is slower.
+pub fn find_best_subsequence_with_typos
+(text:impl Str, pattern:impl Str, metric:impl Metric, max_typos:usize) -> Option<Subsequence> {
+    let text    = text.as_ref();
+    let pattern = pattern.as_ref();
+    if pattern.is_empty() {
+        Some(default())
+    } else {
+        Matcher::new(text,pattern,metric,max_typos).run()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::metric;
+    use crate::score::find_best_subsequence;
+
+    fn find(text:&str, pattern:&str, max_typos:usize) -> Option<Subsequence> {
+        find_best_subsequence_with_typos(text,pattern,metric::extended(),max_typos)
+    }
+
+    #[test]
+    fn matching_without_typos() {
+        for (text,pattern) in &[("length","lgh"),("read_csv","rc"),("aabxbacc","abc")] {
+            let expected = find_best_subsequence(*text,*pattern,metric::extended());
+            assert_eq!(find(text,pattern,0).map(|s| s.indices), expected.map(|s| s.indices));
+        }
+        assert_eq!(find("length","lenght",0), None);
+        assert_eq!(find("length","",0), Some(default()));
+        assert_eq!(find("","length",2), None);
+    }
+
+    #[test]
+    fn tolerating_transpositions() {
+        let found = find("length","lenght",1).unwrap();
+        assert_eq!(found.indices, vec![0,1,2,3,4,5]);
+        let found = find("length","elnght",2).unwrap();
+        assert_eq!(found.indices, vec![0,1,2,3,4,5]);
+        assert!(find("length","elnght",1).is_none());
+    }
+
+    #[test]
+    fn tolerating_substitutions() {
+        let found = find("length","lemgth",1).unwrap();
+        assert_eq!(found.indices, vec![0,1,2,3,4,5]);
+        let found = find("length","kength",1).unwrap();
+        assert_eq!(found.indices, vec![0,1,2,3,4,5]);
+        assert!(find("length","lemgtj",1).is_none());
+        assert!(find("length","lemgtj",2).is_some());
+    }
+
+    #[test]
+    fn preferring_exact_matches() {
+        let exact      = find("length","length",2).unwrap();
+        let transposed = find("length","lenght",2).unwrap();
+        let substituted = find("length","lemgth",2).unwrap();
+        assert!(exact.score > transposed.score);
+        assert!(transposed.score > substituted.score);
+    }
+
+    #[test]
+    fn default_typos_limit() {
+        assert_eq!(max_typos_for("rc")           , 0);
+        assert_eq!(max_typos_for("lenght")       , 1);
+        assert_eq!(max_typos_for("read_cvs_fiel"), 2);
+    }
+}
+
+
+
+// ==================
+// === Benchmarks ===
+// ==================
+
+#[cfg(test)]
+mod benchmarks {
+    use super::*;
+
+    use crate::metric;
+    use crate::score::find_best_subsequence;
+    use crate::score::matches;
+
+    use test::Bencher;
+
+    const WORDS : &[&str] = &
+        [ "read","write","csv","table","text","length","to","from","json","at","map","filter"
+        , "each","sort","group","by","index","column","row","value","file","join","split","trim"
+        ];
+
+    /// About as many names as there are in the Suggestion Database of the Standard Library, in
+    /// both `snake_case` and `camelCase`.
+    fn names() -> Vec<String> {
+        let pairs = WORDS.iter().cartesian_product(WORDS.iter());
+        let words = pairs.cartesian_product(WORDS.iter().take(4));
+        words.flat_map(|((first,second),third)| {
+            let snake = format!("{}_{}_{}",first,second,third);
+            let camel = format!("{}{}{}",first,capitalize(second),capitalize(third));
+            vec![snake,camel]
+        }).collect()
+    }
+
+    fn capitalize(word:&str) -> String {
+        let mut chars = word.chars();
+        chars.next().map(|ch| ch.to_uppercase().chain(chars).collect()).unwrap_or_default()
+    }
+
+    fn bench_subsequence_graph(pattern:&str, bencher:&mut Bencher) {
+        let names = names();
+        bencher.iter(|| {
+            let matching = names.iter().filter(|name| matches(name.as_str(),pattern));
+            let scored   = matching.filter_map(|name| {
+                find_best_subsequence(name.as_str(),pattern,metric::default())
+            });
+            test::black_box(scored.count())
+        });
+    }
+
+    fn bench_typo_tolerant(pattern:&str, max_typos:usize, bencher:&mut Bencher) {
+        let names = names();
+        bencher.iter(|| {
+            let scored = names.iter().filter_map(|name| {
+                find_best_subsequence_with_typos(name.as_str(),pattern,metric::extended(),max_typos)
+            });
+            test::black_box(scored.count())
+        });
+    }
+
+    #[bench]
+    fn subsequence_graph_short_pattern(bencher:&mut Bencher) {
+        bench_subsequence_graph("rc",bencher);
+    }
+
+    #[bench]
+    fn subsequence_graph_long_pattern(bencher:&mut Bencher) {
+        bench_subsequence_graph("length",bencher);
+    }
+
+    #[bench]
+    fn typo_tolerant_short_pattern(bencher:&mut Bencher) {
+        bench_typo_tolerant("rc",max_typos_for("rc"),bencher);
+    }
+
+    #[bench]
+    fn typo_tolerant_long_pattern(bencher:&mut Bencher) {
+        bench_typo_tolerant("length",max_typos_for("length"),bencher);
+    }
+
+    #[bench]
+    fn typo_tolerant_long_pattern_with_typo(bencher:&mut Bencher) {
+        bench_typo_tolerant("lenght",max_typos_for("lenght"),bencher);
+    }
+
+    #[bench]
+    fn typo_tolerant_long_pattern_two_typos(bencher:&mut Bencher) {
+        bench_typo_tolerant("lenght",2,bencher);
+    }
+}
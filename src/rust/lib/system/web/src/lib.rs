@@ -307,6 +307,20 @@ pub fn create_canvas() -> HtmlCanvasElement {
     create_element("canvas").unchecked_into()
 }
 
+/// Let the user download a text file with the given name and content. The file is offered by
+/// clicking a temporary link to the object URL of the content.
+pub fn try_download_text_file(name:&str, content:&str) -> Result<()> {
+    let parts = js_sys::Array::of1(&JsValue::from_str(content));
+    let blob  = web_sys::Blob::new_with_str_sequence(&parts)?;
+    let url   = web_sys::Url::create_object_url_with_blob(&blob)?;
+    let link  = try_create_element("a")?.unchecked_into::<web_sys::HtmlAnchorElement>();
+    link.set_href(&url);
+    link.set_download(name);
+    link.click();
+    web_sys::Url::revoke_object_url(&url)?;
+    Ok(())
+}
+
 pub fn get_webgl2_context(canvas:&HtmlCanvasElement) -> WebGl2RenderingContext {
     let options = js_sys::Object::new();
     js_sys::Reflect::set(&options, &"antialias".into(), &false.into()).unwrap();